@@ -0,0 +1,61 @@
+-- Create comments table for threaded discussions on compliance items, documents and risk scores
+CREATE TABLE IF NOT EXISTS comments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    entity_type VARCHAR(50) NOT NULL CHECK (entity_type IN ('compliance_item', 'document', 'risk_score')),
+    entity_id UUID NOT NULL,
+    parent_id UUID REFERENCES comments(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    edited_at TIMESTAMPTZ,
+    deleted_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_comments_entity ON comments(entity_type, entity_id);
+CREATE INDEX IF NOT EXISTS idx_comments_parent_id ON comments(parent_id);
+CREATE INDEX IF NOT EXISTS idx_comments_user_id ON comments(user_id);
+
+-- Users mentioned in a comment (resolved from @email tokens)
+CREATE TABLE IF NOT EXISTS comment_mentions (
+    comment_id UUID NOT NULL REFERENCES comments(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    PRIMARY KEY (comment_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_comment_mentions_user_id ON comment_mentions(user_id);
+
+-- Previous bodies of edited comments
+CREATE TABLE IF NOT EXISTS comment_edits (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    comment_id UUID NOT NULL REFERENCES comments(id) ON DELETE CASCADE,
+    previous_body TEXT NOT NULL,
+    edited_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    edited_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_comment_edits_comment_id ON comment_edits(comment_id);
+
+-- Comments reference their parent entity polymorphically, so remove them when the entity goes away
+CREATE OR REPLACE FUNCTION delete_entity_comments()
+RETURNS TRIGGER AS $$
+BEGIN
+    DELETE FROM comments WHERE entity_type = TG_ARGV[0] AND entity_id = OLD.id;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER compliance_items_delete_comments
+    AFTER DELETE ON compliance_items
+    FOR EACH ROW
+    EXECUTE FUNCTION delete_entity_comments('compliance_item');
+
+CREATE TRIGGER documents_delete_comments
+    AFTER DELETE ON documents
+    FOR EACH ROW
+    EXECUTE FUNCTION delete_entity_comments('document');
+
+CREATE TRIGGER risk_scores_delete_comments
+    AFTER DELETE ON risk_scores
+    FOR EACH ROW
+    EXECUTE FUNCTION delete_entity_comments('risk_score');
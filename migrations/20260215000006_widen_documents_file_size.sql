@@ -0,0 +1,2 @@
+-- Store document sizes as BIGINT to match the i64 model field
+ALTER TABLE documents ALTER COLUMN file_size TYPE BIGINT;
@@ -0,0 +1,68 @@
+-- Create tags table for user-defined classification
+CREATE TABLE IF NOT EXISTS tags (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    color VARCHAR(20),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tags_user_name ON tags(user_id, LOWER(name));
+
+-- Tag assignments (many-to-many between tags and compliance items / documents)
+CREATE TABLE IF NOT EXISTS entity_tags (
+    tag_id UUID NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    entity_type VARCHAR(50) NOT NULL CHECK (entity_type IN ('compliance_item', 'document')),
+    entity_id UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (tag_id, entity_type, entity_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_entity_tags_entity ON entity_tags(entity_type, entity_id);
+
+-- Typed custom field definitions
+CREATE TABLE IF NOT EXISTS custom_field_definitions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    entity_type VARCHAR(50) NOT NULL CHECK (entity_type IN ('compliance_item', 'document')),
+    name VARCHAR(100) NOT NULL,
+    label VARCHAR(255) NOT NULL,
+    field_type VARCHAR(20) NOT NULL CHECK (field_type IN ('text', 'number', 'date', 'enum')),
+    options JSONB,
+    required BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, entity_type, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_custom_field_definitions_user_id ON custom_field_definitions(user_id);
+
+-- Custom field values per entity
+CREATE TABLE IF NOT EXISTS custom_field_values (
+    definition_id UUID NOT NULL REFERENCES custom_field_definitions(id) ON DELETE CASCADE,
+    entity_id UUID NOT NULL,
+    value JSONB NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (definition_id, entity_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_custom_field_values_entity_id ON custom_field_values(entity_id);
+
+-- Tags and custom field values reference their entity polymorphically, so remove them with it
+CREATE OR REPLACE FUNCTION delete_entity_metadata()
+RETURNS TRIGGER AS $$
+BEGIN
+    DELETE FROM entity_tags WHERE entity_type = TG_ARGV[0] AND entity_id = OLD.id;
+    DELETE FROM custom_field_values WHERE entity_id = OLD.id;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER compliance_items_delete_metadata
+    AFTER DELETE ON compliance_items
+    FOR EACH ROW
+    EXECUTE FUNCTION delete_entity_metadata('compliance_item');
+
+CREATE TRIGGER documents_delete_metadata
+    AFTER DELETE ON documents
+    FOR EACH ROW
+    EXECUTE FUNCTION delete_entity_metadata('document');
@@ -0,0 +1,9 @@
+-- Add likelihood and impact dimensions to risk assessments
+ALTER TABLE risk_scores
+    ADD COLUMN IF NOT EXISTS likelihood INTEGER CHECK (likelihood >= 1 AND likelihood <= 5),
+    ADD COLUMN IF NOT EXISTS impact INTEGER CHECK (impact >= 1 AND impact <= 5);
+
+-- Both dimensions are set together or not at all
+ALTER TABLE risk_scores
+    ADD CONSTRAINT risk_scores_likelihood_impact_pair
+    CHECK ((likelihood IS NULL) = (impact IS NULL));
@@ -0,0 +1,28 @@
+-- Residual risk after mitigating controls (risk_score/risk_level hold the inherent risk)
+ALTER TABLE risk_scores
+    ADD COLUMN IF NOT EXISTS residual_score INTEGER CHECK (residual_score >= 0 AND residual_score <= 100),
+    ADD COLUMN IF NOT EXISTS residual_level VARCHAR(20) CHECK (residual_level IN ('low', 'medium', 'high', 'critical'));
+
+-- Without controls the residual risk equals the inherent risk
+UPDATE risk_scores SET residual_score = risk_score, residual_level = risk_level WHERE residual_score IS NULL;
+
+ALTER TABLE risk_scores
+    ALTER COLUMN residual_score SET NOT NULL,
+    ALTER COLUMN residual_level SET NOT NULL;
+
+-- Mitigating controls: compliance items that reduce a risk
+CREATE TABLE IF NOT EXISTS risk_controls (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    risk_score_id UUID NOT NULL REFERENCES risk_scores(id) ON DELETE CASCADE,
+    compliance_item_id UUID NOT NULL REFERENCES compliance_items(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    effectiveness INTEGER NOT NULL CHECK (effectiveness >= 0 AND effectiveness <= 100),
+    notes TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (risk_score_id, compliance_item_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_risk_controls_risk_score_id ON risk_controls(risk_score_id);
+CREATE INDEX IF NOT EXISTS idx_risk_controls_compliance_item_id ON risk_controls(compliance_item_id);
+CREATE INDEX IF NOT EXISTS idx_risk_controls_user_id ON risk_controls(user_id);
@@ -0,0 +1,43 @@
+-- Framework (e.g., "SOC 2", "ISO 27001") and assignee of compliance items
+ALTER TABLE compliance_items
+    ADD COLUMN IF NOT EXISTS framework VARCHAR(100),
+    ADD COLUMN IF NOT EXISTS assignee_id UUID REFERENCES users(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_compliance_framework ON compliance_items(framework);
+CREATE INDEX IF NOT EXISTS idx_compliance_assignee_id ON compliance_items(assignee_id);
+
+-- Status transitions of compliance items
+CREATE TABLE IF NOT EXISTS compliance_status_history (
+    id BIGSERIAL PRIMARY KEY,
+    compliance_item_id UUID NOT NULL REFERENCES compliance_items(id) ON DELETE CASCADE,
+    from_status VARCHAR(50),
+    to_status VARCHAR(50) NOT NULL,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_compliance_status_history_item ON compliance_status_history(compliance_item_id, changed_at);
+
+-- Existing items: assume they were opened at creation and reached their current status at their last update
+INSERT INTO compliance_status_history (compliance_item_id, from_status, to_status, changed_at)
+SELECT id, NULL, 'pending', created_at FROM compliance_items
+UNION ALL
+SELECT id, 'pending', status, updated_at FROM compliance_items WHERE status <> 'pending';
+
+CREATE OR REPLACE FUNCTION record_compliance_status_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO compliance_status_history (compliance_item_id, from_status, to_status, changed_at)
+        VALUES (NEW.id, NULL, NEW.status, NEW.created_at);
+    ELSIF OLD.status IS DISTINCT FROM NEW.status THEN
+        INSERT INTO compliance_status_history (compliance_item_id, from_status, to_status)
+        VALUES (NEW.id, OLD.status, NEW.status);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_compliance_items_status_history ON compliance_items;
+CREATE TRIGGER trg_compliance_items_status_history
+    AFTER INSERT OR UPDATE OF status ON compliance_items
+    FOR EACH ROW EXECUTE FUNCTION record_compliance_status_change();
@@ -0,0 +1,25 @@
+-- Recorded activity: who did what to which entity
+CREATE TABLE IF NOT EXISTS activity_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    actor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    verb VARCHAR(50) NOT NULL CHECK (verb IN ('created', 'updated', 'status_changed', 'analyzed', 'deleted')),
+    entity_type VARCHAR(50) NOT NULL CHECK (entity_type IN ('compliance_item', 'document', 'risk_score')),
+    entity_id UUID NOT NULL,
+    entity_title TEXT,
+    before JSONB,
+    after JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_activity_events_feed ON activity_events(user_id, created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_activity_events_entity ON activity_events(entity_type, entity_id);
+CREATE INDEX IF NOT EXISTS idx_activity_events_actor_id ON activity_events(actor_id);
+
+-- Seed the feed with the creation of existing entities
+INSERT INTO activity_events (user_id, actor_id, verb, entity_type, entity_id, entity_title, created_at)
+SELECT user_id, user_id, 'created', 'compliance_item', id, title, created_at FROM compliance_items
+UNION ALL
+SELECT user_id, user_id, 'created', 'document', id, filename, uploaded_at FROM documents
+UNION ALL
+SELECT user_id, user_id, 'created', 'risk_score', id, risk_category, created_at FROM risk_scores;
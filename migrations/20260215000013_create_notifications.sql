@@ -0,0 +1,38 @@
+-- Per-user notification preferences (defaults apply while no row exists)
+CREATE TABLE IF NOT EXISTS notification_preferences (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    in_app_enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    email_enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    due_date_reminders BOOLEAN NOT NULL DEFAULT TRUE,
+    status_changes BOOLEAN NOT NULL DEFAULT TRUE,
+    assignments BOOLEAN NOT NULL DEFAULT TRUE,
+    risk_alerts BOOLEAN NOT NULL DEFAULT TRUE,
+    reminder_lead_days INTEGER[] NOT NULL DEFAULT '{7,1}',
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- In-app notifications
+CREATE TABLE IF NOT EXISTS notifications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(50) NOT NULL CHECK (kind IN ('due_date_reminder', 'status_changed', 'assigned', 'risk_alert')),
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    entity_type VARCHAR(50) NOT NULL CHECK (entity_type IN ('compliance_item', 'risk_score')),
+    entity_id UUID NOT NULL,
+    read_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_user_id ON notifications(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_notifications_unread ON notifications(user_id) WHERE read_at IS NULL;
+
+-- Due-date reminders already sent, so each lead time fires once per due date
+CREATE TABLE IF NOT EXISTS due_date_reminders (
+    compliance_item_id UUID NOT NULL REFERENCES compliance_items(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    due_date TIMESTAMPTZ NOT NULL,
+    lead_days INTEGER NOT NULL,
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (compliance_item_id, user_id, due_date, lead_days)
+);
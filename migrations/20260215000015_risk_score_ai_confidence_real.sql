@@ -0,0 +1,2 @@
+-- Store AI confidence as REAL to match the f32 fields reading it
+ALTER TABLE risk_scores ALTER COLUMN ai_confidence TYPE REAL;
@@ -0,0 +1,8 @@
+-- Documents reference an object in the configured storage backend instead of
+-- a path on the local disk. Files used to be written directly into UPLOAD_DIR,
+-- so the key of an existing document is its file name.
+ALTER TABLE documents RENAME COLUMN file_path TO storage_key;
+
+UPDATE documents SET storage_key = regexp_replace(storage_key, '^.*/', '');
+
+CREATE INDEX IF NOT EXISTS idx_documents_storage_key ON documents(storage_key);
@@ -0,0 +1,36 @@
+-- Content-addressed file storage: every distinct file is stored once, keyed
+-- by its SHA-256, and shared by all documents with identical content
+CREATE TABLE IF NOT EXISTS blobs (
+    sha256 CHAR(64) PRIMARY KEY,
+    storage_key VARCHAR(1024) NOT NULL UNIQUE,
+    size BIGINT NOT NULL,
+    ref_count INTEGER NOT NULL DEFAULT 0 CHECK (ref_count >= 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_blobs_unreferenced ON blobs(created_at) WHERE ref_count = 0;
+
+-- Documents uploaded before deduplication have no hash
+ALTER TABLE documents ADD COLUMN IF NOT EXISTS content_sha256 CHAR(64) REFERENCES blobs(sha256);
+
+CREATE INDEX IF NOT EXISTS idx_documents_content_sha256 ON documents(content_sha256);
+
+-- Keep blob reference counts in step with documents, including rows removed
+-- by ON DELETE CASCADE
+CREATE OR REPLACE FUNCTION count_blob_references()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') AND OLD.content_sha256 IS NOT NULL THEN
+        UPDATE blobs SET ref_count = ref_count - 1 WHERE sha256 = OLD.content_sha256;
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE') AND NEW.content_sha256 IS NOT NULL THEN
+        UPDATE blobs SET ref_count = ref_count + 1 WHERE sha256 = NEW.content_sha256;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER documents_count_blob_references
+    AFTER INSERT OR DELETE OR UPDATE OF content_sha256 ON documents
+    FOR EACH ROW
+    EXECUTE FUNCTION count_blob_references();
@@ -0,0 +1,71 @@
+-- Document versions: a document is the parent of an ordered list of versions
+-- and mirrors its current one, so existing queries keep working unchanged
+ALTER TABLE documents ADD COLUMN IF NOT EXISTS current_version INTEGER NOT NULL DEFAULT 1;
+
+CREATE TABLE IF NOT EXISTS document_versions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    document_id UUID NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    version_number INTEGER NOT NULL CHECK (version_number >= 1),
+    filename VARCHAR(500) NOT NULL,
+    storage_key VARCHAR(1024) NOT NULL,
+    content_sha256 CHAR(64) REFERENCES blobs(sha256),
+    file_size BIGINT NOT NULL,
+    mime_type VARCHAR(100) NOT NULL,
+    extracted_text TEXT,
+    ai_analysis JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (document_id, version_number)
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_versions_storage_key ON document_versions(storage_key);
+CREATE INDEX IF NOT EXISTS idx_document_versions_content_sha256 ON document_versions(content_sha256);
+
+-- Older versions keep their content alive
+CREATE TRIGGER document_versions_count_blob_references
+    AFTER INSERT OR DELETE OR UPDATE OF content_sha256 ON document_versions
+    FOR EACH ROW
+    EXECUTE FUNCTION count_blob_references();
+
+-- Every existing document becomes version 1 of itself
+INSERT INTO document_versions
+    (document_id, version_number, filename, storage_key, content_sha256, file_size, mime_type,
+     extracted_text, ai_analysis, created_at)
+SELECT id, 1, filename, storage_key, content_sha256, file_size, mime_type, extracted_text, ai_analysis, uploaded_at
+FROM documents;
+
+-- New documents start at version 1, and text or analysis added to a document
+-- belongs to its current version
+CREATE OR REPLACE FUNCTION sync_current_document_version()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO document_versions
+            (document_id, version_number, filename, storage_key, content_sha256, file_size, mime_type,
+             extracted_text, ai_analysis, created_at)
+        VALUES
+            (NEW.id, NEW.current_version, NEW.filename, NEW.storage_key, NEW.content_sha256, NEW.file_size,
+             NEW.mime_type, NEW.extracted_text, NEW.ai_analysis, NEW.uploaded_at);
+    ELSE
+        UPDATE document_versions
+        SET extracted_text = NEW.extracted_text, ai_analysis = NEW.ai_analysis
+        WHERE document_id = NEW.id AND version_number = NEW.current_version;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER documents_sync_current_version
+    AFTER INSERT OR UPDATE OF extracted_text, ai_analysis ON documents
+    FOR EACH ROW
+    EXECUTE FUNCTION sync_current_document_version();
+
+-- Risk assessments record the document version they were made against
+ALTER TABLE risk_scores ADD COLUMN IF NOT EXISTS document_version INTEGER;
+
+UPDATE risk_scores SET document_version = 1 WHERE document_id IS NOT NULL;
+
+ALTER TABLE risk_scores
+    ADD CONSTRAINT risk_scores_document_version_fkey
+    FOREIGN KEY (document_id, document_version)
+    REFERENCES document_versions(document_id, version_number)
+    ON DELETE SET NULL (document_version);
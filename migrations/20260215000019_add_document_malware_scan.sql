@@ -0,0 +1,42 @@
+-- Malware scan results of stored files. Files stored while scanning was
+-- disabled (and all earlier uploads) are 'not_scanned'
+ALTER TABLE documents
+    ADD COLUMN IF NOT EXISTS scan_status VARCHAR(20) NOT NULL DEFAULT 'not_scanned'
+        CHECK (scan_status IN ('not_scanned', 'clean', 'infected', 'failed')),
+    ADD COLUMN IF NOT EXISTS scan_signature VARCHAR(255),
+    ADD COLUMN IF NOT EXISTS scanned_at TIMESTAMPTZ;
+
+ALTER TABLE document_versions
+    ADD COLUMN IF NOT EXISTS scan_status VARCHAR(20) NOT NULL DEFAULT 'not_scanned'
+        CHECK (scan_status IN ('not_scanned', 'clean', 'infected', 'failed')),
+    ADD COLUMN IF NOT EXISTS scan_signature VARCHAR(255),
+    ADD COLUMN IF NOT EXISTS scanned_at TIMESTAMPTZ;
+
+-- The current version also carries the scan result of its document
+CREATE OR REPLACE FUNCTION sync_current_document_version()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO document_versions
+            (document_id, version_number, filename, storage_key, content_sha256, file_size, mime_type,
+             extracted_text, ai_analysis, scan_status, scan_signature, scanned_at, created_at)
+        VALUES
+            (NEW.id, NEW.current_version, NEW.filename, NEW.storage_key, NEW.content_sha256, NEW.file_size,
+             NEW.mime_type, NEW.extracted_text, NEW.ai_analysis, NEW.scan_status, NEW.scan_signature,
+             NEW.scanned_at, NEW.uploaded_at);
+    ELSE
+        UPDATE document_versions
+        SET extracted_text = NEW.extracted_text, ai_analysis = NEW.ai_analysis,
+            scan_status = NEW.scan_status, scan_signature = NEW.scan_signature, scanned_at = NEW.scanned_at
+        WHERE document_id = NEW.id AND version_number = NEW.current_version;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS documents_sync_current_version ON documents;
+
+CREATE TRIGGER documents_sync_current_version
+    AFTER INSERT OR UPDATE OF extracted_text, ai_analysis, scan_status, scan_signature, scanned_at ON documents
+    FOR EACH ROW
+    EXECUTE FUNCTION sync_current_document_version();
@@ -0,0 +1,40 @@
+-- Envelope encryption of stored files: the per-file data key, wrapped by the
+-- master key with the given fingerprint. NULL for files stored unencrypted
+ALTER TABLE documents
+    ADD COLUMN IF NOT EXISTS encryption_key_id VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS wrapped_data_key TEXT,
+    ADD CONSTRAINT documents_encryption_complete
+        CHECK ((encryption_key_id IS NULL) = (wrapped_data_key IS NULL));
+
+ALTER TABLE document_versions
+    ADD COLUMN IF NOT EXISTS encryption_key_id VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS wrapped_data_key TEXT,
+    ADD CONSTRAINT document_versions_encryption_complete
+        CHECK ((encryption_key_id IS NULL) = (wrapped_data_key IS NULL));
+
+-- Key rotation looks up the data keys still wrapped by a retired master key
+CREATE INDEX IF NOT EXISTS idx_document_versions_encryption_key_id ON document_versions(encryption_key_id);
+CREATE INDEX IF NOT EXISTS idx_document_versions_wrapped_data_key ON document_versions(wrapped_data_key);
+CREATE INDEX IF NOT EXISTS idx_documents_wrapped_data_key ON documents(wrapped_data_key);
+
+CREATE OR REPLACE FUNCTION sync_current_document_version()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO document_versions
+            (document_id, version_number, filename, storage_key, content_sha256, file_size, mime_type,
+             extracted_text, ai_analysis, scan_status, scan_signature, scanned_at,
+             encryption_key_id, wrapped_data_key, created_at)
+        VALUES
+            (NEW.id, NEW.current_version, NEW.filename, NEW.storage_key, NEW.content_sha256, NEW.file_size,
+             NEW.mime_type, NEW.extracted_text, NEW.ai_analysis, NEW.scan_status, NEW.scan_signature,
+             NEW.scanned_at, NEW.encryption_key_id, NEW.wrapped_data_key, NEW.uploaded_at);
+    ELSE
+        UPDATE document_versions
+        SET extracted_text = NEW.extracted_text, ai_analysis = NEW.ai_analysis,
+            scan_status = NEW.scan_status, scan_signature = NEW.scan_signature, scanned_at = NEW.scanned_at
+        WHERE document_id = NEW.id AND version_number = NEW.current_version;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
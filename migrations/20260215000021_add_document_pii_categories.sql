@@ -0,0 +1,37 @@
+-- Kinds of personal data detected in the extracted text (email, iban, ...)
+ALTER TABLE documents
+    ADD COLUMN IF NOT EXISTS pii_categories TEXT[] NOT NULL DEFAULT '{}';
+
+ALTER TABLE document_versions
+    ADD COLUMN IF NOT EXISTS pii_categories TEXT[] NOT NULL DEFAULT '{}';
+
+CREATE OR REPLACE FUNCTION sync_current_document_version()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO document_versions
+            (document_id, version_number, filename, storage_key, content_sha256, file_size, mime_type,
+             extracted_text, ai_analysis, scan_status, scan_signature, scanned_at,
+             encryption_key_id, wrapped_data_key, pii_categories, created_at)
+        VALUES
+            (NEW.id, NEW.current_version, NEW.filename, NEW.storage_key, NEW.content_sha256, NEW.file_size,
+             NEW.mime_type, NEW.extracted_text, NEW.ai_analysis, NEW.scan_status, NEW.scan_signature,
+             NEW.scanned_at, NEW.encryption_key_id, NEW.wrapped_data_key, NEW.pii_categories, NEW.uploaded_at);
+    ELSE
+        UPDATE document_versions
+        SET extracted_text = NEW.extracted_text, ai_analysis = NEW.ai_analysis,
+            scan_status = NEW.scan_status, scan_signature = NEW.scan_signature, scanned_at = NEW.scanned_at,
+            pii_categories = NEW.pii_categories
+        WHERE document_id = NEW.id AND version_number = NEW.current_version;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS documents_sync_current_version ON documents;
+
+CREATE TRIGGER documents_sync_current_version
+    AFTER INSERT OR UPDATE OF extracted_text, ai_analysis, scan_status, scan_signature, scanned_at, pii_categories
+    ON documents
+    FOR EACH ROW
+    EXECUTE FUNCTION sync_current_document_version();
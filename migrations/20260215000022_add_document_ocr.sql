@@ -0,0 +1,34 @@
+-- OCR of scanned PDF pages and image uploads: mean word confidence (0-100)
+-- per recognized page (NULL if no page went through OCR), and whether a page
+-- fell below the configured minimum
+ALTER TABLE documents
+    ADD COLUMN IF NOT EXISTS ocr_pages JSONB,
+    ADD COLUMN IF NOT EXISTS ocr_low_confidence BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE document_versions
+    ADD COLUMN IF NOT EXISTS ocr_pages JSONB,
+    ADD COLUMN IF NOT EXISTS ocr_low_confidence BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE OR REPLACE FUNCTION sync_current_document_version()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO document_versions
+            (document_id, version_number, filename, storage_key, content_sha256, file_size, mime_type,
+             extracted_text, ai_analysis, scan_status, scan_signature, scanned_at,
+             encryption_key_id, wrapped_data_key, pii_categories, ocr_pages, ocr_low_confidence, created_at)
+        VALUES
+            (NEW.id, NEW.current_version, NEW.filename, NEW.storage_key, NEW.content_sha256, NEW.file_size,
+             NEW.mime_type, NEW.extracted_text, NEW.ai_analysis, NEW.scan_status, NEW.scan_signature,
+             NEW.scanned_at, NEW.encryption_key_id, NEW.wrapped_data_key, NEW.pii_categories, NEW.ocr_pages,
+             NEW.ocr_low_confidence, NEW.uploaded_at);
+    ELSE
+        UPDATE document_versions
+        SET extracted_text = NEW.extracted_text, ai_analysis = NEW.ai_analysis,
+            scan_status = NEW.scan_status, scan_signature = NEW.scan_signature, scanned_at = NEW.scanned_at,
+            pii_categories = NEW.pii_categories
+        WHERE document_id = NEW.id AND version_number = NEW.current_version;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,41 @@
+use axum::{
+    extract::{Extension, Query, State},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{ActivityFeedQuery, ActivityPage, Claims},
+    services::ActivityService,
+    AppState,
+};
+
+/// Get the activity feed
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `query` - Page size, cursor and filters (`entity_type`, `entity_id`, `actor`)
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Page of activity events, newest first
+///
+/// # Errors
+///
+/// Returns database error if query fails
+pub async fn get_feed(
+    State(state): State<AppState>,
+    Query(query): Query<ActivityFeedQuery>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<ActivityPage>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = ActivityService::new(state.pool.clone());
+    let page = service.feed(user_id, &query).await?;
+
+    Ok(Json(page))
+}
@@ -1,12 +1,14 @@
 use axum::{
-    extract::{State, Json},
+    extract::{Extension, State, Json},
     response::IntoResponse,
     http::StatusCode,
 };
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 use crate::{
-    error::AppResult,
-    services::ai_service::AiService,
+    error::{AppError, AppResult},
+    models::{AnalysisStage, Claims, RealtimeEvent},
+    services::{ai_service::AiService, RealtimeService},
     AppState,
 };
 
@@ -14,6 +16,9 @@ use crate::{
 #[derive(Deserialize)]
 pub struct AnalyzeDocumentDto {
     pub text: String,
+
+    /// Client-chosen job ID used in progress events (optional)
+    pub job_id: Option<Uuid>,
 }
 
 /// Request dto for risk assessment
@@ -32,12 +37,30 @@ pub struct RiskAssessmentResponse {
 }
 
 /// Analyze document content
+///
+/// Progress is pushed to the user's real-time clients on the `analysis` topic.
 pub async fn analyze_document(
     State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
     Json(dto): Json<AnalyzeDocumentDto>,
 ) -> AppResult<impl IntoResponse> {
-    let ai_service = AiService::new(state.config.ollama_url.clone());
-    let analysis = ai_service.analyze_document(&dto.text).await?;
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+    let job_id = dto.job_id.unwrap_or_else(Uuid::new_v4);
+
+    let realtime = RealtimeService::new(state.pool.clone());
+    let progress = |stage, progress, message| RealtimeEvent::AnalysisProgress { job_id, stage, progress, message };
+    realtime.publish(user_id, progress(AnalysisStage::Started, 0, None)).await;
+
+    let ai_service = AiService::new(state.config.ollama_url.clone(), state.config.pii_mode);
+    let analysis = match ai_service.analyze_document(&dto.text).await {
+        Ok(analysis) => analysis,
+        Err(e) => {
+            realtime.publish(user_id, progress(AnalysisStage::Failed, 100, Some(e.to_string()))).await;
+            return Err(e);
+        }
+    };
+    realtime.publish(user_id, progress(AnalysisStage::Completed, 100, None)).await;
     
     Ok((StatusCode::OK, Json(analysis)))
 }
@@ -47,7 +70,7 @@ pub async fn assess_risk(
     State(state): State<AppState>,
     Json(dto): Json<AssessRiskDto>,
 ) -> AppResult<impl IntoResponse> {
-    let ai_service = AiService::new(state.config.ollama_url.clone());
+    let ai_service = AiService::new(state.config.ollama_url.clone(), state.config.pii_mode);
     let (score, level, confidence) = ai_service.assess_risk(&dto.title, dto.description.as_deref()).await?;
     
     let response = RiskAssessmentResponse {
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{CalendarFeed, Claims, MetadataFilter, UpcomingCompliance},
+    services::{CalendarService, MetadataService},
+    AppState,
+};
+
+/// Get open compliance items grouped by due window
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `params` - Filters (`tags=a,b` and `cf.<field>=<value>`)
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Overdue items and items due this week and this month
+///
+/// # Errors
+///
+/// Returns database error if query fails
+pub async fn get_upcoming(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<UpcomingCompliance>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let filter = MetadataFilter::from_query(&params);
+
+    let service = CalendarService::new(state.pool.clone());
+    let mut upcoming = service.upcoming(user_id, &filter).await?;
+
+    let metadata = MetadataService::new(state.pool.clone());
+    metadata.attach_to_compliance_items(&mut upcoming.overdue).await?;
+    metadata.attach_to_compliance_items(&mut upcoming.this_week).await?;
+    metadata.attach_to_compliance_items(&mut upcoming.this_month).await?;
+
+    Ok(Json(upcoming))
+}
+
+/// Get the calendar feed subscription, enabling the feed on first use
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Feed token and path
+///
+/// # Errors
+///
+/// Returns database error if query fails
+pub async fn get_feed(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<CalendarFeed>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = CalendarService::new(state.pool.clone());
+    let feed = service.feed(user_id).await?;
+
+    Ok(Json(feed))
+}
+
+/// Rotate the calendar feed token
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// New feed token and path; existing subscriptions stop working
+///
+/// # Errors
+///
+/// Returns database error if update fails
+pub async fn rotate_feed(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<CalendarFeed>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = CalendarService::new(state.pool.clone());
+    let feed = service.rotate_token(user_id).await?;
+
+    Ok(Json(feed))
+}
+
+/// Disable the calendar feed
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// 204 No Content
+///
+/// # Errors
+///
+/// Returns database error if update fails
+pub async fn revoke_feed(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<StatusCode> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = CalendarService::new(state.pool.clone());
+    service.revoke_token(user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Serve the iCalendar feed (public; the token is the credential)
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `token` - Feed token, optionally with an `.ics` suffix
+///
+/// # Returns
+///
+/// `text/calendar` document of compliance due dates
+///
+/// # Errors
+///
+/// Returns 404 if the token is unknown or revoked
+pub async fn ics_feed(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> AppResult<impl IntoResponse> {
+    let token = token.strip_suffix(".ics").unwrap_or(&token);
+
+    let service = CalendarService::new(state.pool.clone());
+    let calendar = service.render_feed(token).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/calendar; charset=utf-8"),
+            (header::CACHE_CONTROL, "private, max-age=300"),
+        ],
+        calendar,
+    ))
+}
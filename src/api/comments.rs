@@ -0,0 +1,203 @@
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{Claims, CommentEdit, CommentEntityType, CommentResponse, CreateCommentDto, UpdateCommentDto},
+    services::CommentService,
+    AppState,
+};
+
+/// Entity whose comment routes are being served
+///
+/// Implemented by marker types so one set of handlers covers every commentable entity.
+pub trait CommentTarget {
+    /// Entity type the comments are attached to
+    const ENTITY_TYPE: CommentEntityType;
+}
+
+/// Comments on compliance items (`/compliance/:id/comments`)
+pub struct ComplianceComments;
+
+impl CommentTarget for ComplianceComments {
+    const ENTITY_TYPE: CommentEntityType = CommentEntityType::ComplianceItem;
+}
+
+/// Comments on documents (`/documents/:id/comments`)
+pub struct DocumentComments;
+
+impl CommentTarget for DocumentComments {
+    const ENTITY_TYPE: CommentEntityType = CommentEntityType::Document;
+}
+
+/// Comments on risk scores (`/risk-scores/:id/comments`)
+pub struct RiskScoreComments;
+
+impl CommentTarget for RiskScoreComments {
+    const ENTITY_TYPE: CommentEntityType = CommentEntityType::RiskScore;
+}
+
+/// List comment threads on an entity
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Parent entity UUID
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Top-level comments with nested replies
+///
+/// # Errors
+///
+/// Returns 404 if the entity is not found or not authorized
+pub async fn list_comments<T: CommentTarget>(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<Vec<CommentResponse>>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = CommentService::new(state.pool.clone());
+    let comments = service.list(T::ENTITY_TYPE, id, user_id).await?;
+
+    Ok(Json(comments))
+}
+
+/// Create a comment or reply on an entity
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Parent entity UUID
+/// * `claims` - Authenticated user claims
+/// * `dto` - Comment data
+///
+/// # Returns
+///
+/// Created comment
+///
+/// # Errors
+///
+/// Returns validation error or 404 if the entity or parent comment is not found
+pub async fn create_comment<T: CommentTarget>(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Json(dto): Json<CreateCommentDto>,
+) -> AppResult<(StatusCode, Json<CommentResponse>)> {
+    // Validate input
+    dto.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = CommentService::new(state.pool.clone());
+    let comment = service.create(T::ENTITY_TYPE, id, user_id, &dto).await?;
+
+    Ok((StatusCode::CREATED, Json(comment)))
+}
+
+/// Edit a comment
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Parent entity UUID
+/// * `comment_id` - Comment UUID
+/// * `claims` - Authenticated user claims
+/// * `dto` - New comment data
+///
+/// # Returns
+///
+/// Updated comment
+///
+/// # Errors
+///
+/// Returns 404 if not found, 403 if the user is not the author
+pub async fn update_comment<T: CommentTarget>(
+    State(state): State<AppState>,
+    Path((id, comment_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Json(dto): Json<UpdateCommentDto>,
+) -> AppResult<Json<CommentResponse>> {
+    // Validate input
+    dto.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = CommentService::new(state.pool.clone());
+    let comment = service.update(T::ENTITY_TYPE, id, comment_id, user_id, &dto).await?;
+
+    Ok(Json(comment))
+}
+
+/// Delete a comment
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Parent entity UUID
+/// * `comment_id` - Comment UUID
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// 204 No Content on success
+///
+/// # Errors
+///
+/// Returns 404 if not found, 403 if the user is not the author
+pub async fn delete_comment<T: CommentTarget>(
+    State(state): State<AppState>,
+    Path((id, comment_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<StatusCode> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = CommentService::new(state.pool.clone());
+    service.delete(T::ENTITY_TYPE, id, comment_id, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Get the edit history of a comment
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Parent entity UUID
+/// * `comment_id` - Comment UUID
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Previous versions of the comment, newest first
+///
+/// # Errors
+///
+/// Returns 404 if the entity or comment is not found
+pub async fn comment_history<T: CommentTarget>(
+    State(state): State<AppState>,
+    Path((id, comment_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<Vec<CommentEdit>>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = CommentService::new(state.pool.clone());
+    let edits = service.history(T::ENTITY_TYPE, id, comment_id, user_id).await?;
+
+    Ok(Json(edits))
+}
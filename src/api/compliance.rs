@@ -1,5 +1,7 @@
+use std::collections::HashMap;
+
 use axum::{
-    extract::{Extension, Path, State},
+    extract::{Extension, Path, Query, State},
     http::StatusCode,
     Json,
 };
@@ -7,9 +9,13 @@ use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    db::repository::ComplianceRepository,
+    db::repository::{ComplianceRepository, RiskControlRepository},
     error::{AppError, AppResult},
-    models::{Claims, ComplianceItem, CreateComplianceDto, UpdateComplianceDto},
+    models::{
+        ActivityEntityType, Claims, ComplianceItem, CreateComplianceDto, MetadataEntityType,
+        MetadataFilter, NewActivityEvent, UpdateComplianceDto,
+    },
+    services::{ActivityService, MetadataService, NotificationService},
     AppState,
 };
 
@@ -18,6 +24,7 @@ use crate::{
 /// # Arguments
 ///
 /// * `state` - Application state
+/// * `params` - Filters (`tags=a,b` and `cf.<field>=<value>`)
 /// * `claims` - Authenticated user claims from middleware
 ///
 /// # Returns
@@ -29,13 +36,19 @@ use crate::{
 /// Returns database error if query fails
 pub async fn list_compliance(
     State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
     Extension(claims): Extension<Claims>,
 ) -> AppResult<Json<Vec<ComplianceItem>>> {
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
 
+    let filter = MetadataFilter::from_query(&params);
+
     let repo = ComplianceRepository::new(state.pool.clone());
-    let items = repo.find_by_user(user_id).await?;
+    let mut items = repo.find_by_user(user_id, &filter).await?;
+
+    let metadata = MetadataService::new(state.pool.clone());
+    metadata.attach_to_compliance_items(&mut items).await?;
 
     Ok(Json(items))
 }
@@ -64,10 +77,13 @@ pub async fn get_compliance(
         .map_err(|_| AppError::Internal("Invalid user ID in tokenToken".to_string()))?;
 
     let repo = ComplianceRepository::new(state.pool.clone());
-    let item = repo.find_by_id(id, user_id)
+    let mut item = repo.find_by_id(id, user_id)
         .await?
         .ok_or_else(|| AppError::NotFound("Compliance item not found".to_string()))?;
 
+    let metadata = MetadataService::new(state.pool.clone());
+    metadata.attach_to_compliance_items(std::slice::from_mut(&mut item)).await?;
+
     Ok(Json(item))
 }
 
@@ -98,8 +114,37 @@ pub async fn create_compliance(
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
 
-    let repo = ComplianceRepository::new(state.pool.clone());
-    let item = repo.create(user_id, &dto).await?;
+    let metadata = MetadataService::new(state.pool.clone());
+    let changes = metadata
+        .prepare(
+            user_id,
+            MetadataEntityType::ComplianceItem,
+            dto.tag_ids.as_deref(),
+            dto.custom_fields.as_ref(),
+            true,
+        )
+        .await?;
+
+    // The item and its tags and custom fields are written together
+    let mut tx = state.pool.begin().await?;
+    let mut item = ComplianceRepository::create_in(&mut tx, user_id, &dto).await?;
+    MetadataService::apply_in(&mut tx, MetadataEntityType::ComplianceItem, item.id, &changes).await?;
+    tx.commit().await?;
+
+    metadata.attach_to_compliance_items(std::slice::from_mut(&mut item)).await?;
+
+    ActivityService::new(state.pool.clone())
+        .record(NewActivityEvent::created(
+            user_id,
+            ActivityEntityType::ComplianceItem,
+            item.id,
+            &item.title,
+            &item,
+        ))
+        .await;
+    NotificationService::new(state.pool.clone(), state.mailer.clone())
+        .compliance_created(user_id, &item)
+        .await;
 
     Ok((StatusCode::CREATED, Json(item)))
 }
@@ -133,10 +178,45 @@ pub async fn update_compliance(
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
 
+    let metadata = MetadataService::new(state.pool.clone());
+    let changes = metadata
+        .prepare(
+            user_id,
+            MetadataEntityType::ComplianceItem,
+            dto.tag_ids.as_deref(),
+            dto.custom_fields.as_ref(),
+            false,
+        )
+        .await?;
+
     let repo = ComplianceRepository::new(state.pool.clone());
-    let item = repo.update(id, user_id, &dto)
+    let mut before = repo.find_by_id(id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Compliance item not found".to_string()))?;
+    metadata.attach_to_compliance_items(std::slice::from_mut(&mut before)).await?;
+
+    let mut tx = state.pool.begin().await?;
+    let mut item = ComplianceRepository::update_in(&mut tx, id, user_id, &dto)
         .await?
         .ok_or_else(|| AppError::NotFound("Compliance item not found".to_string()))?;
+    MetadataService::apply_in(&mut tx, MetadataEntityType::ComplianceItem, item.id, &changes).await?;
+    tx.commit().await?;
+
+    metadata.attach_to_compliance_items(std::slice::from_mut(&mut item)).await?;
+
+    ActivityService::new(state.pool.clone())
+        .record(NewActivityEvent::updated(
+            user_id,
+            ActivityEntityType::ComplianceItem,
+            item.id,
+            &item.title,
+            &before,
+            &item,
+        ))
+        .await;
+    NotificationService::new(state.pool.clone(), state.mailer.clone())
+        .compliance_updated(user_id, &before, &item)
+        .await;
 
     Ok(Json(item))
 }
@@ -164,12 +244,30 @@ pub async fn delete_compliance(
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
 
+    // Risks this item mitigates lose the control when it is deleted
+    let controls = RiskControlRepository::new(state.pool.clone(), state.config.risk_matrix.clone());
+    let mitigated = controls.find_risks_mitigated_by(id).await?;
+
     let repo = ComplianceRepository::new(state.pool.clone());
-    let deleted = repo.delete(id, user_id).await?;
+    let item = repo.find_by_id(id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Compliance item not found".to_string()))?;
 
-    if deleted {
-        Ok(StatusCode::NO_CONTENT)
-    } else {
-        Err(AppError::NotFound("Compliance item not found".to_string()))
+    if !repo.delete(id, user_id).await? {
+        return Err(AppError::NotFound("Compliance item not found".to_string()));
     }
+
+    controls.refresh_residuals(&mitigated).await?;
+
+    ActivityService::new(state.pool.clone())
+        .record(NewActivityEvent::deleted(
+            user_id,
+            ActivityEntityType::ComplianceItem,
+            item.id,
+            &item.title,
+            &item,
+        ))
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
 }
@@ -0,0 +1,181 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    db::repository::CustomFieldRepository,
+    error::{AppError, AppResult},
+    models::{
+        Claims, CreateCustomFieldDto, CustomFieldDefinition, CustomFieldType, MetadataEntityType,
+        UpdateCustomFieldDto,
+    },
+    AppState,
+};
+
+/// Query parameters for custom field listing
+#[derive(Debug, Deserialize)]
+pub struct CustomFieldQuery {
+    /// Restrict to one entity type (optional)
+    pub entity_type: Option<MetadataEntityType>,
+}
+
+/// List custom field definitions for authenticated user
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `query` - Query parameters (entity_type)
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// List of custom field definitions
+///
+/// # Errors
+///
+/// Returns database error if query fails
+pub async fn list_custom_fields(
+    State(state): State<AppState>,
+    Query(query): Query<CustomFieldQuery>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<Vec<CustomFieldDefinition>>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let repo = CustomFieldRepository::new(state.pool.clone());
+    let definitions = repo.find_by_user(user_id, query.entity_type).await?;
+
+    Ok(Json(definitions))
+}
+
+/// Create custom field definition
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+/// * `dto` - Definition data
+///
+/// # Returns
+///
+/// Created definition
+///
+/// # Errors
+///
+/// Returns validation error if invalid or the name is taken
+pub async fn create_custom_field(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(dto): Json<CreateCustomFieldDto>,
+) -> AppResult<(StatusCode, Json<CustomFieldDefinition>)> {
+    // Validate input
+    dto.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+    validate_options(dto.field_type, dto.options.as_deref())?;
+
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let repo = CustomFieldRepository::new(state.pool.clone());
+    let definition = repo.create(user_id, &dto).await?;
+
+    Ok((StatusCode::CREATED, Json(definition)))
+}
+
+/// Update custom field definition
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Definition UUID
+/// * `claims` - Authenticated user claims
+/// * `dto` - Update data
+///
+/// # Returns
+///
+/// Updated definition
+///
+/// # Errors
+///
+/// Returns 404 if not found or validation error
+pub async fn update_custom_field(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Json(dto): Json<UpdateCustomFieldDto>,
+) -> AppResult<Json<CustomFieldDefinition>> {
+    // Validate input
+    dto.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let repo = CustomFieldRepository::new(state.pool.clone());
+    if let Some(ref options) = dto.options {
+        let existing = repo.find_by_id(id, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Custom field not found".to_string()))?;
+        let field_type = CustomFieldType::parse(&existing.field_type)
+            .ok_or_else(|| AppError::Internal(format!("Unknown custom field type '{}'", existing.field_type)))?;
+        validate_options(field_type, Some(options))?;
+    }
+
+    let definition = repo.update(id, user_id, &dto)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Custom field not found".to_string()))?;
+
+    Ok(Json(definition))
+}
+
+/// Delete custom field definition (and its values)
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Definition UUID
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// 204 No Content on success
+///
+/// # Errors
+///
+/// Returns 404 if not found or not authorized
+pub async fn delete_custom_field(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<StatusCode> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let repo = CustomFieldRepository::new(state.pool.clone());
+    let deleted = repo.delete(id, user_id).await?;
+
+    if deleted {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(AppError::NotFound("Custom field not found".to_string()))
+    }
+}
+
+/// Enum fields need at least one option; other types take none
+fn validate_options(field_type: CustomFieldType, options: Option<&[String]>) -> AppResult<()> {
+    match (field_type, options) {
+        (CustomFieldType::Enum, Some(options)) if !options.is_empty() => Ok(()),
+        (CustomFieldType::Enum, _) => Err(AppError::Validation(
+            "Enum fields require at least one option".to_string(),
+        )),
+        (_, Some(_)) => Err(AppError::Validation(
+            "Options are only allowed for enum fields".to_string(),
+        )),
+        (_, None) => Ok(()),
+    }
+}
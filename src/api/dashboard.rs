@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use axum::{
     extract::{Extension, Query, State},
     Json,
@@ -7,11 +9,14 @@ use uuid::Uuid;
 
 use crate::{
     error::{AppError, AppResult},
-    models::Claims,
-    services::{ActivityItem, DashboardService, DashboardStats},
+    models::{Claims, DashboardFilter},
+    services::{ActivityItem, DashboardService, DashboardStats, HeatmapAxes, RiskHeatmap, RiskPosture},
     AppState,
 };
 
+/// Longest staleness window accepted for the risk posture (10 years)
+const MAX_STALE_DAYS: i64 = 3650;
+
 /// Query parameters for activity endpoint
 #[derive(Debug, Deserialize)]
 pub struct ActivityQuery {
@@ -24,29 +29,56 @@ fn default_limit() -> i64 {
     10
 }
 
+/// Query parameters for risk heatmap endpoint
+#[derive(Debug, Deserialize)]
+pub struct HeatmapQuery {
+    /// Heatmap axes, `matrix` or `category` (default: matrix)
+    #[serde(default)]
+    pub axes: HeatmapAxes,
+}
+
+/// Query parameters for risk posture endpoint
+#[derive(Debug, Deserialize)]
+pub struct PostureQuery {
+    /// Number of riskiest items to return (default: 5)
+    #[serde(default = "default_top")]
+    pub top: i64,
+
+    /// Staleness window in days, 0-3650 (default: RISK_STALE_AFTER_DAYS)
+    pub stale_days: Option<i64>,
+}
+
+fn default_top() -> i64 {
+    5
+}
+
 /// Get dashboard statistics
 ///
 /// # Arguments
 ///
 /// * `state` - Application state
+/// * `params` - Filters (`from`, `to`, `compare`, `framework`, `assignee`, `tags`, `cf.<field>`)
 /// * `claims` - Authenticated user claims
 ///
 /// # Returns
 ///
-/// Dashboard statistics for the user
+/// Dashboard statistics for the user, with deltas and burn-down
 ///
 /// # Errors
 ///
-/// Returns database error if queries fail
+/// Returns validation error for malformed filters, database error if queries fail
 pub async fn get_stats(
     State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
     Extension(claims): Extension<Claims>,
 ) -> AppResult<Json<DashboardStats>> {
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
 
+    let filter = DashboardFilter::from_query(&params).map_err(AppError::Validation)?;
+
     let service = DashboardService::new(state.pool.clone());
-    let stats = service.get_stats(user_id).await?;
+    let stats = service.get_stats(user_id, &filter).await?;
 
     Ok(Json(stats))
 }
@@ -86,3 +118,80 @@ pub async fn get_activity(
 
     Ok(Json(activity))
 }
+
+/// Get risk heatmap
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `query` - Query parameters (axes)
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Heatmap of the latest assessment per compliance item
+///
+/// # Errors
+///
+/// Returns database error if queries fail
+pub async fn get_risk_heatmap(
+    State(state): State<AppState>,
+    Query(query): Query<HeatmapQuery>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<RiskHeatmap>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = DashboardService::new(state.pool.clone());
+    let heatmap = service
+        .get_risk_heatmap(user_id, query.axes, &state.config.risk_matrix)
+        .await?;
+
+    Ok(Json(heatmap))
+}
+
+/// Get aggregate risk posture
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `query` - Query parameters (top, stale_days)
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Posture score, riskiest items and stale assessment counts
+///
+/// # Errors
+///
+/// Returns validation error for a window outside 0-3650 days, database error if queries fail
+pub async fn get_risk_posture(
+    State(state): State<AppState>,
+    Query(query): Query<PostureQuery>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<RiskPosture>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    // Validate top
+    let top = if query.top > 0 && query.top <= 50 {
+        query.top
+    } else {
+        5
+    };
+
+    let stale_after_days = query.stale_days.unwrap_or(state.config.risk_stale_after_days);
+    if !(0..=MAX_STALE_DAYS).contains(&stale_after_days) {
+        return Err(AppError::Validation(format!(
+            "stale_days must be between 0 and {}",
+            MAX_STALE_DAYS
+        )));
+    }
+
+    let service = DashboardService::new(state.pool.clone());
+    let posture = service
+        .get_risk_posture(user_id, top as usize, stale_after_days)
+        .await?;
+
+    Ok(Json(posture))
+}
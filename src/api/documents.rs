@@ -1,15 +1,33 @@
+use std::collections::HashMap;
+
 use axum::{
-    extract::{Extension, Path, State},
-    http::StatusCode,
+    body::Body,
+    extract::{Extension, Multipart, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
+use chrono::Utc;
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    db::repository::DocumentRepository,
+    db::repository::{DocumentRepository, DocumentVersionRepository},
     error::{AppError, AppResult},
-    models::{Claims, CreateDocumentDto, Document, DocumentResponse, UpdateDocumentDto},
+    models::{
+        ActivityEntityType, ActivityVerb, Claims, CreateDocumentDto, Document, DocumentDownloadUrl, DocumentResponse,
+        DocumentUploadResponse, DocumentVersion, DocumentVersionSummary, MetadataEntityType, MetadataFilter,
+        NewActivityEvent, UpdateDocumentDto, VersionDiff, VersionDiffQuery,
+    },
+    services::{
+        ensure_scan_passed, validate_storage_key, ActivityService, DocumentDiffService, MetadataService,
+        StorageService,
+    },
+    utils::{
+        download::{content_disposition, etag_matches, inline_allowed, parse_range, ByteRange, RangeRequest},
+        envelope::WrappedDataKey,
+        pii, validate_file_size, validate_mime_type,
+    },
     AppState,
 };
 
@@ -20,12 +38,36 @@ pub struct CreateTextDocumentDto {
     pub content: String,
 }
 
+/// MIME types whose content is stored as extracted text on upload
+const TEXT_MIME_TYPES: &[&str] = &["text/plain", "text/csv", "application/json"];
+
+/// File read from an upload form
+struct FileUpload {
+    filename: String,
+    mime_type: String,
+    bytes: Vec<u8>,
+    extracted_text: Option<String>,
+}
+
+/// Stored file to send to the client
+struct StoredFile<'a> {
+    storage_key: &'a str,
+    filename: &'a str,
+    mime_type: &'a str,
+
+    /// Data key of an encrypted file
+    encryption: Option<WrappedDataKey>,
+
+    /// Identifies this revision of the file (the size is appended for the ETag)
+    etag_seed: String,
+}
+
 /// Create document from text content
 pub async fn create_from_text(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
     Json(dto): Json<CreateTextDocumentDto>,
-) -> AppResult<(StatusCode, Json<Document>)> {
+) -> AppResult<(StatusCode, Json<DocumentUploadResponse>)> {
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
 
@@ -35,34 +77,340 @@ pub async fn create_from_text(
         .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
         .collect::<String>();
     let filename = format!("{}.txt", safe_filename);
-    let unique_id = Uuid::new_v4();
-    let stored_filename = format!("{}_{}", unique_id, filename);
-    let file_path = std::path::Path::new(&state.config.upload_dir).join(&stored_filename);
 
-    // Ensure upload directory exists
-    tokio::fs::create_dir_all(&state.config.upload_dir)
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to create upload dir: {}", e)))?;
+    let upload = StorageService::new(state.pool.clone(), state.storage.clone(), &state.config)
+        .store_document(
+            user_id,
+            filename,
+            "text/plain".to_string(),
+            dto.content.clone().into_bytes(),
+            Some(dto.content),
+        )
+        .await?;
+
+    record_upload(&state, user_id, &upload.document).await;
+
+    Ok((StatusCode::CREATED, Json(upload)))
+}
+
+/// Upload a file as a new document
+///
+/// Identical files are stored once; the response names the user's earlier
+/// documents with the same content and reports whether text extraction and
+/// AI analysis were taken over from one of them.
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+/// * `multipart` - Form with the `file`
+///
+/// # Returns
+///
+/// Created document with duplicate information
+///
+/// # Errors
+///
+/// Returns validation error for a missing, oversized or unsupported file
+pub async fn upload_document(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    multipart: Multipart,
+) -> AppResult<(StatusCode, Json<DocumentUploadResponse>)> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let file = read_file(&state, multipart).await?;
+    let upload = StorageService::new(state.pool.clone(), state.storage.clone(), &state.config)
+        .store_document(user_id, file.filename, file.mime_type, file.bytes, file.extracted_text)
+        .await?;
+
+    record_upload(&state, user_id, &upload.document).await;
+
+    Ok((StatusCode::CREATED, Json(upload)))
+}
+
+/// Upload a file as the next version of a document
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Document UUID
+/// * `claims` - Authenticated user claims
+/// * `multipart` - Form with the `file`
+///
+/// # Returns
+///
+/// Document describing the new version, with duplicate information
+///
+/// # Errors
+///
+/// Returns 404 if the document is not found or not owned by the user,
+/// validation error for a missing, unsupported or unchanged file
+pub async fn upload_document_version(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    multipart: Multipart,
+) -> AppResult<(StatusCode, Json<DocumentUploadResponse>)> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let repo = DocumentRepository::new(state.pool.clone());
+    let mut before = repo.find_by_id(id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Document not found".to_string()))?;
+
+    let file = read_file(&state, multipart).await?;
+    let mut upload = StorageService::new(state.pool.clone(), state.storage.clone(), &state.config)
+        .store_version(id, user_id, file.filename, file.mime_type, file.bytes, file.extracted_text)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Document not found".to_string()))?;
+
+    let metadata = MetadataService::new(state.pool.clone());
+    metadata.attach_to_documents(std::slice::from_mut(&mut before)).await?;
+    metadata.attach_to_documents(std::slice::from_mut(&mut upload.document)).await?;
+
+    ActivityService::new(state.pool.clone())
+        .record(NewActivityEvent::updated(
+            user_id,
+            ActivityEntityType::Document,
+            upload.document.id,
+            &upload.document.filename,
+            &before,
+            &upload.document,
+        ))
+        .await;
+
+    Ok((StatusCode::CREATED, Json(upload)))
+}
+
+/// List the versions of a document
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Document UUID
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Versions, oldest first
+///
+/// # Errors
+///
+/// Returns 404 if the document is not found or not owned by the user
+pub async fn list_document_versions(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<Vec<DocumentVersionSummary>>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    DocumentRepository::new(state.pool.clone())
+        .find_by_id(id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Document not found".to_string()))?;
+
+    let versions = DocumentVersionRepository::new(state.pool.clone())
+        .find_by_document(id)
+        .await?;
+
+    Ok(Json(versions.into_iter().map(DocumentVersionSummary::from).collect()))
+}
+
+/// Get one version of a document
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Document UUID
+/// * `version` - Version number
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Version with its extracted text and AI analysis
+///
+/// # Errors
+///
+/// Returns 404 if the document or version is not found
+pub async fn get_document_version(
+    State(state): State<AppState>,
+    Path((id, version)): Path<(Uuid, i32)>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<DocumentVersion>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    Ok(Json(find_version(&state, id, version, user_id).await?))
+}
+
+/// Download or preview the stored file of a document version
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Document UUID
+/// * `version` - Version number
+/// * `params` - `disposition=inline` to display a PDF, image or plain text file instead of saving it
+/// * `claims` - Authenticated user claims
+/// * `headers` - Request headers
+///
+/// # Returns
+///
+/// File content, see [`get_document_content`]
+///
+/// # Errors
+///
+/// Returns 404 if the document, version or its stored file is not found,
+/// 403 if the file is quarantined by the malware scan
+pub async fn get_document_version_content(
+    State(state): State<AppState>,
+    Path((id, version)): Path<(Uuid, i32)>,
+    Query(params): Query<HashMap<String, String>>,
+    Extension(claims): Extension<Claims>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let version = find_version(&state, id, version, user_id).await?;
+    ensure_scan_passed(&version.scan_status, version.scan_signature.as_deref(), state.config.clamd_address.is_some())?;
+
+    let file = StoredFile {
+        storage_key: &version.storage_key,
+        filename: &version.filename,
+        mime_type: &version.mime_type,
+        encryption: WrappedDataKey::from_parts(version.encryption_key_id.as_deref(), version.wrapped_data_key.as_deref()),
+        etag_seed: format!("{}-{:x}", version.id.simple(), version.created_at.timestamp_micros()),
+    };
+
+    serve_file(&state, file, &params, &headers).await
+}
+
+/// Compare two versions of a document
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Document UUID
+/// * `query` - `from`/`to` version numbers (default: previous and current),
+///   `granularity=paragraph|sentence` and `classify=true` to have the LLM
+///   classify the changes and flag impacted compliance items
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Changed passages between the versions
+///
+/// # Errors
+///
+/// Returns 404 if the document or a version is not found, validation error
+/// if a version has no extracted text, 403 if a version is quarantined by the
+/// malware scan
+pub async fn diff_document_versions(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<VersionDiffQuery>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<VersionDiff>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = DocumentDiffService::new(
+        state.pool.clone(),
+        state.config.ollama_url.clone(),
+        state.config.pii_mode,
+        state.config.clamd_address.is_some(),
+    );
+
+    Ok(Json(service.diff(user_id, id, &query).await?))
+}
+
+/// Find a version of a document owned by the user
+async fn find_version(state: &AppState, id: Uuid, version: i32, user_id: Uuid) -> AppResult<DocumentVersion> {
+    DocumentRepository::new(state.pool.clone())
+        .find_by_id(id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Document not found".to_string()))?;
+
+    DocumentVersionRepository::new(state.pool.clone())
+        .find_by_number(id, version)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Document version not found".to_string()))
+}
 
-    // Write file
-    tokio::fs::write(&file_path, &dto.content)
+/// Read the `file` form field
+///
+/// Text formats are taken as their own extracted text.
+///
+/// # Returns
+///
+/// Validated file
+async fn read_file(state: &AppState, mut multipart: Multipart) -> AppResult<FileUpload> {
+    let mut file = None;
+    while let Some(field) = multipart
+        .next_field()
         .await
-        .map_err(|e| AppError::Internal(format!("Failed to save file: {}", e)))?;
+        .map_err(|e| AppError::Validation(format!("Invalid multipart body: {}", e)))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
 
-    let file_size = dto.content.len() as i64;
+        let filename = field
+            .file_name()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| AppError::Validation("Uploaded file has no filename".to_string()))?;
+        let mime_type = field
+            .content_type()
+            .map(|mime| mime.split(';').next().unwrap_or_default().trim().to_lowercase())
+            .unwrap_or_default();
+        let bytes = field
+            .bytes()
+            .await
+            .map_err(|e| AppError::Validation(format!("Invalid multipart body: {}", e)))?;
+        file = Some((filename, mime_type, bytes.to_vec()));
+    }
 
-    let create_dto = CreateDocumentDto {
-        filename,
-        file_path: file_path.to_string_lossy().to_string(),
-        file_size,
-        mime_type: "text/plain".to_string(),
-        extracted_text: Some(dto.content),
+    let (filename, mime_type, bytes) =
+        file.ok_or_else(|| AppError::Validation("Form field 'file' is required".to_string()))?;
+    if bytes.is_empty() {
+        return Err(AppError::Validation("Uploaded file is empty".to_string()));
+    }
+    validate_file_size(bytes.len() as u64, state.config.max_file_size)?;
+    validate_mime_type(&mime_type)?;
+
+    let extracted_text = if TEXT_MIME_TYPES.contains(&mime_type.as_str()) {
+        String::from_utf8(bytes.clone()).ok()
+    } else {
+        None
     };
 
-    let repo = DocumentRepository::new(state.pool.clone());
-    let document = repo.create(user_id, &create_dto).await?;
+    Ok(FileUpload {
+        filename,
+        mime_type,
+        bytes,
+        extracted_text,
+    })
+}
 
-    Ok((StatusCode::CREATED, Json(document)))
+/// Record the activity of a stored upload
+async fn record_upload(state: &AppState, user_id: Uuid, document: &Document) {
+    let mut event = NewActivityEvent::created(
+        user_id,
+        ActivityEntityType::Document,
+        document.id,
+        &document.filename,
+        document,
+    );
+    if document.ai_analysis.is_some() {
+        event = event.with_verb(ActivityVerb::Analyzed);
+    }
+    ActivityService::new(state.pool.clone()).record(event).await;
 }
 
 /// Get all documents for authenticated user
@@ -70,6 +418,7 @@ pub async fn create_from_text(
 /// # Arguments
 ///
 /// * `state` - Application state
+/// * `params` - Filters (`tags=a,b` and `cf.<field>=<value>`)
 /// * `claims` - Authenticated user claims from middleware
 ///
 /// # Returns
@@ -81,13 +430,19 @@ pub async fn create_from_text(
 /// Returns database error if query fails
 pub async fn list_documents(
     State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
     Extension(claims): Extension<Claims>,
 ) -> AppResult<Json<Vec<DocumentResponse>>> {
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
 
+    let filter = MetadataFilter::from_query(&params);
+
     let repo = DocumentRepository::new(state.pool.clone());
-    let documents = repo.find_by_user(user_id).await?;
+    let mut documents = repo.find_by_user(user_id, &filter).await?;
+
+    let metadata = MetadataService::new(state.pool.clone());
+    metadata.attach_to_documents(&mut documents).await?;
 
     let responses: Vec<DocumentResponse> = documents
         .into_iter()
@@ -121,14 +476,229 @@ pub async fn get_document(
         .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
 
     let repo = DocumentRepository::new(state.pool.clone());
-    let document = repo.find_by_id(id, user_id)
+    let mut document = repo.find_by_id(id, user_id)
         .await?
         .ok_or_else(|| AppError::NotFound("Document not found".to_string()))?;
 
+    let metadata = MetadataService::new(state.pool.clone());
+    metadata.attach_to_documents(std::slice::from_mut(&mut document)).await?;
+
     Ok(Json(document))
 }
 
-/// Create document record (after file upload)
+/// Download or preview the stored file of a document
+///
+/// Supports single `Range` requests (with `If-Range`) for large files and
+/// conditional requests with `If-None-Match`.
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Document UUID
+/// * `params` - `disposition=inline` to display a PDF, image or plain text file instead of saving it
+/// * `claims` - Authenticated user claims
+/// * `headers` - Request headers
+///
+/// # Returns
+///
+/// File content (200 or 206), 304 if the client copy is current, 416 for an
+/// unsatisfiable range
+///
+/// # Errors
+///
+/// Returns 404 if the document is not found, not owned by the user or has no
+/// stored file, 403 if the file is quarantined by the malware scan
+pub async fn get_document_content(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<HashMap<String, String>>,
+    Extension(claims): Extension<Claims>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let repo = DocumentRepository::new(state.pool.clone());
+    let document = repo.find_by_id(id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Document not found".to_string()))?;
+    ensure_scan_passed(&document.scan_status, document.scan_signature.as_deref(), state.config.clamd_address.is_some())?;
+
+    let file = StoredFile {
+        storage_key: &document.storage_key,
+        filename: &document.filename,
+        mime_type: &document.mime_type,
+        encryption: WrappedDataKey::from_parts(
+            document.encryption_key_id.as_deref(),
+            document.wrapped_data_key.as_deref(),
+        ),
+        etag_seed: format!(
+            "{}-{:x}-{:x}",
+            document.id.simple(),
+            document.uploaded_at.timestamp_micros(),
+            document.current_version
+        ),
+    };
+
+    serve_file(&state, file, &params, &headers).await
+}
+
+/// Send a stored file, honouring range and conditional request headers
+///
+/// Encrypted files are decrypted as a whole and ranges cut from the result;
+/// others are streamed from the backend. Only allowlisted types are shown
+/// inline, and every response is sandboxed so a file never runs script on
+/// the API origin.
+async fn serve_file(
+    state: &AppState,
+    file: StoredFile<'_>,
+    params: &HashMap<String, String>,
+    headers: &HeaderMap,
+) -> AppResult<Response> {
+    let decrypted = match &file.encryption {
+        Some(encryption) => Some(
+            StorageService::new(state.pool.clone(), state.storage.clone(), &state.config)
+                .read_file(file.storage_key, Some(encryption))
+                .await?,
+        ),
+        None => None,
+    };
+    let length = match &decrypted {
+        Some(content) => content.len() as u64,
+        None => state
+            .storage
+            .head(file.storage_key)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Document content not found".to_string()))?
+            .size,
+    };
+    let etag = format!("\"{}-{:x}\"", file.etag_seed, length);
+    let header_value = |name: header::HeaderName| headers.get(name).and_then(|v| v.to_str().ok());
+
+    let cache_headers = [
+        (header::ETAG, etag.clone()),
+        (header::CACHE_CONTROL, "private, no-cache".to_string()),
+    ];
+    if header_value(header::IF_NONE_MATCH).is_some_and(|value| etag_matches(value, &etag)) {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+
+    let range = match header_value(header::RANGE) {
+        Some(range) if header_value(header::IF_RANGE).is_none_or(|value| etag_matches(value, &etag)) => {
+            parse_range(range, length)
+        }
+        _ => RangeRequest::Full,
+    };
+
+    let inline = params.get("disposition").is_some_and(|d| d == "inline") && inline_allowed(file.mime_type);
+    let content_headers = [
+        (header::CONTENT_TYPE, file.mime_type.to_string()),
+        (header::CONTENT_DISPOSITION, content_disposition(file.filename, inline)),
+        (header::ACCEPT_RANGES, "bytes".to_string()),
+        (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        (header::CONTENT_SECURITY_POLICY, "sandbox".to_string()),
+    ];
+
+    let (status, range) = match range {
+        RangeRequest::Unsatisfiable => {
+            return Ok((
+                StatusCode::RANGE_NOT_SATISFIABLE,
+                [(header::CONTENT_RANGE, format!("bytes */{}", length))],
+                cache_headers,
+            )
+                .into_response());
+        }
+        RangeRequest::Partial(range) => (StatusCode::PARTIAL_CONTENT, Some(range)),
+        RangeRequest::Full => (StatusCode::OK, None),
+    };
+
+    let body = match (range.or((length > 0).then(|| ByteRange { start: 0, end: length - 1 })), decrypted) {
+        (Some(bytes), Some(content)) => Body::from(content[bytes.start as usize..=bytes.end as usize].to_vec()),
+        (Some(bytes), None) => Body::from_stream(state.storage.stream(file.storage_key, bytes).await?),
+        (None, _) => Body::empty(),
+    };
+
+    let mut response = (status, content_headers, cache_headers, body).into_response();
+    let response_headers = response.headers_mut();
+    let content_length = range.map_or(length, |r| r.length());
+    response_headers.insert(header::CONTENT_LENGTH, content_length.into());
+    if let Some(range) = range {
+        let content_range = format!("bytes {}-{}/{}", range.start, range.end, length);
+        if let Ok(value) = content_range.parse() {
+            response_headers.insert(header::CONTENT_RANGE, value);
+        }
+    }
+
+    Ok(response)
+}
+
+/// Get a download link for the stored file of a document
+///
+/// Object storage backends hand out a presigned URL so large files bypass the
+/// API; the local backend and encrypted files, which only the API can
+/// decrypt, link to the authenticated content endpoint.
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Document UUID
+/// * `params` - `disposition=inline` to display a PDF, image or plain text file instead of saving it
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Download link and its expiry
+///
+/// # Errors
+///
+/// Returns 404 if the document is not found or not owned by the user, 403 if
+/// the file is quarantined by the malware scan
+pub async fn get_document_download_url(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<HashMap<String, String>>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<DocumentDownloadUrl>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let repo = DocumentRepository::new(state.pool.clone());
+    let document = repo.find_by_id(id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Document not found".to_string()))?;
+    ensure_scan_passed(&document.scan_status, document.scan_signature.as_deref(), state.config.clamd_address.is_some())?;
+
+    let inline = params.get("disposition").is_some_and(|d| d == "inline") && inline_allowed(&document.mime_type);
+    let expires_in = std::time::Duration::from_secs(state.config.storage.presign_ttl_secs);
+    let presigned = match document.encryption_key_id {
+        Some(_) => None,
+        None => state
+            .storage
+            .presign(&document.storage_key, expires_in, &content_disposition(&document.filename, inline))
+            .await?,
+    };
+
+    let link = match presigned {
+        Some(url) => DocumentDownloadUrl {
+            url,
+            presigned: true,
+            expires_at: Some(Utc::now() + expires_in),
+        },
+        None => DocumentDownloadUrl {
+            url: format!(
+                "/api/documents/{}/content{}",
+                document.id,
+                if inline { "?disposition=inline" } else { "" }
+            ),
+            presigned: false,
+            expires_at: None,
+        },
+    };
+
+    Ok(Json(link))
+}
+
+/// Create document record for a file the user uploaded before
 ///
 /// # Arguments
 ///
@@ -142,7 +712,8 @@ pub async fn get_document(
 ///
 /// # Errors
 ///
-/// Returns validation or database error
+/// Returns validation error for an invalid MIME type or a storage key that is
+/// not one of the user's files, database error if the insert fails
 pub async fn create_document(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
@@ -151,12 +722,57 @@ pub async fn create_document(
     // Validate input
     dto.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
+    validate_storage_key(&dto.storage_key)?;
+    validate_mime_type(&dto.mime_type)?;
 
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
 
-    let repo = DocumentRepository::new(state.pool.clone());
-    let document = repo.create(user_id, &dto).await?;
+    let metadata = MetadataService::new(state.pool.clone());
+    let changes = metadata
+        .prepare(
+            user_id,
+            MetadataEntityType::Document,
+            dto.tag_ids.as_deref(),
+            dto.custom_fields.as_ref(),
+            true,
+        )
+        .await?;
+
+    // The new document shares the stored file, its scan result and data key;
+    // a content-addressed file counts as a reference to its blob
+    let file = StorageService::new(state.pool.clone(), state.storage.clone(), &state.config)
+        .uploaded_file(user_id, &dto.storage_key)
+        .await?;
+    let mut dto = dto;
+    dto.content_sha256 = file.content_sha256;
+    dto.scan_status = Some(file.scan_status);
+    dto.scan_signature = file.scan_signature;
+    dto.scanned_at = file.scanned_at;
+    dto.encryption_key_id = file.encryption_key_id;
+    dto.wrapped_data_key = file.wrapped_data_key;
+    dto.pii_categories = match &dto.extracted_text {
+        Some(text) => pii::scan_category_names(text.clone()).await?,
+        None => Vec::new(),
+    };
+
+    // The document and its tags and custom fields are written together
+    let mut tx = state.pool.begin().await?;
+    let mut document = DocumentRepository::create_in(&mut tx, user_id, &dto).await?;
+    MetadataService::apply_in(&mut tx, MetadataEntityType::Document, document.id, &changes).await?;
+    tx.commit().await?;
+
+    metadata.attach_to_documents(std::slice::from_mut(&mut document)).await?;
+
+    ActivityService::new(state.pool.clone())
+        .record(NewActivityEvent::created(
+            user_id,
+            ActivityEntityType::Document,
+            document.id,
+            &document.filename,
+            &document,
+        ))
+        .await;
 
     Ok((StatusCode::CREATED, Json(document)))
 }
@@ -176,7 +792,8 @@ pub async fn create_document(
 ///
 /// # Errors
 ///
-/// Returns 404 if not found or validation error
+/// Returns 404 if not found, validation error, 403 when adding text or
+/// analysis to a file quarantined by the malware scan
 pub async fn update_document(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
@@ -190,10 +807,104 @@ pub async fn update_document(
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
 
+    let metadata = MetadataService::new(state.pool.clone());
+    let changes = metadata
+        .prepare(
+            user_id,
+            MetadataEntityType::Document,
+            dto.tag_ids.as_deref(),
+            dto.custom_fields.as_ref(),
+            false,
+        )
+        .await?;
+
     let repo = DocumentRepository::new(state.pool.clone());
-    let document = repo.update(id, user_id, &dto)
+    let mut before = repo.find_by_id(id, user_id)
         .await?
         .ok_or_else(|| AppError::NotFound("Document not found".to_string()))?;
+    if dto.extracted_text.is_some() || dto.ai_analysis.is_some() {
+        ensure_scan_passed(&before.scan_status, before.scan_signature.as_deref(), state.config.clamd_address.is_some())?;
+    }
+    metadata.attach_to_documents(std::slice::from_mut(&mut before)).await?;
+
+    let mut dto = dto;
+    dto.pii_categories = match &dto.extracted_text {
+        Some(text) => Some(pii::scan_category_names(text.clone()).await?),
+        None => None,
+    };
+    let mut tx = state.pool.begin().await?;
+    let mut document = DocumentRepository::update_in(&mut tx, id, user_id, &dto)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Document not found".to_string()))?;
+    MetadataService::apply_in(&mut tx, MetadataEntityType::Document, document.id, &changes).await?;
+    tx.commit().await?;
+
+    metadata.attach_to_documents(std::slice::from_mut(&mut document)).await?;
+
+    let mut event = NewActivityEvent::updated(
+        user_id,
+        ActivityEntityType::Document,
+        document.id,
+        &document.filename,
+        &before,
+        &document,
+    );
+    if dto.ai_analysis.is_some() {
+        event = event.with_verb(ActivityVerb::Analyzed);
+    }
+    ActivityService::new(state.pool.clone()).record(event).await;
+
+    Ok(Json(document))
+}
+
+/// Scan the current file of a document for malware again
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Document UUID
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Document with the new scan result
+///
+/// # Errors
+///
+/// Returns 404 if not found or not authorized, validation error if scanning
+/// is disabled or the scanner is unavailable
+pub async fn scan_document(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<Document>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let repo = DocumentRepository::new(state.pool.clone());
+    let mut before = repo.find_by_id(id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Document not found".to_string()))?;
+
+    let mut document = StorageService::new(state.pool.clone(), state.storage.clone(), &state.config)
+        .rescan_document(id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Document not found".to_string()))?;
+
+    let metadata = MetadataService::new(state.pool.clone());
+    metadata.attach_to_documents(std::slice::from_mut(&mut before)).await?;
+    metadata.attach_to_documents(std::slice::from_mut(&mut document)).await?;
+
+    ActivityService::new(state.pool.clone())
+        .record(NewActivityEvent::updated(
+            user_id,
+            ActivityEntityType::Document,
+            document.id,
+            &document.filename,
+            &before,
+            &document,
+        ))
+        .await;
 
     Ok(Json(document))
 }
@@ -221,12 +932,20 @@ pub async fn delete_document(
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
 
-    let repo = DocumentRepository::new(state.pool.clone());
-    let deleted = repo.delete(id, user_id).await?;
+    let document = StorageService::new(state.pool.clone(), state.storage.clone(), &state.config)
+        .delete_document(id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Document not found".to_string()))?;
 
-    if deleted {
-        Ok(StatusCode::NO_CONTENT)
-    } else {
-        Err(AppError::NotFound("Document not found".to_string()))
-    }
+    ActivityService::new(state.pool.clone())
+        .record(NewActivityEvent::deleted(
+            user_id,
+            ActivityEntityType::Document,
+            document.id,
+            &document.filename,
+            &document,
+        ))
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
 }
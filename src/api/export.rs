@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use axum::{
+    body::Body,
+    extract::{Extension, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    db::repository::{ComplianceRepository, RiskScoreRepository},
+    error::{AppError, AppResult},
+    models::{Claims, ExportFormat, ExportRecord, ExportRowStream, MetadataFilter},
+    utils::export::{encode, export_filename},
+    AppState,
+};
+
+/// Export the compliance register
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `params` - `format=csv|xlsx|json` plus the list filters (`tags=a,b` and `cf.<field>=<value>`)
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Attachment with one row per compliance item, including tags, assignee,
+/// latest risk score and evidence filenames
+///
+/// # Errors
+///
+/// Returns validation error for an unknown format
+pub async fn export_compliance(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Response> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let format = ExportFormat::from_query(&params)?;
+    let filter = MetadataFilter::from_query(&params);
+
+    let repo = ComplianceRepository::new(state.pool.clone());
+    Ok(attachment(repo.stream_export(user_id, filter), format))
+}
+
+/// Export all risk scores
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `params` - `format=csv|xlsx|json`
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Attachment with one row per risk score, including the compliance item,
+/// control count and evidence filename
+///
+/// # Errors
+///
+/// Returns validation error for an unknown format
+pub async fn export_risk_scores(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Response> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let format = ExportFormat::from_query(&params)?;
+
+    let repo = RiskScoreRepository::new(state.pool.clone(), state.config.risk_matrix.clone());
+    Ok(attachment(repo.stream_export(user_id), format))
+}
+
+/// Stream encoded rows as a file download
+fn attachment<T: ExportRecord>(rows: ExportRowStream<T>, format: ExportFormat) -> Response {
+    let filename = export_filename(T::NAME, format, Utc::now().date_naive());
+
+    (
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        Body::from_stream(encode(rows, format)),
+    )
+        .into_response()
+}
@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Extension, Multipart, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{ActivityEntityType, Claims, ImportPreview, ImportReport, ImportTarget, NewActivityEvent},
+    services::{ActivityService, ImportService, MetadataService, NotificationService},
+    utils::import::{parse_table, ImportTable},
+    AppState,
+};
+
+/// Multipart import request
+struct ImportUpload {
+    /// Parsed file
+    table: ImportTable,
+
+    /// Field → column mapping (optional)
+    mapping: Option<HashMap<String, String>>,
+
+    /// Only validate
+    dry_run: bool,
+}
+
+/// Preview a compliance item import
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+/// * `multipart` - Form with a CSV or XLSX `file`
+///
+/// # Returns
+///
+/// Headers, sample rows, mappable fields and a suggested mapping
+///
+/// # Errors
+///
+/// Returns validation error for a missing or unreadable file
+pub async fn preview_compliance(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    multipart: Multipart,
+) -> AppResult<Json<ImportPreview>> {
+    preview(state, claims, multipart, ImportTarget::ComplianceItem).await
+}
+
+/// Preview a risk score import
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+/// * `multipart` - Form with a CSV or XLSX `file`
+///
+/// # Returns
+///
+/// Headers, sample rows, mappable fields and a suggested mapping
+///
+/// # Errors
+///
+/// Returns validation error for a missing or unreadable file
+pub async fn preview_risk_scores(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    multipart: Multipart,
+) -> AppResult<Json<ImportPreview>> {
+    preview(state, claims, multipart, ImportTarget::RiskScore).await
+}
+
+/// Import compliance items
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+/// * `multipart` - Form with a CSV or XLSX `file`, an optional JSON
+///   `mapping` (field → column) and an optional `dry_run` flag
+///
+/// # Returns
+///
+/// 201 with the report when the rows were created, 200 for a clean dry run,
+/// 422 with per-row errors (nothing is created)
+///
+/// # Errors
+///
+/// Returns validation error for an unreadable file or unusable mapping
+pub async fn import_compliance(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    multipart: Multipart,
+) -> AppResult<(StatusCode, Json<ImportReport>)> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let upload = read_upload(multipart).await?;
+    let service = ImportService::new(state.pool.clone(), state.config.risk_matrix.clone());
+    let (report, mut items) = service
+        .import_compliance(user_id, &upload.table, upload.mapping, upload.dry_run)
+        .await?;
+
+    MetadataService::new(state.pool.clone())
+        .attach_to_compliance_items(&mut items)
+        .await?;
+
+    // The rows are committed: record, queue and notify as the create endpoint does
+    let activity = ActivityService::new(state.pool.clone());
+    let notifications = NotificationService::new(state.pool.clone(), state.mailer.clone());
+    for item in &items {
+        activity
+            .record(NewActivityEvent::created(
+                user_id,
+                ActivityEntityType::ComplianceItem,
+                item.id,
+                &item.title,
+                item,
+            ))
+            .await;
+        notifications.compliance_created(user_id, item).await;
+    }
+
+    Ok((status(&report), Json(report)))
+}
+
+/// Import risk scores
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+/// * `multipart` - Form with a CSV or XLSX `file`, an optional JSON
+///   `mapping` (field → column) and an optional `dry_run` flag
+///
+/// # Returns
+///
+/// 201 with the report when the rows were created, 200 for a clean dry run,
+/// 422 with per-row errors (nothing is created)
+///
+/// # Errors
+///
+/// Returns validation error for an unreadable file or unusable mapping
+pub async fn import_risk_scores(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    multipart: Multipart,
+) -> AppResult<(StatusCode, Json<ImportReport>)> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let upload = read_upload(multipart).await?;
+    let service = ImportService::new(state.pool.clone(), state.config.risk_matrix.clone());
+    let (report, scores) = service
+        .import_risk_scores(user_id, &upload.table, upload.mapping, upload.dry_run)
+        .await?;
+
+    // The rows are committed: record, queue and notify as the create endpoint does
+    let activity = ActivityService::new(state.pool.clone());
+    let notifications = NotificationService::new(state.pool.clone(), state.mailer.clone());
+    for score in &scores {
+        activity
+            .record(NewActivityEvent::created(
+                user_id,
+                ActivityEntityType::RiskScore,
+                score.id,
+                &score.risk_category,
+                score,
+            ))
+            .await;
+        notifications.risk_scored(score).await;
+    }
+
+    Ok((status(&report), Json(report)))
+}
+
+/// Parse the uploaded file and describe it
+async fn preview(
+    state: AppState,
+    claims: Claims,
+    multipart: Multipart,
+    target: ImportTarget,
+) -> AppResult<Json<ImportPreview>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let upload = read_upload(multipart).await?;
+    let service = ImportService::new(state.pool.clone(), state.config.risk_matrix.clone());
+
+    Ok(Json(service.preview(user_id, target, &upload.table).await?))
+}
+
+/// Read the `file`, `mapping` and `dry_run` form fields
+async fn read_upload(mut multipart: Multipart) -> AppResult<ImportUpload> {
+    let mut table = None;
+    let mut mapping = None;
+    let mut dry_run = false;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::Validation(format!("Invalid multipart body: {}", e)))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        let bytes = field
+            .bytes()
+            .await
+            .map_err(|e| AppError::Validation(format!("Invalid multipart body: {}", e)))?;
+
+        match name.as_str() {
+            "file" => table = Some(parse_table(&bytes)?),
+            "mapping" => {
+                mapping = Some(
+                    serde_json::from_slice(&bytes)
+                        .map_err(|e| AppError::Validation(format!("Invalid mapping: {}", e)))?,
+                )
+            }
+            "dry_run" => {
+                dry_run = matches!(String::from_utf8_lossy(&bytes).trim(), "true" | "1" | "yes")
+            }
+            _ => {}
+        }
+    }
+
+    let table = table.ok_or_else(|| AppError::Validation("A file is required".to_string()))?;
+
+    Ok(ImportUpload { table, mapping, dry_run })
+}
+
+/// Response status for an import report
+fn status(report: &ImportReport) -> StatusCode {
+    if !report.errors.is_empty() {
+        StatusCode::UNPROCESSABLE_ENTITY
+    } else if report.committed {
+        StatusCode::CREATED
+    } else {
+        StatusCode::OK
+    }
+}
@@ -1,17 +1,31 @@
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
     routing::{delete, get, post, put},
     Router,
 };
 
+mod activity;
 mod auth;
+mod calendar;
+mod comments;
 mod compliance;
+mod custom_fields;
 mod dashboard;
 mod documents;
+mod export;
+mod import;
+mod notifications;
+mod realtime;
+mod reports;
+mod risk_controls;
 mod risk_scores;
+mod tags;
+mod webhooks;
 mod ai;
 
 use crate::{middleware::auth_middleware, AppState};
+use comments::{ComplianceComments, DocumentComments, RiskScoreComments};
 
 /// Create API router with all endpoints
 ///
@@ -26,7 +40,8 @@ pub fn create_router(state: AppState) -> Router {
     // Public routes (no auth required)
     let public_routes = Router::new()
         .route("/auth/register", post(auth::register))
-        .route("/auth/login", post(auth::login));
+        .route("/auth/login", post(auth::login))
+        .route("/calendar/:token", get(calendar::ics_feed));
 
     // Protected routes (auth required)
     let protected_routes = Router::new()
@@ -35,26 +50,118 @@ pub fn create_router(state: AppState) -> Router {
         // Compliance
         .route("/compliance", get(compliance::list_compliance))
         .route("/compliance", post(compliance::create_compliance))
+        .route("/compliance/export", get(export::export_compliance))
+        .route(
+            "/compliance/import/preview",
+            post(import::preview_compliance).layer(DefaultBodyLimit::max(state.config.max_file_size)),
+        )
+        .route(
+            "/compliance/import",
+            post(import::import_compliance).layer(DefaultBodyLimit::max(state.config.max_file_size)),
+        )
+        .route("/compliance/upcoming", get(calendar::get_upcoming))
+        .route("/compliance/calendar-feed", get(calendar::get_feed))
+        .route("/compliance/calendar-feed", delete(calendar::revoke_feed))
+        .route("/compliance/calendar-feed/rotate", post(calendar::rotate_feed))
         .route("/compliance/:id", get(compliance::get_compliance))
         .route("/compliance/:id", put(compliance::update_compliance))
         .route("/compliance/:id", delete(compliance::delete_compliance))
+        .route("/compliance/:id/comments", get(comments::list_comments::<ComplianceComments>))
+        .route("/compliance/:id/comments", post(comments::create_comment::<ComplianceComments>))
+        .route("/compliance/:id/comments/:comment_id", put(comments::update_comment::<ComplianceComments>))
+        .route("/compliance/:id/comments/:comment_id", delete(comments::delete_comment::<ComplianceComments>))
+        .route("/compliance/:id/comments/:comment_id/history", get(comments::comment_history::<ComplianceComments>))
         // Documents
         .route("/documents", get(documents::list_documents))
         .route("/documents", post(documents::create_document))
         .route("/documents/text", post(documents::create_from_text))
+        .route(
+            "/documents/upload",
+            post(documents::upload_document).layer(DefaultBodyLimit::max(state.config.max_file_size)),
+        )
         .route("/documents/:id", get(documents::get_document))
         .route("/documents/:id", put(documents::update_document))
         .route("/documents/:id", delete(documents::delete_document))
+        .route("/documents/:id/content", get(documents::get_document_content))
+        .route("/documents/:id/download-url", get(documents::get_document_download_url))
+        .route("/documents/:id/scan", post(documents::scan_document))
+        .route("/documents/:id/versions", get(documents::list_document_versions))
+        .route(
+            "/documents/:id/versions",
+            post(documents::upload_document_version).layer(DefaultBodyLimit::max(state.config.max_file_size)),
+        )
+        .route("/documents/:id/versions/:version", get(documents::get_document_version))
+        .route("/documents/:id/diff", get(documents::diff_document_versions))
+        .route("/documents/:id/versions/:version/content", get(documents::get_document_version_content))
+        .route("/documents/:id/comments", get(comments::list_comments::<DocumentComments>))
+        .route("/documents/:id/comments", post(comments::create_comment::<DocumentComments>))
+        .route("/documents/:id/comments/:comment_id", put(comments::update_comment::<DocumentComments>))
+        .route("/documents/:id/comments/:comment_id", delete(comments::delete_comment::<DocumentComments>))
+        .route("/documents/:id/comments/:comment_id/history", get(comments::comment_history::<DocumentComments>))
+        // Tags
+        .route("/tags", get(tags::list_tags))
+        .route("/tags", post(tags::create_tag))
+        .route("/tags/:id", put(tags::update_tag))
+        .route("/tags/:id", delete(tags::delete_tag))
+        // Custom fields
+        .route("/custom-fields", get(custom_fields::list_custom_fields))
+        .route("/custom-fields", post(custom_fields::create_custom_field))
+        .route("/custom-fields/:id", put(custom_fields::update_custom_field))
+        .route("/custom-fields/:id", delete(custom_fields::delete_custom_field))
         // Dashboard
         .route("/dashboard/stats", get(dashboard::get_stats))
         .route("/dashboard/activity", get(dashboard::get_activity))
+        .route("/dashboard/risk-heatmap", get(dashboard::get_risk_heatmap))
+        .route("/dashboard/risk-posture", get(dashboard::get_risk_posture))
+        // Activity feed
+        .route("/activity", get(activity::get_feed))
+        // Notifications
+        .route("/notifications", get(notifications::list_notifications))
+        .route("/notifications/read-all", post(notifications::mark_all_read))
+        .route("/notifications/preferences", get(notifications::get_preferences))
+        .route("/notifications/preferences", put(notifications::update_preferences))
+        .route("/notifications/:id/read", post(notifications::mark_read))
+        // Real-time updates
+        .route("/ws", get(realtime::connect))
+        // Webhooks
+        .route("/webhooks", get(webhooks::list_webhooks))
+        .route("/webhooks", post(webhooks::create_webhook))
+        .route("/webhooks/:id", get(webhooks::get_webhook))
+        .route("/webhooks/:id", put(webhooks::update_webhook))
+        .route("/webhooks/:id", delete(webhooks::delete_webhook))
+        .route("/webhooks/:id/deliveries", get(webhooks::list_deliveries))
+        .route("/webhooks/:id/test", post(webhooks::send_test))
         // Risk Scores
         .route("/risk-scores", get(risk_scores::list_scores))
+        .route("/risk-scores/export", get(export::export_risk_scores))
+        .route(
+            "/risk-scores/import/preview",
+            post(import::preview_risk_scores).layer(DefaultBodyLimit::max(state.config.max_file_size)),
+        )
+        .route(
+            "/risk-scores/import",
+            post(import::import_risk_scores).layer(DefaultBodyLimit::max(state.config.max_file_size)),
+        )
+        .route("/risk-scores/matrix", get(risk_scores::get_matrix))
+        .route("/risk-scores/control-effectiveness", get(risk_controls::control_effectiveness))
+        .route("/risk-scores/trend", get(risk_scores::get_trend))
         .route("/risk-scores/compliance/:id", get(risk_scores::list_by_compliance))
+        .route("/risk-scores/compliance/:id/trend", get(risk_scores::get_compliance_trend))
         .route("/risk-scores", post(risk_scores::create_score))
         .route("/risk-scores/:id", get(risk_scores::get_score))
         .route("/risk-scores/:id", put(risk_scores::update_score))
         .route("/risk-scores/:id", delete(risk_scores::delete_score))
+        .route("/risk-scores/:id/controls", get(risk_controls::list_controls))
+        .route("/risk-scores/:id/controls", post(risk_controls::create_control))
+        .route("/risk-scores/:id/controls/:control_id", put(risk_controls::update_control))
+        .route("/risk-scores/:id/controls/:control_id", delete(risk_controls::delete_control))
+        .route("/risk-scores/:id/comments", get(comments::list_comments::<RiskScoreComments>))
+        .route("/risk-scores/:id/comments", post(comments::create_comment::<RiskScoreComments>))
+        .route("/risk-scores/:id/comments/:comment_id", put(comments::update_comment::<RiskScoreComments>))
+        .route("/risk-scores/:id/comments/:comment_id", delete(comments::delete_comment::<RiskScoreComments>))
+        .route("/risk-scores/:id/comments/:comment_id/history", get(comments::comment_history::<RiskScoreComments>))
+        // Reports
+        .route("/reports/audit", post(reports::create_audit_report))
         // AI
         .route("/ai/analyze", post(ai::analyze_document))
         .route("/ai/assess-risk", post(ai::assess_risk))
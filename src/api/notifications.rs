@@ -0,0 +1,160 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{
+        Claims, Notification, NotificationList, NotificationListQuery, NotificationPreferences,
+        UpdateNotificationPreferencesDto,
+    },
+    services::NotificationService,
+    AppState,
+};
+
+/// List in-app notifications
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `query` - `unread_only` and `limit`
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Notifications, newest first, with the unread count
+///
+/// # Errors
+///
+/// Returns database error if query fails
+pub async fn list_notifications(
+    State(state): State<AppState>,
+    Query(query): Query<NotificationListQuery>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<NotificationList>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = NotificationService::new(state.pool.clone(), state.mailer.clone());
+    let list = service.list(user_id, &query).await?;
+
+    Ok(Json(list))
+}
+
+/// Mark a notification as read
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Notification UUID
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Updated notification
+///
+/// # Errors
+///
+/// Returns 404 if not found or not owned by user
+pub async fn mark_read(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<Notification>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = NotificationService::new(state.pool.clone(), state.mailer.clone());
+    let notification = service.mark_read(user_id, id).await?;
+
+    Ok(Json(notification))
+}
+
+/// Mark all notifications as read
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Number of notifications marked
+///
+/// # Errors
+///
+/// Returns database error if update fails
+pub async fn mark_all_read(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<serde_json::Value>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = NotificationService::new(state.pool.clone(), state.mailer.clone());
+    let updated = service.mark_all_read(user_id).await?;
+
+    Ok(Json(serde_json::json!({ "updated": updated })))
+}
+
+/// Get notification preferences
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Current preferences (defaults if never changed)
+///
+/// # Errors
+///
+/// Returns database error if query fails
+pub async fn get_preferences(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<NotificationPreferences>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = NotificationService::new(state.pool.clone(), state.mailer.clone());
+    let preferences = service.preferences(user_id).await?;
+
+    Ok(Json(preferences))
+}
+
+/// Update notification preferences
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+/// * `dto` - Changed preferences
+///
+/// # Returns
+///
+/// Updated preferences
+///
+/// # Errors
+///
+/// Returns validation error for invalid lead times
+pub async fn update_preferences(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(dto): Json<UpdateNotificationPreferencesDto>,
+) -> AppResult<Json<NotificationPreferences>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    dto.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let service = NotificationService::new(state.pool.clone(), state.mailer.clone());
+    let preferences = service.update_preferences(user_id, &dto).await?;
+
+    Ok(Json(preferences))
+}
@@ -0,0 +1,142 @@
+use std::{collections::BTreeSet, time::Duration};
+
+use axum::{
+    extract::{
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Extension, State,
+    },
+    response::Response,
+};
+use chrono::Utc;
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{Claims, RealtimeClientMessage, RealtimeMessage, RealtimeReply, RealtimeTopic},
+    AppState,
+};
+
+/// Close code sent when the session's token expires
+const TOKEN_EXPIRED_CLOSE_CODE: u16 = 4001;
+
+/// Open a real-time WebSocket session
+///
+/// The upgrade request is authenticated by the auth middleware (Bearer
+/// header or `auth_token` cookie). Clients send
+/// `{"action": "subscribe", "topics": [...]}` to choose what they receive;
+/// topics are `compliance_item`, `document`, `risk_score` and `analysis`.
+/// The session is closed when the token expires.
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+/// * `upgrade` - WebSocket upgrade request
+///
+/// # Returns
+///
+/// 101 Switching Protocols
+///
+/// # Errors
+///
+/// Returns 401 if the token is missing or invalid
+pub async fn connect(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    upgrade: WebSocketUpgrade,
+) -> AppResult<Response> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let expires_in = Duration::from_secs((claims.exp as i64 - Utc::now().timestamp()).max(0) as u64);
+    let events = state.realtime.subscribe();
+
+    Ok(upgrade.on_upgrade(move |socket| run_session(socket, user_id, events, expires_in)))
+}
+
+/// Relay the user's events on subscribed topics until the client leaves
+async fn run_session(
+    mut socket: WebSocket,
+    user_id: Uuid,
+    mut events: broadcast::Receiver<RealtimeMessage>,
+    expires_in: Duration,
+) {
+    let mut topics: BTreeSet<RealtimeTopic> = BTreeSet::new();
+    let expiry = tokio::time::sleep(expires_in);
+    tokio::pin!(expiry);
+
+    loop {
+        tokio::select! {
+            incoming = socket.recv() => {
+                let text = match incoming {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                };
+
+                let reply = match serde_json::from_str::<RealtimeClientMessage>(&text) {
+                    Ok(RealtimeClientMessage::Subscribe { topics: added }) => {
+                        topics.extend(added);
+                        subscribed(&topics)
+                    }
+                    Ok(RealtimeClientMessage::Unsubscribe { topics: removed }) => {
+                        for topic in removed {
+                            topics.remove(&topic);
+                        }
+                        subscribed(&topics)
+                    }
+                    Ok(RealtimeClientMessage::Ping) => RealtimeReply::Pong,
+                    Err(e) => RealtimeReply::Error { message: e.to_string() },
+                };
+                if send_json(&mut socket, &reply).await.is_err() {
+                    break;
+                }
+            }
+            event = events.recv() => {
+                let sent = match event {
+                    Ok(message) if message.user_id == user_id && topics.contains(&message.event.topic()) => {
+                        send_json(&mut socket, &message.event).await
+                    }
+                    Ok(_) => Ok(()),
+                    Err(RecvError::Lagged(missed)) => send_json(&mut socket, &RealtimeReply::Lagged { missed }).await,
+                    Err(RecvError::Closed) => break,
+                };
+                if sent.is_err() {
+                    break;
+                }
+            }
+            _ = &mut expiry => {
+                let frame = CloseFrame {
+                    code: TOKEN_EXPIRED_CLOSE_CODE,
+                    reason: "Token expired".into(),
+                };
+                let _ = socket.send(Message::Close(Some(frame))).await;
+                return;
+            }
+        }
+    }
+
+    debug!("Realtime session for user {} closed", user_id);
+    let _ = socket
+        .send(Message::Close(Some(CloseFrame {
+            code: close_code::NORMAL,
+            reason: "".into(),
+        })))
+        .await;
+}
+
+/// Reply listing the current subscriptions
+fn subscribed(topics: &BTreeSet<RealtimeTopic>) -> RealtimeReply {
+    RealtimeReply::Subscribed {
+        topics: topics.iter().copied().collect(),
+    }
+}
+
+/// Send a value as a JSON text message
+async fn send_json<T: Serialize>(socket: &mut WebSocket, value: &T) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(value).map_err(axum::Error::new)?;
+    socket.send(Message::Text(text)).await
+}
@@ -0,0 +1,55 @@
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{ActivityEntityType, Claims, Document, NewActivityEvent},
+    services::{ActivityService, ReportService, StorageService},
+    AppState,
+};
+
+/// Generate a PDF audit report
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Document holding the report (register summary, status breakdown, risk
+/// heatmap, latest assessments with AI reasoning and evidence list)
+///
+/// # Errors
+///
+/// Returns internal error if the PDF cannot be written
+pub async fn create_audit_report(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<(StatusCode, Json<Document>)> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = ReportService::new(
+        state.pool.clone(),
+        state.config.risk_matrix.clone(),
+        StorageService::new(state.pool.clone(), state.storage.clone(), &state.config),
+    );
+    let document = service.generate_audit_report(user_id).await?;
+
+    ActivityService::new(state.pool.clone())
+        .record(NewActivityEvent::created(
+            user_id,
+            ActivityEntityType::Document,
+            document.id,
+            &document.filename,
+            &document,
+        ))
+        .await;
+
+    Ok((StatusCode::CREATED, Json(document)))
+}
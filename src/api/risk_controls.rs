@@ -0,0 +1,171 @@
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{Claims, ControlEffectivenessReport, CreateRiskControlDto, RiskControlResponse, UpdateRiskControlDto},
+    services::RiskControlService,
+    AppState,
+};
+
+/// List the mitigating controls of a risk assessment
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Risk score UUID
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Controls with the risk reduction each delivers
+///
+/// # Errors
+///
+/// Returns 404 if the risk score is not found or not authorized
+pub async fn list_controls(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<Vec<RiskControlResponse>>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = RiskControlService::new(state.pool.clone(), state.config.risk_matrix.clone());
+    let controls = service.list(id, user_id).await?;
+
+    Ok(Json(controls))
+}
+
+/// Link a compliance item as a mitigating control
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Risk score UUID
+/// * `claims` - Authenticated user claims
+/// * `dto` - Control data
+///
+/// # Returns
+///
+/// Created control
+///
+/// # Errors
+///
+/// Returns validation error or 404 if the risk score or compliance item is not found
+pub async fn create_control(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Json(dto): Json<CreateRiskControlDto>,
+) -> AppResult<(StatusCode, Json<RiskControlResponse>)> {
+    // Validate input
+    dto.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = RiskControlService::new(state.pool.clone(), state.config.risk_matrix.clone());
+    let control = service.create(id, user_id, &dto).await?;
+
+    Ok((StatusCode::CREATED, Json(control)))
+}
+
+/// Update a control's effectiveness or notes
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Risk score UUID
+/// * `control_id` - Control UUID
+/// * `claims` - Authenticated user claims
+/// * `dto` - Update data
+///
+/// # Returns
+///
+/// Updated control
+///
+/// # Errors
+///
+/// Returns validation error or 404 if not found
+pub async fn update_control(
+    State(state): State<AppState>,
+    Path((id, control_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Json(dto): Json<UpdateRiskControlDto>,
+) -> AppResult<Json<RiskControlResponse>> {
+    // Validate input
+    dto.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = RiskControlService::new(state.pool.clone(), state.config.risk_matrix.clone());
+    let control = service.update(id, control_id, user_id, &dto).await?;
+
+    Ok(Json(control))
+}
+
+/// Unlink a control from a risk assessment
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Risk score UUID
+/// * `control_id` - Control UUID
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// 204 No Content on success
+///
+/// # Errors
+///
+/// Returns 404 if not found or not authorized
+pub async fn delete_control(
+    State(state): State<AppState>,
+    Path((id, control_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<StatusCode> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = RiskControlService::new(state.pool.clone(), state.config.risk_matrix.clone());
+    service.delete(id, control_id, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Report the risk reduction delivered by each control
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Controls ranked by total risk reduction across all assessments
+///
+/// # Errors
+///
+/// Returns database error if queries fail
+pub async fn control_effectiveness(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<Vec<ControlEffectivenessReport>>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = RiskControlService::new(state.pool.clone(), state.config.risk_matrix.clone());
+    let report = service.effectiveness_report(user_id).await?;
+
+    Ok(Json(report))
+}
@@ -1,18 +1,50 @@
 use axum::{
-    extract::{Extension, Path, State},
+    extract::{Extension, Path, Query, State},
     http::StatusCode,
     Json,
 };
+use serde::Serialize;
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
     db::repository::RiskScoreRepository,
     error::{AppError, AppResult},
-    models::{Claims, CreateRiskScoreDto, RiskScore, UpdateRiskScoreDto},
+    models::{
+        ActivityEntityType, Claims, CreateRiskScoreDto, NewActivityEvent, RiskMatrix, RiskMatrixCell,
+        RiskScore, RiskTrend, RiskTrendQuery, UpdateRiskScoreDto,
+    },
+    services::{ActivityService, NotificationService, RiskTrendService},
     AppState,
 };
 
+/// Risk matrix definition for rendering on the frontend
+#[derive(Debug, Serialize)]
+pub struct RiskMatrixResponse {
+    /// Matrix configuration (scale, thresholds and per-cell levels)
+    #[serde(flatten)]
+    pub matrix: RiskMatrix,
+
+    /// All cells with their computed scores and levels
+    pub cells: Vec<RiskMatrixCell>,
+}
+
+/// Get the risk matrix definition
+///
+/// # Arguments
+///
+/// * `state` - Application state
+///
+/// # Returns
+///
+/// Scale, thresholds and every likelihood × impact cell
+pub async fn get_matrix(State(state): State<AppState>) -> Json<RiskMatrixResponse> {
+    let matrix = state.config.risk_matrix.clone();
+    let cells = matrix.cells();
+
+    Json(RiskMatrixResponse { matrix, cells })
+}
+
 /// List all risk scores for authenticated user
 ///
 /// # Arguments
@@ -34,7 +66,7 @@ pub async fn list_scores(
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
 
-    let repo = RiskScoreRepository::new(state.pool.clone());
+    let repo = RiskScoreRepository::new(state.pool.clone(), state.config.risk_matrix.clone());
     let scores = repo.find_by_user(user_id).await?;
 
     Ok(Json(scores))
@@ -63,12 +95,80 @@ pub async fn list_by_compliance(
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
 
-    let repo = RiskScoreRepository::new(state.pool.clone());
+    let repo = RiskScoreRepository::new(state.pool.clone(), state.config.risk_matrix.clone());
     let scores = repo.find_by_compliance_item(id, user_id).await?;
 
     Ok(Json(scores))
 }
 
+/// Get the risk trend across all compliance items
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `query` - Interval (`day|week|month`), `from`/`to` range and `jump_threshold`
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Bucketed scores overall and per category, with significant jumps
+///
+/// # Errors
+///
+/// Returns validation or database error
+pub async fn get_trend(
+    State(state): State<AppState>,
+    Query(query): Query<RiskTrendQuery>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<RiskTrend>> {
+    // Validate input
+    query.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = RiskTrendService::new(state.pool.clone(), state.config.risk_matrix.clone());
+    let trend = service.trend(user_id, None, &query).await?;
+
+    Ok(Json(trend))
+}
+
+/// Get the risk trend of a compliance item
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Compliance item UUID
+/// * `query` - Interval (`day|week|month`), `from`/`to` range and `jump_threshold`
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Bucketed scores overall and per category, with significant jumps
+///
+/// # Errors
+///
+/// Returns 404 if the compliance item is not found, validation or database error
+pub async fn get_compliance_trend(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<RiskTrendQuery>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<RiskTrend>> {
+    // Validate input
+    query.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = RiskTrendService::new(state.pool.clone(), state.config.risk_matrix.clone());
+    let trend = service.trend(user_id, Some(id), &query).await?;
+
+    Ok(Json(trend))
+}
+
 /// Get single risk score by ID
 ///
 /// # Arguments
@@ -92,7 +192,7 @@ pub async fn get_score(
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
 
-    let repo = RiskScoreRepository::new(state.pool.clone());
+    let repo = RiskScoreRepository::new(state.pool.clone(), state.config.risk_matrix.clone());
     let score = repo.find_by_id(id, user_id)
         .await?
         .ok_or_else(|| AppError::NotFound("Risk score not found".to_string()))?;
@@ -127,9 +227,22 @@ pub async fn create_score(
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
 
-    let repo = RiskScoreRepository::new(state.pool.clone());
+    let repo = RiskScoreRepository::new(state.pool.clone(), state.config.risk_matrix.clone());
     let score = repo.create(user_id, &dto).await?;
 
+    ActivityService::new(state.pool.clone())
+        .record(NewActivityEvent::created(
+            user_id,
+            ActivityEntityType::RiskScore,
+            score.id,
+            &score.risk_category,
+            &score,
+        ))
+        .await;
+    NotificationService::new(state.pool.clone(), state.mailer.clone())
+        .risk_scored(&score)
+        .await;
+
     Ok((StatusCode::CREATED, Json(score)))
 }
 
@@ -162,11 +275,26 @@ pub async fn update_score(
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
 
-    let repo = RiskScoreRepository::new(state.pool.clone());
+    let repo = RiskScoreRepository::new(state.pool.clone(), state.config.risk_matrix.clone());
+    let before = repo.find_by_id(id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Risk score not found".to_string()))?;
+
     let score = repo.update(id, user_id, &dto)
         .await?
         .ok_or_else(|| AppError::NotFound("Risk score not found".to_string()))?;
 
+    ActivityService::new(state.pool.clone())
+        .record(NewActivityEvent::updated(
+            user_id,
+            ActivityEntityType::RiskScore,
+            score.id,
+            &score.risk_category,
+            &before,
+            &score,
+        ))
+        .await;
+
     Ok(Json(score))
 }
 
@@ -193,12 +321,24 @@ pub async fn delete_score(
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
 
-    let repo = RiskScoreRepository::new(state.pool.clone());
-    let deleted = repo.delete(id, user_id).await?;
+    let repo = RiskScoreRepository::new(state.pool.clone(), state.config.risk_matrix.clone());
+    let score = repo.find_by_id(id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Risk score not found".to_string()))?;
 
-    if deleted {
-        Ok(StatusCode::NO_CONTENT)
-    } else {
-        Err(AppError::NotFound("Risk score not found".to_string()))
+    if !repo.delete(id, user_id).await? {
+        return Err(AppError::NotFound("Risk score not found".to_string()));
     }
+
+    ActivityService::new(state.pool.clone())
+        .record(NewActivityEvent::deleted(
+            user_id,
+            ActivityEntityType::RiskScore,
+            score.id,
+            &score.risk_category,
+            &score,
+        ))
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
 }
@@ -0,0 +1,144 @@
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    db::repository::TagRepository,
+    error::{AppError, AppResult},
+    models::{Claims, CreateTagDto, Tag, UpdateTagDto},
+    AppState,
+};
+
+/// List all tags for authenticated user
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// List of tags
+///
+/// # Errors
+///
+/// Returns database error if query fails
+pub async fn list_tags(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<Vec<Tag>>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let repo = TagRepository::new(state.pool.clone());
+    let tags = repo.find_by_user(user_id).await?;
+
+    Ok(Json(tags))
+}
+
+/// Create new tag
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+/// * `dto` - Tag creation data
+///
+/// # Returns
+///
+/// Created tag
+///
+/// # Errors
+///
+/// Returns validation error if invalid or the name is taken
+pub async fn create_tag(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(dto): Json<CreateTagDto>,
+) -> AppResult<(StatusCode, Json<Tag>)> {
+    // Validate input
+    dto.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let repo = TagRepository::new(state.pool.clone());
+    let tag = repo.create(user_id, &dto).await?;
+
+    Ok((StatusCode::CREATED, Json(tag)))
+}
+
+/// Update tag
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Tag UUID
+/// * `claims` - Authenticated user claims
+/// * `dto` - Update data
+///
+/// # Returns
+///
+/// Updated tag
+///
+/// # Errors
+///
+/// Returns 404 if not found or validation error
+pub async fn update_tag(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Json(dto): Json<UpdateTagDto>,
+) -> AppResult<Json<Tag>> {
+    // Validate input
+    dto.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let repo = TagRepository::new(state.pool.clone());
+    let tag = repo.update(id, user_id, &dto)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Tag not found".to_string()))?;
+
+    Ok(Json(tag))
+}
+
+/// Delete tag
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Tag UUID
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// 204 No Content on success
+///
+/// # Errors
+///
+/// Returns 404 if not found or not authorized
+pub async fn delete_tag(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<StatusCode> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let repo = TagRepository::new(state.pool.clone());
+    let deleted = repo.delete(id, user_id).await?;
+
+    if deleted {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(AppError::NotFound("Tag not found".to_string()))
+    }
+}
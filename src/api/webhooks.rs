@@ -0,0 +1,228 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{
+        Claims, CreateWebhookDto, UpdateWebhookDto, WebhookDelivery, WebhookDeliveryQuery, WebhookSubscription,
+        WebhookWithSecret,
+    },
+    services::WebhookService,
+    AppState,
+};
+
+/// List webhook subscriptions
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Subscriptions, newest first (without secrets)
+///
+/// # Errors
+///
+/// Returns database error if query fails
+pub async fn list_webhooks(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<Vec<WebhookSubscription>>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = WebhookService::new(state.pool.clone(), &state.config);
+    let webhooks = service.list(user_id).await?;
+
+    Ok(Json(webhooks))
+}
+
+/// Get a webhook subscription
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Subscription UUID
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Subscription (without secret)
+///
+/// # Errors
+///
+/// Returns 404 if not found or not owned by user
+pub async fn get_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<WebhookSubscription>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = WebhookService::new(state.pool.clone(), &state.config);
+    let webhook = service.get(user_id, id).await?;
+
+    Ok(Json(webhook))
+}
+
+/// Create a webhook subscription
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `claims` - Authenticated user claims
+/// * `dto` - Subscription data
+///
+/// # Returns
+///
+/// Created subscription with its signing secret (shown only once)
+///
+/// # Errors
+///
+/// Returns validation error for an invalid URL or event type list
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(dto): Json<CreateWebhookDto>,
+) -> AppResult<(StatusCode, Json<WebhookWithSecret>)> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    dto.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let service = WebhookService::new(state.pool.clone(), &state.config);
+    let webhook = service.create(user_id, &dto).await?;
+
+    Ok((StatusCode::CREATED, Json(webhook)))
+}
+
+/// Update a webhook subscription
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Subscription UUID
+/// * `claims` - Authenticated user claims
+/// * `dto` - Update data
+///
+/// # Returns
+///
+/// Updated subscription, with the new secret if it was rotated
+///
+/// # Errors
+///
+/// Returns 404 if not found or not owned by user
+pub async fn update_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Json(dto): Json<UpdateWebhookDto>,
+) -> AppResult<Json<serde_json::Value>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    dto.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let service = WebhookService::new(state.pool.clone(), &state.config);
+    let webhook = service.update(user_id, id, &dto).await?;
+
+    Ok(Json(webhook))
+}
+
+/// Delete a webhook subscription and its delivery log
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Subscription UUID
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// 204 No Content
+///
+/// # Errors
+///
+/// Returns 404 if not found or not owned by user
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<StatusCode> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = WebhookService::new(state.pool.clone(), &state.config);
+    service.delete(user_id, id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Get the delivery log of a webhook subscription
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Subscription UUID
+/// * `query` - `status` and `limit`
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Deliveries with attempts and response codes, newest first
+///
+/// # Errors
+///
+/// Returns 404 if not found or not owned by user
+pub async fn list_deliveries(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<WebhookDeliveryQuery>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<Vec<WebhookDelivery>>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = WebhookService::new(state.pool.clone(), &state.config);
+    let deliveries = service.deliveries(user_id, id, &query).await?;
+
+    Ok(Json(deliveries))
+}
+
+/// Send a test event to a webhook subscription
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `id` - Subscription UUID
+/// * `claims` - Authenticated user claims
+///
+/// # Returns
+///
+/// Delivery with the outcome of the first attempt
+///
+/// # Errors
+///
+/// Returns 404 if not found or not owned by user
+pub async fn send_test(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<WebhookDelivery>> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Internal("Invalid user ID in token".to_string()))?;
+
+    let service = WebhookService::new(state.pool.clone(), &state.config);
+    let delivery = service.send_test(user_id, id).await?;
+
+    Ok(Json(delivery))
+}
@@ -0,0 +1,44 @@
+//! Re-wrap the data keys of stored documents with the active master key
+//!
+//! Put the new master key first in `ENCRYPTION_MASTER_KEY` (or the key file),
+//! keep the old keys after it, run this tool, and remove the old keys once it
+//! reports no unreadable keys. `--dry-run` only counts the affected keys.
+
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+use parseguard_backend::{
+    config::Config,
+    db,
+    error::AppResult,
+    services::{storage_from_config, StorageService},
+};
+
+#[tokio::main]
+async fn main() -> AppResult<()> {
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "parseguard_backend=info".into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    let dry_run = std::env::args().skip(1).any(|arg| arg == "--dry-run");
+
+    let config = Config::from_env();
+    let pool = db::create_pool(&config.database_url).await?;
+    db::run_migrations(&pool).await?;
+
+    let storage = storage_from_config(&config)?;
+    let report = StorageService::new(pool, storage, &config).rotate_data_keys(dry_run).await?;
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&report).expect("rotation report serializes to JSON")
+    );
+    if report.unreadable_keys > 0 {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
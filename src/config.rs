@@ -1,3 +1,8 @@
+use crate::{
+    models::RiskMatrix,
+    utils::{envelope::MasterKeyRing, pii::PiiMode},
+};
+
 /// Application configuration
 #[derive(Clone)]
 pub struct Config {
@@ -13,14 +18,358 @@ pub struct Config {
     /// JWT expiration time in seconds (default: 24 hours)
     pub jwt_expiration: i64,
     
-    /// Upload directory for documents
+    /// Upload directory for documents (root of the local storage backend)
     pub upload_dir: String,
     
+    /// Document storage backend settings
+    pub storage: StorageConfig,
+    
     /// Maximum file upload size in bytes (default: 50MB)
     pub max_file_size: usize,
     
     /// OLLAMA API URL for AI processing
     pub ollama_url: String,
+    
+    /// Likelihood × impact matrix used to score risk assessments
+    pub risk_matrix: RiskMatrix,
+    
+    /// Days after which a compliance item's latest risk assessment is stale (default: 90)
+    pub risk_stale_after_days: i64,
+    
+    /// Outgoing email settings
+    pub email: EmailConfig,
+    
+    /// Seconds between due-date reminder runs (default: 15 minutes)
+    pub reminder_interval_secs: u64,
+    
+    /// Attempts per webhook delivery before giving up (default: 8)
+    pub webhook_max_attempts: i32,
+    
+    /// Delay before the first webhook retry in seconds, doubled per attempt (default: 30)
+    pub webhook_retry_base_secs: i64,
+    
+    /// Timeout of a webhook request in seconds (default: 10)
+    pub webhook_timeout_secs: u64,
+    
+    /// Seconds between webhook queue polls (default: 5)
+    pub webhook_poll_interval_secs: u64,
+    
+    /// Webhook hosts allowed to resolve to private or local addresses (default: none)
+    pub webhook_allowed_hosts: Vec<String>,
+    
+    /// Seconds between orphaned upload sweeps, 0 disables the sweeper (default: 24 hours)
+    pub orphan_sweep_interval_secs: u64,
+    
+    /// Minimum age in seconds before an unreferenced upload counts as orphaned (default: 1 hour)
+    pub orphan_grace_secs: u64,
+    
+    /// clamd daemon that scans uploads for malware, None disables scanning (default: none)
+    pub clamd_address: Option<ClamdAddress>,
+    
+    /// Timeout of a malware scan in seconds (default: 60)
+    pub clamd_timeout_secs: u64,
+    
+    /// Master keys for encrypting stored files, None stores files unencrypted
+    pub encryption_keys: Option<MasterKeyRing>,
+    
+    /// Masking of personal data in text sent to OLLAMA (default: pseudonymize)
+    pub pii_mode: PiiMode,
+    
+    /// OCR of scanned PDF pages and image uploads
+    pub ocr: OcrConfig,
+}
+
+/// Transport used to deliver notification emails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmailTransportKind {
+    /// Deliver through an SMTP relay
+    Smtp,
+    
+    /// Write `.eml` files to a local directory (development and tests)
+    File,
+    
+    /// Do not send emails
+    Disabled,
+}
+
+/// Transport security of the SMTP connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// Upgrade a plain connection with STARTTLS
+    StartTls,
+    
+    /// Implicit TLS (e.g., port 465)
+    Tls,
+    
+    /// Unencrypted connection (local relays only)
+    None,
+}
+
+/// Outgoing email configuration
+#[derive(Debug, Clone)]
+pub struct EmailConfig {
+    /// Transport to use (default: disabled)
+    pub transport: EmailTransportKind,
+    
+    /// Sender mailbox
+    pub from: String,
+    
+    /// SMTP relay host
+    pub smtp_host: String,
+    
+    /// SMTP relay port
+    pub smtp_port: u16,
+    
+    /// SMTP username (optional)
+    pub smtp_username: Option<String>,
+    
+    /// SMTP password (optional)
+    pub smtp_password: Option<String>,
+    
+    /// SMTP transport security
+    pub smtp_security: SmtpSecurity,
+    
+    /// Output directory of the file transport
+    pub file_dir: String,
+}
+
+impl EmailConfig {
+    /// Load email configuration from environment variables
+    ///
+    /// # Returns
+    ///
+    /// Email configuration
+    ///
+    /// # Panics
+    ///
+    /// Panics if a variable has an invalid value
+    pub fn from_env() -> Self {
+        let transport = match std::env::var("EMAIL_TRANSPORT")
+            .unwrap_or_else(|_| "none".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "smtp" => EmailTransportKind::Smtp,
+            "file" => EmailTransportKind::File,
+            "none" => EmailTransportKind::Disabled,
+            other => panic!("EMAIL_TRANSPORT must be smtp, file or none (got '{}')", other),
+        };
+        let smtp_security = match std::env::var("SMTP_SECURITY")
+            .unwrap_or_else(|_| "starttls".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "starttls" => SmtpSecurity::StartTls,
+            "tls" => SmtpSecurity::Tls,
+            "none" => SmtpSecurity::None,
+            other => panic!("SMTP_SECURITY must be starttls, tls or none (got '{}')", other),
+        };
+
+        Self {
+            transport,
+            from: std::env::var("EMAIL_FROM")
+                .unwrap_or_else(|_| "ParseGuard <noreply@parseguard.local>".to_string()),
+            smtp_host: std::env::var("SMTP_HOST")
+                .unwrap_or_else(|_| "localhost".to_string()),
+            smtp_port: std::env::var("SMTP_PORT")
+                .unwrap_or_else(|_| "587".to_string())
+                .parse()
+                .expect("SMTP_PORT must be a valid number"),
+            smtp_username: std::env::var("SMTP_USERNAME").ok(),
+            smtp_password: std::env::var("SMTP_PASSWORD").ok(),
+            smtp_security,
+            file_dir: std::env::var("EMAIL_FILE_DIR")
+                .unwrap_or_else(|_| "./outbox".to_string()),
+        }
+    }
+}
+
+/// Address of the clamd daemon
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClamdAddress {
+    /// TCP socket (`host:port`)
+    Tcp(String),
+    
+    /// Local Unix socket
+    Unix(std::path::PathBuf),
+}
+
+impl ClamdAddress {
+    /// Parse a clamd address
+    ///
+    /// Accepts `tcp://host:port`, `host:port`, `unix:///path/to/clamd.sock`
+    /// and absolute socket paths.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Address string
+    ///
+    /// # Returns
+    ///
+    /// Parsed address, None if the value is empty
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if let Some(path) = value.strip_prefix("unix://") {
+            return Some(Self::Unix(path.into()));
+        }
+        if value.starts_with('/') {
+            return Some(Self::Unix(value.into()));
+        }
+
+        let address = value.strip_prefix("tcp://").unwrap_or(value);
+        (!address.is_empty()).then(|| Self::Tcp(address.to_string()))
+    }
+}
+
+/// Backend that stores document files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageBackendKind {
+    /// Files below `upload_dir` on the local disk
+    Local,
+    
+    /// Objects in an S3-compatible bucket (AWS S3, MinIO, ...)
+    S3,
+}
+
+/// Document storage configuration
+#[derive(Debug, Clone)]
+pub struct StorageConfig {
+    /// Backend to use (default: local)
+    pub backend: StorageBackendKind,
+    
+    /// S3 endpoint URL (default: AWS S3 of the region)
+    pub s3_endpoint: String,
+    
+    /// S3 region used for request signing
+    pub s3_region: String,
+    
+    /// S3 bucket holding the objects
+    pub s3_bucket: String,
+    
+    /// S3 access key ID
+    pub s3_access_key_id: String,
+    
+    /// S3 secret access key
+    pub s3_secret_access_key: String,
+    
+    /// Address the bucket in the URL path instead of the host name (needed for MinIO)
+    pub s3_path_style: bool,
+    
+    /// Lifetime of presigned download URLs in seconds (default: 15 minutes)
+    pub presign_ttl_secs: u64,
+}
+
+impl StorageConfig {
+    /// Load storage configuration from environment variables
+    ///
+    /// # Returns
+    ///
+    /// Storage configuration
+    ///
+    /// # Panics
+    ///
+    /// Panics if a variable has an invalid value or the S3 bucket is missing
+    pub fn from_env() -> Self {
+        let backend = match std::env::var("STORAGE_BACKEND")
+            .unwrap_or_else(|_| "local".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "local" => StorageBackendKind::Local,
+            "s3" => StorageBackendKind::S3,
+            other => panic!("STORAGE_BACKEND must be local or s3 (got '{}')", other),
+        };
+        let s3_region = std::env::var("S3_REGION")
+            .unwrap_or_else(|_| "us-east-1".to_string());
+        let s3_bucket = std::env::var("S3_BUCKET").unwrap_or_default();
+        if backend == StorageBackendKind::S3 && s3_bucket.is_empty() {
+            panic!("S3_BUCKET must be set when STORAGE_BACKEND=s3");
+        }
+
+        Self {
+            backend,
+            s3_endpoint: std::env::var("S3_ENDPOINT")
+                .unwrap_or_else(|_| format!("https://s3.{}.amazonaws.com", s3_region)),
+            s3_region,
+            s3_bucket,
+            s3_access_key_id: std::env::var("S3_ACCESS_KEY_ID").unwrap_or_default(),
+            s3_secret_access_key: std::env::var("S3_SECRET_ACCESS_KEY").unwrap_or_default(),
+            s3_path_style: std::env::var("S3_PATH_STYLE")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .expect("S3_PATH_STYLE must be true or false"),
+            presign_ttl_secs: std::env::var("STORAGE_PRESIGN_TTL_SECS")
+                .unwrap_or_else(|_| "900".to_string())
+                .parse()
+                .expect("STORAGE_PRESIGN_TTL_SECS must be a valid number"),
+        }
+    }
+}
+
+/// OCR configuration (Tesseract)
+#[derive(Debug, Clone)]
+pub struct OcrConfig {
+    /// Tesseract executable, None disables OCR (default: none)
+    pub tesseract_path: Option<std::path::PathBuf>,
+    
+    /// Poppler `pdftoppm` executable rendering PDF pages for OCR (default: pdftoppm)
+    pub pdftoppm_path: std::path::PathBuf,
+    
+    /// Tesseract languages, `+`-separated (default: eng)
+    pub languages: String,
+    
+    /// Resolution PDF pages are rendered at (default: 300 dpi)
+    pub dpi: u32,
+    
+    /// Pages with a lower mean word confidence (0-100) flag the document (default: 60)
+    pub min_confidence: f32,
+    
+    /// Timeout of one OCR run in seconds (default: 120)
+    pub timeout_secs: u64,
+    
+    /// Most pages of one PDF recognized with OCR (default: 50)
+    pub max_pages: u32,
+}
+
+impl OcrConfig {
+    /// Load OCR configuration from environment variables
+    ///
+    /// # Returns
+    ///
+    /// OCR configuration
+    ///
+    /// # Panics
+    ///
+    /// Panics if a variable has an invalid value
+    pub fn from_env() -> Self {
+        Self {
+            tesseract_path: std::env::var("TESSERACT_PATH")
+                .ok()
+                .filter(|path| !path.trim().is_empty())
+                .map(Into::into),
+            pdftoppm_path: std::env::var("PDFTOPPM_PATH")
+                .unwrap_or_else(|_| "pdftoppm".to_string())
+                .into(),
+            languages: std::env::var("OCR_LANGUAGES")
+                .unwrap_or_else(|_| "eng".to_string()),
+            dpi: std::env::var("OCR_DPI")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .expect("OCR_DPI must be a valid number"),
+            min_confidence: std::env::var("OCR_MIN_CONFIDENCE")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .expect("OCR_MIN_CONFIDENCE must be a valid number"),
+            timeout_secs: std::env::var("OCR_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "120".to_string())
+                .parse()
+                .expect("OCR_TIMEOUT_SECS must be a valid number"),
+            max_pages: std::env::var("OCR_MAX_PAGES")
+                .unwrap_or_else(|_| "50".to_string())
+                .parse()
+                .expect("OCR_MAX_PAGES must be a valid number"),
+        }
+    }
 }
 
 impl Config {
@@ -51,12 +400,98 @@ impl Config {
                 .expect("JWT_EXPIRATION must be a valid number"),
             upload_dir: std::env::var("UPLOAD_DIR")
                 .unwrap_or_else(|_| "./uploads".to_string()),
+            storage: StorageConfig::from_env(),
             max_file_size: std::env::var("MAX_FILE_SIZE")
                 .unwrap_or_else(|_| "52428800".to_string()) // 50MB
                 .parse()
                 .expect("MAX_FILE_SIZE must be a valid number"),
             ollama_url: std::env::var("OLLAMA_URL")
                 .unwrap_or_else(|_| "http://localhost:11434".to_string()),
+            risk_matrix: RiskMatrix::from_env(),
+            risk_stale_after_days: std::env::var("RISK_STALE_AFTER_DAYS")
+                .unwrap_or_else(|_| "90".to_string())
+                .parse()
+                .expect("RISK_STALE_AFTER_DAYS must be a valid number"),
+            email: EmailConfig::from_env(),
+            reminder_interval_secs: std::env::var("REMINDER_INTERVAL_SECS")
+                .unwrap_or_else(|_| "900".to_string())
+                .parse()
+                .expect("REMINDER_INTERVAL_SECS must be a valid number"),
+            webhook_max_attempts: std::env::var("WEBHOOK_MAX_ATTEMPTS")
+                .unwrap_or_else(|_| "8".to_string())
+                .parse()
+                .expect("WEBHOOK_MAX_ATTEMPTS must be a valid number"),
+            webhook_retry_base_secs: std::env::var("WEBHOOK_RETRY_BASE_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .expect("WEBHOOK_RETRY_BASE_SECS must be a valid number"),
+            webhook_timeout_secs: std::env::var("WEBHOOK_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .expect("WEBHOOK_TIMEOUT_SECS must be a valid number"),
+            webhook_poll_interval_secs: std::env::var("WEBHOOK_POLL_INTERVAL_SECS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .expect("WEBHOOK_POLL_INTERVAL_SECS must be a valid number"),
+            webhook_allowed_hosts: std::env::var("WEBHOOK_ALLOWED_HOSTS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|host| host.trim().to_string())
+                .filter(|host| !host.is_empty())
+                .collect(),
+            orphan_sweep_interval_secs: std::env::var("ORPHAN_SWEEP_INTERVAL_SECS")
+                .unwrap_or_else(|_| "86400".to_string())
+                .parse()
+                .expect("ORPHAN_SWEEP_INTERVAL_SECS must be a valid number"),
+            orphan_grace_secs: std::env::var("ORPHAN_GRACE_SECS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .expect("ORPHAN_GRACE_SECS must be a valid number"),
+            clamd_address: std::env::var("CLAMD_ADDRESS")
+                .ok()
+                .and_then(|address| ClamdAddress::parse(&address)),
+            clamd_timeout_secs: std::env::var("CLAMD_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .expect("CLAMD_TIMEOUT_SECS must be a valid number"),
+            encryption_keys: master_keys_from_env(),
+            pii_mode: pii_mode_from_env(),
+            ocr: OcrConfig::from_env(),
         }
     }
 }
+
+/// Load the master keys from `ENCRYPTION_MASTER_KEY` or `ENCRYPTION_MASTER_KEY_FILE`
+///
+/// # Returns
+///
+/// Key ring, None if neither variable is set
+///
+/// # Panics
+///
+/// Panics if the key file cannot be read or a key is malformed
+fn master_keys_from_env() -> Option<MasterKeyRing> {
+    let keys = match (std::env::var("ENCRYPTION_MASTER_KEY"), std::env::var("ENCRYPTION_MASTER_KEY_FILE")) {
+        (Ok(keys), _) => keys,
+        (_, Ok(path)) => std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("ENCRYPTION_MASTER_KEY_FILE '{}' cannot be read: {}", path, e)),
+        _ => return None,
+    };
+
+    Some(MasterKeyRing::parse(&keys).unwrap_or_else(|e| panic!("Invalid encryption master key: {}", e)))
+}
+
+/// Read `PII_PROTECTION` (pseudonymize, redact or off)
+///
+/// # Panics
+///
+/// Panics if the value is not one of the supported modes
+fn pii_mode_from_env() -> PiiMode {
+    let mode = std::env::var("PII_PROTECTION").unwrap_or_else(|_| "pseudonymize".to_string());
+    match mode.to_lowercase().as_str() {
+        "pseudonymize" => PiiMode::Pseudonymize,
+        "redact" => PiiMode::Redact,
+        "off" => PiiMode::Off,
+        other => panic!("PII_PROTECTION must be pseudonymize, redact or off (got '{}')", other),
+    }
+}
@@ -0,0 +1,48 @@
+use sqlx::{Postgres, QueryBuilder};
+
+use crate::models::{MetadataEntityType, MetadataFilter};
+
+/// Append tag and custom field conditions to a list query
+///
+/// Each condition is pushed as `AND EXISTS (...)`, so the builder must already
+/// contain a `WHERE` clause.
+///
+/// # Arguments
+///
+/// * `builder` - Query under construction
+/// * `entity_type` - Type of the listed entities
+/// * `id_column` - Column holding the entity ID (e.g., `compliance_items.id`)
+/// * `filter` - Metadata filter from the query string
+pub fn push_metadata_filter(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    entity_type: MetadataEntityType,
+    id_column: &str,
+    filter: &MetadataFilter,
+) {
+    for tag in &filter.tags {
+        builder
+            .push(" AND EXISTS (SELECT 1 FROM entity_tags et JOIN tags t ON t.id = et.tag_id WHERE et.entity_type = ")
+            .push_bind(entity_type.as_str())
+            .push(" AND et.entity_id = ")
+            .push(id_column)
+            .push(" AND LOWER(t.name) = ")
+            .push_bind(tag.clone())
+            .push(")");
+    }
+
+    for (name, value) in &filter.custom_fields {
+        builder
+            .push(
+                " AND EXISTS (SELECT 1 FROM custom_field_values v \
+                 JOIN custom_field_definitions d ON d.id = v.definition_id WHERE d.entity_type = ",
+            )
+            .push_bind(entity_type.as_str())
+            .push(" AND v.entity_id = ")
+            .push(id_column)
+            .push(" AND d.name = ")
+            .push_bind(name.clone())
+            .push(" AND v.value #>> '{}' = ")
+            .push_bind(value.clone())
+            .push(")");
+    }
+}
@@ -1,5 +1,6 @@
 use sqlx::{postgres::PgPoolOptions, PgPool};
 
+pub mod filters;
 pub mod repository;
 
 use crate::error::AppResult;
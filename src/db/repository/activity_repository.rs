@@ -0,0 +1,125 @@
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::{
+    error::AppResult,
+    models::{ActivityEvent, ActivityFeedQuery, NewActivityEvent},
+};
+
+/// Repository for activity event database operations
+pub struct ActivityRepository {
+    pool: PgPool,
+}
+
+impl ActivityRepository {
+    /// Create new repository
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    ///
+    /// # Returns
+    ///
+    /// Repository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record an activity event
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - Event to record
+    ///
+    /// # Returns
+    ///
+    /// Recorded event
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if insert fails
+    pub async fn create(&self, event: &NewActivityEvent) -> AppResult<ActivityEvent> {
+        let event = sqlx::query_as::<_, ActivityEvent>(
+            "WITH inserted AS (
+                 INSERT INTO activity_events
+                     (user_id, actor_id, verb, entity_type, entity_id, entity_title, before, after)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                 RETURNING *
+             )
+             SELECT i.id, i.actor_id, u.full_name AS actor_name, i.verb, i.entity_type, i.entity_id,
+                    i.entity_title, i.before, i.after, i.created_at
+             FROM inserted i
+             LEFT JOIN users u ON u.id = i.actor_id"
+        )
+        .bind(event.user_id)
+        .bind(event.actor_id)
+        .bind(event.verb.as_str())
+        .bind(event.entity_type.as_str())
+        .bind(event.entity_id)
+        .bind(&event.entity_title)
+        .bind(event.before.as_ref().map(sqlx::types::Json))
+        .bind(event.after.as_ref().map(sqlx::types::Json))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(event)
+    }
+
+    /// Find a page of a user's activity feed
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `query` - Cursor and filters
+    /// * `limit` - Maximum number of events
+    ///
+    /// # Returns
+    ///
+    /// Events older than the cursor, newest first
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_page(
+        &self,
+        user_id: Uuid,
+        query: &ActivityFeedQuery,
+        limit: i64,
+    ) -> AppResult<Vec<ActivityEvent>> {
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "SELECT e.id, e.actor_id, u.full_name AS actor_name, e.verb, e.entity_type, e.entity_id,
+                    e.entity_title, e.before, e.after, e.created_at
+             FROM activity_events e
+             LEFT JOIN users u ON u.id = e.actor_id
+             WHERE e.user_id = "
+        );
+        builder.push_bind(user_id);
+
+        if let Some(cursor) = query.cursor {
+            builder
+                .push(" AND (e.created_at, e.id) < (SELECT created_at, id FROM activity_events WHERE id = ")
+                .push_bind(cursor)
+                .push(")");
+        }
+        if let Some(entity_type) = query.entity_type {
+            builder.push(" AND e.entity_type = ").push_bind(entity_type.as_str());
+        }
+        if let Some(entity_id) = query.entity_id {
+            builder.push(" AND e.entity_id = ").push_bind(entity_id);
+        }
+        if let Some(actor) = query.actor {
+            builder.push(" AND e.actor_id = ").push_bind(actor);
+        }
+
+        builder
+            .push(" ORDER BY e.created_at DESC, e.id DESC LIMIT ")
+            .push_bind(limit);
+
+        let events = builder
+            .build_query_as::<ActivityEvent>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(events)
+    }
+}
@@ -0,0 +1,203 @@
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{error::AppResult, models::Blob};
+
+/// Columns of a blob
+const BLOB_COLUMNS: &str = "sha256, storage_key, size, ref_count, created_at";
+
+/// Blob claimed for an upload
+#[derive(Debug, sqlx::FromRow)]
+pub struct ClaimedBlob {
+    /// Blob (locked until the transaction ends)
+    #[sqlx(flatten)]
+    pub blob: Blob,
+
+    /// Whether the row was created by this claim, i.e. the content still has to be stored
+    pub inserted: bool,
+}
+
+/// Repository for content-addressed blobs
+///
+/// Reference counts are maintained by a trigger on `documents`; these
+/// queries only create, lock and remove the rows.
+pub struct BlobRepository {
+    pool: PgPool,
+}
+
+impl BlobRepository {
+    /// Create new repository
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    ///
+    /// # Returns
+    ///
+    /// Repository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Count the blobs no document references
+    ///
+    /// # Returns
+    ///
+    /// Number of unreferenced blobs
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn count_unreferenced(&self) -> AppResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM blobs WHERE ref_count = 0")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count)
+    }
+
+    /// Create or lock the blob of some content
+    ///
+    /// The row stays locked until the transaction ends, so a concurrent
+    /// delete cannot remove the object between the claim and the insert of
+    /// the referencing document.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `sha256` - SHA-256 of the content
+    /// * `storage_key` - Storage key to use if the blob is new
+    /// * `size` - Content size in bytes
+    ///
+    /// # Returns
+    ///
+    /// Claimed blob
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if the upsert fails
+    pub async fn claim_in(
+        tx: &mut Transaction<'_, Postgres>,
+        sha256: &str,
+        storage_key: &str,
+        size: i64,
+    ) -> AppResult<ClaimedBlob> {
+        let claimed = sqlx::query_as::<_, ClaimedBlob>(&format!(
+            "INSERT INTO blobs (sha256, storage_key, size)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (sha256) DO UPDATE SET sha256 = EXCLUDED.sha256
+             RETURNING {}, (xmax = 0) AS inserted",
+            BLOB_COLUMNS
+        ))
+        .bind(sha256)
+        .bind(storage_key)
+        .bind(size)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(claimed)
+    }
+
+    /// Lock a blob if no document references it any more
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `sha256` - SHA-256 of the content
+    ///
+    /// # Returns
+    ///
+    /// Locked blob, None if it is still referenced or gone
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn lock_unreferenced_in(tx: &mut Transaction<'_, Postgres>, sha256: &str) -> AppResult<Option<Blob>> {
+        let blob = sqlx::query_as::<_, Blob>(&format!(
+            "SELECT {} FROM blobs WHERE sha256 = $1 AND ref_count = 0 FOR UPDATE",
+            BLOB_COLUMNS
+        ))
+        .bind(sha256)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        Ok(blob)
+    }
+
+    /// Lock the oldest unreferenced blob nobody else is working on
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    ///
+    /// # Returns
+    ///
+    /// Locked blob, None if there is none
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn lock_next_unreferenced_in(tx: &mut Transaction<'_, Postgres>) -> AppResult<Option<Blob>> {
+        let blob = sqlx::query_as::<_, Blob>(&format!(
+            "SELECT {} FROM blobs WHERE ref_count = 0
+             ORDER BY created_at
+             LIMIT 1
+             FOR UPDATE SKIP LOCKED",
+            BLOB_COLUMNS
+        ))
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        Ok(blob)
+    }
+
+    /// Re-create the row of a released blob while its object is removed
+    ///
+    /// The new row blocks concurrent claims of the same content until the
+    /// transaction ends, so an upload cannot store the object in between.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `blob` - Blob whose row was deleted
+    ///
+    /// # Returns
+    ///
+    /// False if the content was claimed again in the meantime
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if the insert fails
+    pub async fn claim_released_in(tx: &mut Transaction<'_, Postgres>, blob: &Blob) -> AppResult<bool> {
+        let result = sqlx::query(
+            "INSERT INTO blobs (sha256, storage_key, size)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (sha256) DO NOTHING",
+        )
+        .bind(&blob.sha256)
+        .bind(&blob.storage_key)
+        .bind(blob.size)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Delete a blob row
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `sha256` - SHA-256 of the content
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if deletion fails
+    pub async fn delete_in(tx: &mut Transaction<'_, Postgres>, sha256: &str) -> AppResult<()> {
+        sqlx::query("DELETE FROM blobs WHERE sha256 = $1")
+            .bind(sha256)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+}
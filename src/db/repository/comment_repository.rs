@@ -0,0 +1,362 @@
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    error::AppResult,
+    models::{Comment, CommentEdit, CommentEntityType, CommentMention},
+};
+
+/// Repository for comment database operations
+///
+/// Comments are keyed by entity type and entity ID so a single table serves
+/// compliance items, documents and risk scores.
+pub struct CommentRepository {
+    pool: PgPool,
+}
+
+impl CommentRepository {
+    /// Create new repository
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    ///
+    /// # Returns
+    ///
+    /// Repository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Check that the commented entity exists and is owned by the user
+    ///
+    /// # Arguments
+    ///
+    /// * `entity_type` - Type of the parent entity
+    /// * `entity_id` - Parent entity UUID
+    /// * `user_id` - User UUID for authorization
+    ///
+    /// # Returns
+    ///
+    /// true if the entity exists and belongs to the user
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn entity_exists(
+        &self,
+        entity_type: CommentEntityType,
+        entity_id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<bool> {
+        let query = format!(
+            "SELECT EXISTS(SELECT 1 FROM {} WHERE id = $1 AND user_id = $2)",
+            entity_type.table_name()
+        );
+
+        let exists: bool = sqlx::query_scalar(&query)
+            .bind(entity_id)
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(exists)
+    }
+
+    /// Find all comments on an entity
+    ///
+    /// # Arguments
+    ///
+    /// * `entity_type` - Type of the parent entity
+    /// * `entity_id` - Parent entity UUID
+    ///
+    /// # Returns
+    ///
+    /// Comments ordered oldest first
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_by_entity(
+        &self,
+        entity_type: CommentEntityType,
+        entity_id: Uuid,
+    ) -> AppResult<Vec<Comment>> {
+        let comments = sqlx::query_as::<_, Comment>(
+            "SELECT id, entity_type, entity_id, parent_id, user_id, body,
+                    edited_at, deleted_at, created_at, updated_at
+             FROM comments
+             WHERE entity_type = $1 AND entity_id = $2
+             ORDER BY created_at ASC"
+        )
+        .bind(entity_type.as_str())
+        .bind(entity_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(comments)
+    }
+
+    /// Find a comment on an entity by ID
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Comment UUID
+    /// * `entity_type` - Type of the parent entity
+    /// * `entity_id` - Parent entity UUID
+    ///
+    /// # Returns
+    ///
+    /// Comment if found on the given entity
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_by_id(
+        &self,
+        id: Uuid,
+        entity_type: CommentEntityType,
+        entity_id: Uuid,
+    ) -> AppResult<Option<Comment>> {
+        let comment = sqlx::query_as::<_, Comment>(
+            "SELECT id, entity_type, entity_id, parent_id, user_id, body,
+                    edited_at, deleted_at, created_at, updated_at
+             FROM comments
+             WHERE id = $1 AND entity_type = $2 AND entity_id = $3"
+        )
+        .bind(id)
+        .bind(entity_type.as_str())
+        .bind(entity_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(comment)
+    }
+
+    /// Create a comment and record its mentions
+    ///
+    /// # Arguments
+    ///
+    /// * `entity_type` - Type of the parent entity
+    /// * `entity_id` - Parent entity UUID
+    /// * `parent_id` - Comment being replied to
+    /// * `user_id` - Author UUID
+    /// * `body` - Comment text
+    /// * `mentioned_emails` - Emails mentioned in the body
+    ///
+    /// # Returns
+    ///
+    /// Created comment
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if insert fails
+    pub async fn create(
+        &self,
+        entity_type: CommentEntityType,
+        entity_id: Uuid,
+        parent_id: Option<Uuid>,
+        user_id: Uuid,
+        body: &str,
+        mentioned_emails: &[String],
+    ) -> AppResult<Comment> {
+        let mut tx = self.pool.begin().await?;
+
+        let comment = sqlx::query_as::<_, Comment>(
+            "INSERT INTO comments (entity_type, entity_id, parent_id, user_id, body)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING id, entity_type, entity_id, parent_id, user_id, body,
+                       edited_at, deleted_at, created_at, updated_at"
+        )
+        .bind(entity_type.as_str())
+        .bind(entity_id)
+        .bind(parent_id)
+        .bind(user_id)
+        .bind(body)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        Self::replace_mentions(&mut tx, comment.id, user_id, mentioned_emails).await?;
+
+        tx.commit().await?;
+
+        Ok(comment)
+    }
+
+    /// Edit a comment, keeping the previous body in the edit history
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Comment UUID
+    /// * `user_id` - Author UUID (only authors may edit)
+    /// * `body` - New comment text
+    /// * `mentioned_emails` - Emails mentioned in the new body
+    ///
+    /// # Returns
+    ///
+    /// Updated comment or None if not found/not the author/deleted
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if update fails
+    pub async fn update(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        body: &str,
+        mentioned_emails: &[String],
+    ) -> AppResult<Option<Comment>> {
+        let mut tx = self.pool.begin().await?;
+
+        let recorded = sqlx::query(
+            "INSERT INTO comment_edits (comment_id, previous_body, edited_by)
+             SELECT id, body, user_id
+             FROM comments
+             WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL"
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if recorded.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        let comment = sqlx::query_as::<_, Comment>(
+            "UPDATE comments
+             SET body = $3, edited_at = NOW(), updated_at = NOW()
+             WHERE id = $1 AND user_id = $2
+             RETURNING id, entity_type, entity_id, parent_id, user_id, body,
+                       edited_at, deleted_at, created_at, updated_at"
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(body)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        Self::replace_mentions(&mut tx, id, user_id, mentioned_emails).await?;
+
+        tx.commit().await?;
+
+        Ok(Some(comment))
+    }
+
+    /// Soft delete a comment so replies stay attached to the thread
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Comment UUID
+    /// * `user_id` - Author UUID (only authors may delete)
+    ///
+    /// # Returns
+    ///
+    /// True if deleted, false if not found
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if update fails
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> AppResult<bool> {
+        let result = sqlx::query(
+            "UPDATE comments
+             SET deleted_at = NOW(), updated_at = NOW()
+             WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL"
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Find users mentioned in the given comments
+    ///
+    /// # Arguments
+    ///
+    /// * `comment_ids` - Comment UUIDs
+    ///
+    /// # Returns
+    ///
+    /// Mentions with user details
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_mentions(&self, comment_ids: &[Uuid]) -> AppResult<Vec<CommentMention>> {
+        let mentions = sqlx::query_as::<_, CommentMention>(
+            "SELECT m.comment_id, u.id AS user_id, u.email, u.full_name
+             FROM comment_mentions m
+             JOIN users u ON u.id = m.user_id
+             WHERE m.comment_id = ANY($1)
+             ORDER BY u.full_name"
+        )
+        .bind(comment_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(mentions)
+    }
+
+    /// Find the edit history of a comment
+    ///
+    /// # Arguments
+    ///
+    /// * `comment_id` - Comment UUID
+    ///
+    /// # Returns
+    ///
+    /// Previous versions, newest first
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_edits(&self, comment_id: Uuid) -> AppResult<Vec<CommentEdit>> {
+        let edits = sqlx::query_as::<_, CommentEdit>(
+            "SELECT id, comment_id, previous_body, edited_by, edited_at
+             FROM comment_edits
+             WHERE comment_id = $1
+             ORDER BY edited_at DESC"
+        )
+        .bind(comment_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(edits)
+    }
+
+    /// Replace the mentions of a comment with users matching the given emails
+    ///
+    /// Only users the author can already see are resolved, which without
+    /// shared workspaces is the author alone; other emails are ignored, so a
+    /// mention never reveals whether an email is registered or whose it is.
+    async fn replace_mentions(
+        tx: &mut Transaction<'_, Postgres>,
+        comment_id: Uuid,
+        author_id: Uuid,
+        mentioned_emails: &[String],
+    ) -> AppResult<()> {
+        sqlx::query("DELETE FROM comment_mentions WHERE comment_id = $1")
+            .bind(comment_id)
+            .execute(&mut **tx)
+            .await?;
+
+        if mentioned_emails.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "INSERT INTO comment_mentions (comment_id, user_id)
+             SELECT $1, id FROM users WHERE id = $3 AND LOWER(email) = ANY($2)
+             ON CONFLICT DO NOTHING"
+        )
+        .bind(comment_id)
+        .bind(mentioned_emails)
+        .bind(author_id)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -1,9 +1,15 @@
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use futures_util::{StreamExt, TryStreamExt};
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
 use uuid::Uuid;
 
 use crate::{
-    error::AppResult,
-    models::{ComplianceItem, CreateComplianceDto, UpdateComplianceDto},
+    db::filters::push_metadata_filter,
+    error::{AppError, AppResult},
+    models::{
+        ComplianceExportRow, ComplianceItem, CreateComplianceDto, ExportRowStream, MetadataEntityType, MetadataFilter,
+        UpdateComplianceDto,
+    },
 };
 
 /// Compliance repository for database operations
@@ -33,6 +39,7 @@ impl ComplianceRepository {
     /// # Arguments
     ///
     /// * `user_id` - User UUID
+    /// * `filter` - Tag and custom field filters
     ///
     /// # Returns
     ///
@@ -41,12 +48,82 @@ impl ComplianceRepository {
     /// # Errors
     ///
     /// Returns database error if query fails
-    pub async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<ComplianceItem>> {
+    pub async fn find_by_user(&self, user_id: Uuid, filter: &MetadataFilter) -> AppResult<Vec<ComplianceItem>> {
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "SELECT id, user_id, title, description, risk_level, status, due_date, framework, assignee_id, created_at, updated_at
+             FROM compliance_items
+             WHERE user_id = "
+        );
+        builder.push_bind(user_id);
+        push_metadata_filter(&mut builder, MetadataEntityType::ComplianceItem, "compliance_items.id", filter);
+        builder.push(" ORDER BY created_at DESC");
+
+        let items = builder
+            .build_query_as::<ComplianceItem>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(items)
+    }
+
+    /// Find open compliance items due before a given time
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `until` - Exclusive upper bound of the due date
+    /// * `filter` - Tag and custom field filters
+    ///
+    /// # Returns
+    ///
+    /// Pending and in-progress items, ordered by due date
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_open_due_before(
+        &self,
+        user_id: Uuid,
+        until: DateTime<Utc>,
+        filter: &MetadataFilter,
+    ) -> AppResult<Vec<ComplianceItem>> {
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "SELECT id, user_id, title, description, risk_level, status, due_date, framework, assignee_id, created_at, updated_at
+             FROM compliance_items
+             WHERE status IN ('pending', 'in_progress') AND user_id = "
+        );
+        builder.push_bind(user_id);
+        builder.push(" AND due_date < ").push_bind(until);
+        push_metadata_filter(&mut builder, MetadataEntityType::ComplianceItem, "compliance_items.id", filter);
+        builder.push(" ORDER BY due_date, created_at");
+
+        let items = builder
+            .build_query_as::<ComplianceItem>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(items)
+    }
+
+    /// Find all compliance items with a due date
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// Items ordered by due date
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_with_due_date(&self, user_id: Uuid) -> AppResult<Vec<ComplianceItem>> {
         let items = sqlx::query_as::<_, ComplianceItem>(
-            "SELECT id, user_id, title, description, risk_level, status, due_date, created_at, updated_at
+            "SELECT id, user_id, title, description, risk_level, status, due_date, framework, assignee_id, created_at, updated_at
              FROM compliance_items
-             WHERE user_id = $1
-             ORDER BY created_at DESC"
+             WHERE user_id = $1 AND due_date IS NOT NULL
+             ORDER BY due_date, created_at"
         )
         .bind(user_id)
         .fetch_all(&self.pool)
@@ -71,7 +148,7 @@ impl ComplianceRepository {
     /// Returns database error if query fails
     pub async fn find_by_id(&self, id: Uuid, user_id: Uuid) -> AppResult<Option<ComplianceItem>> {
         let item = sqlx::query_as::<_, ComplianceItem>(
-            "SELECT id, user_id, title, description, risk_level, status, due_date, created_at, updated_at
+            "SELECT id, user_id, title, description, risk_level, status, due_date, framework, assignee_id, created_at, updated_at
              FROM compliance_items
              WHERE id = $1 AND user_id = $2"
         )
@@ -98,10 +175,37 @@ impl ComplianceRepository {
     ///
     /// Returns database error if insertion fails
     pub async fn create(&self, user_id: Uuid, dto: &CreateComplianceDto) -> AppResult<ComplianceItem> {
+        let mut tx = self.pool.begin().await?;
+        let item = Self::create_in(&mut tx, user_id, dto).await?;
+        tx.commit().await?;
+
+        Ok(item)
+    }
+
+    /// Create a compliance item inside an open transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `user_id` - Owner user UUID
+    /// * `dto` - Compliance item data
+    ///
+    /// # Returns
+    ///
+    /// Created ComplianceItem
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error for an unknown assignee, database error if insertion fails
+    pub async fn create_in(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        dto: &CreateComplianceDto,
+    ) -> AppResult<ComplianceItem> {
         let item = sqlx::query_as::<_, ComplianceItem>(
-            "INSERT INTO compliance_items (user_id, title, description, risk_level, status, due_date)
-             VALUES ($1, $2, $3, $4, $5, $6)
-             RETURNING id, user_id, title, description, risk_level, status, due_date, created_at, updated_at"
+            "INSERT INTO compliance_items (user_id, title, description, risk_level, status, due_date, framework, assignee_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             RETURNING id, user_id, title, description, risk_level, status, due_date, framework, assignee_id, created_at, updated_at"
         )
         .bind(user_id)
         .bind(&dto.title)
@@ -109,16 +213,20 @@ impl ComplianceRepository {
         .bind(&dto.risk_level)
         .bind(&dto.status)
         .bind(dto.due_date)
-        .fetch_one(&self.pool)
-        .await?;
+        .bind(&dto.framework)
+        .bind(dto.assignee_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(map_assignee_error)?;
 
         Ok(item)
     }
 
-    /// Update a compliance item
+    /// Update a compliance item inside an open transaction
     ///
     /// # Arguments
     ///
+    /// * `tx` - Open transaction
     /// * `id` - Compliance item UUID
     /// * `user_id` - User UUID (for authorization)
     /// * `dto` - Update data
@@ -129,9 +237,9 @@ impl ComplianceRepository {
     ///
     /// # Errors
     ///
-    /// Returns database error if update fails
-    pub async fn update(
-        &self,
+    /// Returns validation error for an unknown assignee, database error if update fails
+    pub async fn update_in(
+        tx: &mut Transaction<'_, Postgres>,
         id: Uuid,
         user_id: Uuid,
         dto: &UpdateComplianceDto,
@@ -161,15 +269,32 @@ impl ComplianceRepository {
             updates.push(format!("due_date = ${}", param_count));
             param_count += 1;
         }
+        if dto.framework.is_some() {
+            updates.push(format!("framework = ${}", param_count));
+            param_count += 1;
+        }
+        if dto.assignee_id.is_some() {
+            updates.push(format!("assignee_id = ${}", param_count));
+        }
 
         if updates.is_empty() {
             // No updates provided, just return existing item
-            return self.find_by_id(id, user_id).await;
+            let item = sqlx::query_as::<_, ComplianceItem>(
+                "SELECT id, user_id, title, description, risk_level, status, due_date, framework, assignee_id, created_at, updated_at
+                 FROM compliance_items
+                 WHERE id = $1 AND user_id = $2"
+            )
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+            return Ok(item);
         }
 
         updates.push("updated_at = NOW()".to_string());
         query.push_str(&updates.join(", "));
-        query.push_str(" WHERE id = $1 AND user_id = $2 RETURNING id, user_id, title, description, risk_level, status, due_date, created_at, updated_at");
+        query.push_str(" WHERE id = $1 AND user_id = $2 RETURNING id, user_id, title, description, risk_level, status, due_date, framework, assignee_id, created_at, updated_at");
 
         let mut query_builder = sqlx::query_as::<_, ComplianceItem>(&query)
             .bind(id)
@@ -190,8 +315,17 @@ impl ComplianceRepository {
         if let Some(due_date) = dto.due_date {
             query_builder = query_builder.bind(due_date);
         }
+        if let Some(ref framework) = dto.framework {
+            query_builder = query_builder.bind(framework);
+        }
+        if let Some(assignee_id) = dto.assignee_id {
+            query_builder = query_builder.bind(assignee_id);
+        }
 
-        let item = query_builder.fetch_optional(&self.pool).await?;
+        let item = query_builder
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(map_assignee_error)?;
 
         Ok(item)
     }
@@ -221,4 +355,66 @@ impl ComplianceRepository {
 
         Ok(result.rows_affected() > 0)
     }
+
+    /// Stream the compliance register with joined columns for export
+    ///
+    /// Rows are fetched lazily while the stream is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `filter` - Tag and custom field filters
+    ///
+    /// # Returns
+    ///
+    /// Stream of rows, newest first, with tags, assignee, latest risk score
+    /// and evidence filenames
+    pub fn stream_export(&self, user_id: Uuid, filter: MetadataFilter) -> ExportRowStream<ComplianceExportRow> {
+        let pool = self.pool.clone();
+
+        async_stream::try_stream! {
+            let mut builder = QueryBuilder::<Postgres>::new(
+                "SELECT c.id, c.title, c.description, c.risk_level, c.status, c.framework, c.due_date,
+                        a.full_name AS assignee_name, a.email AS assignee_email,
+                        COALESCE((SELECT string_agg(t.name, ', ' ORDER BY t.name)
+                                  FROM entity_tags et JOIN tags t ON t.id = et.tag_id
+                                  WHERE et.entity_type = 'compliance_item' AND et.entity_id = c.id), '') AS tags,
+                        rs.risk_score AS latest_risk_score, rs.risk_level AS latest_risk_level,
+                        rs.residual_score AS latest_residual_score, rs.assessment_date AS latest_assessment_date,
+                        COALESCE((SELECT string_agg(DISTINCT d.filename, '; ' ORDER BY d.filename)
+                                  FROM risk_scores r JOIN documents d ON d.id = r.document_id
+                                  WHERE r.compliance_item_id = c.id), '') AS evidence_files,
+                        c.created_at, c.updated_at
+                 FROM compliance_items c
+                 LEFT JOIN users a ON a.id = c.assignee_id
+                 LEFT JOIN LATERAL (
+                     SELECT risk_score, risk_level, residual_score, assessment_date
+                     FROM risk_scores
+                     WHERE compliance_item_id = c.id
+                     ORDER BY assessment_date DESC, created_at DESC
+                     LIMIT 1
+                 ) rs ON TRUE
+                 WHERE c.user_id = "
+            );
+            builder.push_bind(user_id);
+            push_metadata_filter(&mut builder, MetadataEntityType::ComplianceItem, "c.id", &filter);
+            builder.push(" ORDER BY c.created_at DESC");
+
+            let mut rows = builder.build_query_as::<ComplianceExportRow>().fetch(&pool);
+            while let Some(row) = rows.try_next().await? {
+                yield row;
+            }
+        }
+        .boxed()
+    }
+}
+
+/// Report an unknown assignee as a validation error
+fn map_assignee_error(e: sqlx::Error) -> AppError {
+    match e {
+        sqlx::Error::Database(ref db) if db.is_foreign_key_violation() => {
+            AppError::Validation("Unknown assignee".to_string())
+        }
+        e => AppError::Database(e),
+    }
 }
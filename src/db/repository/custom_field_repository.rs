@@ -0,0 +1,282 @@
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{CreateCustomFieldDto, CustomFieldDefinition, MetadataEntityType, UpdateCustomFieldDto},
+};
+
+/// Custom field value of an entity
+#[derive(Debug, sqlx::FromRow)]
+pub struct EntityFieldValue {
+    /// Entity the value belongs to
+    pub entity_id: Uuid,
+
+    /// Field name
+    pub name: String,
+
+    /// Stored value
+    pub value: sqlx::types::Json<serde_json::Value>,
+}
+
+/// Repository for custom field definitions and values
+pub struct CustomFieldRepository {
+    pool: PgPool,
+}
+
+impl CustomFieldRepository {
+    /// Create new repository
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    ///
+    /// # Returns
+    ///
+    /// Repository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find custom field definitions for a user
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `entity_type` - Restrict to one entity type (optional)
+    ///
+    /// # Returns
+    ///
+    /// List of definitions ordered by name
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_by_user(
+        &self,
+        user_id: Uuid,
+        entity_type: Option<MetadataEntityType>,
+    ) -> AppResult<Vec<CustomFieldDefinition>> {
+        let definitions = sqlx::query_as::<_, CustomFieldDefinition>(
+            "SELECT id, user_id, entity_type, name, label, field_type, options, required, created_at
+             FROM custom_field_definitions
+             WHERE user_id = $1 AND ($2::text IS NULL OR entity_type = $2)
+             ORDER BY entity_type, name"
+        )
+        .bind(user_id)
+        .bind(entity_type.map(|t| t.as_str()))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(definitions)
+    }
+
+    /// Find custom field definition by ID
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Definition UUID
+    /// * `user_id` - User UUID for authorization
+    ///
+    /// # Returns
+    ///
+    /// Definition if found and owned by user
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_by_id(&self, id: Uuid, user_id: Uuid) -> AppResult<Option<CustomFieldDefinition>> {
+        let definition = sqlx::query_as::<_, CustomFieldDefinition>(
+            "SELECT id, user_id, entity_type, name, label, field_type, options, required, created_at
+             FROM custom_field_definitions
+             WHERE id = $1 AND user_id = $2"
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(definition)
+    }
+
+    /// Create custom field definition
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `dto` - Definition data
+    ///
+    /// # Returns
+    ///
+    /// Created definition
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error if the name is taken, database error otherwise
+    pub async fn create(&self, user_id: Uuid, dto: &CreateCustomFieldDto) -> AppResult<CustomFieldDefinition> {
+        let definition = sqlx::query_as::<_, CustomFieldDefinition>(
+            "INSERT INTO custom_field_definitions
+                (user_id, entity_type, name, label, field_type, options, required)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING id, user_id, entity_type, name, label, field_type, options, required, created_at"
+        )
+        .bind(user_id)
+        .bind(dto.entity_type.as_str())
+        .bind(&dto.name)
+        .bind(&dto.label)
+        .bind(dto.field_type.as_str())
+        .bind(dto.options.clone().map(sqlx::types::Json))
+        .bind(dto.required)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                AppError::Validation("A custom field with this name already exists".to_string())
+            }
+            e => AppError::Database(e),
+        })?;
+
+        Ok(definition)
+    }
+
+    /// Update custom field definition
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Definition UUID
+    /// * `user_id` - User UUID for authorization
+    /// * `dto` - Update data
+    ///
+    /// # Returns
+    ///
+    /// Updated definition or None if not found
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if update fails
+    pub async fn update(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        dto: &UpdateCustomFieldDto,
+    ) -> AppResult<Option<CustomFieldDefinition>> {
+        let definition = sqlx::query_as::<_, CustomFieldDefinition>(
+            "UPDATE custom_field_definitions
+             SET label = COALESCE($3, label),
+                 options = COALESCE($4, options),
+                 required = COALESCE($5, required)
+             WHERE id = $1 AND user_id = $2
+             RETURNING id, user_id, entity_type, name, label, field_type, options, required, created_at"
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(&dto.label)
+        .bind(dto.options.clone().map(sqlx::types::Json))
+        .bind(dto.required)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(definition)
+    }
+
+    /// Delete custom field definition and all of its values
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Definition UUID
+    /// * `user_id` - User UUID for authorization
+    ///
+    /// # Returns
+    ///
+    /// True if deleted, false if not found
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if delete fails
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> AppResult<bool> {
+        let result = sqlx::query("DELETE FROM custom_field_definitions WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Find custom field values of the given entities
+    ///
+    /// # Arguments
+    ///
+    /// * `entity_type` - Type of the entities
+    /// * `entity_ids` - Entity UUIDs
+    ///
+    /// # Returns
+    ///
+    /// Values with their field names
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_values(
+        &self,
+        entity_type: MetadataEntityType,
+        entity_ids: &[Uuid],
+    ) -> AppResult<Vec<EntityFieldValue>> {
+        let values = sqlx::query_as::<_, EntityFieldValue>(
+            "SELECT v.entity_id, d.name, v.value
+             FROM custom_field_values v
+             JOIN custom_field_definitions d ON d.id = v.definition_id
+             WHERE d.entity_type = $1 AND v.entity_id = ANY($2)"
+        )
+        .bind(entity_type.as_str())
+        .bind(entity_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(values)
+    }
+
+    /// Set or clear a custom field value
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `definition_id` - Definition UUID
+    /// * `entity_id` - Entity UUID
+    /// * `value` - New value, or None to remove it
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if the write fails
+    pub async fn set_value(
+        tx: &mut Transaction<'_, Postgres>,
+        definition_id: Uuid,
+        entity_id: Uuid,
+        value: Option<&serde_json::Value>,
+    ) -> AppResult<()> {
+        match value {
+            Some(value) => {
+                sqlx::query(
+                    "INSERT INTO custom_field_values (definition_id, entity_id, value)
+                     VALUES ($1, $2, $3)
+                     ON CONFLICT (definition_id, entity_id)
+                     DO UPDATE SET value = EXCLUDED.value, updated_at = NOW()"
+                )
+                .bind(definition_id)
+                .bind(entity_id)
+                .bind(sqlx::types::Json(value))
+                .execute(&mut **tx)
+                .await?;
+            }
+            None => {
+                sqlx::query("DELETE FROM custom_field_values WHERE definition_id = $1 AND entity_id = $2")
+                    .bind(definition_id)
+                    .bind(entity_id)
+                    .execute(&mut **tx)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
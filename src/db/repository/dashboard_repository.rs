@@ -1,8 +1,13 @@
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, QueryBuilder};
 use tracing::instrument;
 use uuid::Uuid;
 
-use crate::error::AppResult;
+use crate::{
+    db::filters::push_metadata_filter,
+    error::AppResult,
+    models::{BurndownPoint, DashboardFilter, MetadataEntityType, MetadataFilter, TrendInterval},
+};
 
 /// Dashboard statistics
 #[derive(Debug, sqlx::FromRow)]
@@ -30,6 +35,22 @@ pub struct ActivityItemQuery {
     pub timestamp: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+/// Latest risk assessment of a compliance item from DB
+#[derive(Debug, sqlx::FromRow)]
+pub struct LatestRiskQuery {
+    pub compliance_item_id: Uuid,
+    pub title: String,
+    pub risk_score_id: Uuid,
+    pub risk_category: String,
+    pub risk_score: i32,
+    pub risk_level: String,
+    pub residual_score: i32,
+    pub residual_level: String,
+    pub likelihood: Option<i32>,
+    pub impact: Option<i32>,
+    pub assessment_date: chrono::DateTime<chrono::Utc>,
+}
+
 /// Dashboard repository for database operations
 pub struct DashboardRepository {
     pool: PgPool,
@@ -42,70 +63,201 @@ impl DashboardRepository {
     }
 
     /// Get compliance statistics
+    ///
+    /// Items are counted by their status at the end of the filtered period.
     #[instrument(skip(self))]
-    pub async fn get_compliance_stats(&self, user_id: Uuid) -> AppResult<DashboardStatsQuery> {
-        let stats = sqlx::query_as!(
-            DashboardStatsQuery,
-            r#"
-            SELECT 
-                COUNT(*) as "total!",
-                COUNT(*) FILTER (WHERE status = 'pending') as "pending!",
-                COUNT(*) FILTER (WHERE status = 'in_progress') as "in_progress!",
-                COUNT(*) FILTER (WHERE status = 'completed') as "completed!",
-                COUNT(*) FILTER (WHERE status = 'expired') as "expired!"
-            FROM compliance_items
-            WHERE user_id = $1
-            "#,
-            user_id
-        )
-        .fetch_one(&self.pool)
-        .await?;
+    pub async fn get_compliance_stats(&self, user_id: Uuid, filter: &DashboardFilter) -> AppResult<DashboardStatsQuery> {
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "SELECT
+                COUNT(*) AS total,
+                COUNT(*) FILTER (WHERE s.status = 'pending') AS pending,
+                COUNT(*) FILTER (WHERE s.status = 'in_progress') AS in_progress,
+                COUNT(*) FILTER (WHERE s.status = 'completed') AS completed,
+                COUNT(*) FILTER (WHERE s.status = 'expired') AS expired
+            FROM compliance_items c
+            CROSS JOIN LATERAL (SELECT "
+        );
+        match filter.to {
+            Some(to) => {
+                builder
+                    .push(
+                        "COALESCE((SELECT h.to_status FROM compliance_status_history h \
+                         WHERE h.compliance_item_id = c.id AND h.changed_at < ",
+                    )
+                    .push_bind(to)
+                    .push(" ORDER BY h.changed_at DESC, h.id DESC LIMIT 1), c.status)");
+            }
+            None => {
+                builder.push("c.status");
+            }
+        }
+        builder.push(" AS status) s WHERE c.user_id = ");
+        builder.push_bind(user_id);
+        push_period_filter(&mut builder, "c.created_at", filter);
+        push_compliance_filter(&mut builder, "c", filter);
+
+        let stats = builder
+            .build_query_as::<DashboardStatsQuery>()
+            .fetch_one(&self.pool)
+            .await?;
 
         Ok(stats)
     }
 
     /// Get document statistics
+    ///
+    /// Only the period and tag filters apply to documents.
     #[instrument(skip(self))]
-    pub async fn get_document_stats(&self, user_id: Uuid) -> AppResult<DocumentStatsQuery> {
-        let stats = sqlx::query_as!(
-            DocumentStatsQuery,
-            r#"
-            SELECT 
-                COUNT(*) as "total!",
-                COUNT(*) FILTER (WHERE ai_analysis IS NOT NULL) as "analyzed!"
+    pub async fn get_document_stats(&self, user_id: Uuid, filter: &DashboardFilter) -> AppResult<DocumentStatsQuery> {
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "SELECT
+                COUNT(*) AS total,
+                COUNT(*) FILTER (WHERE ai_analysis IS NOT NULL) AS analyzed
             FROM documents
-            WHERE user_id = $1
-            "#,
-            user_id
-        )
-        .fetch_one(&self.pool)
-        .await?;
+            WHERE user_id = "
+        );
+        builder.push_bind(user_id);
+        push_period_filter(&mut builder, "uploaded_at", filter);
+        let tags = MetadataFilter {
+            tags: filter.metadata.tags.clone(),
+            custom_fields: Vec::new(),
+        };
+        push_metadata_filter(&mut builder, MetadataEntityType::Document, "documents.id", &tags);
+
+        let stats = builder
+            .build_query_as::<DocumentStatsQuery>()
+            .fetch_one(&self.pool)
+            .await?;
 
         Ok(stats)
     }
 
-    /// Get recent activity
+    /// Get open compliance items over time
+    ///
+    /// Points run from `from` to `to` inclusive. Status at each point comes
+    /// from the status history; the period filter only sets the range, so
+    /// items created earlier still count.
+    #[instrument(skip(self))]
+    pub async fn get_burndown(
+        &self,
+        user_id: Uuid,
+        filter: &DashboardFilter,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        interval: TrendInterval,
+    ) -> AppResult<Vec<BurndownPoint>> {
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "SELECT p.point AS date,
+                COUNT(i.id) FILTER (WHERE s.status IN ('pending', 'in_progress')) AS open_items,
+                COUNT(i.id) FILTER (WHERE s.status IN ('pending', 'in_progress') AND i.due_date <= p.point) AS overdue_items,
+                COUNT(i.id) FILTER (WHERE i.due_date IS NULL OR i.due_date > p.point) AS planned_open_items
+            FROM (SELECT generate_series("
+        );
+        builder
+            .push_bind(from)
+            .push("::timestamptz, ")
+            .push_bind(to)
+            .push("::timestamptz, ")
+            .push_bind(format!("1 {}", interval.as_str()))
+            .push("::interval) UNION SELECT ")
+            .push_bind(to)
+            .push("::timestamptz) AS p(point)
+            LEFT JOIN compliance_items i ON i.created_at <= p.point AND i.user_id = ")
+            .push_bind(user_id);
+        push_compliance_filter(&mut builder, "i", filter);
+        builder.push(
+            " LEFT JOIN LATERAL (SELECT h.to_status AS status FROM compliance_status_history h \
+             WHERE h.compliance_item_id = i.id AND h.changed_at <= p.point \
+             ORDER BY h.changed_at DESC, h.id DESC LIMIT 1) s ON true
+            GROUP BY p.point
+            ORDER BY p.point",
+        );
+
+        let points = builder
+            .build_query_as::<BurndownPoint>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(points)
+    }
+
+    /// Get recent activity from recorded events
     #[instrument(skip(self))]
     pub async fn get_recent_activity(&self, user_id: Uuid, limit: i64) -> AppResult<Vec<ActivityItemQuery>> {
-        let activities = sqlx::query_as!(
-            ActivityItemQuery,
+        let activities = sqlx::query_as::<_, ActivityItemQuery>(
             r#"
-            SELECT id, 'compliance_created' as "activity_type!", title, created_at as "timestamp!"
-            FROM compliance_items
+            SELECT
+                entity_id AS id,
+                CASE
+                    WHEN entity_type = 'compliance_item' AND verb = 'created' THEN 'compliance_created'
+                    WHEN entity_type = 'document' AND verb = 'created' THEN 'document_uploaded'
+                    ELSE REPLACE(entity_type, 'compliance_item', 'compliance') || '_' || verb
+                END AS activity_type,
+                entity_title AS title,
+                created_at AS timestamp
+            FROM activity_events
             WHERE user_id = $1
-            UNION ALL
-            SELECT id, 'document_uploaded' as "activity_type!", filename as title, uploaded_at as "timestamp!"
-            FROM documents
-            WHERE user_id = $1
-            ORDER BY "timestamp!" DESC
+            ORDER BY created_at DESC, id DESC
             LIMIT $2
-            "#,
-            user_id,
-            limit
+            "#
         )
+        .bind(user_id)
+        .bind(limit)
         .fetch_all(&self.pool)
         .await?;
 
         Ok(activities)
     }
+
+    /// Get the latest risk assessment of each assessed compliance item
+    #[instrument(skip(self))]
+    pub async fn get_latest_risk_scores(&self, user_id: Uuid) -> AppResult<Vec<LatestRiskQuery>> {
+        let scores = sqlx::query_as::<_, LatestRiskQuery>(
+            r#"
+            SELECT DISTINCT ON (rs.compliance_item_id)
+                rs.compliance_item_id, c.title, rs.id AS risk_score_id, rs.risk_category,
+                rs.risk_score, rs.risk_level, rs.residual_score, rs.residual_level,
+                rs.likelihood, rs.impact, rs.assessment_date
+            FROM risk_scores rs
+            JOIN compliance_items c ON c.id = rs.compliance_item_id
+            WHERE c.user_id = $1
+            ORDER BY rs.compliance_item_id, rs.assessment_date DESC, rs.created_at DESC
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(scores)
+    }
+}
+
+/// Restrict a timestamp column to the filtered period
+fn push_period_filter(builder: &mut QueryBuilder<'_, Postgres>, column: &str, filter: &DashboardFilter) {
+    if let Some(from) = filter.from {
+        builder.push(format!(" AND {} >= ", column)).push_bind(from);
+    }
+    if let Some(to) = filter.to {
+        builder.push(format!(" AND {} < ", column)).push_bind(to);
+    }
+}
+
+/// Apply framework, assignee and metadata filters to compliance items aliased `alias`
+fn push_compliance_filter(builder: &mut QueryBuilder<'_, Postgres>, alias: &str, filter: &DashboardFilter) {
+    if let Some(ref framework) = filter.framework {
+        builder
+            .push(format!(" AND {}.framework = ", alias))
+            .push_bind(framework.clone());
+    }
+    if let Some(assignee_id) = filter.assignee_id {
+        builder
+            .push(format!(" AND {}.assignee_id = ", alias))
+            .push_bind(assignee_id);
+    }
+    push_metadata_filter(
+        builder,
+        MetadataEntityType::ComplianceItem,
+        &format!("{}.id", alias),
+        &filter.metadata,
+    );
 }
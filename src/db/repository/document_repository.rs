@@ -1,11 +1,21 @@
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
 use uuid::Uuid;
 
 use crate::{
+    db::filters::push_metadata_filter,
+    utils::envelope::WrappedDataKey,
     error::AppResult,
-    models::{CreateDocumentDto, Document, UpdateDocumentDto},
+    models::{
+        CreateDocumentDto, Document, DocumentVersion, DuplicateDocument, MetadataEntityType, MetadataFilter,
+        ScanStatus, UpdateDocumentDto,
+    },
 };
 
+/// Columns of a document
+const DOCUMENT_COLUMNS: &str = "id, user_id, filename, storage_key, content_sha256, file_size, mime_type, \
+     extracted_text, ai_analysis, scan_status, scan_signature, scanned_at, encryption_key_id, wrapped_data_key, \
+     pii_categories, ocr_pages, ocr_low_confidence, current_version, uploaded_at";
+
 /// Document repository for database operations
 ///
 /// Handles all document-related database queries
@@ -33,6 +43,7 @@ impl DocumentRepository {
     /// # Arguments
     ///
     /// * `user_id` - User UUID
+    /// * `filter` - Tag and custom field filters
     ///
     /// # Returns
     ///
@@ -41,17 +52,19 @@ impl DocumentRepository {
     /// # Errors
     ///
     /// Returns database error if query fails
-    pub async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Document>> {
-        let documents = sqlx::query_as::<_, Document>(
-            "SELECT id, user_id, filename, file_path, file_size, mime_type, 
-                    extracted_text, ai_analysis, uploaded_at
-             FROM documents
-             WHERE user_id = $1
-             ORDER BY uploaded_at DESC"
-        )
-        .bind(user_id)
-        .fetch_all(&self.pool)
-        .await?;
+    pub async fn find_by_user(&self, user_id: Uuid, filter: &MetadataFilter) -> AppResult<Vec<Document>> {
+        let mut builder = QueryBuilder::<Postgres>::new(format!(
+            "SELECT {} FROM documents WHERE user_id = ",
+            DOCUMENT_COLUMNS
+        ));
+        builder.push_bind(user_id);
+        push_metadata_filter(&mut builder, MetadataEntityType::Document, "documents.id", filter);
+        builder.push(" ORDER BY uploaded_at DESC");
+
+        let documents = builder
+            .build_query_as::<Document>()
+            .fetch_all(&self.pool)
+            .await?;
 
         Ok(documents)
     }
@@ -71,12 +84,10 @@ impl DocumentRepository {
     ///
     /// Returns database error if query fails
     pub async fn find_by_id(&self, id: Uuid, user_id: Uuid) -> AppResult<Option<Document>> {
-        let document = sqlx::query_as::<_, Document>(
-            "SELECT id, user_id, filename, file_path, file_size, mime_type, 
-                    extracted_text, ai_analysis, uploaded_at
-             FROM documents
-             WHERE id = $1 AND user_id = $2"
-        )
+        let document = sqlx::query_as::<_, Document>(&format!(
+            "SELECT {} FROM documents WHERE id = $1 AND user_id = $2",
+            DOCUMENT_COLUMNS
+        ))
         .bind(id)
         .bind(user_id)
         .fetch_optional(&self.pool)
@@ -100,28 +111,152 @@ impl DocumentRepository {
     ///
     /// Returns database error if insertion fails
     pub async fn create(&self, user_id: Uuid, dto: &CreateDocumentDto) -> AppResult<Document> {
-        let document = sqlx::query_as::<_, Document>(
-            "INSERT INTO documents (user_id, filename, file_path, file_size, mime_type, extracted_text)
-             VALUES ($1, $2, $3, $4, $5, $6)
-             RETURNING id, user_id, filename, file_path, file_size, mime_type, 
-                       extracted_text, ai_analysis, uploaded_at"
-        )
+        let mut tx = self.pool.begin().await?;
+        let document = Self::create_in(&mut tx, user_id, dto).await?;
+        tx.commit().await?;
+
+        Ok(document)
+    }
+
+    /// Find and lock a document inside a transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `id` - Document UUID
+    /// * `user_id` - User UUID (for authorization)
+    ///
+    /// # Returns
+    ///
+    /// Locked document if found and owned by user
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn lock_in(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<Option<Document>> {
+        let document = sqlx::query_as::<_, Document>(&format!(
+            "SELECT {} FROM documents WHERE id = $1 AND user_id = $2 FOR UPDATE",
+            DOCUMENT_COLUMNS
+        ))
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        Ok(document)
+    }
+
+    /// Make a version the current one of its document
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `version` - New current version
+    ///
+    /// # Returns
+    ///
+    /// Updated Document
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if update fails
+    pub async fn set_current_version_in(
+        tx: &mut Transaction<'_, Postgres>,
+        version: &DocumentVersion,
+    ) -> AppResult<Document> {
+        let document = sqlx::query_as::<_, Document>(&format!(
+            "UPDATE documents
+             SET filename = $2, storage_key = $3, content_sha256 = $4, file_size = $5, mime_type = $6,
+                 extracted_text = $7, ai_analysis = $8, scan_status = $9, scan_signature = $10,
+                 scanned_at = $11, encryption_key_id = $12, wrapped_data_key = $13, pii_categories = $14,
+                 ocr_pages = $15, ocr_low_confidence = $16, current_version = $17
+             WHERE id = $1
+             RETURNING {}",
+            DOCUMENT_COLUMNS
+        ))
+        .bind(version.document_id)
+        .bind(&version.filename)
+        .bind(&version.storage_key)
+        .bind(&version.content_sha256)
+        .bind(version.file_size)
+        .bind(&version.mime_type)
+        .bind(&version.extracted_text)
+        .bind(&version.ai_analysis)
+        .bind(&version.scan_status)
+        .bind(&version.scan_signature)
+        .bind(version.scanned_at)
+        .bind(&version.encryption_key_id)
+        .bind(&version.wrapped_data_key)
+        .bind(&version.pii_categories)
+        .bind(&version.ocr_pages)
+        .bind(version.ocr_low_confidence)
+        .bind(version.version_number)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(document)
+    }
+
+    /// Create a new document record inside a transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `user_id` - User UUID who uploaded this document
+    /// * `dto` - Document creation data
+    ///
+    /// # Returns
+    ///
+    /// Created Document
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if insertion fails
+    pub async fn create_in(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        dto: &CreateDocumentDto,
+    ) -> AppResult<Document> {
+        let document = sqlx::query_as::<_, Document>(&format!(
+            "INSERT INTO documents (user_id, filename, storage_key, content_sha256, file_size, mime_type,
+                                    extracted_text, ai_analysis, scan_status, scan_signature, scanned_at,
+                                    encryption_key_id, wrapped_data_key, pii_categories, ocr_pages,
+                                    ocr_low_confidence)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, COALESCE($9, 'not_scanned'), $10, $11, $12, $13, $14, $15, $16)
+             RETURNING {}",
+            DOCUMENT_COLUMNS
+        ))
         .bind(user_id)
         .bind(&dto.filename)
-        .bind(&dto.file_path)
-        .bind(dto.file_size as i32)
+        .bind(&dto.storage_key)
+        .bind(&dto.content_sha256)
+        .bind(dto.file_size)
         .bind(&dto.mime_type)
         .bind(&dto.extracted_text)
-        .fetch_one(&self.pool)
+        .bind(&dto.ai_analysis)
+        .bind(&dto.scan_status)
+        .bind(&dto.scan_signature)
+        .bind(dto.scanned_at)
+        .bind(&dto.encryption_key_id)
+        .bind(&dto.wrapped_data_key)
+        .bind(&dto.pii_categories)
+        .bind(&dto.ocr_pages)
+        .bind(dto.ocr_low_confidence)
+        .fetch_one(&mut **tx)
         .await?;
 
         Ok(document)
     }
 
-    /// Update document with AI analysis
+    /// Update document with AI analysis inside an open transaction
     ///
     /// # Arguments
     ///
+    /// * `tx` - Open transaction
     /// * `id` - Document UUID
     /// * `user_id` - User UUID (for authorization)
     /// * `dto` - Update data
@@ -133,53 +268,218 @@ impl DocumentRepository {
     /// # Errors
     ///
     /// Returns database error if update fails
-    pub async fn update(
-        &self,
+    pub async fn update_in(
+        tx: &mut Transaction<'_, Postgres>,
         id: Uuid,
         user_id: Uuid,
         dto: &UpdateDocumentDto,
     ) -> AppResult<Option<Document>> {
-        let document = sqlx::query_as::<_, Document>(
+        let document = sqlx::query_as::<_, Document>(&format!(
             "UPDATE documents 
              SET extracted_text = COALESCE($3, extracted_text),
-                 ai_analysis = COALESCE($4, ai_analysis)
+                 ai_analysis = COALESCE($4, ai_analysis),
+                 pii_categories = COALESCE($5, pii_categories)
              WHERE id = $1 AND user_id = $2
-             RETURNING id, user_id, filename, file_path, file_size, mime_type, 
-                       extracted_text, ai_analysis, uploaded_at"
-        )
+             RETURNING {}",
+            DOCUMENT_COLUMNS
+        ))
         .bind(id)
         .bind(user_id)
         .bind(&dto.extracted_text)
         .bind(&dto.ai_analysis)
-        .fetch_optional(&self.pool)
+        .bind(&dto.pii_categories)
+        .fetch_optional(&mut **tx)
         .await?;
 
         Ok(document)
     }
 
-    /// Delete a document
+    /// Record the malware scan result of a document's current file
     ///
     /// # Arguments
     ///
     /// * `id` - Document UUID
     /// * `user_id` - User UUID (for authorization)
+    /// * `status` - Scan status
+    /// * `signature` - Detected malware signature, if infected
     ///
     /// # Returns
     ///
-    /// true if deleted, false if not found/unauthorized
+    /// Updated Document or None if not found/unauthorized
     ///
     /// # Errors
     ///
-    /// Returns database error if deletion fails
-    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> AppResult<bool> {
+    /// Returns database error if update fails
+    pub async fn set_scan_result(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        status: ScanStatus,
+        signature: Option<&str>,
+    ) -> AppResult<Option<Document>> {
+        let document = sqlx::query_as::<_, Document>(&format!(
+            "UPDATE documents
+             SET scan_status = $3, scan_signature = $4, scanned_at = NOW()
+             WHERE id = $1 AND user_id = $2
+             RETURNING {}",
+            DOCUMENT_COLUMNS
+        ))
+        .bind(id)
+        .bind(user_id)
+        .bind(status.as_str())
+        .bind(signature)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(document)
+    }
+
+    /// Replace a wrapped data key inside a transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `old` - Data key as currently stored
+    /// * `new` - The same data key wrapped by another master key
+    ///
+    /// # Returns
+    ///
+    /// Number of documents updated
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if update fails
+    pub async fn rewrap_data_key_in(
+        tx: &mut Transaction<'_, Postgres>,
+        old: &WrappedDataKey,
+        new: &WrappedDataKey,
+    ) -> AppResult<u64> {
         let result = sqlx::query(
-            "DELETE FROM documents WHERE id = $1 AND user_id = $2"
+            "UPDATE documents
+             SET encryption_key_id = $3, wrapped_data_key = $4
+             WHERE encryption_key_id = $1 AND wrapped_data_key = $2",
         )
+        .bind(&old.key_id)
+        .bind(&old.wrapped)
+        .bind(&new.key_id)
+        .bind(&new.wrapped)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Delete a document inside a transaction
+    ///
+    /// The stored object is left in place; callers remove it once nothing
+    /// references it any more.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `id` - Document UUID
+    /// * `user_id` - User UUID (for authorization)
+    ///
+    /// # Returns
+    ///
+    /// Deleted document, None if not found/unauthorized
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if deletion fails
+    pub async fn delete_in(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<Option<Document>> {
+        let document = sqlx::query_as::<_, Document>(&format!(
+            "DELETE FROM documents WHERE id = $1 AND user_id = $2 RETURNING {}",
+            DOCUMENT_COLUMNS
+        ))
         .bind(id)
         .bind(user_id)
-        .execute(&self.pool)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        Ok(document)
+    }
+
+    /// Find a user's other documents with the given content
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `content_sha256` - SHA-256 of the file content
+    /// * `exclude_id` - Document to leave out (the new upload)
+    ///
+    /// # Returns
+    ///
+    /// Duplicates, oldest first
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_duplicates(
+        &self,
+        user_id: Uuid,
+        content_sha256: &str,
+        exclude_id: Uuid,
+    ) -> AppResult<Vec<DuplicateDocument>> {
+        let duplicates = sqlx::query_as::<_, DuplicateDocument>(
+            "SELECT id, filename, uploaded_at FROM documents
+             WHERE user_id = $1 AND content_sha256 = $2 AND id <> $3
+             ORDER BY uploaded_at"
+        )
+        .bind(user_id)
+        .bind(content_sha256)
+        .bind(exclude_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(duplicates)
+    }
+
+    /// Check whether any document or version still references a stored object
+    ///
+    /// # Arguments
+    ///
+    /// * `storage_key` - Storage key
+    ///
+    /// # Returns
+    ///
+    /// True if at least one document uses the object
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn storage_key_in_use(&self, storage_key: &str) -> AppResult<bool> {
+        let in_use = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM documents WHERE storage_key = $1)
+                 OR EXISTS (SELECT 1 FROM document_versions WHERE storage_key = $1)"
+        )
+        .bind(storage_key)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(in_use)
+    }
+
+    /// Find the storage keys of all documents and their versions
+    ///
+    /// # Returns
+    ///
+    /// Distinct storage keys
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_all_storage_keys(&self) -> AppResult<Vec<String>> {
+        let keys = sqlx::query_scalar::<_, String>(
+            "SELECT storage_key FROM documents UNION SELECT storage_key FROM document_versions"
+        )
+        .fetch_all(&self.pool)
         .await?;
 
-        Ok(result.rows_affected() > 0)
+        Ok(keys)
     }
 }
@@ -0,0 +1,337 @@
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    error::AppResult,
+    models::{CreateDocumentDto, DocumentVersion},
+    utils::envelope::WrappedDataKey,
+};
+
+/// Columns of a document version
+const VERSION_COLUMNS: &str = "id, document_id, version_number, filename, storage_key, content_sha256, \
+     file_size, mime_type, extracted_text, ai_analysis, scan_status, scan_signature, scanned_at, \
+     encryption_key_id, wrapped_data_key, pii_categories, ocr_pages, ocr_low_confidence, created_at";
+
+/// Repository for document version database operations
+///
+/// Callers check ownership of the parent document first.
+pub struct DocumentVersionRepository {
+    pool: PgPool,
+}
+
+impl DocumentVersionRepository {
+    /// Create new repository
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    ///
+    /// # Returns
+    ///
+    /// Repository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find all versions of a document
+    ///
+    /// # Arguments
+    ///
+    /// * `document_id` - Document UUID
+    ///
+    /// # Returns
+    ///
+    /// Versions, oldest first
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_by_document(&self, document_id: Uuid) -> AppResult<Vec<DocumentVersion>> {
+        let versions = sqlx::query_as::<_, DocumentVersion>(&format!(
+            "SELECT {} FROM document_versions WHERE document_id = $1 ORDER BY version_number",
+            VERSION_COLUMNS
+        ))
+        .bind(document_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(versions)
+    }
+
+    /// Find one version of a document
+    ///
+    /// # Arguments
+    ///
+    /// * `document_id` - Document UUID
+    /// * `version_number` - Version number
+    ///
+    /// # Returns
+    ///
+    /// Version if it exists
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_by_number(&self, document_id: Uuid, version_number: i32) -> AppResult<Option<DocumentVersion>> {
+        let version = sqlx::query_as::<_, DocumentVersion>(&format!(
+            "SELECT {} FROM document_versions WHERE document_id = $1 AND version_number = $2",
+            VERSION_COLUMNS
+        ))
+        .bind(document_id)
+        .bind(version_number)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(version)
+    }
+
+    /// Find all versions of a document inside a transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `document_id` - Document UUID
+    ///
+    /// # Returns
+    ///
+    /// Versions, oldest first
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_by_document_in(
+        tx: &mut Transaction<'_, Postgres>,
+        document_id: Uuid,
+    ) -> AppResult<Vec<DocumentVersion>> {
+        let versions = sqlx::query_as::<_, DocumentVersion>(&format!(
+            "SELECT {} FROM document_versions WHERE document_id = $1 ORDER BY version_number",
+            VERSION_COLUMNS
+        ))
+        .bind(document_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(versions)
+    }
+
+    /// Find the most complete version of a user's documents with the given content
+    ///
+    /// Versions carrying an AI analysis are preferred over ones that only
+    /// have extracted text. Only the user's own documents qualify, since
+    /// clients can edit text and analysis and another user's could be forged.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `content_sha256` - SHA-256 of the file content
+    ///
+    /// # Returns
+    ///
+    /// Version with extracted text or analysis, None if there is none
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_processed_by_sha256(
+        &self,
+        user_id: Uuid,
+        content_sha256: &str,
+    ) -> AppResult<Option<DocumentVersion>> {
+        let version = sqlx::query_as::<_, DocumentVersion>(&format!(
+            "SELECT {} FROM document_versions
+             WHERE content_sha256 = $2 AND (extracted_text IS NOT NULL OR ai_analysis IS NOT NULL)
+               AND document_id IN (SELECT id FROM documents WHERE user_id = $1)
+             ORDER BY (ai_analysis IS NOT NULL) DESC, created_at DESC
+             LIMIT 1",
+            VERSION_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(content_sha256)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(version)
+    }
+
+    /// Find the newest version of a user's documents stored under a key
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `storage_key` - Storage key
+    ///
+    /// # Returns
+    ///
+    /// Version if the user uploaded a file with this key
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_by_storage_key(&self, user_id: Uuid, storage_key: &str) -> AppResult<Option<DocumentVersion>> {
+        let version = sqlx::query_as::<_, DocumentVersion>(&format!(
+            "SELECT {} FROM document_versions
+             WHERE storage_key = $2 AND document_id IN (SELECT id FROM documents WHERE user_id = $1)
+             ORDER BY created_at DESC
+             LIMIT 1",
+            VERSION_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(storage_key)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(version)
+    }
+
+    /// Find any version stored with the given content inside a transaction
+    ///
+    /// Used to encrypt a shared file with the data key its existing versions use.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `content_sha256` - SHA-256 of the file content
+    ///
+    /// # Returns
+    ///
+    /// Oldest version with this content, None if there is none
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_by_sha256_in(
+        tx: &mut Transaction<'_, Postgres>,
+        content_sha256: &str,
+    ) -> AppResult<Option<DocumentVersion>> {
+        let version = sqlx::query_as::<_, DocumentVersion>(&format!(
+            "SELECT {} FROM document_versions WHERE content_sha256 = $1 ORDER BY created_at LIMIT 1",
+            VERSION_COLUMNS
+        ))
+        .bind(content_sha256)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        Ok(version)
+    }
+
+    /// Find the data keys not wrapped by the given master key
+    ///
+    /// # Arguments
+    ///
+    /// * `key_id` - Fingerprint of the active master key
+    ///
+    /// # Returns
+    ///
+    /// Distinct wrapped data keys of documents and versions
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_wrapped_keys_not_under(&self, key_id: &str) -> AppResult<Vec<WrappedDataKey>> {
+        let keys: Vec<(String, String)> = sqlx::query_as(
+            "SELECT encryption_key_id, wrapped_data_key FROM document_versions
+             WHERE encryption_key_id <> $1
+             UNION
+             SELECT encryption_key_id, wrapped_data_key FROM documents
+             WHERE encryption_key_id <> $1",
+        )
+        .bind(key_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(keys
+            .into_iter()
+            .map(|(key_id, wrapped)| WrappedDataKey { key_id, wrapped })
+            .collect())
+    }
+
+    /// Replace a wrapped data key inside a transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `old` - Data key as currently stored
+    /// * `new` - The same data key wrapped by another master key
+    ///
+    /// # Returns
+    ///
+    /// Number of versions updated
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if update fails
+    pub async fn rewrap_data_key_in(
+        tx: &mut Transaction<'_, Postgres>,
+        old: &WrappedDataKey,
+        new: &WrappedDataKey,
+    ) -> AppResult<u64> {
+        let result = sqlx::query(
+            "UPDATE document_versions
+             SET encryption_key_id = $3, wrapped_data_key = $4
+             WHERE encryption_key_id = $1 AND wrapped_data_key = $2",
+        )
+        .bind(&old.key_id)
+        .bind(&old.wrapped)
+        .bind(&new.key_id)
+        .bind(&new.wrapped)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Create a version inside a transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `document_id` - Parent document UUID
+    /// * `version_number` - Number of the new version
+    /// * `dto` - File of the version
+    ///
+    /// # Returns
+    ///
+    /// Created version
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if insertion fails
+    pub async fn create_in(
+        tx: &mut Transaction<'_, Postgres>,
+        document_id: Uuid,
+        version_number: i32,
+        dto: &CreateDocumentDto,
+    ) -> AppResult<DocumentVersion> {
+        let version = sqlx::query_as::<_, DocumentVersion>(&format!(
+            "INSERT INTO document_versions
+                (document_id, version_number, filename, storage_key, content_sha256, file_size, mime_type,
+                 extracted_text, ai_analysis, scan_status, scan_signature, scanned_at, encryption_key_id,
+                 wrapped_data_key, pii_categories, ocr_pages, ocr_low_confidence)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, COALESCE($10, 'not_scanned'), $11, $12, $13, $14, $15, $16,
+                     $17)
+             RETURNING {}",
+            VERSION_COLUMNS
+        ))
+        .bind(document_id)
+        .bind(version_number)
+        .bind(&dto.filename)
+        .bind(&dto.storage_key)
+        .bind(&dto.content_sha256)
+        .bind(dto.file_size)
+        .bind(&dto.mime_type)
+        .bind(&dto.extracted_text)
+        .bind(&dto.ai_analysis)
+        .bind(&dto.scan_status)
+        .bind(&dto.scan_signature)
+        .bind(dto.scanned_at)
+        .bind(&dto.encryption_key_id)
+        .bind(&dto.wrapped_data_key)
+        .bind(&dto.pii_categories)
+        .bind(&dto.ocr_pages)
+        .bind(dto.ocr_low_confidence)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(version)
+    }
+}
@@ -1,11 +1,33 @@
+pub mod activity_repository;
+pub mod blob_repository;
+pub mod comment_repository;
 pub mod compliance_repository;
+pub mod custom_field_repository;
 pub mod document_repository;
+pub mod document_version_repository;
+pub mod notification_repository;
+pub mod realtime_repository;
+pub mod report_repository;
+pub mod risk_control_repository;
 pub mod risk_score_repository;
+pub mod tag_repository;
 pub mod dashboard_repository;
 pub mod user_repository;
+pub mod webhook_repository;
 
+pub use activity_repository::ActivityRepository;
+pub use blob_repository::{BlobRepository, ClaimedBlob};
+pub use comment_repository::CommentRepository;
 pub use compliance_repository::ComplianceRepository;
+pub use custom_field_repository::CustomFieldRepository;
 pub use document_repository::DocumentRepository;
+pub use document_version_repository::DocumentVersionRepository;
+pub use notification_repository::{DueReminderCandidate, NotificationRepository};
+pub use realtime_repository::{RealtimeRepository, REALTIME_CHANNEL};
+pub use report_repository::ReportRepository;
+pub use risk_control_repository::{ControlWithInherentScore, RiskControlRepository};
 pub use risk_score_repository::RiskScoreRepository;
+pub use tag_repository::TagRepository;
 pub use dashboard_repository::DashboardRepository;
 pub use user_repository::UserRepository;
+pub use webhook_repository::{AttemptOutcome, ClaimedDelivery, WebhookRepository};
@@ -0,0 +1,338 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    error::AppResult,
+    models::{NewNotification, Notification, NotificationPreferences, DEFAULT_REMINDER_LEAD_DAYS},
+};
+
+/// Open compliance item due soon, paired with a recipient's lead times
+#[derive(Debug, sqlx::FromRow)]
+pub struct DueReminderCandidate {
+    /// Compliance item UUID
+    pub compliance_item_id: Uuid,
+
+    /// Compliance item title
+    pub title: String,
+
+    /// Due date
+    pub due_date: DateTime<Utc>,
+
+    /// Recipient (owner)
+    pub user_id: Uuid,
+
+    /// Recipient's reminder lead times in days
+    pub reminder_lead_days: Vec<i32>,
+}
+
+/// Repository for notification and notification preference database operations
+pub struct NotificationRepository {
+    pool: PgPool,
+}
+
+impl NotificationRepository {
+    /// Create new repository
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    ///
+    /// # Returns
+    ///
+    /// Repository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find a user's notification preferences
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// Preferences if the user has saved any
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_preferences(&self, user_id: Uuid) -> AppResult<Option<NotificationPreferences>> {
+        let preferences = sqlx::query_as::<_, NotificationPreferences>(
+            "SELECT user_id, in_app_enabled, email_enabled, due_date_reminders, status_changes,
+                    assignments, risk_alerts, reminder_lead_days
+             FROM notification_preferences
+             WHERE user_id = $1"
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(preferences)
+    }
+
+    /// Save a user's notification preferences
+    ///
+    /// # Arguments
+    ///
+    /// * `preferences` - Complete preferences
+    ///
+    /// # Returns
+    ///
+    /// Saved preferences
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if the upsert fails
+    pub async fn save_preferences(
+        &self,
+        preferences: &NotificationPreferences,
+    ) -> AppResult<NotificationPreferences> {
+        let saved = sqlx::query_as::<_, NotificationPreferences>(
+            "INSERT INTO notification_preferences
+                 (user_id, in_app_enabled, email_enabled, due_date_reminders, status_changes,
+                  assignments, risk_alerts, reminder_lead_days)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             ON CONFLICT (user_id) DO UPDATE SET
+                 in_app_enabled = EXCLUDED.in_app_enabled,
+                 email_enabled = EXCLUDED.email_enabled,
+                 due_date_reminders = EXCLUDED.due_date_reminders,
+                 status_changes = EXCLUDED.status_changes,
+                 assignments = EXCLUDED.assignments,
+                 risk_alerts = EXCLUDED.risk_alerts,
+                 reminder_lead_days = EXCLUDED.reminder_lead_days,
+                 updated_at = NOW()
+             RETURNING user_id, in_app_enabled, email_enabled, due_date_reminders, status_changes,
+                       assignments, risk_alerts, reminder_lead_days"
+        )
+        .bind(preferences.user_id)
+        .bind(preferences.in_app_enabled)
+        .bind(preferences.email_enabled)
+        .bind(preferences.due_date_reminders)
+        .bind(preferences.status_changes)
+        .bind(preferences.assignments)
+        .bind(preferences.risk_alerts)
+        .bind(&preferences.reminder_lead_days)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(saved)
+    }
+
+    /// Create an in-app notification
+    ///
+    /// # Arguments
+    ///
+    /// * `notification` - Notification to store
+    ///
+    /// # Returns
+    ///
+    /// Created notification
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if insert fails
+    pub async fn create(&self, notification: &NewNotification) -> AppResult<Notification> {
+        let created = sqlx::query_as::<_, Notification>(
+            "INSERT INTO notifications (user_id, kind, title, body, entity_type, entity_id)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING id, user_id, kind, title, body, entity_type, entity_id, read_at, created_at"
+        )
+        .bind(notification.user_id)
+        .bind(notification.kind.as_str())
+        .bind(&notification.title)
+        .bind(&notification.body)
+        .bind(notification.entity_type.as_str())
+        .bind(notification.entity_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(created)
+    }
+
+    /// Find a user's notifications
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `unread_only` - Skip read notifications
+    /// * `limit` - Maximum number of notifications
+    ///
+    /// # Returns
+    ///
+    /// Notifications, newest first
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_by_user(&self, user_id: Uuid, unread_only: bool, limit: i64) -> AppResult<Vec<Notification>> {
+        let notifications = sqlx::query_as::<_, Notification>(
+            "SELECT id, user_id, kind, title, body, entity_type, entity_id, read_at, created_at
+             FROM notifications
+             WHERE user_id = $1 AND (NOT $2 OR read_at IS NULL)
+             ORDER BY created_at DESC, id DESC
+             LIMIT $3"
+        )
+        .bind(user_id)
+        .bind(unread_only)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(notifications)
+    }
+
+    /// Count a user's unread notifications
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// Number of unread notifications
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn count_unread(&self, user_id: Uuid) -> AppResult<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND read_at IS NULL"
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Mark a notification as read
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Notification UUID
+    /// * `user_id` - User UUID (for authorization)
+    ///
+    /// # Returns
+    ///
+    /// Updated notification if found and owned by user
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if update fails
+    pub async fn mark_read(&self, id: Uuid, user_id: Uuid) -> AppResult<Option<Notification>> {
+        let notification = sqlx::query_as::<_, Notification>(
+            "UPDATE notifications
+             SET read_at = COALESCE(read_at, NOW())
+             WHERE id = $1 AND user_id = $2
+             RETURNING id, user_id, kind, title, body, entity_type, entity_id, read_at, created_at"
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(notification)
+    }
+
+    /// Mark all of a user's notifications as read
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// Number of notifications marked
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if update fails
+    pub async fn mark_all_read(&self, user_id: Uuid) -> AppResult<u64> {
+        let result = sqlx::query(
+            "UPDATE notifications SET read_at = NOW() WHERE user_id = $1 AND read_at IS NULL"
+        )
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Find open compliance items within reach of a recipient's reminder lead times
+    ///
+    /// Owners are the recipients, as assignees cannot open the items assigned
+    /// to them; users who turned due-date reminders off are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - Reference time
+    ///
+    /// # Returns
+    ///
+    /// One candidate per item and recipient
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_due_reminder_candidates(&self, now: DateTime<Utc>) -> AppResult<Vec<DueReminderCandidate>> {
+        let candidates = sqlx::query_as::<_, DueReminderCandidate>(
+            "SELECT c.id AS compliance_item_id, c.title, c.due_date, c.user_id,
+                    COALESCE(p.reminder_lead_days, $2) AS reminder_lead_days
+             FROM compliance_items c
+             LEFT JOIN notification_preferences p ON p.user_id = c.user_id
+             WHERE c.status IN ('pending', 'in_progress')
+               AND c.due_date > $1
+               AND COALESCE(p.due_date_reminders, TRUE)
+               AND c.due_date <= $1 + make_interval(days => (
+                   SELECT COALESCE(MAX(d), 0) FROM unnest(COALESCE(p.reminder_lead_days, $2)) d
+               ))"
+        )
+        .bind(now)
+        .bind(DEFAULT_REMINDER_LEAD_DAYS.to_vec())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(candidates)
+    }
+
+    /// Record due-date reminders as sent
+    ///
+    /// # Arguments
+    ///
+    /// * `compliance_item_id` - Compliance item UUID
+    /// * `user_id` - Recipient
+    /// * `due_date` - Due date the reminders refer to
+    /// * `lead_days` - Lead times reached
+    ///
+    /// # Returns
+    ///
+    /// Lead times that had not been recorded before
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if insert fails
+    pub async fn record_reminders(
+        &self,
+        compliance_item_id: Uuid,
+        user_id: Uuid,
+        due_date: DateTime<Utc>,
+        lead_days: &[i32],
+    ) -> AppResult<Vec<i32>> {
+        let recorded: Vec<i32> = sqlx::query_scalar(
+            "INSERT INTO due_date_reminders (compliance_item_id, user_id, due_date, lead_days)
+             SELECT $1, $2, $3, unnest($4::INTEGER[])
+             ON CONFLICT DO NOTHING
+             RETURNING lead_days"
+        )
+        .bind(compliance_item_id)
+        .bind(user_id)
+        .bind(due_date)
+        .bind(lead_days)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(recorded)
+    }
+}
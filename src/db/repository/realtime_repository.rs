@@ -0,0 +1,51 @@
+use sqlx::PgPool;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::RealtimeMessage,
+};
+
+/// Postgres channel carrying real-time events between replicas
+pub const REALTIME_CHANNEL: &str = "parseguard_realtime";
+
+/// Repository for publishing real-time events through `NOTIFY`
+pub struct RealtimeRepository {
+    pool: PgPool,
+}
+
+impl RealtimeRepository {
+    /// Create new repository
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    ///
+    /// # Returns
+    ///
+    /// Repository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Notify every listening replica of an event
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - Event and the user it is addressed to
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if the notification fails
+    pub async fn notify(&self, message: &RealtimeMessage) -> AppResult<()> {
+        let payload = serde_json::to_string(message)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize realtime event: {}", e)))?;
+
+        sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(REALTIME_CHANNEL)
+            .bind(payload)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    error::AppResult,
+    models::{AuditAssessment, AuditEvidence},
+};
+
+/// Repository for report queries
+pub struct ReportRepository {
+    pool: PgPool,
+}
+
+impl ReportRepository {
+    /// Create new repository
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    ///
+    /// # Returns
+    ///
+    /// Repository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find the latest assessment of each compliance item
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// Assessments ordered by compliance item title
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_latest_assessments(&self, user_id: Uuid) -> AppResult<Vec<AuditAssessment>> {
+        let assessments = sqlx::query_as::<_, AuditAssessment>(
+            "SELECT * FROM (
+                 SELECT DISTINCT ON (rs.compliance_item_id)
+                     rs.compliance_item_id, c.title AS compliance_item_title, rs.risk_category,
+                     rs.risk_score, rs.risk_level, rs.residual_score, rs.residual_level,
+                     rs.likelihood, rs.impact, rs.assessment_date, rs.assessed_by, rs.notes,
+                     rs.ai_confidence, rs.ai_reasoning
+                 FROM risk_scores rs
+                 JOIN compliance_items c ON c.id = rs.compliance_item_id
+                 WHERE c.user_id = $1
+                 ORDER BY rs.compliance_item_id, rs.assessment_date DESC, rs.created_at DESC
+             ) latest
+             ORDER BY LOWER(compliance_item_title)"
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(assessments)
+    }
+
+    /// Find documents linked to compliance items through risk scores
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// Evidence documents ordered by filename
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_evidence(&self, user_id: Uuid) -> AppResult<Vec<AuditEvidence>> {
+        let evidence = sqlx::query_as::<_, AuditEvidence>(
+            "SELECT d.id AS document_id, d.filename, d.mime_type, d.file_size, d.uploaded_at,
+                    ARRAY_AGG(DISTINCT c.title ORDER BY c.title) AS compliance_items
+             FROM documents d
+             JOIN risk_scores rs ON rs.document_id = d.id
+             JOIN compliance_items c ON c.id = rs.compliance_item_id
+             WHERE d.user_id = $1 AND c.user_id = $1
+             GROUP BY d.id
+             ORDER BY LOWER(d.filename), d.uploaded_at"
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(evidence)
+    }
+}
@@ -0,0 +1,302 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    db::repository::RiskScoreRepository,
+    error::{AppError, AppResult},
+    models::{CreateRiskControlDto, RiskControl, RiskMatrix, UpdateRiskControlDto},
+};
+
+/// Control together with the inherent score of the risk it mitigates
+#[derive(Debug, sqlx::FromRow)]
+pub struct ControlWithInherentScore {
+    /// Control link
+    #[sqlx(flatten)]
+    pub control: RiskControl,
+
+    /// Inherent score of the mitigated risk
+    pub inherent_score: i32,
+}
+
+/// Repository for mitigating control database operations
+///
+/// Every write recomputes the residual risk of the affected assessment.
+pub struct RiskControlRepository {
+    pool: PgPool,
+    matrix: RiskMatrix,
+}
+
+impl RiskControlRepository {
+    /// Create new repository
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    /// * `matrix` - Risk matrix used to classify residual scores
+    ///
+    /// # Returns
+    ///
+    /// Repository instance
+    pub fn new(pool: PgPool, matrix: RiskMatrix) -> Self {
+        Self { pool, matrix }
+    }
+
+    /// Find controls mitigating a risk assessment
+    ///
+    /// # Arguments
+    ///
+    /// * `risk_score_id` - Risk score UUID
+    /// * `user_id` - User UUID for authorization
+    ///
+    /// # Returns
+    ///
+    /// List of controls, most effective first
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_by_risk_score(&self, risk_score_id: Uuid, user_id: Uuid) -> AppResult<Vec<RiskControl>> {
+        let controls = sqlx::query_as::<_, RiskControl>(
+            "SELECT rc.id, rc.risk_score_id, rc.compliance_item_id, c.title AS control_title,
+                    rc.user_id, rc.effectiveness, rc.notes, rc.created_at, rc.updated_at
+             FROM risk_controls rc
+             JOIN compliance_items c ON c.id = rc.compliance_item_id
+             WHERE rc.risk_score_id = $1 AND rc.user_id = $2
+             ORDER BY rc.effectiveness DESC, rc.created_at"
+        )
+        .bind(risk_score_id)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(controls)
+    }
+
+    /// Find all controls of a user with the inherent score of each mitigated risk
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// Controls ordered by mitigated risk
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<ControlWithInherentScore>> {
+        let controls = sqlx::query_as::<_, ControlWithInherentScore>(
+            "SELECT rc.id, rc.risk_score_id, rc.compliance_item_id, c.title AS control_title,
+                    rc.user_id, rc.effectiveness, rc.notes, rc.created_at, rc.updated_at,
+                    rs.risk_score AS inherent_score
+             FROM risk_controls rc
+             JOIN compliance_items c ON c.id = rc.compliance_item_id
+             JOIN risk_scores rs ON rs.id = rc.risk_score_id
+             WHERE rc.user_id = $1
+             ORDER BY rc.risk_score_id, rc.created_at"
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(controls)
+    }
+
+    /// Find risk assessments mitigated by a compliance item
+    ///
+    /// # Arguments
+    ///
+    /// * `compliance_item_id` - Compliance item UUID
+    ///
+    /// # Returns
+    ///
+    /// Risk score UUIDs
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_risks_mitigated_by(&self, compliance_item_id: Uuid) -> AppResult<Vec<Uuid>> {
+        let ids = sqlx::query_scalar(
+            "SELECT risk_score_id FROM risk_controls WHERE compliance_item_id = $1"
+        )
+        .bind(compliance_item_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Link a control to a risk assessment
+    ///
+    /// # Arguments
+    ///
+    /// * `risk_score_id` - Risk score UUID (must be owned by the user)
+    /// * `user_id` - User UUID
+    /// * `dto` - Control data (compliance item must be owned by the user)
+    ///
+    /// # Returns
+    ///
+    /// Created control or None if the risk score or compliance item was not found
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error if already linked, database error otherwise
+    pub async fn create(
+        &self,
+        risk_score_id: Uuid,
+        user_id: Uuid,
+        dto: &CreateRiskControlDto,
+    ) -> AppResult<Option<RiskControl>> {
+        let mut tx = self.pool.begin().await?;
+
+        let control = sqlx::query_as::<_, RiskControl>(
+            "WITH inserted AS (
+                 INSERT INTO risk_controls (risk_score_id, compliance_item_id, user_id, effectiveness, notes)
+                 SELECT rs.id, c.id, $3, $4, $5
+                 FROM risk_scores rs, compliance_items c
+                 WHERE rs.id = $1 AND rs.user_id = $3 AND c.id = $2 AND c.user_id = $3
+                 RETURNING *
+             )
+             SELECT i.id, i.risk_score_id, i.compliance_item_id, c.title AS control_title,
+                    i.user_id, i.effectiveness, i.notes, i.created_at, i.updated_at
+             FROM inserted i
+             JOIN compliance_items c ON c.id = i.compliance_item_id"
+        )
+        .bind(risk_score_id)
+        .bind(dto.compliance_item_id)
+        .bind(user_id)
+        .bind(dto.effectiveness)
+        .bind(&dto.notes)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                AppError::Validation("Control is already linked to this risk".to_string())
+            }
+            e => AppError::Database(e),
+        })?;
+
+        if control.is_some() {
+            RiskScoreRepository::recompute_residual(&mut tx, risk_score_id, &self.matrix).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(control)
+    }
+
+    /// Update a control's effectiveness or notes
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Control UUID
+    /// * `risk_score_id` - Risk score UUID
+    /// * `user_id` - User UUID for authorization
+    /// * `dto` - Update data
+    ///
+    /// # Returns
+    ///
+    /// Updated control or None if not found
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if update fails
+    pub async fn update(
+        &self,
+        id: Uuid,
+        risk_score_id: Uuid,
+        user_id: Uuid,
+        dto: &UpdateRiskControlDto,
+    ) -> AppResult<Option<RiskControl>> {
+        let mut tx = self.pool.begin().await?;
+
+        let control = sqlx::query_as::<_, RiskControl>(
+            "WITH updated AS (
+                 UPDATE risk_controls
+                 SET effectiveness = COALESCE($4, effectiveness),
+                     notes = COALESCE($5, notes),
+                     updated_at = NOW()
+                 WHERE id = $1 AND risk_score_id = $2 AND user_id = $3
+                 RETURNING *
+             )
+             SELECT u.id, u.risk_score_id, u.compliance_item_id, c.title AS control_title,
+                    u.user_id, u.effectiveness, u.notes, u.created_at, u.updated_at
+             FROM updated u
+             JOIN compliance_items c ON c.id = u.compliance_item_id"
+        )
+        .bind(id)
+        .bind(risk_score_id)
+        .bind(user_id)
+        .bind(dto.effectiveness)
+        .bind(&dto.notes)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if control.is_some() {
+            RiskScoreRepository::recompute_residual(&mut tx, risk_score_id, &self.matrix).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(control)
+    }
+
+    /// Unlink a control from a risk assessment
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Control UUID
+    /// * `risk_score_id` - Risk score UUID
+    /// * `user_id` - User UUID for authorization
+    ///
+    /// # Returns
+    ///
+    /// True if deleted, false if not found
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if delete fails
+    pub async fn delete(&self, id: Uuid, risk_score_id: Uuid, user_id: Uuid) -> AppResult<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query(
+            "DELETE FROM risk_controls WHERE id = $1 AND risk_score_id = $2 AND user_id = $3"
+        )
+        .bind(id)
+        .bind(risk_score_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let deleted = result.rows_affected() > 0;
+        if deleted {
+            RiskScoreRepository::recompute_residual(&mut tx, risk_score_id, &self.matrix).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(deleted)
+    }
+
+    /// Recompute the residual risk of assessments (e.g., after a control was removed)
+    ///
+    /// # Arguments
+    ///
+    /// * `risk_score_ids` - Risk score UUIDs
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if update fails
+    pub async fn refresh_residuals(&self, risk_score_ids: &[Uuid]) -> AppResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for id in risk_score_ids {
+            RiskScoreRepository::recompute_residual(&mut tx, *id, &self.matrix).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
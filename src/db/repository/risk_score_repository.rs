@@ -1,14 +1,23 @@
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use futures_util::{StreamExt, TryStreamExt};
+use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
 use crate::{
-    error::AppResult,
-    models::{CreateRiskScoreDto, RiskScore, UpdateRiskScoreDto},
+    error::{AppError, AppResult},
+    models::{
+        residual_score, CategoryTrendBucket, CreateRiskScoreDto, ExportRowStream, RiskJump, RiskMatrix, RiskScore,
+        RiskScoreExportRow, TrendBucket, TrendInterval, UpdateRiskScoreDto,
+    },
 };
 
 /// Repository for risk score database operations
+///
+/// Scores and levels are resolved against the risk matrix on every write so
+/// stored assessments are always consistent.
 pub struct RiskScoreRepository {
     pool: PgPool,
+    matrix: RiskMatrix,
 }
 
 impl RiskScoreRepository {
@@ -17,12 +26,13 @@ impl RiskScoreRepository {
     /// # Arguments
     ///
     /// * `pool` - Database connection pool
+    /// * `matrix` - Risk matrix used to compute scores and levels
     ///
     /// # Returns
     ///
     /// Repository instance
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, matrix: RiskMatrix) -> Self {
+        Self { pool, matrix }
     }
 
     /// Find all risk scores for a user
@@ -40,9 +50,10 @@ impl RiskScoreRepository {
     /// Returns database error if query fails
     pub async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<RiskScore>> {
         let scores = sqlx::query_as::<_, RiskScore>(
-            "SELECT id, compliance_item_id, document_id, user_id, risk_category,
+            "SELECT id, compliance_item_id, document_id, document_version, user_id, risk_category,
                     risk_score, risk_level, assessment_date, assessed_by, notes,
-                    ai_confidence, ai_reasoning, created_at, updated_at
+                    ai_confidence, ai_reasoning, likelihood, impact, residual_score, residual_level,
+                    created_at, updated_at
              FROM risk_scores
              WHERE user_id = $1
              ORDER BY created_at DESC"
@@ -74,9 +85,10 @@ impl RiskScoreRepository {
         user_id: Uuid,
     ) -> AppResult<Vec<RiskScore>> {
         let scores = sqlx::query_as::<_, RiskScore>(
-            "SELECT id, compliance_item_id, document_id, user_id, risk_category,
+            "SELECT id, compliance_item_id, document_id, document_version, user_id, risk_category,
                     risk_score, risk_level, assessment_date, assessed_by, notes,
-                    ai_confidence, ai_reasoning, created_at, updated_at
+                    ai_confidence, ai_reasoning, likelihood, impact, residual_score, residual_level,
+                    created_at, updated_at
              FROM risk_scores
              WHERE compliance_item_id = $1 AND user_id = $2
              ORDER BY assessment_date DESC"
@@ -105,9 +117,10 @@ impl RiskScoreRepository {
     /// Returns database error if query fails
     pub async fn find_by_id(&self, id: Uuid, user_id: Uuid) -> AppResult<Option<RiskScore>> {
         let score = sqlx::query_as::<_, RiskScore>(
-            "SELECT id, compliance_item_id, document_id, user_id, risk_category,
+            "SELECT id, compliance_item_id, document_id, document_version, user_id, risk_category,
                     risk_score, risk_level, assessment_date, assessed_by, notes,
-                    ai_confidence, ai_reasoning, created_at, updated_at
+                    ai_confidence, ai_reasoning, likelihood, impact, residual_score, residual_level,
+                    created_at, updated_at
              FROM risk_scores
              WHERE id = $1 AND user_id = $2"
         )
@@ -121,6 +134,9 @@ impl RiskScoreRepository {
 
     /// Create new risk score
     ///
+    /// The score and level are computed from likelihood and impact when both
+    /// are given, otherwise the level is derived from the manual score.
+    ///
     /// # Arguments
     ///
     /// * `user_id` - User UUID
@@ -132,37 +148,97 @@ impl RiskScoreRepository {
     ///
     /// # Errors
     ///
-    /// Returns database error if insert fails
+    /// Returns validation error for inconsistent input, database error if insert fails
     pub async fn create(&self, user_id: Uuid, dto: &CreateRiskScoreDto) -> AppResult<RiskScore> {
+        let mut tx = self.pool.begin().await?;
+        let score = Self::create_in(&mut tx, user_id, dto, &self.matrix).await?;
+        tx.commit().await?;
+
+        Ok(score)
+    }
+
+    /// Create a risk score inside an open transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `user_id` - User UUID
+    /// * `dto` - Risk score data
+    /// * `matrix` - Risk matrix used to compute the score and level
+    ///
+    /// # Returns
+    ///
+    /// Created risk score
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error for inconsistent input, database error if insert fails
+    pub async fn create_in(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        dto: &CreateRiskScoreDto,
+        matrix: &RiskMatrix,
+    ) -> AppResult<RiskScore> {
         let compliance_item_id = Uuid::parse_str(&dto.compliance_item_id)
-            .map_err(|_| crate::error::AppError::Validation("Invalid compliance item ID".to_string()))?;
+            .map_err(|_| AppError::Validation("Invalid compliance item ID".to_string()))?;
 
         let document_id = dto.document_id
             .as_ref()
             .map(|id| Uuid::parse_str(id))
             .transpose()
-            .map_err(|_| crate::error::AppError::Validation("Invalid document ID".to_string()))?;
+            .map_err(|_| AppError::Validation("Invalid document ID".to_string()))?;
+
+        let resolved = matrix
+            .resolve(dto.likelihood, dto.impact, dto.risk_score, dto.risk_level.as_deref())
+            .map_err(AppError::Validation)?;
+
+        // Assessments of a document are tied to the version they looked at
+        let document_version = match document_id {
+            Some(document_id) => Some(
+                sqlx::query_scalar::<_, i32>(
+                    "SELECT v.version_number
+                     FROM documents d
+                     JOIN document_versions v ON v.document_id = d.id
+                     WHERE d.id = $1 AND v.version_number = COALESCE($2, d.current_version)"
+                )
+                .bind(document_id)
+                .bind(dto.document_version)
+                .fetch_optional(&mut **tx)
+                .await?
+                .ok_or_else(|| AppError::Validation("Unknown document or document version".to_string()))?,
+            ),
+            None if dto.document_version.is_some() => {
+                return Err(AppError::Validation("Document version requires a document ID".to_string()));
+            }
+            None => None,
+        };
 
         let score = sqlx::query_as::<_, RiskScore>(
             "INSERT INTO risk_scores 
                 (user_id, compliance_item_id, document_id, risk_category, risk_score,
-                 risk_level, assessed_by, notes, ai_confidence, ai_reasoning)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-             RETURNING id, compliance_item_id, document_id, user_id, risk_category,
+                 risk_level, assessed_by, notes, ai_confidence, ai_reasoning, likelihood, impact,
+                 residual_score, residual_level, assessment_date, document_version)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $5, $6, COALESCE($13, NOW()), $14)
+             RETURNING id, compliance_item_id, document_id, document_version, user_id, risk_category,
                        risk_score, risk_level, assessment_date, assessed_by, notes,
-                       ai_confidence, ai_reasoning, created_at, updated_at"
+                       ai_confidence, ai_reasoning, likelihood, impact, residual_score, residual_level,
+                       created_at, updated_at"
         )
         .bind(user_id)
         .bind(compliance_item_id)
         .bind(document_id)
         .bind(&dto.risk_category)
-        .bind(dto.risk_score)
-        .bind(&dto.risk_level)
+        .bind(resolved.score)
+        .bind(&resolved.level)
         .bind(&dto.assessed_by)
         .bind(&dto.notes)
         .bind(dto.ai_confidence)
         .bind(&dto.ai_reasoning)
-        .fetch_one(&self.pool)
+        .bind(resolved.likelihood)
+        .bind(resolved.impact)
+        .bind(dto.assessment_date)
+        .bind(document_version)
+        .fetch_one(&mut **tx)
         .await?;
 
         Ok(score)
@@ -170,6 +246,9 @@ impl RiskScoreRepository {
 
     /// Update risk score
     ///
+    /// Changes to likelihood, impact, score or level are merged with the
+    /// stored assessment and re-resolved against the risk matrix.
+    ///
     /// # Arguments
     ///
     /// * `id` - Risk score UUID
@@ -182,35 +261,151 @@ impl RiskScoreRepository {
     ///
     /// # Errors
     ///
-    /// Returns database error if update fails
+    /// Returns validation error for inconsistent input, database error if update fails
     pub async fn update(
         &self,
         id: Uuid,
         user_id: Uuid,
         dto: &UpdateRiskScoreDto,
     ) -> AppResult<Option<RiskScore>> {
+        let mut tx = self.pool.begin().await?;
+
+        let current = sqlx::query_as::<_, RiskScore>(
+            "SELECT id, compliance_item_id, document_id, document_version, user_id, risk_category,
+                    risk_score, risk_level, assessment_date, assessed_by, notes,
+                    ai_confidence, ai_reasoning, likelihood, impact, residual_score, residual_level,
+                    created_at, updated_at
+             FROM risk_scores
+             WHERE id = $1 AND user_id = $2
+             FOR UPDATE"
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(current) = current else {
+            return Ok(None);
+        };
+
+        let scoring_changed = dto.likelihood.is_some()
+            || dto.impact.is_some()
+            || dto.risk_score.is_some()
+            || dto.risk_level.is_some();
+
+        let (likelihood, impact, risk_score, risk_level) = if scoring_changed {
+            let likelihood = dto.likelihood.or(current.likelihood);
+            let impact = dto.impact.or(current.impact);
+            // A manual score replaces the stored one only for manually scored assessments
+            let manual_score = if likelihood.is_some() || impact.is_some() {
+                dto.risk_score
+            } else {
+                dto.risk_score.or(Some(current.risk_score))
+            };
+
+            let resolved = self
+                .matrix
+                .resolve(likelihood, impact, manual_score, dto.risk_level.as_deref())
+                .map_err(AppError::Validation)?;
+            (resolved.likelihood, resolved.impact, resolved.score, resolved.level)
+        } else {
+            (current.likelihood, current.impact, current.risk_score, current.risk_level)
+        };
+
         let score = sqlx::query_as::<_, RiskScore>(
             "UPDATE risk_scores
              SET risk_category = COALESCE($3, risk_category),
-                 risk_score = COALESCE($4, risk_score),
-                 risk_level = COALESCE($5, risk_level),
+                 risk_score = $4,
+                 risk_level = $5,
                  notes = COALESCE($6, notes),
                  ai_confidence = COALESCE($7, ai_confidence),
-                 ai_reasoning = COALESCE($8, ai_reasoning)
+                 ai_reasoning = COALESCE($8, ai_reasoning),
+                 likelihood = $9,
+                 impact = $10
              WHERE id = $1 AND user_id = $2
-             RETURNING id, compliance_item_id, document_id, user_id, risk_category,
+             RETURNING id, compliance_item_id, document_id, document_version, user_id, risk_category,
                        risk_score, risk_level, assessment_date, assessed_by, notes,
-                       ai_confidence, ai_reasoning, created_at, updated_at"
+                       ai_confidence, ai_reasoning, likelihood, impact, residual_score, residual_level,
+                       created_at, updated_at"
         )
         .bind(id)
         .bind(user_id)
         .bind(&dto.risk_category)
-        .bind(dto.risk_score)
-        .bind(&dto.risk_level)
+        .bind(risk_score)
+        .bind(&risk_level)
         .bind(&dto.notes)
         .bind(dto.ai_confidence)
         .bind(&dto.ai_reasoning)
-        .fetch_optional(&self.pool)
+        .bind(likelihood)
+        .bind(impact)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let score = match score {
+            Some(_) if scoring_changed => {
+                Self::recompute_residual(&mut tx, id, &self.matrix).await?
+            }
+            score => score,
+        };
+
+        tx.commit().await?;
+
+        Ok(score)
+    }
+
+    /// Recompute the residual risk of an assessment from its controls
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `id` - Risk score UUID
+    /// * `matrix` - Risk matrix used to classify the residual score
+    ///
+    /// # Returns
+    ///
+    /// Updated risk score or None if not found
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if update fails
+    pub async fn recompute_residual(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        matrix: &RiskMatrix,
+    ) -> AppResult<Option<RiskScore>> {
+        let inherent: Option<i32> = sqlx::query_scalar(
+            "SELECT risk_score FROM risk_scores WHERE id = $1 FOR UPDATE"
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        let Some(inherent) = inherent else {
+            return Ok(None);
+        };
+
+        let effectiveness: Vec<i32> = sqlx::query_scalar(
+            "SELECT effectiveness FROM risk_controls WHERE risk_score_id = $1"
+        )
+        .bind(id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        let residual = residual_score(inherent, &effectiveness);
+
+        let score = sqlx::query_as::<_, RiskScore>(
+            "UPDATE risk_scores
+             SET residual_score = $2, residual_level = $3
+             WHERE id = $1
+             RETURNING id, compliance_item_id, document_id, document_version, user_id, risk_category,
+                       risk_score, risk_level, assessment_date, assessed_by, notes,
+                       ai_confidence, ai_reasoning, likelihood, impact, residual_score, residual_level,
+                       created_at, updated_at"
+        )
+        .bind(id)
+        .bind(residual)
+        .bind(matrix.level_for_score(residual))
+        .fetch_optional(&mut **tx)
         .await?;
 
         Ok(score)
@@ -241,4 +436,204 @@ impl RiskScoreRepository {
 
         Ok(result.rows_affected() > 0)
     }
+
+    /// Stream all risk scores of a user with joined columns for export
+    ///
+    /// Rows are fetched lazily while the stream is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// Stream of rows, newest first, with the compliance item, control count
+    /// and evidence filename
+    pub fn stream_export(&self, user_id: Uuid) -> ExportRowStream<RiskScoreExportRow> {
+        let pool = self.pool.clone();
+
+        async_stream::try_stream! {
+            let mut rows = sqlx::query_as::<_, RiskScoreExportRow>(
+                "SELECT r.id, r.compliance_item_id, c.title AS compliance_item_title,
+                        c.status AS compliance_item_status, r.risk_category, r.risk_score, r.risk_level,
+                        r.likelihood, r.impact, r.residual_score, r.residual_level,
+                        (SELECT COUNT(*) FROM risk_controls rc WHERE rc.risk_score_id = r.id) AS control_count,
+                        r.assessment_date, r.assessed_by, d.filename AS evidence_file, r.ai_confidence,
+                        r.notes, r.created_at
+                 FROM risk_scores r
+                 JOIN compliance_items c ON c.id = r.compliance_item_id
+                 LEFT JOIN documents d ON d.id = r.document_id
+                 WHERE r.user_id = $1
+                 ORDER BY r.created_at DESC"
+            )
+            .bind(user_id)
+            .fetch(&pool);
+
+            while let Some(row) = rows.try_next().await? {
+                yield row;
+            }
+        }
+        .boxed()
+    }
+
+    /// Aggregate assessments into time buckets
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `compliance_item_id` - Restrict to one compliance item (optional)
+    /// * `interval` - Bucket size
+    /// * `from` - Inclusive lower bound on assessment date (optional)
+    /// * `to` - Exclusive upper bound on assessment date (optional)
+    ///
+    /// # Returns
+    ///
+    /// Buckets in chronological order
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_trend_buckets(
+        &self,
+        user_id: Uuid,
+        compliance_item_id: Option<Uuid>,
+        interval: TrendInterval,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> AppResult<Vec<TrendBucket>> {
+        let buckets = sqlx::query_as::<_, TrendBucket>(
+            "SELECT date_trunc($5, assessment_date) AS bucket_start,
+                    COUNT(*) AS assessments,
+                    (ARRAY_AGG(risk_score ORDER BY assessment_date DESC, created_at DESC))[1] AS latest_score,
+                    AVG(risk_score)::float8 AS average_score,
+                    MAX(risk_score) AS max_score
+             FROM risk_scores
+             WHERE user_id = $1
+               AND ($2::uuid IS NULL OR compliance_item_id = $2)
+               AND ($3::timestamptz IS NULL OR assessment_date >= $3)
+               AND ($4::timestamptz IS NULL OR assessment_date < $4)
+             GROUP BY bucket_start
+             ORDER BY bucket_start"
+        )
+        .bind(user_id)
+        .bind(compliance_item_id)
+        .bind(from)
+        .bind(to)
+        .bind(interval.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(buckets)
+    }
+
+    /// Aggregate assessments into time buckets per risk category
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `compliance_item_id` - Restrict to one compliance item (optional)
+    /// * `interval` - Bucket size
+    /// * `from` - Inclusive lower bound on assessment date (optional)
+    /// * `to` - Exclusive upper bound on assessment date (optional)
+    ///
+    /// # Returns
+    ///
+    /// Buckets ordered by category, then chronologically
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_category_trend_buckets(
+        &self,
+        user_id: Uuid,
+        compliance_item_id: Option<Uuid>,
+        interval: TrendInterval,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> AppResult<Vec<CategoryTrendBucket>> {
+        let buckets = sqlx::query_as::<_, CategoryTrendBucket>(
+            "SELECT risk_category,
+                    date_trunc($5, assessment_date) AS bucket_start,
+                    COUNT(*) AS assessments,
+                    (ARRAY_AGG(risk_score ORDER BY assessment_date DESC, created_at DESC))[1] AS latest_score,
+                    AVG(risk_score)::float8 AS average_score,
+                    MAX(risk_score) AS max_score
+             FROM risk_scores
+             WHERE user_id = $1
+               AND ($2::uuid IS NULL OR compliance_item_id = $2)
+               AND ($3::timestamptz IS NULL OR assessment_date >= $3)
+               AND ($4::timestamptz IS NULL OR assessment_date < $4)
+             GROUP BY risk_category, bucket_start
+             ORDER BY risk_category, bucket_start"
+        )
+        .bind(user_id)
+        .bind(compliance_item_id)
+        .bind(from)
+        .bind(to)
+        .bind(interval.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(buckets)
+    }
+
+    /// Find significant score changes between consecutive assessments
+    ///
+    /// Assessments of the same compliance item and risk category are compared
+    /// in assessment order; the preceding assessment may lie before `from`.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `compliance_item_id` - Restrict to one compliance item (optional)
+    /// * `threshold` - Minimum absolute score change
+    /// * `from` - Inclusive lower bound on assessment date (optional)
+    /// * `to` - Exclusive upper bound on assessment date (optional)
+    ///
+    /// # Returns
+    ///
+    /// Jumps, most recent first
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_jumps(
+        &self,
+        user_id: Uuid,
+        compliance_item_id: Option<Uuid>,
+        threshold: i32,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> AppResult<Vec<RiskJump>> {
+        let jumps = sqlx::query_as::<_, RiskJump>(
+            "SELECT risk_score_id, previous_risk_score_id, compliance_item_id, risk_category,
+                    previous_score, score, score - previous_score AS change, assessment_date
+             FROM (
+                 SELECT id AS risk_score_id,
+                        LAG(id) OVER w AS previous_risk_score_id,
+                        compliance_item_id, risk_category,
+                        LAG(risk_score) OVER w AS previous_score,
+                        risk_score AS score,
+                        assessment_date
+                 FROM risk_scores
+                 WHERE user_id = $1 AND ($2::uuid IS NULL OR compliance_item_id = $2)
+                 WINDOW w AS (PARTITION BY compliance_item_id, risk_category
+                              ORDER BY assessment_date, created_at)
+             ) consecutive
+             WHERE previous_score IS NOT NULL
+               AND ABS(score - previous_score) >= $3
+               AND ($4::timestamptz IS NULL OR assessment_date >= $4)
+               AND ($5::timestamptz IS NULL OR assessment_date < $5)
+             ORDER BY assessment_date DESC"
+        )
+        .bind(user_id)
+        .bind(compliance_item_id)
+        .bind(threshold)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(jumps)
+    }
 }
@@ -0,0 +1,261 @@
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{CreateTagDto, MetadataEntityType, Tag, UpdateTagDto},
+};
+
+/// Tag assigned to an entity
+#[derive(Debug, sqlx::FromRow)]
+pub struct EntityTag {
+    /// Tagged entity
+    pub entity_id: Uuid,
+
+    /// Assigned tag
+    #[sqlx(flatten)]
+    pub tag: Tag,
+}
+
+/// Repository for tag database operations
+pub struct TagRepository {
+    pool: PgPool,
+}
+
+impl TagRepository {
+    /// Create new repository
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    ///
+    /// # Returns
+    ///
+    /// Repository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find all tags for a user
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// List of tags ordered by name
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Tag>> {
+        let tags = sqlx::query_as::<_, Tag>(
+            "SELECT id, user_id, name, color, created_at
+             FROM tags
+             WHERE user_id = $1
+             ORDER BY LOWER(name)"
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    /// Create new tag
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `dto` - Tag data
+    ///
+    /// # Returns
+    ///
+    /// Created tag
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error if the name is taken, database error otherwise
+    pub async fn create(&self, user_id: Uuid, dto: &CreateTagDto) -> AppResult<Tag> {
+        let tag = sqlx::query_as::<_, Tag>(
+            "INSERT INTO tags (user_id, name, color)
+             VALUES ($1, $2, $3)
+             RETURNING id, user_id, name, color, created_at"
+        )
+        .bind(user_id)
+        .bind(dto.name.trim())
+        .bind(&dto.color)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(map_duplicate_name)?;
+
+        Ok(tag)
+    }
+
+    /// Update tag
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Tag UUID
+    /// * `user_id` - User UUID for authorization
+    /// * `dto` - Update data
+    ///
+    /// # Returns
+    ///
+    /// Updated tag or None if not found
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error if the name is taken, database error otherwise
+    pub async fn update(&self, id: Uuid, user_id: Uuid, dto: &UpdateTagDto) -> AppResult<Option<Tag>> {
+        let tag = sqlx::query_as::<_, Tag>(
+            "UPDATE tags
+             SET name = COALESCE($3, name),
+                 color = COALESCE($4, color)
+             WHERE id = $1 AND user_id = $2
+             RETURNING id, user_id, name, color, created_at"
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(dto.name.as_deref().map(str::trim))
+        .bind(&dto.color)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_duplicate_name)?;
+
+        Ok(tag)
+    }
+
+    /// Delete tag (removes it from all entities)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Tag UUID
+    /// * `user_id` - User UUID for authorization
+    ///
+    /// # Returns
+    ///
+    /// True if deleted, false if not found
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if delete fails
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> AppResult<bool> {
+        let result = sqlx::query("DELETE FROM tags WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Count how many of the given tags belong to the user
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - Tag UUIDs
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// Number of matching tags
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn count_owned(&self, ids: &[Uuid], user_id: Uuid) -> AppResult<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM tags WHERE id = ANY($1) AND user_id = $2"
+        )
+        .bind(ids)
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Find tags assigned to the given entities
+    ///
+    /// # Arguments
+    ///
+    /// * `entity_type` - Type of the tagged entities
+    /// * `entity_ids` - Entity UUIDs
+    ///
+    /// # Returns
+    ///
+    /// Entity/tag pairs ordered by tag name
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_for_entities(
+        &self,
+        entity_type: MetadataEntityType,
+        entity_ids: &[Uuid],
+    ) -> AppResult<Vec<EntityTag>> {
+        let tags = sqlx::query_as::<_, EntityTag>(
+            "SELECT et.entity_id, t.id, t.user_id, t.name, t.color, t.created_at
+             FROM entity_tags et
+             JOIN tags t ON t.id = et.tag_id
+             WHERE et.entity_type = $1 AND et.entity_id = ANY($2)
+             ORDER BY LOWER(t.name)"
+        )
+        .bind(entity_type.as_str())
+        .bind(entity_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    /// Replace the tags of an entity
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `entity_type` - Type of the tagged entity
+    /// * `entity_id` - Entity UUID
+    /// * `tag_ids` - New set of tag UUIDs
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if the update fails
+    pub async fn replace_entity_tags(
+        tx: &mut Transaction<'_, Postgres>,
+        entity_type: MetadataEntityType,
+        entity_id: Uuid,
+        tag_ids: &[Uuid],
+    ) -> AppResult<()> {
+        sqlx::query("DELETE FROM entity_tags WHERE entity_type = $1 AND entity_id = $2")
+            .bind(entity_type.as_str())
+            .bind(entity_id)
+            .execute(&mut **tx)
+            .await?;
+
+        sqlx::query(
+            "INSERT INTO entity_tags (tag_id, entity_type, entity_id)
+             SELECT UNNEST($1::uuid[]), $2, $3
+             ON CONFLICT DO NOTHING"
+        )
+        .bind(tag_ids)
+        .bind(entity_type.as_str())
+        .bind(entity_id)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Turn unique violations on the tag name into validation errors
+fn map_duplicate_name(e: sqlx::Error) -> AppError {
+    match e {
+        sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+            AppError::Validation("A tag with this name already exists".to_string())
+        }
+        e => AppError::Database(e),
+    }
+}
@@ -110,6 +110,79 @@ impl UserRepository {
         Ok(user)
     }
 
+    /// Find the user owning a calendar feed token
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - Calendar feed token
+    ///
+    /// # Returns
+    ///
+    /// Optional User if the token is active
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    #[instrument(skip(self, token))]
+    pub async fn find_by_calendar_token(&self, token: &str) -> AppResult<Option<User>> {
+        let user = sqlx::query_as::<_, User>(
+            "SELECT id, email, password_hash, full_name, created_at, updated_at
+             FROM users
+             WHERE calendar_token = $1"
+        )
+        .bind(token)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    /// Get a user's calendar feed token
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// Token if the feed is enabled
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    #[instrument(skip(self))]
+    pub async fn get_calendar_token(&self, id: Uuid) -> AppResult<Option<String>> {
+        let token: Option<Option<String>> = sqlx::query_scalar(
+            "SELECT calendar_token FROM users WHERE id = $1"
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(token.flatten())
+    }
+
+    /// Set or clear a user's calendar feed token
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - User UUID
+    /// * `token` - New token (None disables the feed)
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if update fails
+    #[instrument(skip(self, token))]
+    pub async fn set_calendar_token(&self, id: Uuid, token: Option<&str>) -> AppResult<()> {
+        sqlx::query("UPDATE users SET calendar_token = $2, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(token)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     /// Check if email already exists
     ///
     /// # Arguments
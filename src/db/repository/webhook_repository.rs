@@ -0,0 +1,448 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    error::AppResult,
+    models::{
+        CreateWebhookDto, UpdateWebhookDto, WebhookDelivery, WebhookDeliveryStatus, WebhookEventType,
+        WebhookSubscription,
+    },
+};
+
+/// Columns of a webhook subscription
+const SUBSCRIPTION_COLUMNS: &str =
+    "id, user_id, url, description, event_types, secret, active, created_at, updated_at";
+
+/// Columns of a webhook delivery
+const DELIVERY_COLUMNS: &str = "id, subscription_id, event_id, event_type, payload, status, attempts, \
+     next_attempt_at, last_attempt_at, response_status, response_body, error, delivered_at, created_at";
+
+/// Delivery claimed for an attempt, with its endpoint
+#[derive(Debug, sqlx::FromRow)]
+pub struct ClaimedDelivery {
+    /// Delivery (attempt count already incremented)
+    #[sqlx(flatten)]
+    pub delivery: WebhookDelivery,
+
+    /// Endpoint URL
+    pub url: String,
+
+    /// Signing secret
+    pub secret: String,
+}
+
+/// Outcome of a delivery attempt
+#[derive(Debug)]
+pub struct AttemptOutcome {
+    /// HTTP status of the response, if any
+    pub response_status: Option<i32>,
+
+    /// Response body (truncated)
+    pub response_body: Option<String>,
+
+    /// Transport error
+    pub error: Option<String>,
+}
+
+/// Repository for webhook subscription and delivery database operations
+pub struct WebhookRepository {
+    pool: PgPool,
+}
+
+impl WebhookRepository {
+    /// Create new repository
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    ///
+    /// # Returns
+    ///
+    /// Repository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find all webhook subscriptions of a user
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// Subscriptions, newest first
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<WebhookSubscription>> {
+        let subscriptions = sqlx::query_as::<_, WebhookSubscription>(&format!(
+            "SELECT {} FROM webhook_subscriptions WHERE user_id = $1 ORDER BY created_at DESC",
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(subscriptions)
+    }
+
+    /// Find webhook subscription by ID
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Subscription UUID
+    /// * `user_id` - User UUID (for authorization)
+    ///
+    /// # Returns
+    ///
+    /// Subscription if found and owned by user
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_by_id(&self, id: Uuid, user_id: Uuid) -> AppResult<Option<WebhookSubscription>> {
+        let subscription = sqlx::query_as::<_, WebhookSubscription>(&format!(
+            "SELECT {} FROM webhook_subscriptions WHERE id = $1 AND user_id = $2",
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(subscription)
+    }
+
+    /// Create a webhook subscription
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID who owns the subscription
+    /// * `dto` - Subscription data
+    /// * `secret` - Signing secret
+    ///
+    /// # Returns
+    ///
+    /// Created subscription
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if insertion fails
+    pub async fn create(&self, user_id: Uuid, dto: &CreateWebhookDto, secret: &str) -> AppResult<WebhookSubscription> {
+        let subscription = sqlx::query_as::<_, WebhookSubscription>(&format!(
+            "INSERT INTO webhook_subscriptions (user_id, url, description, event_types, secret, active)
+             VALUES ($1, $2, $3, $4, $5, COALESCE($6, TRUE))
+             RETURNING {}",
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(&dto.url)
+        .bind(&dto.description)
+        .bind(event_type_names(&dto.event_types))
+        .bind(secret)
+        .bind(dto.active)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(subscription)
+    }
+
+    /// Update a webhook subscription
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Subscription UUID
+    /// * `user_id` - User UUID (for authorization)
+    /// * `dto` - Update data
+    /// * `secret` - New signing secret (None keeps the current one)
+    ///
+    /// # Returns
+    ///
+    /// Updated subscription or None if not found/unauthorized
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if update fails
+    pub async fn update(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        dto: &UpdateWebhookDto,
+        secret: Option<&str>,
+    ) -> AppResult<Option<WebhookSubscription>> {
+        let subscription = sqlx::query_as::<_, WebhookSubscription>(&format!(
+            "UPDATE webhook_subscriptions
+             SET url = COALESCE($3, url),
+                 description = COALESCE($4, description),
+                 event_types = COALESCE($5, event_types),
+                 active = COALESCE($6, active),
+                 secret = COALESCE($7, secret),
+                 updated_at = NOW()
+             WHERE id = $1 AND user_id = $2
+             RETURNING {}",
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(id)
+        .bind(user_id)
+        .bind(&dto.url)
+        .bind(&dto.description)
+        .bind(dto.event_types.as_deref().map(event_type_names))
+        .bind(dto.active)
+        .bind(secret)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(subscription)
+    }
+
+    /// Delete a webhook subscription and its delivery log
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Subscription UUID
+    /// * `user_id` - User UUID (for authorization)
+    ///
+    /// # Returns
+    ///
+    /// true if deleted, false if not found
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if deletion fails
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> AppResult<bool> {
+        let result = sqlx::query("DELETE FROM webhook_subscriptions WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Queue an event for every active subscription of a user that wants it
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - Owner of the subscriptions
+    /// * `event_type` - Event type
+    /// * `event_id` - Event identifier
+    /// * `payload` - Request body
+    ///
+    /// # Returns
+    ///
+    /// Number of deliveries queued
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if insertion fails
+    pub async fn enqueue(
+        &self,
+        user_id: Uuid,
+        event_type: WebhookEventType,
+        event_id: Uuid,
+        payload: &serde_json::Value,
+    ) -> AppResult<u64> {
+        let result = sqlx::query(
+            "INSERT INTO webhook_deliveries (subscription_id, event_id, event_type, payload)
+             SELECT id, $2, $3, $4
+             FROM webhook_subscriptions
+             WHERE user_id = $1 AND active AND $3 = ANY(event_types)"
+        )
+        .bind(user_id)
+        .bind(event_id)
+        .bind(event_type.as_str())
+        .bind(sqlx::types::Json(payload))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Queue an event for one subscription regardless of its event types
+    ///
+    /// # Arguments
+    ///
+    /// * `subscription_id` - Subscription UUID
+    /// * `event_type` - Event type
+    /// * `event_id` - Event identifier
+    /// * `payload` - Request body
+    ///
+    /// # Returns
+    ///
+    /// Queued delivery
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if insertion fails
+    pub async fn enqueue_for(
+        &self,
+        subscription_id: Uuid,
+        event_type: WebhookEventType,
+        event_id: Uuid,
+        payload: &serde_json::Value,
+    ) -> AppResult<WebhookDelivery> {
+        let delivery = sqlx::query_as::<_, WebhookDelivery>(&format!(
+            "INSERT INTO webhook_deliveries (subscription_id, event_id, event_type, payload)
+             VALUES ($1, $2, $3, $4)
+             RETURNING {}",
+            DELIVERY_COLUMNS
+        ))
+        .bind(subscription_id)
+        .bind(event_id)
+        .bind(event_type.as_str())
+        .bind(sqlx::types::Json(payload))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(delivery)
+    }
+
+    /// Claim deliveries that are due for an attempt
+    ///
+    /// Claimed deliveries are marked as delivering so concurrent workers skip
+    /// them; deliveries stuck in that state for five minutes are reclaimed.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - Reference time
+    /// * `delivery_id` - Only claim this delivery (None for any due delivery)
+    /// * `limit` - Maximum number of deliveries
+    ///
+    /// # Returns
+    ///
+    /// Claimed deliveries with their endpoints
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if update fails
+    pub async fn claim_due(
+        &self,
+        now: DateTime<Utc>,
+        delivery_id: Option<Uuid>,
+        limit: i64,
+    ) -> AppResult<Vec<ClaimedDelivery>> {
+        let claimed = sqlx::query_as::<_, ClaimedDelivery>(&format!(
+            "WITH claimed AS (
+                 UPDATE webhook_deliveries
+                 SET status = 'delivering', attempts = attempts + 1, last_attempt_at = $1
+                 WHERE id IN (
+                     SELECT id FROM webhook_deliveries
+                     WHERE ($2::UUID IS NULL OR id = $2)
+                       AND ((status = 'pending' AND next_attempt_at <= $1)
+                            OR (status = 'delivering' AND last_attempt_at < $1 - INTERVAL '5 minutes'))
+                     ORDER BY next_attempt_at
+                     LIMIT $3
+                     FOR UPDATE SKIP LOCKED
+                 )
+                 RETURNING {}
+             )
+             SELECT claimed.*, s.url, s.secret
+             FROM claimed
+             JOIN webhook_subscriptions s ON s.id = claimed.subscription_id",
+            DELIVERY_COLUMNS
+        ))
+        .bind(now)
+        .bind(delivery_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(claimed)
+    }
+
+    /// Record the outcome of a delivery attempt
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Delivery UUID
+    /// * `status` - New status
+    /// * `outcome` - Response or error of the attempt
+    /// * `next_attempt_at` - When to retry (pending deliveries only)
+    ///
+    /// # Returns
+    ///
+    /// Updated delivery
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if update fails
+    pub async fn record_attempt(
+        &self,
+        id: Uuid,
+        status: WebhookDeliveryStatus,
+        outcome: &AttemptOutcome,
+        next_attempt_at: Option<DateTime<Utc>>,
+    ) -> AppResult<WebhookDelivery> {
+        let delivery = sqlx::query_as::<_, WebhookDelivery>(&format!(
+            "UPDATE webhook_deliveries
+             SET status = $2,
+                 response_status = $3,
+                 response_body = $4,
+                 error = $5,
+                 next_attempt_at = COALESCE($6, next_attempt_at),
+                 delivered_at = CASE WHEN $2 = 'succeeded' THEN NOW() END
+             WHERE id = $1
+             RETURNING {}",
+            DELIVERY_COLUMNS
+        ))
+        .bind(id)
+        .bind(status.as_str())
+        .bind(outcome.response_status)
+        .bind(&outcome.response_body)
+        .bind(&outcome.error)
+        .bind(next_attempt_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(delivery)
+    }
+
+    /// Find the delivery log of a subscription
+    ///
+    /// # Arguments
+    ///
+    /// * `subscription_id` - Subscription UUID
+    /// * `status` - Only deliveries with this status
+    /// * `limit` - Maximum number of deliveries
+    ///
+    /// # Returns
+    ///
+    /// Deliveries, newest first
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    pub async fn find_deliveries(
+        &self,
+        subscription_id: Uuid,
+        status: Option<WebhookDeliveryStatus>,
+        limit: i64,
+    ) -> AppResult<Vec<WebhookDelivery>> {
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(&format!(
+            "SELECT {}
+             FROM webhook_deliveries
+             WHERE subscription_id = $1 AND ($2::TEXT IS NULL OR status = $2)
+             ORDER BY created_at DESC, id DESC
+             LIMIT $3",
+            DELIVERY_COLUMNS
+        ))
+        .bind(subscription_id)
+        .bind(status.map(|s| s.as_str()))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(deliveries)
+    }
+}
+
+/// Database names of event types
+fn event_type_names(event_types: &[WebhookEventType]) -> Vec<String> {
+    let mut names: Vec<String> = event_types.iter().map(|t| t.as_str().to_string()).collect();
+    names.sort();
+    names.dedup();
+    names
+}
@@ -27,6 +27,10 @@ pub enum AppError {
     #[error("Resource not found: {0}")]
     NotFound(String),
     
+    /// Forbidden error (authenticated but not allowed)
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+    
     /// Internal server error
     #[error("Internal server error: {0}")]
     Internal(String),
@@ -63,6 +67,7 @@ impl IntoResponse for AppError {
             AppError::Auth(_) => (StatusCode::UNAUTHORIZED, "Authentication failed"),
             AppError::Validation(ref msg) => (StatusCode::BAD_REQUEST, msg.as_str()),
             AppError::NotFound(ref msg) => (StatusCode::NOT_FOUND, msg.as_str()),
+            AppError::Forbidden(ref msg) => (StatusCode::FORBIDDEN, msg.as_str()),
             AppError::Internal(ref msg) => {
                 tracing::error!("Internal error: {}", msg);
                 (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
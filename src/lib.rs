@@ -18,6 +18,15 @@ pub struct AppState {
     
     /// Application configuration
     pub config: Config,
+    
+    /// Notification email transport
+    pub mailer: std::sync::Arc<dyn services::EmailTransport>,
+
+    /// Document file storage
+    pub storage: std::sync::Arc<dyn services::StorageBackend>,
+
+    /// Real-time event fan-out for WebSocket sessions
+    pub realtime: services::RealtimeHub,
 }
 
 /// Health check endpoint
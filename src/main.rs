@@ -14,6 +14,9 @@ use parseguard_backend::{
     db,
     error::AppResult,
     middleware,
+    services::{
+        storage_from_config, transport_from_config, NotificationService, RealtimeHub, StorageService, WebhookService,
+    },
     AppState,
 };
 
@@ -62,10 +65,31 @@ async fn main() -> AppResult<()> {
         )
         .allow_credentials(true);
 
+    // Create email transport and start due-date reminders
+    let mailer = transport_from_config(&config.email)?;
+    NotificationService::spawn_reminder_scheduler(
+        pool.clone(),
+        mailer.clone(),
+        std::time::Duration::from_secs(config.reminder_interval_secs),
+    );
+
+    // Start webhook delivery
+    WebhookService::spawn_delivery_worker(pool.clone(), &config);
+
+    // Open document storage and reclaim objects no document references
+    let storage = storage_from_config(&config)?;
+    StorageService::spawn_orphan_sweeper(pool.clone(), storage.clone(), &config);
+
+    // Listen for real-time events from all replicas
+    let realtime = RealtimeHub::start(pool.clone()).await?;
+
     // Create AppState
     let state = AppState {
         pool: pool.clone(),
         config: config.clone(),
+        mailer,
+        storage,
+        realtime,
     };
 
     // Build application router
@@ -23,7 +23,7 @@ pub async fn logger_middleware(req: Request, next: Next) -> Response {
 
     // Log Request
     let mut log_message = String::new();
-    log_message.push_str(&format!("\n🚀 INCOMING REQUEST 🚀\n"));
+    log_message.push_str("\n🚀 INCOMING REQUEST 🚀\n");
     log_message.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
     log_message.push_str(&format!("Method:  {}\n", method));
     log_message.push_str(&format!("Path:    {}\n", path));
@@ -74,7 +74,7 @@ pub async fn logger_middleware(req: Request, next: Next) -> Response {
         } else {
             log_message.push_str("<binary body>");
         }
-        log_message.push_str("\n");
+        log_message.push('\n');
     }
     log_message.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     
@@ -101,7 +101,7 @@ pub async fn logger_middleware(req: Request, next: Next) -> Response {
     res_log.push_str(&format!("Status:   {} ({})\n", status.as_u16(), status));
     res_log.push_str(&format!("Duration: {:?}\n", duration));
     res_log.push_str(&format!("ID:       {}\n", request_id));
-    res_log.push_str(&format!("{}", border));
+    res_log.push_str(border);
 
     info!("{}", res_log);
 
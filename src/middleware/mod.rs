@@ -1,5 +1,5 @@
 pub mod auth;
-mod logger;
+pub mod logger;
 
 pub use auth::auth_middleware;
 pub use logger::logger_middleware;
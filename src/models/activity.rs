@@ -0,0 +1,309 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Fields left out of activity snapshots (identifiers, timestamps and bulky content)
+const OMITTED_FIELDS: [&str; 9] = [
+    "id",
+    "user_id",
+    "created_at",
+    "updated_at",
+    "uploaded_at",
+    "storage_key",
+    "content_sha256",
+    "extracted_text",
+    "ai_analysis",
+];
+
+/// Entity types that appear in the activity feed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActivityEntityType {
+    #[serde(rename = "compliance_item")]
+    ComplianceItem,
+
+    #[serde(rename = "document")]
+    Document,
+
+    #[serde(rename = "risk_score")]
+    RiskScore,
+}
+
+impl ActivityEntityType {
+    /// Convert ActivityEntityType to database string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActivityEntityType::ComplianceItem => "compliance_item",
+            ActivityEntityType::Document => "document",
+            ActivityEntityType::RiskScore => "risk_score",
+        }
+    }
+}
+
+/// What happened to the entity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityVerb {
+    Created,
+    Updated,
+    StatusChanged,
+    Analyzed,
+    Deleted,
+}
+
+impl ActivityVerb {
+    /// Convert ActivityVerb to database string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActivityVerb::Created => "created",
+            ActivityVerb::Updated => "updated",
+            ActivityVerb::StatusChanged => "status_changed",
+            ActivityVerb::Analyzed => "analyzed",
+            ActivityVerb::Deleted => "deleted",
+        }
+    }
+}
+
+/// Recorded activity event
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct ActivityEvent {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// User who performed the action (None if the account was removed)
+    pub actor_id: Option<Uuid>,
+
+    /// Name of the actor
+    pub actor_name: Option<String>,
+
+    /// What happened
+    pub verb: String,
+
+    /// Type of the target entity
+    pub entity_type: String,
+
+    /// Target entity (may no longer exist)
+    pub entity_id: Uuid,
+
+    /// Title of the target at the time of the event
+    pub entity_title: Option<String>,
+
+    /// Changed fields before the action
+    pub before: Option<sqlx::types::Json<Value>>,
+
+    /// Changed fields after the action
+    pub after: Option<sqlx::types::Json<Value>>,
+
+    /// When the event happened
+    pub created_at: DateTime<Utc>,
+}
+
+/// Activity event to record
+#[derive(Debug, Clone)]
+pub struct NewActivityEvent {
+    /// Owner of the target entity (whose feed the event appears in)
+    pub user_id: Uuid,
+
+    /// User who performed the action
+    pub actor_id: Uuid,
+
+    /// What happened
+    pub verb: ActivityVerb,
+
+    /// Type of the target entity
+    pub entity_type: ActivityEntityType,
+
+    /// Target entity
+    pub entity_id: Uuid,
+
+    /// Title of the target
+    pub entity_title: Option<String>,
+
+    /// Changed fields before the action
+    pub before: Option<Value>,
+
+    /// Changed fields after the action
+    pub after: Option<Value>,
+
+    /// Full entity after the action (before it, for deletions)
+    pub entity: Value,
+}
+
+impl NewActivityEvent {
+    /// Event for a newly created entity
+    ///
+    /// # Arguments
+    ///
+    /// * `actor_id` - User who created the entity (and owns it)
+    /// * `entity_type` - Type of the entity
+    /// * `entity_id` - Entity UUID
+    /// * `title` - Entity title
+    /// * `entity` - Created entity
+    pub fn created<T: Serialize>(
+        actor_id: Uuid,
+        entity_type: ActivityEntityType,
+        entity_id: Uuid,
+        title: &str,
+        entity: &T,
+    ) -> Self {
+        Self {
+            user_id: actor_id,
+            actor_id,
+            verb: ActivityVerb::Created,
+            entity_type,
+            entity_id,
+            entity_title: Some(title.to_string()),
+            before: None,
+            after: Some(snapshot(entity)),
+            entity: serde_json::to_value(entity).unwrap_or(Value::Null),
+        }
+    }
+
+    /// Event for a changed entity, recording only the fields that changed
+    ///
+    /// The verb becomes `status_changed` when the status changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `actor_id` - User who changed the entity (and owns it)
+    /// * `entity_type` - Type of the entity
+    /// * `entity_id` - Entity UUID
+    /// * `title` - Entity title after the change
+    /// * `before` - Entity before the change
+    /// * `after` - Entity after the change
+    pub fn updated<T: Serialize>(
+        actor_id: Uuid,
+        entity_type: ActivityEntityType,
+        entity_id: Uuid,
+        title: &str,
+        before: &T,
+        after: &T,
+    ) -> Self {
+        let entity = serde_json::to_value(after).unwrap_or(Value::Null);
+        let (before, after) = diff(snapshot(before), snapshot(after));
+        let verb = if before.get("status").is_some() {
+            ActivityVerb::StatusChanged
+        } else {
+            ActivityVerb::Updated
+        };
+
+        Self {
+            user_id: actor_id,
+            actor_id,
+            verb,
+            entity_type,
+            entity_id,
+            entity_title: Some(title.to_string()),
+            before: Some(before),
+            after: Some(after),
+            entity,
+        }
+    }
+
+    /// Event for a deleted entity
+    ///
+    /// # Arguments
+    ///
+    /// * `actor_id` - User who deleted the entity (and owned it)
+    /// * `entity_type` - Type of the entity
+    /// * `entity_id` - Entity UUID
+    /// * `title` - Entity title
+    /// * `entity` - Entity before deletion
+    pub fn deleted<T: Serialize>(
+        actor_id: Uuid,
+        entity_type: ActivityEntityType,
+        entity_id: Uuid,
+        title: &str,
+        entity: &T,
+    ) -> Self {
+        Self {
+            user_id: actor_id,
+            actor_id,
+            verb: ActivityVerb::Deleted,
+            entity_type,
+            entity_id,
+            entity_title: Some(title.to_string()),
+            before: Some(snapshot(entity)),
+            after: None,
+            entity: serde_json::to_value(entity).unwrap_or(Value::Null),
+        }
+    }
+
+    /// Replace the verb (e.g., `analyzed` for an update that stored an AI analysis)
+    pub fn with_verb(mut self, verb: ActivityVerb) -> Self {
+        self.verb = verb;
+        self
+    }
+}
+
+/// Query parameters for the activity feed
+#[derive(Debug, Deserialize)]
+pub struct ActivityFeedQuery {
+    /// Maximum number of events to return (default: 20, max: 100)
+    pub limit: Option<i64>,
+
+    /// Return events older than this event
+    pub cursor: Option<Uuid>,
+
+    /// Only events on this entity type
+    pub entity_type: Option<ActivityEntityType>,
+
+    /// Only events on this entity
+    pub entity_id: Option<Uuid>,
+
+    /// Only events by this actor
+    pub actor: Option<Uuid>,
+}
+
+/// Page of the activity feed
+#[derive(Debug, Serialize)]
+pub struct ActivityPage {
+    /// Events, newest first
+    pub events: Vec<ActivityEvent>,
+
+    /// Cursor for the next page (None on the last page)
+    pub next_cursor: Option<Uuid>,
+}
+
+/// Serialize an entity into a compact snapshot
+///
+/// Identifiers, timestamps and bulky content are dropped and tags are
+/// reduced to their names.
+fn snapshot<T: Serialize>(entity: &T) -> Value {
+    let mut value = serde_json::to_value(entity).unwrap_or(Value::Null);
+
+    if let Value::Object(ref mut fields) = value {
+        for field in OMITTED_FIELDS {
+            fields.remove(field);
+        }
+        if let Some(Value::Array(tags)) = fields.get_mut("tags") {
+            for tag in tags.iter_mut() {
+                if let Some(name) = tag.get("name").cloned() {
+                    *tag = name;
+                }
+            }
+        }
+    }
+
+    value
+}
+
+/// Keep only the fields whose values differ between two snapshots
+fn diff(before: Value, after: Value) -> (Value, Value) {
+    let (Value::Object(before), Value::Object(after)) = (before, after) else {
+        return (Value::Null, Value::Null);
+    };
+
+    let mut changed_before = Map::new();
+    let mut changed_after = Map::new();
+    for (key, new) in after {
+        let old = before.get(&key).cloned().unwrap_or(Value::Null);
+        if old != new {
+            changed_before.insert(key.clone(), old);
+            changed_after.insert(key, new);
+        }
+    }
+
+    (Value::Object(changed_before), Value::Object(changed_after))
+}
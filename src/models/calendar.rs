@@ -0,0 +1,81 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use super::ComplianceItem;
+
+/// Length of the "this week" window in days
+pub const WEEK_WINDOW_DAYS: i64 = 7;
+
+/// Length of the "this month" window in days
+pub const MONTH_WINDOW_DAYS: i64 = 30;
+
+/// Open compliance items grouped by due window
+///
+/// Windows are rolling: "this week" covers the next 7 days and "this month"
+/// the 23 days after that. Each group is ordered by due date.
+#[derive(Debug, Serialize)]
+pub struct UpcomingCompliance {
+    /// Open items past their due date
+    pub overdue: Vec<ComplianceItem>,
+
+    /// Items due within the next 7 days
+    pub this_week: Vec<ComplianceItem>,
+
+    /// Items due within the next 30 days, after this week
+    pub this_month: Vec<ComplianceItem>,
+
+    /// Reference time of the windows
+    pub generated_at: DateTime<Utc>,
+}
+
+impl UpcomingCompliance {
+    /// Group items by due window
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - Open items ordered by due date
+    /// * `now` - Reference time
+    ///
+    /// # Returns
+    ///
+    /// Grouped items; items without a due date or due later are dropped
+    pub fn group(items: Vec<ComplianceItem>, now: DateTime<Utc>) -> Self {
+        let week_end = now + Duration::days(WEEK_WINDOW_DAYS);
+        let month_end = now + Duration::days(MONTH_WINDOW_DAYS);
+
+        let mut upcoming = Self {
+            overdue: Vec::new(),
+            this_week: Vec::new(),
+            this_month: Vec::new(),
+            generated_at: now,
+        };
+        for item in items {
+            match item.due_date {
+                Some(due) if due < now => upcoming.overdue.push(item),
+                Some(due) if due < week_end => upcoming.this_week.push(item),
+                Some(due) if due < month_end => upcoming.this_month.push(item),
+                _ => {}
+            }
+        }
+
+        upcoming
+    }
+}
+
+/// Subscription details of a user's calendar feed
+#[derive(Debug, Serialize)]
+pub struct CalendarFeed {
+    /// Secret feed token
+    pub token: String,
+
+    /// Feed path relative to the server root
+    pub feed_path: String,
+}
+
+impl CalendarFeed {
+    /// Build subscription details for a token
+    pub fn new(token: String) -> Self {
+        let feed_path = format!("/api/calendar/{}.ics", token);
+        Self { token, feed_path }
+    }
+}
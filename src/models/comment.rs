@@ -0,0 +1,198 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Entity types that can carry comments
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CommentEntityType {
+    #[serde(rename = "compliance_item")]
+    ComplianceItem,
+
+    #[serde(rename = "document")]
+    Document,
+
+    #[serde(rename = "risk_score")]
+    RiskScore,
+}
+
+impl CommentEntityType {
+    /// Convert CommentEntityType to database string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommentEntityType::ComplianceItem => "compliance_item",
+            CommentEntityType::Document => "document",
+            CommentEntityType::RiskScore => "risk_score",
+        }
+    }
+
+    /// Table holding the parent entity (used for ownership checks)
+    pub fn table_name(&self) -> &'static str {
+        match self {
+            CommentEntityType::ComplianceItem => "compliance_items",
+            CommentEntityType::Document => "documents",
+            CommentEntityType::RiskScore => "risk_scores",
+        }
+    }
+
+    /// Human readable name used in error messages
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            CommentEntityType::ComplianceItem => "Compliance item",
+            CommentEntityType::Document => "Document",
+            CommentEntityType::RiskScore => "Risk score",
+        }
+    }
+}
+
+/// Comment model from database
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Comment {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Type of the commented entity
+    pub entity_type: String,
+
+    /// ID of the commented entity
+    pub entity_id: Uuid,
+
+    /// Parent comment when this is a reply
+    pub parent_id: Option<Uuid>,
+
+    /// Author of the comment
+    pub user_id: Uuid,
+
+    /// Comment text
+    pub body: String,
+
+    /// Last edit timestamp
+    pub edited_at: Option<DateTime<Utc>>,
+
+    /// Deletion timestamp (soft delete keeps replies attached)
+    pub deleted_at: Option<DateTime<Utc>>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Previous version of an edited comment
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct CommentEdit {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Edited comment
+    pub comment_id: Uuid,
+
+    /// Body before the edit
+    pub previous_body: String,
+
+    /// User who made the edit
+    pub edited_by: Uuid,
+
+    /// Edit timestamp
+    pub edited_at: DateTime<Utc>,
+}
+
+/// DTO for creating comments
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateCommentDto {
+    /// Comment text (mention users with `@email`)
+    #[validate(length(min = 1, max = 10000, message = "Comment must be 1-10000 characters"))]
+    pub body: String,
+
+    /// Comment being replied to (optional)
+    pub parent_id: Option<Uuid>,
+}
+
+/// DTO for editing comments
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateCommentDto {
+    /// New comment text
+    #[validate(length(min = 1, max = 10000, message = "Comment must be 1-10000 characters"))]
+    pub body: String,
+}
+
+/// Mentioned user summary
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct CommentMention {
+    /// Comment containing the mention
+    #[serde(skip_serializing)]
+    pub comment_id: Uuid,
+
+    /// Mentioned user ID
+    pub user_id: Uuid,
+
+    /// Mentioned user email
+    pub email: String,
+
+    /// Mentioned user name
+    pub full_name: String,
+}
+
+/// Comment response with replies nested under their parent
+#[derive(Debug, Serialize)]
+pub struct CommentResponse {
+    /// Comment ID
+    pub id: Uuid,
+
+    /// Parent comment ID
+    pub parent_id: Option<Uuid>,
+
+    /// Author ID
+    pub user_id: Uuid,
+
+    /// Comment text (empty once deleted)
+    pub body: String,
+
+    /// Users mentioned in the comment
+    pub mentions: Vec<CommentMention>,
+
+    /// Whether the comment has been edited
+    pub edited: bool,
+
+    /// Whether the comment has been deleted
+    pub deleted: bool,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+
+    /// Replies to this comment
+    pub replies: Vec<CommentResponse>,
+}
+
+impl From<Comment> for CommentResponse {
+    /// Convert Comment to CommentResponse
+    ///
+    /// # Arguments
+    ///
+    /// * `comment` - Comment model from database
+    ///
+    /// # Returns
+    ///
+    /// Comment representation without mentions or replies
+    fn from(comment: Comment) -> Self {
+        let deleted = comment.deleted_at.is_some();
+
+        Self {
+            id: comment.id,
+            parent_id: comment.parent_id,
+            user_id: comment.user_id,
+            body: if deleted { String::new() } else { comment.body },
+            mentions: Vec::new(),
+            edited: comment.edited_at.is_some(),
+            deleted,
+            created_at: comment.created_at,
+            updated_at: comment.updated_at,
+            replies: Vec::new(),
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::{BTreeMap, HashMap};
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -88,11 +90,25 @@ pub struct ComplianceItem {
     /// Due date for completion
     pub due_date: Option<DateTime<Utc>>,
     
+    /// Compliance framework (e.g., "SOC 2", "ISO 27001")
+    pub framework: Option<String>,
+    
+    /// User responsible for the item
+    pub assignee_id: Option<Uuid>,
+    
     /// Creation timestamp
     pub created_at: DateTime<Utc>,
     
     /// Last update timestamp
     pub updated_at: DateTime<Utc>,
+    
+    /// Tags assigned to the item
+    #[sqlx(skip)]
+    pub tags: Vec<super::Tag>,
+    
+    /// Custom field values keyed by field name
+    #[sqlx(skip)]
+    pub custom_fields: BTreeMap<String, serde_json::Value>,
 }
 
 /// DTO for creating complian ce items
@@ -113,6 +129,19 @@ pub struct CreateComplianceDto {
     
     /// Due date (optional)
     pub due_date: Option<DateTime<Utc>>,
+    
+    /// Compliance framework (optional)
+    #[validate(length(min = 1, max = 100, message = "Framework must be 1-100 characters"))]
+    pub framework: Option<String>,
+    
+    /// Assignee user ID (optional)
+    pub assignee_id: Option<Uuid>,
+    
+    /// Tag IDs to assign (optional)
+    pub tag_ids: Option<Vec<Uuid>>,
+    
+    /// Custom field values keyed by field name (optional)
+    pub custom_fields: Option<HashMap<String, serde_json::Value>>,
 }
 
 /// DTO for updating compliance items
@@ -133,4 +162,17 @@ pub struct UpdateComplianceDto {
     
     /// Due date (optional)
     pub due_date: Option<DateTime<Utc>>,
+    
+    /// Compliance framework (optional)
+    #[validate(length(min = 1, max = 100, message = "Framework must be 1-100 characters"))]
+    pub framework: Option<String>,
+    
+    /// Assignee user ID (optional)
+    pub assignee_id: Option<Uuid>,
+    
+    /// Tag IDs (optional, replaces the current tags)
+    pub tag_ids: Option<Vec<Uuid>>,
+    
+    /// Custom field values (optional, merged; null removes a value)
+    pub custom_fields: Option<HashMap<String, serde_json::Value>>,
 }
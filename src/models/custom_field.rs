@@ -0,0 +1,169 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Value types supported by custom fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CustomFieldType {
+    #[serde(rename = "text")]
+    Text,
+
+    #[serde(rename = "number")]
+    Number,
+
+    #[serde(rename = "date")]
+    Date,
+
+    #[serde(rename = "enum")]
+    Enum,
+}
+
+impl CustomFieldType {
+    /// Convert CustomFieldType to database string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CustomFieldType::Text => "text",
+            CustomFieldType::Number => "number",
+            CustomFieldType::Date => "date",
+            CustomFieldType::Enum => "enum",
+        }
+    }
+
+    /// Parse CustomFieldType from database string
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "text" => Some(CustomFieldType::Text),
+            "number" => Some(CustomFieldType::Number),
+            "date" => Some(CustomFieldType::Date),
+            "enum" => Some(CustomFieldType::Enum),
+            _ => None,
+        }
+    }
+}
+
+/// Custom field definition model from database
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct CustomFieldDefinition {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// User who owns this definition
+    pub user_id: Uuid,
+
+    /// Entity type the field applies to ("compliance_item" or "document")
+    pub entity_type: String,
+
+    /// Machine name used as the key in `custom_fields`
+    pub name: String,
+
+    /// Display label
+    pub label: String,
+
+    /// Value type ("text", "number", "date" or "enum")
+    pub field_type: String,
+
+    /// Allowed values for enum fields
+    pub options: Option<sqlx::types::Json<Vec<String>>>,
+
+    /// Whether a value must be provided when creating an entity
+    pub required: bool,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+impl CustomFieldDefinition {
+    /// Validate a value against this definition
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - JSON value supplied by the client
+    ///
+    /// # Returns
+    ///
+    /// Ok if the value matches the field type, Err with a message otherwise
+    pub fn validate_value(&self, value: &serde_json::Value) -> Result<(), String> {
+        let valid = match CustomFieldType::parse(&self.field_type) {
+            Some(CustomFieldType::Text) => value.is_string(),
+            Some(CustomFieldType::Number) => value.is_number(),
+            Some(CustomFieldType::Date) => value
+                .as_str()
+                .is_some_and(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok()),
+            Some(CustomFieldType::Enum) => value.as_str().is_some_and(|s| {
+                self.options
+                    .as_ref()
+                    .is_some_and(|options| options.iter().any(|o| o == s))
+            }),
+            None => false,
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(match CustomFieldType::parse(&self.field_type) {
+                Some(CustomFieldType::Date) => {
+                    format!("Custom field '{}' must be a date (YYYY-MM-DD)", self.name)
+                }
+                Some(CustomFieldType::Enum) => format!(
+                    "Custom field '{}' must be one of: {}",
+                    self.name,
+                    self.options.as_ref().map(|o| o.join(", ")).unwrap_or_default()
+                ),
+                _ => format!("Custom field '{}' must be a {}", self.name, self.field_type),
+            })
+        }
+    }
+}
+
+/// DTO for creating custom field definitions
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateCustomFieldDto {
+    /// Entity type ("compliance_item" or "document")
+    pub entity_type: super::MetadataEntityType,
+
+    /// Machine name (letters, digits and underscores)
+    #[validate(length(min = 1, max = 100, message = "Name must be 1-100 characters"))]
+    #[validate(custom(function = "validate_field_name"))]
+    pub name: String,
+
+    /// Display label
+    #[validate(length(min = 1, max = 255, message = "Label must be 1-255 characters"))]
+    pub label: String,
+
+    /// Value type
+    pub field_type: CustomFieldType,
+
+    /// Allowed values (required for enum fields)
+    pub options: Option<Vec<String>>,
+
+    /// Whether a value is required (default: false)
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// DTO for updating custom field definitions
+///
+/// The entity type, name and value type are fixed once values exist.
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateCustomFieldDto {
+    /// Display label
+    #[validate(length(min = 1, max = 255, message = "Label must be 1-255 characters"))]
+    pub label: Option<String>,
+
+    /// Allowed values for enum fields
+    pub options: Option<Vec<String>>,
+
+    /// Whether a value is required
+    pub required: Option<bool>,
+}
+
+/// Validate custom field machine name
+fn validate_field_name(name: &str) -> Result<(), validator::ValidationError> {
+    if name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Ok(())
+    } else {
+        Err(validator::ValidationError::new("invalid_field_name"))
+    }
+}
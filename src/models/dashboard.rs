@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::{MetadataFilter, TrendInterval};
+
+/// Burn-down length used when no start date is given
+const DEFAULT_BURNDOWN_DAYS: i64 = 30;
+
+/// Filters for dashboard statistics
+///
+/// Compliance items are limited to those created within the date range and
+/// counted by their status at the end of it. Documents honour the date range
+/// (by upload date) and tag filters only.
+#[derive(Debug, Default, Clone)]
+pub struct DashboardFilter {
+    /// Inclusive start of the period
+    pub from: Option<DateTime<Utc>>,
+
+    /// Exclusive end of the period
+    pub to: Option<DateTime<Utc>>,
+
+    /// Compliance framework
+    pub framework: Option<String>,
+
+    /// Assignee user ID
+    pub assignee_id: Option<Uuid>,
+
+    /// Tag and custom field filters
+    pub metadata: MetadataFilter,
+
+    /// Whether to compare with the preceding period of equal length
+    pub compare: bool,
+}
+
+impl DashboardFilter {
+    /// Build filter from raw query parameters
+    ///
+    /// Accepts `from`/`to` (RFC 3339), `framework`, `assignee` (user UUID),
+    /// `compare=true` and the metadata filters of list endpoints.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Query parameters
+    ///
+    /// # Returns
+    ///
+    /// Filter
+    ///
+    /// # Errors
+    ///
+    /// Returns a message for malformed values or an invalid period
+    pub fn from_query(params: &HashMap<String, String>) -> Result<Self, String> {
+        let parse_date = |key: &str| {
+            params
+                .get(key)
+                .map(|value| {
+                    DateTime::parse_from_rfc3339(value)
+                        .map(|date| date.with_timezone(&Utc))
+                        .map_err(|_| format!("'{}' must be an RFC 3339 timestamp", key))
+                })
+                .transpose()
+        };
+
+        let filter = Self {
+            from: parse_date("from")?,
+            to: parse_date("to")?,
+            framework: params.get("framework").filter(|f| !f.is_empty()).cloned(),
+            assignee_id: params
+                .get("assignee")
+                .map(|id| Uuid::parse_str(id).map_err(|_| "'assignee' must be a user ID".to_string()))
+                .transpose()?,
+            metadata: MetadataFilter::from_query(params),
+            compare: params
+                .get("compare")
+                .map(|c| c.parse().map_err(|_| "'compare' must be true or false".to_string()))
+                .transpose()?
+                .unwrap_or(false),
+        };
+
+        if let (Some(from), Some(to)) = (filter.from, filter.to) {
+            if from >= to {
+                return Err("'from' must be before 'to'".to_string());
+            }
+        }
+        if filter.compare && filter.from.is_none() {
+            return Err("'compare' requires a 'from' date".to_string());
+        }
+
+        Ok(filter)
+    }
+
+    /// Same filter over the preceding period of equal length
+    ///
+    /// # Returns
+    ///
+    /// Filter for the previous period, None without a start date
+    pub fn previous_period(&self) -> Option<Self> {
+        let from = self.from?;
+        let to = self.to.unwrap_or_else(Utc::now);
+
+        Some(Self {
+            from: Some(from - (to - from)),
+            to: Some(from),
+            compare: false,
+            ..self.clone()
+        })
+    }
+
+    /// Range and step of the burn-down series
+    ///
+    /// Defaults to the last 30 days; the step widens to weeks beyond two
+    /// months and to months beyond a year.
+    ///
+    /// # Returns
+    ///
+    /// Start, end and bucket size
+    pub fn burndown_range(&self) -> (DateTime<Utc>, DateTime<Utc>, TrendInterval) {
+        let to = self.to.unwrap_or_else(Utc::now);
+        let from = self.from.unwrap_or(to - Duration::days(DEFAULT_BURNDOWN_DAYS));
+
+        let interval = match (to - from).num_days() {
+            days if days > 366 => TrendInterval::Month,
+            days if days > 62 => TrendInterval::Week,
+            _ => TrendInterval::Day,
+        };
+
+        (from, to, interval)
+    }
+}
+
+/// Open compliance items at one point in time
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct BurndownPoint {
+    /// Point in time
+    pub date: DateTime<Utc>,
+
+    /// Items pending or in progress
+    pub open_items: i64,
+
+    /// Open items past their due date
+    pub overdue_items: i64,
+
+    /// Items that may still be open without missing a due date
+    pub planned_open_items: i64,
+}
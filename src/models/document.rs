@@ -1,3 +1,5 @@
+use std::collections::{BTreeMap, HashMap};
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -18,8 +20,12 @@ pub struct Document {
     /// Original filename
     pub filename: String,
     
-    /// File path on server
-    pub file_path: String,
+    /// Key of the file in the storage backend (never sent to clients)
+    #[serde(skip_serializing)]
+    pub storage_key: String,
+    
+    /// SHA-256 of the file content (None for files stored before deduplication)
+    pub content_sha256: Option<String>,
     
     /// File size in bytes
     pub file_size: i64,
@@ -33,8 +39,44 @@ pub struct Document {
     /// AI analysis results (JSON)
     pub ai_analysis: Option<sqlx::types::Json<serde_json::Value>>,
     
+    /// Malware scan status of the file (see [`super::ScanStatus`])
+    pub scan_status: String,
+    
+    /// Malware signature found in the file
+    pub scan_signature: Option<String>,
+    
+    /// When the file was last scanned
+    pub scanned_at: Option<DateTime<Utc>>,
+    
+    /// Fingerprint of the master key wrapping the file's data key (None if stored unencrypted)
+    pub encryption_key_id: Option<String>,
+    
+    /// Data key of the file, wrapped by the master key (hex)
+    #[serde(skip_serializing)]
+    pub wrapped_data_key: Option<String>,
+    
+    /// Kinds of personal data found in the extracted text (see [`crate::utils::pii::PiiCategory`])
+    pub pii_categories: Vec<String>,
+    
+    /// OCR confidence of the pages whose text was recognized (None if OCR was not used)
+    pub ocr_pages: Option<sqlx::types::Json<Vec<OcrPageConfidence>>>,
+    
+    /// Whether a recognized page is below the minimum OCR confidence
+    pub ocr_low_confidence: bool,
+    
+    /// Number of the current version (the fields above describe it)
+    pub current_version: i32,
+    
     /// Upload timestamp
     pub uploaded_at: DateTime<Utc>,
+    
+    /// Tags assigned to the document
+    #[sqlx(skip)]
+    pub tags: Vec<super::Tag>,
+    
+    /// Custom field values keyed by field name
+    #[sqlx(skip)]
+    pub custom_fields: BTreeMap<String, serde_json::Value>,
 }
 
 /// DTO for creating document records
@@ -46,9 +88,10 @@ pub struct CreateDocumentDto {
     #[validate(length(min = 1, message = "Filename is required"))]
     pub filename: String,
     
-    /// File path on server
-    #[validate(length(min = 1, message = "File path is required"))]
-    pub file_path: String,
+    /// Key of a file the user uploaded before (see [`crate::services::StorageService::uploaded_file`])
+    #[serde(alias = "file_path")]
+    #[validate(length(min = 1, max = 1024, message = "Storage key must be 1-1024 characters"))]
+    pub storage_key: String,
     
     /// File size in bytes
     #[validate(range(min = 1, message = "File size must be positive"))]
@@ -58,8 +101,54 @@ pub struct CreateDocumentDto {
     #[validate(length(min = 1, message = "MIME type is required"))]
     pub mime_type: String,
     
+    /// SHA-256 of the stored content (set by the server, never by clients)
+    #[serde(skip)]
+    pub content_sha256: Option<String>,
+    
     /// Extracted text (optional, can be added later)
     pub extracted_text: Option<String>,
+    
+    /// AI analysis carried over from identical content (set by the server)
+    #[serde(skip)]
+    pub ai_analysis: Option<sqlx::types::Json<serde_json::Value>>,
+    
+    /// Malware scan status (set by the server, default: not scanned)
+    #[serde(skip)]
+    pub scan_status: Option<String>,
+    
+    /// Malware signature found in the file (set by the server)
+    #[serde(skip)]
+    pub scan_signature: Option<String>,
+    
+    /// When the file was scanned (set by the server)
+    #[serde(skip)]
+    pub scanned_at: Option<DateTime<Utc>>,
+    
+    /// Fingerprint of the master key wrapping the data key (set by the server)
+    #[serde(skip)]
+    pub encryption_key_id: Option<String>,
+    
+    /// Wrapped data key of the encrypted file (set by the server)
+    #[serde(skip)]
+    pub wrapped_data_key: Option<String>,
+    
+    /// Personal data found in the extracted text (set by the server)
+    #[serde(skip)]
+    pub pii_categories: Vec<String>,
+    
+    /// OCR confidence of recognized pages (set by the server)
+    #[serde(skip)]
+    pub ocr_pages: Option<sqlx::types::Json<Vec<OcrPageConfidence>>>,
+    
+    /// Whether a recognized page is below the minimum OCR confidence (set by the server)
+    #[serde(skip)]
+    pub ocr_low_confidence: bool,
+    
+    /// Tag IDs to assign (optional)
+    pub tag_ids: Option<Vec<Uuid>>,
+    
+    /// Custom field values keyed by field name (optional)
+    pub custom_fields: Option<HashMap<String, serde_json::Value>>,
 }
 
 /// DTO for updating document analysis
@@ -72,6 +161,16 @@ pub struct UpdateDocumentDto {
     
     /// AI analysis results (JSON)
     pub ai_analysis: Option<sqlx::types::Json<serde_json::Value>>,
+    
+    /// Personal data found in the new extracted text (set by the server)
+    #[serde(skip)]
+    pub pii_categories: Option<Vec<String>>,
+    
+    /// Tag IDs (optional, replaces the current tags)
+    pub tag_ids: Option<Vec<Uuid>>,
+    
+    /// Custom field values (optional, merged; null removes a value)
+    pub custom_fields: Option<HashMap<String, serde_json::Value>>,
 }
 
 /// Document response with metadata
@@ -97,8 +196,26 @@ pub struct DocumentResponse {
     /// Whether AI analysis is available
     pub has_ai_analysis: bool,
     
+    /// Malware scan status of the file
+    pub scan_status: String,
+    
+    /// Kinds of personal data found in the extracted text
+    pub pii_categories: Vec<String>,
+    
+    /// Whether the text comes from a low-quality scan
+    pub ocr_low_confidence: bool,
+    
+    /// Number of the current version
+    pub current_version: i32,
+    
     /// Upload timestamp
     pub uploaded_at: DateTime<Utc>,
+    
+    /// Tags assigned to the document
+    pub tags: Vec<super::Tag>,
+    
+    /// Custom field values keyed by field name
+    pub custom_fields: BTreeMap<String, serde_json::Value>,
 }
 
 impl From<Document> for DocumentResponse {
@@ -119,7 +236,150 @@ impl From<Document> for DocumentResponse {
             mime_type: doc.mime_type,
             has_extracted_text: doc.extracted_text.is_some(),
             has_ai_analysis: doc.ai_analysis.is_some(),
+            scan_status: doc.scan_status,
+            pii_categories: doc.pii_categories,
+            ocr_low_confidence: doc.ocr_low_confidence,
+            current_version: doc.current_version,
             uploaded_at: doc.uploaded_at,
+            tags: doc.tags,
+            custom_fields: doc.custom_fields,
+        }
+    }
+}
+
+/// OCR result of one page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OcrPageConfidence {
+    /// Page number (from 1)
+    pub page: i32,
+    
+    /// Mean word confidence reported by the OCR engine (0-100)
+    pub confidence: f32,
+}
+
+/// Version of a document
+///
+/// Versions are numbered from 1; the document itself mirrors the latest one.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct DocumentVersion {
+    /// Unique identifier
+    pub id: Uuid,
+    
+    /// Parent document
+    pub document_id: Uuid,
+    
+    /// Version number (1 = original upload)
+    pub version_number: i32,
+    
+    /// Filename of this version
+    pub filename: String,
+    
+    /// Key of the file in the storage backend (never sent to clients)
+    #[serde(skip_serializing)]
+    pub storage_key: String,
+    
+    /// SHA-256 of the file content
+    pub content_sha256: Option<String>,
+    
+    /// File size in bytes
+    pub file_size: i64,
+    
+    /// MIME type
+    pub mime_type: String,
+    
+    /// Extracted text of this version
+    pub extracted_text: Option<String>,
+    
+    /// AI analysis computed against this version (JSON)
+    pub ai_analysis: Option<sqlx::types::Json<serde_json::Value>>,
+    
+    /// Malware scan status of this version's file
+    pub scan_status: String,
+    
+    /// Malware signature found in the file
+    pub scan_signature: Option<String>,
+    
+    /// When the file was last scanned
+    pub scanned_at: Option<DateTime<Utc>>,
+    
+    /// Fingerprint of the master key wrapping the file's data key (None if stored unencrypted)
+    pub encryption_key_id: Option<String>,
+    
+    /// Data key of the file, wrapped by the master key (hex)
+    #[serde(skip_serializing)]
+    pub wrapped_data_key: Option<String>,
+    
+    /// Kinds of personal data found in the extracted text
+    pub pii_categories: Vec<String>,
+    
+    /// OCR confidence of the pages whose text was recognized (None if OCR was not used)
+    pub ocr_pages: Option<sqlx::types::Json<Vec<OcrPageConfidence>>>,
+    
+    /// Whether a recognized page is below the minimum OCR confidence
+    pub ocr_low_confidence: bool,
+    
+    /// Upload timestamp of this version
+    pub created_at: DateTime<Utc>,
+}
+
+/// Document version without content
+///
+/// Used in version history listings
+#[derive(Debug, Serialize)]
+pub struct DocumentVersionSummary {
+    /// Version ID
+    pub id: Uuid,
+    
+    /// Version number
+    pub version_number: i32,
+    
+    /// Filename
+    pub filename: String,
+    
+    /// File size in bytes
+    pub file_size: i64,
+    
+    /// MIME type
+    pub mime_type: String,
+    
+    /// Whether text has been extracted
+    pub has_extracted_text: bool,
+    
+    /// Whether AI analysis is available
+    pub has_ai_analysis: bool,
+    
+    /// Malware scan status of the file
+    pub scan_status: String,
+    
+    /// Whether the text comes from a low-quality scan
+    pub ocr_low_confidence: bool,
+    
+    /// Upload timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<DocumentVersion> for DocumentVersionSummary {
+    /// Convert DocumentVersion to DocumentVersionSummary
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - Document version from database
+    ///
+    /// # Returns
+    ///
+    /// Version representation for history listings
+    fn from(version: DocumentVersion) -> Self {
+        Self {
+            id: version.id,
+            version_number: version.version_number,
+            filename: version.filename,
+            file_size: version.file_size,
+            mime_type: version.mime_type,
+            has_extracted_text: version.extracted_text.is_some(),
+            has_ai_analysis: version.ai_analysis.is_some(),
+            scan_status: version.scan_status,
+            ocr_low_confidence: version.ocr_low_confidence,
+            created_at: version.created_at,
         }
     }
 }
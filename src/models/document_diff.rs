@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Unit of text two versions are compared in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffGranularity {
+    #[default]
+    Paragraph,
+    Sentence,
+}
+
+/// Query parameters for the version diff endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct VersionDiffQuery {
+    /// Older version (default: the one before `to`)
+    pub from: Option<i32>,
+
+    /// Newer version (default: the current version)
+    pub to: Option<i32>,
+
+    /// Comparison unit (default: paragraph)
+    pub granularity: Option<DiffGranularity>,
+
+    /// Ask the LLM to classify the changes and flag impacted compliance items
+    #[serde(default)]
+    pub classify: bool,
+}
+
+/// How a passage changed between versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// What a change means for compliance (LLM classification)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeClassification {
+    AddedObligation,
+    RemovedObligation,
+    WordingOnly,
+}
+
+/// One changed passage
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionChange {
+    /// Kind of change
+    pub kind: ChangeKind,
+
+    /// Position of the passage in the older version (segment index)
+    pub before_index: usize,
+
+    /// Position of the passage in the newer version (segment index)
+    pub after_index: usize,
+
+    /// Text in the older version (None for additions)
+    pub before: Option<String>,
+
+    /// Text in the newer version (None for removals)
+    pub after: Option<String>,
+
+    /// Compliance meaning, if classified
+    pub classification: Option<ChangeClassification>,
+
+    /// Explanation of the classification
+    pub rationale: Option<String>,
+}
+
+/// Compliance item the LLM considers affected by the changes
+#[derive(Debug, Clone, Serialize)]
+pub struct ImpactedComplianceItem {
+    /// Compliance item ID
+    pub id: Uuid,
+
+    /// Title
+    pub title: String,
+
+    /// Why the item may be affected
+    pub reason: String,
+}
+
+/// Differences between two versions of a document
+#[derive(Debug, Serialize)]
+pub struct VersionDiff {
+    /// Document ID
+    pub document_id: Uuid,
+
+    /// Older version number
+    pub from_version: i32,
+
+    /// Newer version number
+    pub to_version: i32,
+
+    /// Comparison unit
+    pub granularity: DiffGranularity,
+
+    /// Passages present unchanged in both versions
+    pub unchanged: usize,
+
+    /// Changed passages in document order
+    pub changes: Vec<VersionChange>,
+
+    /// Whether the LLM classified the changes
+    pub classified: bool,
+
+    /// Compliance items possibly affected (only when classified)
+    pub impacted_items: Vec<ImpactedComplianceItem>,
+}
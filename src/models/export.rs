@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use futures_util::stream::BoxStream;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+
+/// Rows streamed from the database for an export
+pub type ExportRowStream<T> = BoxStream<'static, AppResult<T>>;
+
+/// Spreadsheet formats offered by the export endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Xlsx,
+    Json,
+}
+
+impl ExportFormat {
+    /// Read the `format` query parameter (default: csv)
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Query parameters
+    ///
+    /// # Returns
+    ///
+    /// Requested format
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error for an unknown format
+    pub fn from_query(params: &HashMap<String, String>) -> AppResult<Self> {
+        match params.get("format").map(|f| f.to_lowercase()).as_deref() {
+            None | Some("csv") => Ok(ExportFormat::Csv),
+            Some("xlsx") => Ok(ExportFormat::Xlsx),
+            Some("json") => Ok(ExportFormat::Json),
+            Some(other) => Err(AppError::Validation(format!(
+                "Unsupported export format '{}' (expected csv, xlsx or json)",
+                other
+            ))),
+        }
+    }
+
+    /// MIME type of the format
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            ExportFormat::Json => "application/json",
+        }
+    }
+
+    /// File extension of the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// Row type that can be exported
+///
+/// `COLUMNS` lists the serialized field names in column order.
+pub trait ExportRecord: Serialize + Send + 'static {
+    /// Column names, matching the serialized field names
+    const COLUMNS: &'static [&'static str];
+
+    /// Worksheet name and file name stem
+    const NAME: &'static str;
+}
+
+/// Compliance register row with joined columns
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct ComplianceExportRow {
+    /// Compliance item UUID
+    pub id: Uuid,
+
+    /// Title
+    pub title: String,
+
+    /// Description
+    pub description: Option<String>,
+
+    /// Risk level
+    pub risk_level: String,
+
+    /// Status
+    pub status: String,
+
+    /// Framework
+    pub framework: Option<String>,
+
+    /// Due date
+    pub due_date: Option<DateTime<Utc>>,
+
+    /// Assignee name
+    pub assignee_name: Option<String>,
+
+    /// Assignee email
+    pub assignee_email: Option<String>,
+
+    /// Tag names, comma-separated
+    pub tags: String,
+
+    /// Most recent risk score
+    pub latest_risk_score: Option<i32>,
+
+    /// Level of the most recent risk score
+    pub latest_risk_level: Option<String>,
+
+    /// Residual score of the most recent risk score
+    pub latest_residual_score: Option<i32>,
+
+    /// When the most recent risk score was assessed
+    pub latest_assessment_date: Option<DateTime<Utc>>,
+
+    /// Filenames of documents linked through risk scores, separated by `; `
+    pub evidence_files: String,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ExportRecord for ComplianceExportRow {
+    const COLUMNS: &'static [&'static str] = &[
+        "id",
+        "title",
+        "description",
+        "risk_level",
+        "status",
+        "framework",
+        "due_date",
+        "assignee_name",
+        "assignee_email",
+        "tags",
+        "latest_risk_score",
+        "latest_risk_level",
+        "latest_residual_score",
+        "latest_assessment_date",
+        "evidence_files",
+        "created_at",
+        "updated_at",
+    ];
+
+    const NAME: &'static str = "compliance-register";
+}
+
+/// Risk score row with joined columns
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct RiskScoreExportRow {
+    /// Risk score UUID
+    pub id: Uuid,
+
+    /// Assessed compliance item UUID
+    pub compliance_item_id: Uuid,
+
+    /// Title of the compliance item
+    pub compliance_item_title: String,
+
+    /// Status of the compliance item
+    pub compliance_item_status: String,
+
+    /// Risk category
+    pub risk_category: String,
+
+    /// Inherent score
+    pub risk_score: i32,
+
+    /// Inherent level
+    pub risk_level: String,
+
+    /// Likelihood rating
+    pub likelihood: Option<i32>,
+
+    /// Impact rating
+    pub impact: Option<i32>,
+
+    /// Score after controls
+    pub residual_score: i32,
+
+    /// Level after controls
+    pub residual_level: String,
+
+    /// Number of controls
+    pub control_count: i64,
+
+    /// Assessment date
+    pub assessment_date: DateTime<Utc>,
+
+    /// Assessor
+    pub assessed_by: Option<String>,
+
+    /// Evidence document filename
+    pub evidence_file: Option<String>,
+
+    /// AI confidence
+    pub ai_confidence: Option<f32>,
+
+    /// Notes
+    pub notes: Option<String>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+impl ExportRecord for RiskScoreExportRow {
+    const COLUMNS: &'static [&'static str] = &[
+        "id",
+        "compliance_item_id",
+        "compliance_item_title",
+        "compliance_item_status",
+        "risk_category",
+        "risk_score",
+        "risk_level",
+        "likelihood",
+        "impact",
+        "residual_score",
+        "residual_level",
+        "control_count",
+        "assessment_date",
+        "assessed_by",
+        "evidence_file",
+        "ai_confidence",
+        "notes",
+        "created_at",
+    ];
+
+    const NAME: &'static str = "risk-scores";
+}
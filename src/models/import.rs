@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Entities that can be bulk imported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportTarget {
+    #[serde(rename = "compliance_item")]
+    ComplianceItem,
+
+    #[serde(rename = "risk_score")]
+    RiskScore,
+}
+
+impl ImportTarget {
+    /// Built-in fields a column can be mapped to
+    ///
+    /// Compliance items additionally accept `cf.<name>` for each custom field.
+    pub fn fields(&self) -> &'static [ImportField] {
+        match self {
+            ImportTarget::ComplianceItem => COMPLIANCE_IMPORT_FIELDS,
+            ImportTarget::RiskScore => RISK_SCORE_IMPORT_FIELDS,
+        }
+    }
+}
+
+/// How a cell is converted before validation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFieldKind {
+    /// Free text
+    Text,
+
+    /// Enumerated value, normalized to snake_case (`In Progress` → `in_progress`)
+    Choice,
+
+    /// Date (`YYYY-MM-DD` or RFC 3339)
+    Date,
+
+    /// Whole number
+    Integer,
+
+    /// UUID
+    Uuid,
+
+    /// Email address of an existing user
+    Email,
+
+    /// Comma-separated names of existing tags
+    TagList,
+}
+
+/// Field a column can be mapped to
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportField {
+    /// Field name used in the mapping
+    pub name: &'static str,
+
+    /// Whether every row must have a value
+    pub required: bool,
+
+    /// Value conversion
+    pub kind: ImportFieldKind,
+}
+
+const fn field(name: &'static str, required: bool, kind: ImportFieldKind) -> ImportField {
+    ImportField { name, required, kind }
+}
+
+/// Importable compliance item fields
+pub const COMPLIANCE_IMPORT_FIELDS: &[ImportField] = &[
+    field("title", true, ImportFieldKind::Text),
+    field("description", false, ImportFieldKind::Text),
+    field("risk_level", true, ImportFieldKind::Choice),
+    field("status", true, ImportFieldKind::Choice),
+    field("due_date", false, ImportFieldKind::Date),
+    field("framework", false, ImportFieldKind::Text),
+    field("assignee_email", false, ImportFieldKind::Email),
+    field("tags", false, ImportFieldKind::TagList),
+];
+
+/// Importable risk score fields
+///
+/// Each row needs either `compliance_item_id` or `compliance_item_title`.
+pub const RISK_SCORE_IMPORT_FIELDS: &[ImportField] = &[
+    field("compliance_item_id", false, ImportFieldKind::Uuid),
+    field("compliance_item_title", false, ImportFieldKind::Text),
+    field("risk_category", true, ImportFieldKind::Text),
+    field("likelihood", false, ImportFieldKind::Integer),
+    field("impact", false, ImportFieldKind::Integer),
+    field("risk_score", false, ImportFieldKind::Integer),
+    field("risk_level", false, ImportFieldKind::Choice),
+    field("assessment_date", false, ImportFieldKind::Date),
+    field("assessed_by", false, ImportFieldKind::Text),
+    field("notes", false, ImportFieldKind::Text),
+];
+
+/// Field offered in the column-mapping step
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportFieldInfo {
+    /// Field name used in the mapping
+    pub name: String,
+
+    /// Whether every row must have a value
+    pub required: bool,
+
+    /// Value conversion
+    pub kind: ImportFieldKind,
+}
+
+/// Parsed spreadsheet offered for mapping
+#[derive(Debug, Serialize)]
+pub struct ImportPreview {
+    /// Column headers in file order
+    pub headers: Vec<String>,
+
+    /// First rows of the file
+    pub sample_rows: Vec<Vec<String>>,
+
+    /// Number of data rows
+    pub row_count: usize,
+
+    /// Fields the columns can be mapped to
+    pub fields: Vec<ImportFieldInfo>,
+
+    /// Field → column mapping guessed from the headers
+    pub suggested_mapping: BTreeMap<String, String>,
+}
+
+/// Problem with one row of an import
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportRowError {
+    /// Line in the file (the header is line 1)
+    pub row: usize,
+
+    /// Field the problem is about (None for row-level problems)
+    pub field: Option<String>,
+
+    /// Description of the problem
+    pub message: String,
+}
+
+/// Outcome of an import or dry run
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    /// Whether only validation was performed
+    pub dry_run: bool,
+
+    /// Whether the rows were written
+    pub committed: bool,
+
+    /// Number of data rows in the file
+    pub total_rows: usize,
+
+    /// Number of rows without errors
+    pub valid_rows: usize,
+
+    /// Problems found, by row
+    pub errors: Vec<ImportRowError>,
+
+    /// IDs of the created entities (empty unless committed)
+    pub created_ids: Vec<Uuid>,
+}
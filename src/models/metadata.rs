@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Entity types that can carry tags and custom fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MetadataEntityType {
+    #[serde(rename = "compliance_item")]
+    ComplianceItem,
+
+    #[serde(rename = "document")]
+    Document,
+}
+
+impl MetadataEntityType {
+    /// Convert MetadataEntityType to database string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetadataEntityType::ComplianceItem => "compliance_item",
+            MetadataEntityType::Document => "document",
+        }
+    }
+}
+
+/// Query prefix for custom field filters (`?cf.jurisdiction=EU`)
+pub const CUSTOM_FIELD_FILTER_PREFIX: &str = "cf.";
+
+/// Tag and custom field filters for list endpoints
+#[derive(Debug, Default, Clone)]
+pub struct MetadataFilter {
+    /// Tag names the entity must carry (all of them, case-insensitive)
+    pub tags: Vec<String>,
+
+    /// Custom field name/value pairs the entity must match
+    pub custom_fields: Vec<(String, String)>,
+}
+
+impl MetadataFilter {
+    /// Build filter from raw query parameters
+    ///
+    /// `tags` is a comma-separated list of tag names and every `cf.<name>`
+    /// parameter filters on a custom field value.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Query parameters
+    ///
+    /// # Returns
+    ///
+    /// Filter (empty if no metadata parameters were supplied)
+    pub fn from_query(params: &HashMap<String, String>) -> Self {
+        let tags = params
+            .get("tags")
+            .map(|tags| {
+                tags.split(',')
+                    .map(|t| t.trim().to_lowercase())
+                    .filter(|t| !t.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut custom_fields: Vec<(String, String)> = params
+            .iter()
+            .filter_map(|(key, value)| {
+                key.strip_prefix(CUSTOM_FIELD_FILTER_PREFIX)
+                    .map(|name| (name.to_string(), value.clone()))
+            })
+            .collect();
+        custom_fields.sort();
+
+        Self { tags, custom_fields }
+    }
+
+    /// Whether the filter has no conditions
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.custom_fields.is_empty()
+    }
+}
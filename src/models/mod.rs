@@ -1,9 +1,79 @@
+pub mod activity;
+pub mod calendar;
+pub mod comment;
 pub mod compliance;
+pub mod custom_field;
+pub mod dashboard;
 pub mod document;
+pub mod document_diff;
+pub mod export;
+pub mod import;
+pub mod metadata;
+pub mod notification;
+pub mod realtime;
+pub mod report;
+pub mod risk_control;
+pub mod risk_matrix;
 pub mod risk_score;
+pub mod risk_trend;
+pub mod storage;
+pub mod tag;
 pub mod user;
+pub mod webhook;
 
+pub use activity::{
+    ActivityEntityType, ActivityEvent, ActivityFeedQuery, ActivityPage, ActivityVerb, NewActivityEvent,
+};
+pub use calendar::{CalendarFeed, UpcomingCompliance};
+pub use comment::{
+    Comment, CommentEdit, CommentEntityType, CommentMention, CommentResponse, CreateCommentDto,
+    UpdateCommentDto,
+};
 pub use compliance::{ComplianceItem, ComplianceStatus, CreateComplianceDto, RiskLevel, UpdateComplianceDto};
-pub use document::{CreateDocumentDto, Document, DocumentResponse, UpdateDocumentDto};
+pub use custom_field::{
+    CreateCustomFieldDto, CustomFieldDefinition, CustomFieldType, UpdateCustomFieldDto,
+};
+pub use dashboard::{BurndownPoint, DashboardFilter};
+pub use document::{
+    CreateDocumentDto, Document, DocumentResponse, DocumentVersion, DocumentVersionSummary, OcrPageConfidence,
+    UpdateDocumentDto,
+};
+pub use document_diff::{
+    ChangeClassification, ChangeKind, DiffGranularity, ImpactedComplianceItem, VersionChange, VersionDiff,
+    VersionDiffQuery,
+};
+pub use export::{
+    ComplianceExportRow, ExportFormat, ExportRecord, ExportRowStream, RiskScoreExportRow,
+};
+pub use import::{
+    ImportField, ImportFieldInfo, ImportFieldKind, ImportPreview, ImportReport, ImportRowError, ImportTarget,
+};
+pub use metadata::{MetadataEntityType, MetadataFilter};
+pub use notification::{
+    NewNotification, Notification, NotificationKind, NotificationList, NotificationListQuery,
+    NotificationPreferences, UpdateNotificationPreferencesDto, DEFAULT_REMINDER_LEAD_DAYS,
+};
+pub use realtime::{
+    AnalysisStage, RealtimeClientMessage, RealtimeEvent, RealtimeMessage, RealtimeReply, RealtimeTopic,
+};
+pub use report::{AuditAssessment, AuditEvidence, AuditHeatmapCell, AuditReport};
+pub use risk_control::{
+    control_reductions, residual_score, ControlEffectivenessReport, CreateRiskControlDto, RiskControl,
+    RiskControlResponse, UpdateRiskControlDto,
+};
+pub use risk_matrix::{ResolvedRisk, RiskMatrix, RiskMatrixCell, RiskThresholds, RISK_LEVELS};
 pub use risk_score::{CreateRiskScoreDto, RiskScore, UpdateRiskScoreDto};
+pub use risk_trend::{
+    CategoryTrend, CategoryTrendBucket, RiskJump, RiskTrend, RiskTrendQuery, TrendBucket,
+    TrendInterval, DEFAULT_JUMP_THRESHOLD,
+};
+pub use storage::{
+    Blob, DocumentDownloadUrl, DocumentUploadResponse, DuplicateDocument, KeyRotationReport, OrphanSweepReport,
+    ScanStatus,
+};
+pub use tag::{CreateTagDto, Tag, UpdateTagDto};
 pub use user::{AuthResponse, Claims, CreateUserDto, LoginDto, User, UserResponse};
+pub use webhook::{
+    CreateWebhookDto, UpdateWebhookDto, WebhookDelivery, WebhookDeliveryQuery, WebhookDeliveryStatus,
+    WebhookEvent, WebhookEventType, WebhookSubscription, WebhookWithSecret,
+};
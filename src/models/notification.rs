@@ -0,0 +1,203 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+use super::ActivityEntityType;
+
+/// Lead times of due-date reminders for users without preferences
+pub const DEFAULT_REMINDER_LEAD_DAYS: [i32; 2] = [7, 1];
+
+/// What a notification is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// A compliance item is due soon
+    DueDateReminder,
+
+    /// A compliance item changed status
+    StatusChanged,
+
+    /// A compliance item was assigned to the recipient
+    Assigned,
+
+    /// A high or critical risk score was recorded
+    RiskAlert,
+}
+
+impl NotificationKind {
+    /// Convert NotificationKind to database string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::DueDateReminder => "due_date_reminder",
+            NotificationKind::StatusChanged => "status_changed",
+            NotificationKind::Assigned => "assigned",
+            NotificationKind::RiskAlert => "risk_alert",
+        }
+    }
+}
+
+/// In-app notification model from database
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Notification {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Recipient
+    pub user_id: Uuid,
+
+    /// What the notification is about
+    pub kind: String,
+
+    /// Short headline
+    pub title: String,
+
+    /// Message text
+    pub body: String,
+
+    /// Type of the related entity
+    pub entity_type: String,
+
+    /// Related entity
+    pub entity_id: Uuid,
+
+    /// When the recipient read the notification
+    pub read_at: Option<DateTime<Utc>>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+/// Notification to deliver
+#[derive(Debug, Clone)]
+pub struct NewNotification {
+    /// Recipient
+    pub user_id: Uuid,
+
+    /// What the notification is about
+    pub kind: NotificationKind,
+
+    /// Short headline (also the email subject)
+    pub title: String,
+
+    /// Message text
+    pub body: String,
+
+    /// Type of the related entity
+    pub entity_type: ActivityEntityType,
+
+    /// Related entity
+    pub entity_id: Uuid,
+}
+
+/// Notification preferences of a user
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct NotificationPreferences {
+    /// User the preferences belong to
+    #[serde(skip_serializing)]
+    pub user_id: Uuid,
+
+    /// Show notifications in the app
+    pub in_app_enabled: bool,
+
+    /// Send notifications by email
+    pub email_enabled: bool,
+
+    /// Remind of upcoming due dates
+    pub due_date_reminders: bool,
+
+    /// Notify of status changes
+    pub status_changes: bool,
+
+    /// Notify of new assignments
+    pub assignments: bool,
+
+    /// Notify of new high and critical risk scores
+    pub risk_alerts: bool,
+
+    /// Days before a due date at which to send reminders
+    pub reminder_lead_days: Vec<i32>,
+}
+
+impl NotificationPreferences {
+    /// Preferences of a user who has not changed any
+    pub fn defaults(user_id: Uuid) -> Self {
+        Self {
+            user_id,
+            in_app_enabled: true,
+            email_enabled: true,
+            due_date_reminders: true,
+            status_changes: true,
+            assignments: true,
+            risk_alerts: true,
+            reminder_lead_days: DEFAULT_REMINDER_LEAD_DAYS.to_vec(),
+        }
+    }
+
+    /// Whether the user wants notifications of a kind
+    pub fn wants(&self, kind: NotificationKind) -> bool {
+        match kind {
+            NotificationKind::DueDateReminder => self.due_date_reminders,
+            NotificationKind::StatusChanged => self.status_changes,
+            NotificationKind::Assigned => self.assignments,
+            NotificationKind::RiskAlert => self.risk_alerts,
+        }
+    }
+}
+
+/// DTO for updating notification preferences
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateNotificationPreferencesDto {
+    /// Show notifications in the app (optional)
+    pub in_app_enabled: Option<bool>,
+
+    /// Send notifications by email (optional)
+    pub email_enabled: Option<bool>,
+
+    /// Remind of upcoming due dates (optional)
+    pub due_date_reminders: Option<bool>,
+
+    /// Notify of status changes (optional)
+    pub status_changes: Option<bool>,
+
+    /// Notify of new assignments (optional)
+    pub assignments: Option<bool>,
+
+    /// Notify of new high and critical risk scores (optional)
+    pub risk_alerts: Option<bool>,
+
+    /// Reminder lead times in days (optional, up to 5 values of 1-365)
+    #[validate(custom(function = "validate_lead_days"))]
+    pub reminder_lead_days: Option<Vec<i32>>,
+}
+
+/// Query parameters for listing notifications
+#[derive(Debug, Deserialize)]
+pub struct NotificationListQuery {
+    /// Only unread notifications (default: false)
+    #[serde(default)]
+    pub unread_only: bool,
+
+    /// Maximum number of notifications (default: 50, max: 200)
+    pub limit: Option<i64>,
+}
+
+/// Notifications with the unread count
+#[derive(Debug, Serialize)]
+pub struct NotificationList {
+    /// Notifications, newest first
+    pub notifications: Vec<Notification>,
+
+    /// Total unread notifications
+    pub unread_count: i64,
+}
+
+/// Validate reminder lead times
+fn validate_lead_days(days: &[i32]) -> Result<(), validator::ValidationError> {
+    if days.len() <= 5 && days.iter().all(|d| (1..=365).contains(d)) {
+        Ok(())
+    } else {
+        Err(validator::ValidationError::new("invalid_lead_days"))
+    }
+}
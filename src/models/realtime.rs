@@ -0,0 +1,142 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{ActivityEntityType, ActivityVerb};
+
+/// Topics a real-time client can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RealtimeTopic {
+    /// Compliance item changes
+    ComplianceItem,
+
+    /// Document changes
+    Document,
+
+    /// Risk score changes
+    RiskScore,
+
+    /// Analysis job progress
+    Analysis,
+}
+
+impl From<ActivityEntityType> for RealtimeTopic {
+    fn from(entity_type: ActivityEntityType) -> Self {
+        match entity_type {
+            ActivityEntityType::ComplianceItem => RealtimeTopic::ComplianceItem,
+            ActivityEntityType::Document => RealtimeTopic::Document,
+            ActivityEntityType::RiskScore => RealtimeTopic::RiskScore,
+        }
+    }
+}
+
+/// Stage of an analysis job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalysisStage {
+    /// The model is working on the text
+    Started,
+
+    /// Analysis finished successfully
+    Completed,
+
+    /// Analysis failed
+    Failed,
+}
+
+/// Event pushed to real-time clients
+///
+/// Events are kept small (entity references rather than entities) so they
+/// fit in a Postgres notification; clients refetch what they display.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RealtimeEvent {
+    /// An entity was created, changed or deleted
+    EntityChanged {
+        /// Activity event identifier
+        event_id: Uuid,
+
+        /// Type of the entity
+        entity_type: ActivityEntityType,
+
+        /// Entity UUID
+        entity_id: Uuid,
+
+        /// What happened
+        verb: ActivityVerb,
+
+        /// User who made the change
+        actor_id: Uuid,
+
+        /// When the change happened
+        occurred_at: DateTime<Utc>,
+    },
+
+    /// An analysis job made progress
+    AnalysisProgress {
+        /// Job identifier
+        job_id: Uuid,
+
+        /// Current stage
+        stage: AnalysisStage,
+
+        /// Completion percentage (0-100)
+        progress: u8,
+
+        /// Error message for failed jobs
+        message: Option<String>,
+    },
+}
+
+impl RealtimeEvent {
+    /// Topic the event is published on
+    pub fn topic(&self) -> RealtimeTopic {
+        match self {
+            RealtimeEvent::EntityChanged { entity_type, .. } => (*entity_type).into(),
+            RealtimeEvent::AnalysisProgress { .. } => RealtimeTopic::Analysis,
+        }
+    }
+}
+
+/// Event addressed to a user, as sent between replicas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RealtimeMessage {
+    /// User whose clients receive the event
+    pub user_id: Uuid,
+
+    /// Event
+    #[serde(flatten)]
+    pub event: RealtimeEvent,
+}
+
+/// Message sent by a real-time client
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum RealtimeClientMessage {
+    /// Start receiving events on topics
+    Subscribe { topics: Vec<RealtimeTopic> },
+
+    /// Stop receiving events on topics
+    Unsubscribe { topics: Vec<RealtimeTopic> },
+
+    /// Check that the connection is alive
+    Ping,
+}
+
+/// Reply to a real-time client message
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RealtimeReply {
+    /// Current subscriptions
+    Subscribed { topics: Vec<RealtimeTopic> },
+
+    /// Answer to a ping
+    Pong,
+
+    /// Events were dropped because the client fell behind
+    Lagged { missed: u64 },
+
+    /// The message could not be understood
+    Error { message: String },
+}
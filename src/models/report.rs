@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::ComplianceExportRow;
+
+/// Point-in-time snapshot of the compliance register for auditors
+#[derive(Debug, Serialize)]
+pub struct AuditReport {
+    /// When the snapshot was taken
+    pub generated_at: DateTime<Utc>,
+
+    /// Name of the user the report was prepared for
+    pub prepared_for: String,
+
+    /// Email of the user the report was prepared for
+    pub prepared_for_email: String,
+
+    /// Compliance items with joined columns, by title
+    pub items: Vec<ComplianceExportRow>,
+
+    /// Number of items per status
+    pub status_counts: BTreeMap<String, i64>,
+
+    /// Risk matrix scale (heatmap rows and columns)
+    pub matrix_scale: i32,
+
+    /// Likelihood × impact heatmap of the latest assessments
+    pub heatmap: Vec<AuditHeatmapCell>,
+
+    /// Items whose latest assessment has no likelihood/impact
+    pub unplotted_items: i64,
+
+    /// Latest assessment per compliance item, by item title
+    pub assessments: Vec<AuditAssessment>,
+
+    /// Documents linked to compliance items through risk scores
+    pub evidence: Vec<AuditEvidence>,
+}
+
+/// Heatmap cell of an audit report
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditHeatmapCell {
+    /// Likelihood rating
+    pub likelihood: i32,
+
+    /// Impact rating
+    pub impact: i32,
+
+    /// Risk level of the cell
+    pub risk_level: String,
+
+    /// Number of items in the cell
+    pub count: i64,
+}
+
+/// Latest assessment of a compliance item
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct AuditAssessment {
+    /// Compliance item UUID
+    pub compliance_item_id: Uuid,
+
+    /// Compliance item title
+    pub compliance_item_title: String,
+
+    /// Risk category
+    pub risk_category: String,
+
+    /// Inherent score
+    pub risk_score: i32,
+
+    /// Inherent level
+    pub risk_level: String,
+
+    /// Score after controls
+    pub residual_score: i32,
+
+    /// Level after controls
+    pub residual_level: String,
+
+    /// Likelihood rating
+    pub likelihood: Option<i32>,
+
+    /// Impact rating
+    pub impact: Option<i32>,
+
+    /// Assessment date
+    pub assessment_date: DateTime<Utc>,
+
+    /// Who assessed
+    pub assessed_by: Option<String>,
+
+    /// Notes
+    pub notes: Option<String>,
+
+    /// AI confidence (0.0-1.0)
+    pub ai_confidence: Option<f32>,
+
+    /// AI reasoning
+    pub ai_reasoning: Option<String>,
+}
+
+/// Evidence document of an audit report
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct AuditEvidence {
+    /// Document UUID
+    pub document_id: Uuid,
+
+    /// Original filename
+    pub filename: String,
+
+    /// MIME type
+    pub mime_type: String,
+
+    /// File size in bytes
+    pub file_size: i64,
+
+    /// Upload timestamp
+    pub uploaded_at: DateTime<Utc>,
+
+    /// Titles of the compliance items the document supports
+    pub compliance_items: Vec<String>,
+}
@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Mitigating control linked to a risk assessment
+///
+/// A control is a compliance item that reduces the inherent risk of an
+/// assessment by its effectiveness rating.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct RiskControl {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Mitigated risk assessment
+    pub risk_score_id: Uuid,
+
+    /// Compliance item implementing the control
+    pub compliance_item_id: Uuid,
+
+    /// Title of the compliance item implementing the control
+    pub control_title: String,
+
+    /// User who owns this control link
+    pub user_id: Uuid,
+
+    /// Effectiveness rating (0-100% of the risk removed)
+    pub effectiveness: i32,
+
+    /// Additional notes
+    pub notes: Option<String>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+/// DTO for linking a mitigating control
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateRiskControlDto {
+    /// Compliance item implementing the control
+    pub compliance_item_id: Uuid,
+
+    /// Effectiveness rating (0-100)
+    #[validate(range(min = 0, max = 100, message = "Effectiveness must be 0-100"))]
+    pub effectiveness: i32,
+
+    /// Notes (optional)
+    pub notes: Option<String>,
+}
+
+/// DTO for updating a mitigating control
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateRiskControlDto {
+    /// Effectiveness rating (0-100)
+    #[validate(range(min = 0, max = 100, message = "Effectiveness must be 0-100"))]
+    pub effectiveness: Option<i32>,
+
+    /// Notes
+    pub notes: Option<String>,
+}
+
+/// Control with the risk reduction it delivers on one assessment
+#[derive(Debug, Serialize)]
+pub struct RiskControlResponse {
+    /// Control link
+    #[serde(flatten)]
+    pub control: RiskControl,
+
+    /// Points of residual risk that would return if this control were removed
+    pub risk_reduction: i32,
+}
+
+/// Risk reduction delivered by one control across all assessments
+#[derive(Debug, Serialize)]
+pub struct ControlEffectivenessReport {
+    /// Compliance item implementing the control
+    pub compliance_item_id: Uuid,
+
+    /// Title of the compliance item
+    pub control_title: String,
+
+    /// Number of assessments the control mitigates
+    pub risks_mitigated: i64,
+
+    /// Average effectiveness rating
+    pub average_effectiveness: f64,
+
+    /// Sum of the risk reduction delivered on each assessment
+    pub total_risk_reduction: i64,
+}
+
+/// Compute residual risk from inherent risk and control effectiveness
+///
+/// Controls act independently, so each removes its share of the risk left
+/// by the others: `inherent × Π(1 - effectiveness / 100)`.
+///
+/// # Arguments
+///
+/// * `inherent` - Inherent risk score (0-100)
+/// * `effectiveness` - Effectiveness ratings of the controls (0-100)
+///
+/// # Returns
+///
+/// Residual risk score (0-100)
+pub fn residual_score(inherent: i32, effectiveness: &[i32]) -> i32 {
+    let remaining = effectiveness
+        .iter()
+        .fold(1.0, |acc, e| acc * (1.0 - f64::from(*e) / 100.0));
+
+    (f64::from(inherent) * remaining).round() as i32
+}
+
+/// Risk reduction attributable to each control
+///
+/// Measured as the residual risk with the control removed minus the residual
+/// risk with all controls in place.
+///
+/// # Arguments
+///
+/// * `inherent` - Inherent risk score (0-100)
+/// * `effectiveness` - Effectiveness ratings of the controls (0-100)
+///
+/// # Returns
+///
+/// Reduction per control, in the same order as `effectiveness`
+pub fn control_reductions(inherent: i32, effectiveness: &[i32]) -> Vec<i32> {
+    let residual = residual_score(inherent, effectiveness);
+
+    (0..effectiveness.len())
+        .map(|i| {
+            let others: Vec<i32> = effectiveness
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, e)| *e)
+                .collect();
+            residual_score(inherent, &others) - residual
+        })
+        .collect()
+}
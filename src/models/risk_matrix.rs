@@ -0,0 +1,296 @@
+use serde::Serialize;
+
+/// Risk levels from lowest to highest
+pub const RISK_LEVELS: [&str; 4] = ["low", "medium", "high", "critical"];
+
+/// Minimum 0-100 scores for each elevated risk level
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskThresholds {
+    /// Minimum score classified as medium
+    pub medium: i32,
+
+    /// Minimum score classified as high
+    pub high: i32,
+
+    /// Minimum score classified as critical
+    pub critical: i32,
+}
+
+/// One cell of the likelihood × impact matrix
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskMatrixCell {
+    /// Likelihood rating (1..=scale)
+    pub likelihood: i32,
+
+    /// Impact rating (1..=scale)
+    pub impact: i32,
+
+    /// Computed 0-100 risk score
+    pub score: i32,
+
+    /// Risk level for this cell
+    pub level: String,
+}
+
+/// Risk score and level resolved from assessment input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedRisk {
+    /// Likelihood rating (None for manually scored assessments)
+    pub likelihood: Option<i32>,
+
+    /// Impact rating (None for manually scored assessments)
+    pub impact: Option<i32>,
+
+    /// 0-100 risk score
+    pub score: i32,
+
+    /// Risk level
+    pub level: String,
+}
+
+/// Likelihood × impact risk matrix
+///
+/// Scores are `likelihood * impact` normalized to 0-100. Levels come from the
+/// per-cell matrix, which defaults to classifying each cell's score against
+/// the thresholds.
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskMatrix {
+    /// Number of points on the likelihood and impact scales (1-5)
+    pub scale: i32,
+
+    /// Score thresholds used for manually scored assessments
+    pub thresholds: RiskThresholds,
+
+    /// Risk level per cell, indexed `[likelihood - 1][impact - 1]`
+    pub levels: Vec<Vec<String>>,
+}
+
+impl Default for RiskMatrix {
+    fn default() -> Self {
+        Self::new(
+            5,
+            RiskThresholds {
+                medium: 25,
+                high: 50,
+                critical: 75,
+            },
+            None,
+        )
+        .expect("default risk matrix is valid")
+    }
+}
+
+impl RiskMatrix {
+    /// Create a risk matrix
+    ///
+    /// # Arguments
+    ///
+    /// * `scale` - Points on each axis (1-5)
+    /// * `thresholds` - Score thresholds for medium/high/critical
+    /// * `levels` - Explicit per-cell levels (derived from thresholds if None)
+    ///
+    /// # Returns
+    ///
+    /// Risk matrix
+    ///
+    /// # Errors
+    ///
+    /// Returns a message if the scale, thresholds or cell levels are invalid
+    pub fn new(
+        scale: i32,
+        thresholds: RiskThresholds,
+        levels: Option<Vec<Vec<String>>>,
+    ) -> Result<Self, String> {
+        if !(1..=5).contains(&scale) {
+            return Err("Risk matrix scale must be between 1 and 5".to_string());
+        }
+        if !(0 < thresholds.medium
+            && thresholds.medium < thresholds.high
+            && thresholds.high < thresholds.critical
+            && thresholds.critical <= 100)
+        {
+            return Err("Risk thresholds must be increasing values between 1 and 100".to_string());
+        }
+
+        let mut matrix = Self {
+            scale,
+            thresholds,
+            levels: Vec::new(),
+        };
+
+        matrix.levels = match levels {
+            Some(levels) => {
+                let well_formed = levels.len() == scale as usize
+                    && levels.iter().all(|row| {
+                        row.len() == scale as usize
+                            && row.iter().all(|level| RISK_LEVELS.contains(&level.as_str()))
+                    });
+                if !well_formed {
+                    return Err(format!(
+                        "Risk matrix levels must be {0}x{0} cells of low/medium/high/critical",
+                        scale
+                    ));
+                }
+                levels
+            }
+            None => (1..=scale)
+                .map(|likelihood| {
+                    (1..=scale)
+                        .map(|impact| matrix.level_for_score(matrix.score(likelihood, impact)).to_string())
+                        .collect()
+                })
+                .collect(),
+        };
+
+        Ok(matrix)
+    }
+
+    /// Load the risk matrix from environment variables
+    ///
+    /// * `RISK_MATRIX_SCALE` - points per axis (default: 5)
+    /// * `RISK_LEVEL_THRESHOLDS` - `medium,high,critical` minimum scores (default: `25,50,75`)
+    /// * `RISK_MATRIX_LEVELS` - optional explicit cells, rows by likelihood separated
+    ///   by `;`, levels by impact separated by `,`
+    ///
+    /// # Returns
+    ///
+    /// Risk matrix
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is invalid
+    pub fn from_env() -> Self {
+        let scale = std::env::var("RISK_MATRIX_SCALE")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse()
+            .expect("RISK_MATRIX_SCALE must be a valid number");
+
+        let thresholds: Vec<i32> = std::env::var("RISK_LEVEL_THRESHOLDS")
+            .unwrap_or_else(|_| "25,50,75".to_string())
+            .split(',')
+            .map(|t| t.trim().parse().expect("RISK_LEVEL_THRESHOLDS must be numbers"))
+            .collect();
+        let [medium, high, critical] = thresholds[..] else {
+            panic!("RISK_LEVEL_THRESHOLDS must contain three values");
+        };
+
+        let levels = std::env::var("RISK_MATRIX_LEVELS").ok().map(|levels| {
+            levels
+                .split(';')
+                .map(|row| row.split(',').map(|l| l.trim().to_lowercase()).collect())
+                .collect()
+        });
+
+        Self::new(scale, RiskThresholds { medium, high, critical }, levels)
+            .unwrap_or_else(|e| panic!("Invalid risk matrix configuration: {}", e))
+    }
+
+    /// Compute the 0-100 score of a matrix cell
+    pub fn score(&self, likelihood: i32, impact: i32) -> i32 {
+        let max = self.scale * self.scale;
+        ((likelihood * impact * 100) as f64 / max as f64).round() as i32
+    }
+
+    /// Risk level of a matrix cell
+    pub fn level(&self, likelihood: i32, impact: i32) -> &str {
+        &self.levels[(likelihood - 1) as usize][(impact - 1) as usize]
+    }
+
+    /// Classify a 0-100 score using the thresholds
+    pub fn level_for_score(&self, score: i32) -> &'static str {
+        if score >= self.thresholds.critical {
+            "critical"
+        } else if score >= self.thresholds.high {
+            "high"
+        } else if score >= self.thresholds.medium {
+            "medium"
+        } else {
+            "low"
+        }
+    }
+
+    /// All cells of the matrix, row by row
+    pub fn cells(&self) -> Vec<RiskMatrixCell> {
+        (1..=self.scale)
+            .flat_map(|likelihood| {
+                (1..=self.scale).map(move |impact| RiskMatrixCell {
+                    likelihood,
+                    impact,
+                    score: self.score(likelihood, impact),
+                    level: self.level(likelihood, impact).to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// Resolve the score and level of an assessment
+    ///
+    /// With likelihood and impact the score and level are computed from the
+    /// matrix; otherwise the level is derived from the manual score. Any
+    /// score or level supplied alongside must agree with the derived values.
+    ///
+    /// # Arguments
+    ///
+    /// * `likelihood` - Likelihood rating
+    /// * `impact` - Impact rating
+    /// * `score` - Manually supplied score
+    /// * `level` - Manually supplied level
+    ///
+    /// # Returns
+    ///
+    /// Resolved score and level
+    ///
+    /// # Errors
+    ///
+    /// Returns a message describing the inconsistency
+    pub fn resolve(
+        &self,
+        likelihood: Option<i32>,
+        impact: Option<i32>,
+        score: Option<i32>,
+        level: Option<&str>,
+    ) -> Result<ResolvedRisk, String> {
+        let (computed_score, computed_level) = match (likelihood, impact) {
+            (Some(likelihood), Some(impact)) => {
+                let range = 1..=self.scale;
+                if !range.contains(&likelihood) || !range.contains(&impact) {
+                    return Err(format!("Likelihood and impact must be between 1 and {}", self.scale));
+                }
+                let computed = self.score(likelihood, impact);
+                if score.is_some_and(|s| s != computed) {
+                    return Err(format!(
+                        "Risk score {} does not match likelihood {} × impact {} (expected {})",
+                        score.unwrap_or_default(),
+                        likelihood,
+                        impact,
+                        computed
+                    ));
+                }
+                (computed, self.level(likelihood, impact).to_string())
+            }
+            (None, None) => {
+                let score = score.ok_or_else(|| {
+                    "Provide likelihood and impact, or a risk score".to_string()
+                })?;
+                (score, self.level_for_score(score).to_string())
+            }
+            _ => return Err("Likelihood and impact must be provided together".to_string()),
+        };
+
+        if let Some(level) = level {
+            if level != computed_level {
+                return Err(format!(
+                    "Risk level '{}' is inconsistent with score {} (expected '{}')",
+                    level, computed_score, computed_level
+                ));
+            }
+        }
+
+        Ok(ResolvedRisk {
+            likelihood,
+            impact,
+            score: computed_score,
+            level: computed_level,
+        })
+    }
+}
@@ -18,6 +18,9 @@ pub struct RiskScore {
     /// Related document (optional)
     pub document_id: Option<Uuid>,
     
+    /// Version of the related document the assessment was made against
+    pub document_version: Option<i32>,
+    
     /// User who created this assessment
     pub user_id: Uuid,
     
@@ -45,6 +48,18 @@ pub struct RiskScore {
     /// AI reasoning/explanation
     pub ai_reasoning: Option<String>,
     
+    /// Likelihood rating on the risk matrix scale
+    pub likelihood: Option<i32>,
+    
+    /// Impact rating on the risk matrix scale
+    pub impact: Option<i32>,
+    
+    /// Residual risk score after mitigating controls (0-100)
+    pub residual_score: i32,
+    
+    /// Residual risk level classification
+    pub residual_level: String,
+    
     /// Creation timestamp
     pub created_at: DateTime<Utc>,
     
@@ -62,17 +77,32 @@ pub struct CreateRiskScoreDto {
     /// Document ID (optional)
     pub document_id: Option<String>,
     
+    /// Document version assessed (defaults to the current version)
+    #[validate(range(min = 1, message = "Document version must be positive"))]
+    pub document_version: Option<i32>,
+    
     /// Risk category
     #[validate(length(min = 1, max = 100, message = "Risk category must be 1-100 characters"))]
     pub risk_category: String,
     
-    /// Risk score (0-100)
+    /// Likelihood rating (computes score and level together with impact)
+    #[validate(range(min = 1, max = 5, message = "Likelihood must be 1-5"))]
+    pub likelihood: Option<i32>,
+    
+    /// Impact rating (computes score and level together with likelihood)
+    #[validate(range(min = 1, max = 5, message = "Impact must be 1-5"))]
+    pub impact: Option<i32>,
+    
+    /// Risk score (0-100, required without likelihood and impact)
     #[validate(range(min = 0, max = 100, message = "Risk score must be 0-100"))]
-    pub risk_score: i32,
+    pub risk_score: Option<i32>,
     
-    /// Risk level
+    /// Risk level (derived if omitted, must match if provided)
     #[validate(custom(function = "validate_risk_level"))]
-    pub risk_level: String,
+    pub risk_level: Option<String>,
+    
+    /// When the assessment was made (defaults to now)
+    pub assessment_date: Option<DateTime<Utc>>,
     
     /// Who assessed
     pub assessed_by: Option<String>,
@@ -102,11 +132,20 @@ pub struct UpdateRiskScoreDto {
     #[validate(length(min = 1, max = 100))]
     pub risk_category: Option<String>,
     
+    /// Likelihood rating
+    #[validate(range(min = 1, max = 5, message = "Likelihood must be 1-5"))]
+    pub likelihood: Option<i32>,
+    
+    /// Impact rating
+    #[validate(range(min = 1, max = 5, message = "Impact must be 1-5"))]
+    pub impact: Option<i32>,
+    
     /// Risk score
     #[validate(range(min = 0, max = 100))]
     pub risk_score: Option<i32>,
     
     /// Risk level (validated if present)
+    #[validate(custom(function = "validate_risk_level"))]
     pub risk_level: Option<String>,
     
     /// Notes
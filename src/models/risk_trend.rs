@@ -0,0 +1,135 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Default score change between consecutive assessments flagged as a jump
+pub const DEFAULT_JUMP_THRESHOLD: i32 = 20;
+
+/// Time bucket size for risk trends
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendInterval {
+    Day,
+    #[default]
+    Week,
+    Month,
+}
+
+impl TrendInterval {
+    /// Convert to the PostgreSQL `date_trunc` field name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrendInterval::Day => "day",
+            TrendInterval::Week => "week",
+            TrendInterval::Month => "month",
+        }
+    }
+}
+
+/// Query parameters for risk trend endpoints
+#[derive(Debug, Default, Deserialize, Validate)]
+pub struct RiskTrendQuery {
+    /// Bucket size (default: week)
+    pub interval: Option<TrendInterval>,
+
+    /// Only include assessments on or after this instant
+    pub from: Option<DateTime<Utc>>,
+
+    /// Only include assessments before this instant
+    pub to: Option<DateTime<Utc>>,
+
+    /// Minimum score change flagged as a jump (default: 20)
+    #[validate(range(min = 1, max = 100, message = "Jump threshold must be 1-100"))]
+    pub jump_threshold: Option<i32>,
+}
+
+/// Aggregated assessments within one time bucket
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct TrendBucket {
+    /// Start of the bucket
+    pub bucket_start: DateTime<Utc>,
+
+    /// Number of assessments in the bucket
+    pub assessments: i64,
+
+    /// Score of the most recent assessment in the bucket
+    pub latest_score: i32,
+
+    /// Average score
+    pub average_score: f64,
+
+    /// Highest score
+    pub max_score: i32,
+}
+
+/// Trend bucket of a single risk category
+#[derive(Debug, FromRow)]
+pub struct CategoryTrendBucket {
+    /// Risk category
+    pub risk_category: String,
+
+    /// Aggregates for the category
+    #[sqlx(flatten)]
+    pub bucket: TrendBucket,
+}
+
+/// Trend of one risk category
+#[derive(Debug, Serialize)]
+pub struct CategoryTrend {
+    /// Risk category
+    pub risk_category: String,
+
+    /// Buckets in chronological order
+    pub buckets: Vec<TrendBucket>,
+}
+
+/// Significant score change between consecutive assessments
+///
+/// Assessments are consecutive when they share a compliance item and risk category.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct RiskJump {
+    /// Assessment where the change was observed
+    pub risk_score_id: Uuid,
+
+    /// Preceding assessment
+    pub previous_risk_score_id: Uuid,
+
+    /// Assessed compliance item
+    pub compliance_item_id: Uuid,
+
+    /// Risk category
+    pub risk_category: String,
+
+    /// Score of the preceding assessment
+    pub previous_score: i32,
+
+    /// Score of this assessment
+    pub score: i32,
+
+    /// Signed score change
+    pub change: i32,
+
+    /// When this assessment was made
+    pub assessment_date: DateTime<Utc>,
+}
+
+/// Risk score history bucketed over time
+#[derive(Debug, Serialize)]
+pub struct RiskTrend {
+    /// Bucket size
+    pub interval: TrendInterval,
+
+    /// Minimum change flagged as a jump
+    pub jump_threshold: i32,
+
+    /// All categories combined
+    pub buckets: Vec<TrendBucket>,
+
+    /// Per-category breakdown
+    pub categories: Vec<CategoryTrend>,
+
+    /// Significant jumps, most recent first
+    pub jumps: Vec<RiskJump>,
+}
@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::Document;
+
+/// Result of scanning a stored file for malware
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanStatus {
+    /// Stored while scanning was disabled, or before it was introduced
+    NotScanned,
+
+    /// No malware found
+    Clean,
+
+    /// Malware found, the file is quarantined
+    Infected,
+
+    /// The scanner could not be reached or rejected the file, the file is quarantined
+    Failed,
+}
+
+impl ScanStatus {
+    /// Convert ScanStatus to database string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScanStatus::NotScanned => "not_scanned",
+            ScanStatus::Clean => "clean",
+            ScanStatus::Infected => "infected",
+            ScanStatus::Failed => "failed",
+        }
+    }
+}
+
+/// Stored file content shared by all documents with the same SHA-256
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Blob {
+    /// SHA-256 of the content (hex)
+    pub sha256: String,
+
+    /// Key of the object in the storage backend
+    pub storage_key: String,
+
+    /// Content size in bytes
+    pub size: i64,
+
+    /// Number of documents referencing the content
+    pub ref_count: i32,
+
+    /// When the content was first stored
+    pub created_at: DateTime<Utc>,
+}
+
+/// Earlier document of the same user with identical content
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct DuplicateDocument {
+    /// Document ID
+    pub id: Uuid,
+
+    /// Filename
+    pub filename: String,
+
+    /// Upload timestamp
+    pub uploaded_at: DateTime<Utc>,
+}
+
+/// Document created from an uploaded file
+#[derive(Debug, Serialize)]
+pub struct DocumentUploadResponse {
+    /// Created document
+    #[serde(flatten)]
+    pub document: Document,
+
+    /// The user's other documents with the same content, oldest first
+    pub duplicate_of: Vec<DuplicateDocument>,
+
+    /// Whether extracted text or AI analysis was copied from identical content
+    pub reused_analysis: bool,
+
+    /// Notice shown when the file was uploaded before
+    pub message: Option<String>,
+}
+
+/// Outcome of reconciling the storage backend against the documents table
+#[derive(Debug, Default, Serialize)]
+pub struct OrphanSweepReport {
+    /// Whether objects were only reported, not removed
+    pub dry_run: bool,
+
+    /// Objects found in the storage backend
+    pub scanned_objects: usize,
+
+    /// Keys of objects no document references (older than the grace period)
+    pub orphaned_keys: Vec<String>,
+
+    /// Bytes freed by removing orphaned objects (or that would be, in a dry run)
+    pub reclaimed_bytes: u64,
+
+    /// Unreferenced objects skipped because they are younger than the grace period
+    pub recent_objects: usize,
+
+    /// Storage keys of documents whose object is missing
+    pub missing_keys: Vec<String>,
+
+    /// Unreferenced content entries removed (with their object)
+    pub released_blobs: usize,
+}
+
+/// Outcome of re-wrapping data keys with the active master key
+#[derive(Debug, Default, Serialize)]
+pub struct KeyRotationReport {
+    /// Whether keys were only counted, not re-wrapped
+    pub dry_run: bool,
+
+    /// Fingerprint of the master key now wrapping the data keys
+    pub active_key_id: String,
+
+    /// Distinct data keys re-wrapped (or that would be, in a dry run)
+    pub rewrapped_keys: usize,
+
+    /// Data keys wrapped by a master key that is not configured
+    pub unreadable_keys: usize,
+
+    /// Document rows updated
+    pub documents: u64,
+
+    /// Document version rows updated
+    pub versions: u64,
+}
+
+/// Download link of a stored document file
+#[derive(Debug, Serialize)]
+pub struct DocumentDownloadUrl {
+    /// URL to fetch the file from
+    pub url: String,
+
+    /// Whether the URL is presigned (works without the API session)
+    pub presigned: bool,
+
+    /// When a presigned URL stops working
+    pub expires_at: Option<DateTime<Utc>>,
+}
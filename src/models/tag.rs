@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// User-defined tag model from database
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Tag {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// User who owns this tag
+    pub user_id: Uuid,
+
+    /// Tag name (unique per user, case-insensitive)
+    pub name: String,
+
+    /// Display color (e.g., "#ff8800")
+    pub color: Option<String>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+/// DTO for creating tags
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateTagDto {
+    /// Tag name
+    #[validate(length(min = 1, max = 100, message = "Tag name must be 1-100 characters"))]
+    pub name: String,
+
+    /// Display color (optional)
+    #[validate(length(max = 20, message = "Color must be at most 20 characters"))]
+    pub color: Option<String>,
+}
+
+/// DTO for updating tags
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateTagDto {
+    /// Tag name (optional)
+    #[validate(length(min = 1, max = 100, message = "Tag name must be 1-100 characters"))]
+    pub name: Option<String>,
+
+    /// Display color (optional)
+    #[validate(length(max = 20, message = "Color must be at most 20 characters"))]
+    pub color: Option<String>,
+}
@@ -0,0 +1,316 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+use super::{ActivityEntityType, ActivityVerb};
+
+/// Events that can be delivered to webhooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WebhookEventType {
+    #[serde(rename = "compliance_item.created")]
+    ComplianceItemCreated,
+
+    #[serde(rename = "compliance_item.updated")]
+    ComplianceItemUpdated,
+
+    #[serde(rename = "compliance_item.status_changed")]
+    ComplianceItemStatusChanged,
+
+    #[serde(rename = "compliance_item.deleted")]
+    ComplianceItemDeleted,
+
+    #[serde(rename = "document.created")]
+    DocumentCreated,
+
+    #[serde(rename = "document.updated")]
+    DocumentUpdated,
+
+    #[serde(rename = "document.analysis_completed")]
+    DocumentAnalysisCompleted,
+
+    #[serde(rename = "document.deleted")]
+    DocumentDeleted,
+
+    #[serde(rename = "risk_score.created")]
+    RiskScoreCreated,
+
+    #[serde(rename = "risk_score.updated")]
+    RiskScoreUpdated,
+
+    #[serde(rename = "risk_score.deleted")]
+    RiskScoreDeleted,
+
+    /// Sent on request to check an endpoint; never subscribed to
+    #[serde(rename = "webhook.test")]
+    Test,
+}
+
+impl WebhookEventType {
+    /// Convert WebhookEventType to database string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEventType::ComplianceItemCreated => "compliance_item.created",
+            WebhookEventType::ComplianceItemUpdated => "compliance_item.updated",
+            WebhookEventType::ComplianceItemStatusChanged => "compliance_item.status_changed",
+            WebhookEventType::ComplianceItemDeleted => "compliance_item.deleted",
+            WebhookEventType::DocumentCreated => "document.created",
+            WebhookEventType::DocumentUpdated => "document.updated",
+            WebhookEventType::DocumentAnalysisCompleted => "document.analysis_completed",
+            WebhookEventType::DocumentDeleted => "document.deleted",
+            WebhookEventType::RiskScoreCreated => "risk_score.created",
+            WebhookEventType::RiskScoreUpdated => "risk_score.updated",
+            WebhookEventType::RiskScoreDeleted => "risk_score.deleted",
+            WebhookEventType::Test => "webhook.test",
+        }
+    }
+
+    /// Webhook event for a recorded activity
+    ///
+    /// # Arguments
+    ///
+    /// * `entity_type` - Type of the changed entity
+    /// * `verb` - What happened
+    ///
+    /// # Returns
+    ///
+    /// Matching event type
+    pub fn from_activity(entity_type: ActivityEntityType, verb: ActivityVerb) -> Self {
+        match (entity_type, verb) {
+            (ActivityEntityType::ComplianceItem, ActivityVerb::Created) => Self::ComplianceItemCreated,
+            (ActivityEntityType::ComplianceItem, ActivityVerb::StatusChanged) => Self::ComplianceItemStatusChanged,
+            (ActivityEntityType::ComplianceItem, ActivityVerb::Deleted) => Self::ComplianceItemDeleted,
+            (ActivityEntityType::ComplianceItem, _) => Self::ComplianceItemUpdated,
+            (ActivityEntityType::Document, ActivityVerb::Created) => Self::DocumentCreated,
+            (ActivityEntityType::Document, ActivityVerb::Analyzed) => Self::DocumentAnalysisCompleted,
+            (ActivityEntityType::Document, ActivityVerb::Deleted) => Self::DocumentDeleted,
+            (ActivityEntityType::Document, _) => Self::DocumentUpdated,
+            (ActivityEntityType::RiskScore, ActivityVerb::Created) => Self::RiskScoreCreated,
+            (ActivityEntityType::RiskScore, ActivityVerb::Deleted) => Self::RiskScoreDeleted,
+            (ActivityEntityType::RiskScore, _) => Self::RiskScoreUpdated,
+        }
+    }
+}
+
+/// Status of a webhook delivery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    /// Waiting for its next attempt
+    Pending,
+
+    /// Attempt in progress
+    Delivering,
+
+    /// Endpoint answered with a 2xx status
+    Succeeded,
+
+    /// All attempts failed
+    Failed,
+}
+
+impl WebhookDeliveryStatus {
+    /// Convert WebhookDeliveryStatus to database string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookDeliveryStatus::Pending => "pending",
+            WebhookDeliveryStatus::Delivering => "delivering",
+            WebhookDeliveryStatus::Succeeded => "succeeded",
+            WebhookDeliveryStatus::Failed => "failed",
+        }
+    }
+}
+
+/// Webhook subscription model from database
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct WebhookSubscription {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// User who owns this subscription
+    pub user_id: Uuid,
+
+    /// Endpoint URL
+    pub url: String,
+
+    /// Description (optional)
+    pub description: Option<String>,
+
+    /// Subscribed event types
+    pub event_types: Vec<String>,
+
+    /// Signing secret (only returned when created or rotated)
+    #[serde(skip_serializing)]
+    pub secret: String,
+
+    /// Whether events are delivered
+    pub active: bool,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Webhook subscription together with its signing secret
+#[derive(Debug, Serialize)]
+pub struct WebhookWithSecret {
+    /// Subscription
+    #[serde(flatten)]
+    pub subscription: WebhookSubscription,
+
+    /// Signing secret
+    pub secret: String,
+}
+
+impl From<WebhookSubscription> for WebhookWithSecret {
+    fn from(subscription: WebhookSubscription) -> Self {
+        let secret = subscription.secret.clone();
+        Self { subscription, secret }
+    }
+}
+
+/// DTO for creating webhook subscriptions
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateWebhookDto {
+    /// Endpoint URL (http or https)
+    #[validate(url(message = "Invalid webhook URL"), custom(function = "validate_http_url"))]
+    pub url: String,
+
+    /// Description (optional)
+    #[validate(length(max = 500, message = "Description must be at most 500 characters"))]
+    pub description: Option<String>,
+
+    /// Event types to deliver (at least one)
+    #[validate(
+        length(min = 1, message = "Subscribe to at least one event type"),
+        custom(function = "validate_event_types")
+    )]
+    pub event_types: Vec<WebhookEventType>,
+
+    /// Signing secret (optional, generated if missing)
+    #[validate(length(min = 16, max = 255, message = "Secret must be 16-255 characters"))]
+    pub secret: Option<String>,
+
+    /// Whether events are delivered (default: true)
+    pub active: Option<bool>,
+}
+
+/// DTO for updating webhook subscriptions
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateWebhookDto {
+    /// Endpoint URL (optional)
+    #[validate(url(message = "Invalid webhook URL"), custom(function = "validate_http_url"))]
+    pub url: Option<String>,
+
+    /// Description (optional)
+    #[validate(length(max = 500, message = "Description must be at most 500 characters"))]
+    pub description: Option<String>,
+
+    /// Event types (optional, replaces the current ones)
+    #[validate(
+        length(min = 1, message = "Subscribe to at least one event type"),
+        custom(function = "validate_event_types")
+    )]
+    pub event_types: Option<Vec<WebhookEventType>>,
+
+    /// Whether events are delivered (optional)
+    pub active: Option<bool>,
+
+    /// Generate a new signing secret (optional)
+    #[serde(default)]
+    pub rotate_secret: bool,
+}
+
+/// Webhook delivery model from database (queue entry and log)
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct WebhookDelivery {
+    /// Unique identifier (sent as `X-ParseGuard-Delivery`)
+    pub id: Uuid,
+
+    /// Subscription delivered to
+    pub subscription_id: Uuid,
+
+    /// Event identifier (shared by all subscriptions)
+    pub event_id: Uuid,
+
+    /// Event type
+    pub event_type: String,
+
+    /// JSON body sent to the endpoint
+    pub payload: sqlx::types::Json<serde_json::Value>,
+
+    /// Delivery status
+    pub status: String,
+
+    /// Attempts made so far
+    pub attempts: i32,
+
+    /// When the next attempt is due
+    pub next_attempt_at: DateTime<Utc>,
+
+    /// When the last attempt was made
+    pub last_attempt_at: Option<DateTime<Utc>>,
+
+    /// HTTP status of the last response
+    pub response_status: Option<i32>,
+
+    /// Body of the last response (truncated)
+    pub response_body: Option<String>,
+
+    /// Transport error of the last attempt
+    pub error: Option<String>,
+
+    /// When the endpoint accepted the event
+    pub delivered_at: Option<DateTime<Utc>>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+/// Body of a webhook request
+#[derive(Debug, Serialize)]
+pub struct WebhookEvent {
+    /// Event identifier
+    pub id: Uuid,
+
+    /// Event type
+    #[serde(rename = "type")]
+    pub event_type: WebhookEventType,
+
+    /// When the event happened
+    pub created_at: DateTime<Utc>,
+
+    /// Entity the event is about
+    pub data: serde_json::Value,
+}
+
+/// Query parameters for the delivery log
+#[derive(Debug, Deserialize)]
+pub struct WebhookDeliveryQuery {
+    /// Only deliveries with this status
+    pub status: Option<WebhookDeliveryStatus>,
+
+    /// Maximum number of deliveries (default: 50, max: 200)
+    pub limit: Option<i64>,
+}
+
+/// Validate that a webhook URL uses HTTP(S)
+fn validate_http_url(url: &str) -> Result<(), validator::ValidationError> {
+    if url.starts_with("https://") || url.starts_with("http://") {
+        Ok(())
+    } else {
+        Err(validator::ValidationError::new("invalid_webhook_scheme"))
+    }
+}
+
+/// Validate that only subscribable event types are listed
+fn validate_event_types(event_types: &[WebhookEventType]) -> Result<(), validator::ValidationError> {
+    if event_types.contains(&WebhookEventType::Test) {
+        Err(validator::ValidationError::new("test_event_not_subscribable"))
+    } else {
+        Ok(())
+    }
+}
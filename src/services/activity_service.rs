@@ -0,0 +1,140 @@
+use sqlx::PgPool;
+use tracing::{info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::repository::{ActivityRepository, RealtimeRepository, WebhookRepository},
+    error::{AppError, AppResult},
+    models::{
+        ActivityFeedQuery, ActivityPage, NewActivityEvent, RealtimeEvent, RealtimeMessage, WebhookEvent,
+        WebhookEventType,
+    },
+};
+
+/// Default number of events per feed page
+const DEFAULT_PAGE_SIZE: i64 = 20;
+
+/// Maximum number of events per feed page
+const MAX_PAGE_SIZE: i64 = 100;
+
+/// Activity service for the recorded activity stream
+///
+/// Records who did what to which entity, queues the matching webhook
+/// events, notifies connected clients and serves the paginated feed
+pub struct ActivityService {
+    /// Activity repository
+    repository: ActivityRepository,
+
+    /// Webhook repository
+    webhooks: WebhookRepository,
+
+    /// Realtime repository
+    realtime: RealtimeRepository,
+}
+
+impl ActivityService {
+    /// Create a new ActivityService
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    ///
+    /// # Returns
+    ///
+    /// New ActivityService instance
+    pub fn new(pool: PgPool) -> Self {
+        info!("📰 ActivityService started");
+        Self {
+            repository: ActivityRepository::new(pool.clone()),
+            webhooks: WebhookRepository::new(pool.clone()),
+            realtime: RealtimeRepository::new(pool),
+        }
+    }
+
+    /// Record an activity event, queue it for the owner's webhooks and
+    /// notify the owner's connected clients
+    ///
+    /// The action it describes has already been committed, so a failure to
+    /// record is logged rather than failing the request. The webhook event
+    /// shares the activity event's identifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - Event to record
+    #[instrument(skip(self, event), fields(verb = event.verb.as_str(), entity_id = %event.entity_id))]
+    pub async fn record(&self, event: NewActivityEvent) {
+        let recorded = match self.repository.create(&event).await {
+            Ok(recorded) => recorded,
+            Err(e) => {
+                warn!("Failed to record activity event: {}", e);
+                return;
+            }
+        };
+
+        let change = RealtimeMessage {
+            user_id: event.user_id,
+            event: RealtimeEvent::EntityChanged {
+                event_id: recorded.id,
+                entity_type: event.entity_type,
+                entity_id: event.entity_id,
+                verb: event.verb,
+                actor_id: event.actor_id,
+                occurred_at: recorded.created_at,
+            },
+        };
+        if let Err(e) = self.realtime.notify(&change).await {
+            warn!("Failed to publish realtime event: {}", e);
+        }
+
+        let webhook_event = WebhookEvent {
+            id: recorded.id,
+            event_type: WebhookEventType::from_activity(event.entity_type, event.verb),
+            created_at: recorded.created_at,
+            data: event.entity,
+        };
+        let queued = match serde_json::to_value(&webhook_event) {
+            Ok(payload) => {
+                self.webhooks
+                    .enqueue(event.user_id, webhook_event.event_type, webhook_event.id, &payload)
+                    .await
+            }
+            Err(e) => Err(AppError::Internal(format!("Failed to serialize webhook event: {}", e))),
+        };
+        if let Err(e) = queued {
+            warn!("Failed to queue webhook event: {}", e);
+        }
+    }
+
+    /// Get a page of the activity feed
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `query` - Page size, cursor and filters
+    ///
+    /// # Returns
+    ///
+    /// Events, newest first, with the cursor of the next page
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    #[instrument(skip(self))]
+    pub async fn feed(&self, user_id: Uuid, query: &ActivityFeedQuery) -> AppResult<ActivityPage> {
+        let limit = query
+            .limit
+            .filter(|l| (1..=MAX_PAGE_SIZE).contains(l))
+            .unwrap_or(DEFAULT_PAGE_SIZE);
+
+        // Fetch one extra event to learn whether another page exists
+        let mut events = self.repository.find_page(user_id, query, limit + 1).await?;
+        let next_cursor = if events.len() as i64 > limit {
+            events.truncate(limit as usize);
+            events.last().map(|e| e.id)
+        } else {
+            None
+        };
+
+        Ok(ActivityPage { events, next_cursor })
+    }
+}
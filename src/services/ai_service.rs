@@ -2,7 +2,23 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tracing::{info, instrument};
 
-use crate::error::{AppError, AppResult};
+use crate::{
+    error::{AppError, AppResult},
+    models::{ChangeClassification, ComplianceItem, ImpactedComplianceItem, VersionChange},
+    utils::pii::{self, PiiCategory, PiiMode},
+};
+
+/// Most changes sent to the model in one classification request
+const MAX_CLASSIFIED_CHANGES: usize = 50;
+
+/// Most compliance items offered to the model as possibly impacted
+const MAX_CANDIDATE_ITEMS: usize = 100;
+
+/// Longest passage (in characters) quoted in a classification prompt
+const MAX_PASSAGE_CHARS: usize = 600;
+
+/// Longest document excerpt (in bytes) quoted in an analysis prompt
+const MAX_ANALYSIS_BYTES: usize = 4000;
 
 /// OLLAMA AI Service for document analysis
 pub struct AiService {
@@ -11,6 +27,9 @@ pub struct AiService {
     
     /// OLLAMA API base URL
     base_url: String,
+    
+    /// Masking of personal data in prompts
+    pii_mode: PiiMode,
 }
 
 /// AI analysis request
@@ -33,6 +52,7 @@ struct AnalysisResponse {
     response: String,
     
     /// Whether generation is complete
+    #[allow(dead_code)]
     done: bool,
 }
 
@@ -53,6 +73,10 @@ pub struct DocumentAnalysis {
     
     /// Confidence score (0.0-1.0)
     pub confidence: f32,
+    
+    /// Kinds of personal data found in the document
+    #[serde(default)]
+    pub pii_categories: Vec<PiiCategory>,
 }
 
 /// Suggested compliance item from AI
@@ -77,11 +101,12 @@ impl AiService {
     /// # Arguments
     ///
     /// * `ollama_url` - OLLAMA API base URL
+    /// * `pii_mode` - Masking of personal data in prompts
     ///
     /// # Returns
     ///
     /// AI service instance
-    pub fn new(ollama_url: String) -> Self {
+    pub fn new(ollama_url: String, pii_mode: PiiMode) -> Self {
         info!("🤖 AiService started");
         let client = Client::builder()
             .timeout(std::time::Duration::from_secs(300))
@@ -91,6 +116,7 @@ impl AiService {
         Self {
             client,
             base_url: ollama_url,
+            pii_mode,
         }
     }
 
@@ -115,7 +141,10 @@ impl AiService {
         let response = self.generate(&prompt, "llama2").await?;
 
         // Parse AI response into structured format
-        self.parse_analysis_response(&response)
+        let mut analysis = self.parse_analysis_response(&response)?;
+        analysis.pii_categories = pii::scan_categories(text.to_string()).await?;
+
+        Ok(analysis)
     }
 
     /// Generate risk assessment for compliance item
@@ -160,8 +189,48 @@ impl AiService {
         self.parse_risk_response(&response)
     }
 
+    /// Classify document changes and flag affected compliance items
+    ///
+    /// Each change is labelled as an added obligation, a removed obligation
+    /// or a wording-only edit. Only the first changes are sent to the model;
+    /// the rest stay unclassified.
+    ///
+    /// # Arguments
+    ///
+    /// * `changes` - Changes between two versions (classified in place)
+    /// * `items` - Compliance items the changes may affect
+    ///
+    /// # Returns
+    ///
+    /// Compliance items the model considers affected
+    ///
+    /// # Errors
+    ///
+    /// Returns error if AI request fails
+    #[instrument(skip(self, changes, items), fields(changes = changes.len(), items = items.len()))]
+    pub async fn classify_changes(
+        &self,
+        changes: &mut [VersionChange],
+        items: &[ComplianceItem],
+    ) -> AppResult<Vec<ImpactedComplianceItem>> {
+        if changes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let items = &items[..items.len().min(MAX_CANDIDATE_ITEMS)];
+        let count = changes.len().min(MAX_CLASSIFIED_CHANGES);
+        let prompt = self.create_change_prompt(&changes[..count], items);
+
+        let response = self.generate(&prompt, "llama2").await?;
+
+        Ok(self.parse_change_response(&response, &mut changes[..count], items))
+    }
+
     /// Generate text with OLLAMA
     ///
+    /// Personal data in the prompt is masked according to the PII mode;
+    /// pseudonymized values are put back into the generated text.
+    ///
     /// # Arguments
     ///
     /// * `prompt` - Input prompt
@@ -179,9 +248,14 @@ impl AiService {
         info!("Generating with model: {}", model);
         let url = format!("{}/api/generate", self.base_url);
 
+        let masked = pii::mask(prompt, self.pii_mode);
+        if !masked.categories.is_empty() {
+            info!("Masked personal data in prompt: {:?}", masked.categories);
+        }
+
         let request = AnalysisRequest {
             model: model.to_string(),
-            prompt: prompt.to_string(),
+            prompt: masked.text.clone(),
             stream: false,
         };
 
@@ -205,14 +279,18 @@ impl AiService {
             .await
             .map_err(|e| AppError::Ollama(format!("Failed to parse OLLAMA response: {}", e)))?;
 
-        Ok(result.response)
+        Ok(masked.reidentify(&result.response))
     }
 
     /// Create analysis prompt
     fn create_analysis_prompt(&self, text: &str) -> String {
-        // Truncate text if too long (max 4000 chars)
-        let truncated_text = if text.len() > 4000 {
-            &text[..4000]
+        // Truncate text if too long, at a word break so no identifier is cut in half and left unmasked
+        let truncated_text = if text.len() > MAX_ANALYSIS_BYTES {
+            let mut end = MAX_ANALYSIS_BYTES;
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            text[..end].rfind(char::is_whitespace).map_or(&text[..end], |at| &text[..at])
         } else {
             text
         };
@@ -230,6 +308,120 @@ impl AiService {
         )
     }
 
+    /// Create change classification prompt
+    fn create_change_prompt(&self, changes: &[VersionChange], items: &[ComplianceItem]) -> String {
+        let passage = |text: &Option<String>| {
+            text.as_deref().map_or_else(
+                || "(none)".to_string(),
+                |text| text.chars().take(MAX_PASSAGE_CHARS).collect::<String>(),
+            )
+        };
+
+        let mut prompt = String::from(
+            "A compliance document was revised. Classify each numbered change as one of:\n\
+             ADDED_OBLIGATION - introduces or tightens a requirement\n\
+             REMOVED_OBLIGATION - drops or relaxes a requirement\n\
+             WORDING_ONLY - does not change what is required\n\n\
+             Changes:\n",
+        );
+        for (n, change) in changes.iter().enumerate() {
+            prompt.push_str(&format!(
+                "CHANGE {}\nBEFORE: {}\nAFTER: {}\n\n",
+                n + 1,
+                passage(&change.before),
+                passage(&change.after)
+            ));
+        }
+
+        if !items.is_empty() {
+            prompt.push_str("Compliance items:\n");
+            for (n, item) in items.iter().enumerate() {
+                prompt.push_str(&format!(
+                    "ITEM {}: {} - {}\n",
+                    n + 1,
+                    item.title,
+                    passage(&item.description)
+                ));
+            }
+            prompt.push('\n');
+        }
+
+        prompt.push_str(
+            "Format your response as one line per change, followed by one line per \
+             compliance item the changes may affect:\n\
+             CHANGE <number>: <ADDED_OBLIGATION|REMOVED_OBLIGATION|WORDING_ONLY> - <reason>\n\
+             ITEM <number>: <why it may be affected>",
+        );
+
+        prompt
+    }
+
+    /// Parse change classification response
+    fn parse_change_response(
+        &self,
+        response: &str,
+        changes: &mut [VersionChange],
+        items: &[ComplianceItem],
+    ) -> Vec<ImpactedComplianceItem> {
+        let mut impacted: Vec<ImpactedComplianceItem> = Vec::new();
+
+        for line in response.lines() {
+            let line = line.trim().trim_start_matches(['-', '*', ' ']);
+            let Some((label, rest)) = line.split_once(':') else {
+                continue;
+            };
+            let mut words = label.split_whitespace();
+            let (Some(marker), Some(number), None) = (words.next(), words.next(), words.next()) else {
+                continue;
+            };
+            let Some(index) = number.parse::<usize>().ok().and_then(|n| n.checked_sub(1)) else {
+                continue;
+            };
+
+            match marker.to_uppercase().as_str() {
+                "CHANGE" => {
+                    let Some(change) = changes.get_mut(index) else {
+                        continue;
+                    };
+                    let upper = rest.to_ascii_uppercase();
+                    let normalized = upper.replace([' ', '-'], "_");
+                    let (classification, keyword) = if normalized.contains("ADDED_OBLIGATION") {
+                        (ChangeClassification::AddedObligation, "OBLIGATION")
+                    } else if normalized.contains("REMOVED_OBLIGATION") {
+                        (ChangeClassification::RemovedObligation, "OBLIGATION")
+                    } else if normalized.contains("WORDING_ONLY") {
+                        (ChangeClassification::WordingOnly, "ONLY")
+                    } else {
+                        continue;
+                    };
+
+                    let reason = upper
+                        .find(keyword)
+                        .map(|at| rest[at + keyword.len()..].trim_start_matches([' ', '-', ':', '|']).trim())
+                        .filter(|reason| !reason.is_empty());
+                    change.classification = Some(classification);
+                    change.rationale = reason.map(str::to_string);
+                }
+                "ITEM" => {
+                    let Some(item) = items.get(index) else {
+                        continue;
+                    };
+                    if impacted.iter().any(|impacted| impacted.id == item.id) {
+                        continue;
+                    }
+                    impacted.push(ImpactedComplianceItem {
+                        id: item.id,
+                        title: item.title.clone(),
+                        reason: rest.trim().to_string(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        impacted
+    }
+
     /// Parse analysis response
     fn parse_analysis_response(&self, response: &str) -> AppResult<DocumentAnalysis> {
         // Simple parsing (in production, use more robust parsing)
@@ -241,6 +433,7 @@ impl AiService {
             risk_indicators: self.extract_list(response, "risk"),
             suggested_items: self.extract_suggested_items(response),
             confidence: 0.7, // Default confidence
+            pii_categories: Vec::new(),
         })
     }
 
@@ -303,7 +496,7 @@ impl AiService {
                         !trimmed.is_empty() && (
                             trimmed.starts_with('-') || 
                             trimmed.starts_with('*') || 
-                            trimmed.chars().next().map_or(false, |c| c.is_numeric()) // Allow numbered lists
+                            trimmed.chars().next().is_some_and(|c| c.is_numeric()) // Allow numbered lists
                         )
                     })
                     .map(|line| {
@@ -0,0 +1,151 @@
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    db::repository::{ComplianceRepository, UserRepository},
+    error::{AppError, AppResult},
+    models::{calendar::MONTH_WINDOW_DAYS, CalendarFeed, MetadataFilter, UpcomingCompliance},
+    utils::ical,
+};
+
+/// Calendar service for compliance due dates
+///
+/// Groups upcoming deadlines and publishes them as a per-user iCalendar feed
+/// protected by a secret token.
+pub struct CalendarService {
+    /// Compliance repository
+    compliance: ComplianceRepository,
+
+    /// User repository (feed tokens)
+    users: UserRepository,
+}
+
+impl CalendarService {
+    /// Create a new CalendarService
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    ///
+    /// # Returns
+    ///
+    /// New CalendarService instance
+    pub fn new(pool: PgPool) -> Self {
+        info!("📅 CalendarService started");
+        Self {
+            compliance: ComplianceRepository::new(pool.clone()),
+            users: UserRepository::new(pool),
+        }
+    }
+
+    /// Get open compliance items grouped by due window
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `filter` - Tag and custom field filters
+    ///
+    /// # Returns
+    ///
+    /// Overdue items and items due this week and this month
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    #[instrument(skip(self))]
+    pub async fn upcoming(&self, user_id: Uuid, filter: &MetadataFilter) -> AppResult<UpcomingCompliance> {
+        let now = Utc::now();
+        let items = self
+            .compliance
+            .find_open_due_before(user_id, now + Duration::days(MONTH_WINDOW_DAYS), filter)
+            .await?;
+
+        Ok(UpcomingCompliance::group(items, now))
+    }
+
+    /// Get the user's calendar feed, enabling it on first use
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// Feed token and path
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    #[instrument(skip(self))]
+    pub async fn feed(&self, user_id: Uuid) -> AppResult<CalendarFeed> {
+        match self.users.get_calendar_token(user_id).await? {
+            Some(token) => Ok(CalendarFeed::new(token)),
+            None => self.rotate_token(user_id).await,
+        }
+    }
+
+    /// Replace the user's calendar feed token, invalidating existing subscriptions
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// New feed token and path
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if update fails
+    #[instrument(skip(self))]
+    pub async fn rotate_token(&self, user_id: Uuid) -> AppResult<CalendarFeed> {
+        // Two random v4 UUIDs give 244 bits of entropy
+        let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        self.users.set_calendar_token(user_id, Some(&token)).await?;
+
+        Ok(CalendarFeed::new(token))
+    }
+
+    /// Disable the user's calendar feed
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if update fails
+    #[instrument(skip(self))]
+    pub async fn revoke_token(&self, user_id: Uuid) -> AppResult<()> {
+        self.users.set_calendar_token(user_id, None).await
+    }
+
+    /// Render the iCalendar feed for a token
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - Calendar feed token
+    ///
+    /// # Returns
+    ///
+    /// iCalendar document of the owner's compliance due dates
+    ///
+    /// # Errors
+    ///
+    /// Returns 404 if the token is unknown or revoked
+    #[instrument(skip(self, token))]
+    pub async fn render_feed(&self, token: &str) -> AppResult<String> {
+        let user = self
+            .users
+            .find_by_calendar_token(token)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Calendar feed not found".to_string()))?;
+
+        let items = self.compliance.find_with_due_date(user.id).await?;
+        let name = format!("ParseGuard compliance – {}", user.full_name);
+
+        Ok(ical::compliance_calendar(&name, &items, Utc::now()))
+    }
+}
@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+
+use sqlx::PgPool;
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    db::repository::CommentRepository,
+    error::{AppError, AppResult},
+    models::{CommentEdit, CommentEntityType, CommentResponse, CreateCommentDto, UpdateCommentDto},
+};
+
+/// Comment service for threaded discussions
+///
+/// Enforces parent entity ownership, resolves @mentions and assembles threads
+pub struct CommentService {
+    /// Comment repository
+    repository: CommentRepository,
+}
+
+impl CommentService {
+    /// Create a new CommentService
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    ///
+    /// # Returns
+    ///
+    /// New CommentService instance
+    pub fn new(pool: PgPool) -> Self {
+        info!("💬 CommentService started");
+        Self {
+            repository: CommentRepository::new(pool),
+        }
+    }
+
+    /// List the comment threads of an entity
+    ///
+    /// # Arguments
+    ///
+    /// * `entity_type` - Type of the parent entity
+    /// * `entity_id` - Parent entity UUID
+    /// * `user_id` - User UUID for authorization
+    ///
+    /// # Returns
+    ///
+    /// Top-level comments with replies nested beneath them
+    ///
+    /// # Errors
+    ///
+    /// Returns 404 if the entity is not found or not owned by the user
+    #[instrument(skip(self))]
+    pub async fn list(
+        &self,
+        entity_type: CommentEntityType,
+        entity_id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<Vec<CommentResponse>> {
+        self.ensure_entity(entity_type, entity_id, user_id).await?;
+
+        let comments = self.repository.find_by_entity(entity_type, entity_id).await?;
+        let ids: Vec<Uuid> = comments.iter().map(|c| c.id).collect();
+        let mut mentions = HashMap::new();
+        for mention in self.repository.find_mentions(&ids).await? {
+            mentions.entry(mention.comment_id).or_insert_with(Vec::new).push(mention);
+        }
+
+        let responses = comments
+            .into_iter()
+            .map(|comment| {
+                let mut response = CommentResponse::from(comment);
+                if !response.deleted {
+                    response.mentions = mentions.remove(&response.id).unwrap_or_default();
+                }
+                response
+            })
+            .collect();
+
+        Ok(build_threads(responses))
+    }
+
+    /// Create a comment or reply on an entity
+    ///
+    /// # Arguments
+    ///
+    /// * `entity_type` - Type of the parent entity
+    /// * `entity_id` - Parent entity UUID
+    /// * `user_id` - Author UUID
+    /// * `dto` - Comment data
+    ///
+    /// # Returns
+    ///
+    /// Created comment
+    ///
+    /// # Errors
+    ///
+    /// Returns 404 if the entity or replied-to comment is not found
+    #[instrument(skip(self, dto))]
+    pub async fn create(
+        &self,
+        entity_type: CommentEntityType,
+        entity_id: Uuid,
+        user_id: Uuid,
+        dto: &CreateCommentDto,
+    ) -> AppResult<CommentResponse> {
+        self.ensure_entity(entity_type, entity_id, user_id).await?;
+
+        if let Some(parent_id) = dto.parent_id {
+            self.repository
+                .find_by_id(parent_id, entity_type, entity_id)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Parent comment not found".to_string()))?;
+        }
+
+        let mentioned = extract_mentions(&dto.body);
+        let comment = self
+            .repository
+            .create(entity_type, entity_id, dto.parent_id, user_id, &dto.body, &mentioned)
+            .await?;
+
+        self.with_mentions(CommentResponse::from(comment)).await
+    }
+
+    /// Edit a comment
+    ///
+    /// # Arguments
+    ///
+    /// * `entity_type` - Type of the parent entity
+    /// * `entity_id` - Parent entity UUID
+    /// * `comment_id` - Comment UUID
+    /// * `user_id` - User UUID (must be the author)
+    /// * `dto` - New comment data
+    ///
+    /// # Returns
+    ///
+    /// Updated comment
+    ///
+    /// # Errors
+    ///
+    /// Returns 404 if not found and 403 if the user is not the author
+    #[instrument(skip(self, dto))]
+    pub async fn update(
+        &self,
+        entity_type: CommentEntityType,
+        entity_id: Uuid,
+        comment_id: Uuid,
+        user_id: Uuid,
+        dto: &UpdateCommentDto,
+    ) -> AppResult<CommentResponse> {
+        self.ensure_author(entity_type, entity_id, comment_id, user_id).await?;
+
+        let mentioned = extract_mentions(&dto.body);
+        let comment = self
+            .repository
+            .update(comment_id, user_id, &dto.body, &mentioned)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Comment not found".to_string()))?;
+
+        self.with_mentions(CommentResponse::from(comment)).await
+    }
+
+    /// Delete a comment
+    ///
+    /// # Arguments
+    ///
+    /// * `entity_type` - Type of the parent entity
+    /// * `entity_id` - Parent entity UUID
+    /// * `comment_id` - Comment UUID
+    /// * `user_id` - User UUID (must be the author)
+    ///
+    /// # Errors
+    ///
+    /// Returns 404 if not found and 403 if the user is not the author
+    #[instrument(skip(self))]
+    pub async fn delete(
+        &self,
+        entity_type: CommentEntityType,
+        entity_id: Uuid,
+        comment_id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<()> {
+        self.ensure_author(entity_type, entity_id, comment_id, user_id).await?;
+
+        if self.repository.delete(comment_id, user_id).await? {
+            Ok(())
+        } else {
+            Err(AppError::NotFound("Comment not found".to_string()))
+        }
+    }
+
+    /// Get the edit history of a comment
+    ///
+    /// # Arguments
+    ///
+    /// * `entity_type` - Type of the parent entity
+    /// * `entity_id` - Parent entity UUID
+    /// * `comment_id` - Comment UUID
+    /// * `user_id` - User UUID for authorization
+    ///
+    /// # Returns
+    ///
+    /// Previous versions of the comment, newest first
+    ///
+    /// # Errors
+    ///
+    /// Returns 404 if the entity or comment is not found
+    #[instrument(skip(self))]
+    pub async fn history(
+        &self,
+        entity_type: CommentEntityType,
+        entity_id: Uuid,
+        comment_id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<Vec<CommentEdit>> {
+        self.ensure_entity(entity_type, entity_id, user_id).await?;
+
+        let comment = self
+            .repository
+            .find_by_id(comment_id, entity_type, entity_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Comment not found".to_string()))?;
+
+        if comment.deleted_at.is_some() {
+            return Err(AppError::NotFound("Comment not found".to_string()));
+        }
+
+        self.repository.find_edits(comment_id).await
+    }
+
+    /// Ensure the parent entity exists and is owned by the user
+    async fn ensure_entity(
+        &self,
+        entity_type: CommentEntityType,
+        entity_id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<()> {
+        if self.repository.entity_exists(entity_type, entity_id, user_id).await? {
+            Ok(())
+        } else {
+            Err(AppError::NotFound(format!("{} not found", entity_type.display_name())))
+        }
+    }
+
+    /// Ensure the comment exists on the entity and was written by the user
+    async fn ensure_author(
+        &self,
+        entity_type: CommentEntityType,
+        entity_id: Uuid,
+        comment_id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<()> {
+        self.ensure_entity(entity_type, entity_id, user_id).await?;
+
+        let comment = self
+            .repository
+            .find_by_id(comment_id, entity_type, entity_id)
+            .await?
+            .filter(|c| c.deleted_at.is_none())
+            .ok_or_else(|| AppError::NotFound("Comment not found".to_string()))?;
+
+        if comment.user_id != user_id {
+            return Err(AppError::Forbidden("Only the author can modify a comment".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Attach mentions to a single comment response
+    async fn with_mentions(&self, mut response: CommentResponse) -> AppResult<CommentResponse> {
+        response.mentions = self.repository.find_mentions(&[response.id]).await?;
+        Ok(response)
+    }
+}
+
+/// Extract mentioned emails (`@user@example.com`) from a comment body
+///
+/// # Arguments
+///
+/// * `body` - Comment text
+///
+/// # Returns
+///
+/// Lowercased, de-duplicated email addresses
+pub fn extract_mentions(body: &str) -> Vec<String> {
+    let mut emails: Vec<String> = body
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix('@'))
+        .map(|candidate| {
+            candidate
+                .trim_end_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|candidate| {
+            candidate
+                .split_once('@')
+                .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.'))
+        })
+        .collect();
+
+    emails.sort();
+    emails.dedup();
+    emails
+}
+
+/// Nest replies beneath their parent comments
+///
+/// Comments must be ordered oldest first so parents precede their replies.
+fn build_threads(comments: Vec<CommentResponse>) -> Vec<CommentResponse> {
+    let mut children: HashMap<Uuid, Vec<CommentResponse>> = HashMap::new();
+    let mut roots = Vec::new();
+
+    for comment in comments {
+        match comment.parent_id {
+            Some(parent_id) => children.entry(parent_id).or_default().push(comment),
+            None => roots.push(comment),
+        }
+    }
+
+    fn attach(mut comment: CommentResponse, children: &mut HashMap<Uuid, Vec<CommentResponse>>) -> CommentResponse {
+        let replies = children.remove(&comment.id).unwrap_or_default();
+        comment.replies = replies.into_iter().map(|reply| attach(reply, children)).collect();
+        comment
+    }
+
+    roots.into_iter().map(|root| attach(root, &mut children)).collect()
+}
@@ -1,15 +1,20 @@
-use serde::Serialize;
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use tracing::{info, instrument};
 use uuid::Uuid;
 
-use crate::{db::repository::DashboardRepository, error::AppResult};
+use crate::{
+    db::repository::{dashboard_repository::LatestRiskQuery, DashboardRepository},
+    error::AppResult,
+    models::{BurndownPoint, DashboardFilter, RiskMatrix, TrendInterval, RISK_LEVELS},
+};
 
-/// Dashboard statistics
-///
-/// Contains key metrics for the dashboard view
-#[derive(Debug, Serialize)]
-pub struct DashboardStats {
+/// Compliance and document counts for one period
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsSummary {
     /// Total number of compliance items
     pub total_compliance_items: i64,
     
@@ -35,6 +40,63 @@ pub struct DashboardStats {
     pub compliance_score: f64,
 }
 
+impl StatsSummary {
+    /// Change from a previous period to this one
+    pub fn delta(&self, previous: &StatsSummary) -> StatsSummary {
+        StatsSummary {
+            total_compliance_items: self.total_compliance_items - previous.total_compliance_items,
+            pending_items: self.pending_items - previous.pending_items,
+            in_progress_items: self.in_progress_items - previous.in_progress_items,
+            completed_items: self.completed_items - previous.completed_items,
+            expired_items: self.expired_items - previous.expired_items,
+            total_documents: self.total_documents - previous.total_documents,
+            analyzed_documents: self.analyzed_documents - previous.analyzed_documents,
+            compliance_score: self.compliance_score - previous.compliance_score,
+        }
+    }
+}
+
+/// Statistics of the preceding period
+#[derive(Debug, Serialize)]
+pub struct StatsComparison {
+    /// Start of the previous period
+    pub from: DateTime<Utc>,
+    
+    /// End of the previous period
+    pub to: DateTime<Utc>,
+    
+    /// Statistics of the previous period
+    pub stats: StatsSummary,
+    
+    /// Current minus previous values
+    pub deltas: StatsSummary,
+}
+
+/// Dashboard statistics
+///
+/// Contains key metrics for the dashboard view
+#[derive(Debug, Serialize)]
+pub struct DashboardStats {
+    /// Statistics of the requested period
+    #[serde(flatten)]
+    pub summary: StatsSummary,
+    
+    /// Start of the period (None for all time)
+    pub from: Option<DateTime<Utc>>,
+    
+    /// End of the period (None for now)
+    pub to: Option<DateTime<Utc>>,
+    
+    /// Comparison with the preceding period (if requested)
+    pub comparison: Option<StatsComparison>,
+    
+    /// Bucket size of the burn-down series
+    pub burndown_interval: TrendInterval,
+    
+    /// Open compliance items over the period
+    pub burndown: Vec<BurndownPoint>,
+}
+
 /// Recent activity item
 #[derive(Debug, Serialize)]
 pub struct ActivityItem {
@@ -51,6 +113,133 @@ pub struct ActivityItem {
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+/// Axes of the risk heatmap
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HeatmapAxes {
+    /// Likelihood × impact
+    #[default]
+    Matrix,
+    /// Risk level × risk category
+    Category,
+}
+
+/// One heatmap cell
+#[derive(Debug, Serialize)]
+pub struct HeatmapCell {
+    /// Likelihood rating (matrix axes)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub likelihood: Option<i32>,
+    
+    /// Impact rating (matrix axes)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub impact: Option<i32>,
+    
+    /// Risk category (category axes)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub risk_category: Option<String>,
+    
+    /// Risk level of the cell
+    pub risk_level: String,
+    
+    /// Number of compliance items in the cell
+    pub count: i64,
+    
+    /// Compliance items in the cell
+    pub compliance_item_ids: Vec<Uuid>,
+}
+
+/// Risk heatmap of the latest assessment per compliance item
+#[derive(Debug, Serialize)]
+pub struct RiskHeatmap {
+    /// Heatmap axes
+    pub axes: HeatmapAxes,
+    
+    /// Cells (row by row for the matrix, by category then level otherwise)
+    pub cells: Vec<HeatmapCell>,
+    
+    /// Items whose latest assessment has no likelihood/impact (matrix axes only)
+    pub unplotted_items: i64,
+}
+
+/// Latest risk assessment of a compliance item
+#[derive(Debug, Serialize)]
+pub struct RiskItem {
+    /// Compliance item ID
+    pub compliance_item_id: Uuid,
+    
+    /// Compliance item title
+    pub title: String,
+    
+    /// Latest risk assessment ID
+    pub risk_score_id: Uuid,
+    
+    /// Risk category
+    pub risk_category: String,
+    
+    /// Inherent risk score (0-100)
+    pub risk_score: i32,
+    
+    /// Inherent risk level
+    pub risk_level: String,
+    
+    /// Residual risk score after controls (0-100)
+    pub residual_score: i32,
+    
+    /// Residual risk level
+    pub residual_level: String,
+    
+    /// When the assessment was made
+    pub assessment_date: DateTime<Utc>,
+}
+
+impl From<LatestRiskQuery> for RiskItem {
+    fn from(row: LatestRiskQuery) -> Self {
+        Self {
+            compliance_item_id: row.compliance_item_id,
+            title: row.title,
+            risk_score_id: row.risk_score_id,
+            risk_category: row.risk_category,
+            risk_score: row.risk_score,
+            risk_level: row.risk_level,
+            residual_score: row.residual_score,
+            residual_level: row.residual_level,
+            assessment_date: row.assessment_date,
+        }
+    }
+}
+
+/// Aggregate risk posture
+#[derive(Debug, Serialize)]
+pub struct RiskPosture {
+    /// Compliance items with at least one assessment
+    pub assessed_items: i64,
+    
+    /// Compliance items never assessed
+    pub unassessed_items: i64,
+    
+    /// Average latest inherent score (None without assessments)
+    pub average_risk_score: Option<f64>,
+    
+    /// Average latest residual score (None without assessments)
+    pub average_residual_score: Option<f64>,
+    
+    /// Posture score, 100 minus the average residual score (higher is better)
+    pub posture_score: Option<f64>,
+    
+    /// Number of items per residual risk level
+    pub level_counts: BTreeMap<String, i64>,
+    
+    /// Riskiest items by residual score
+    pub top_risks: Vec<RiskItem>,
+    
+    /// Items whose latest assessment is older than the staleness window
+    pub stale_items: i64,
+    
+    /// Staleness window in days
+    pub stale_after_days: i64,
+}
+
 /// Dashboard service for aggregating statistics
 ///
 /// Provides dashboard data and analytics
@@ -81,6 +270,7 @@ impl DashboardService {
     /// # Arguments
     ///
     /// * `user_id` - User UUID
+    /// * `filter` - Period, comparison and item filters
     ///
     /// # Returns
     ///
@@ -90,11 +280,44 @@ impl DashboardService {
     ///
     /// Returns database error if queries fail
     #[instrument(skip(self))]
-    pub async fn get_stats(&self, user_id: Uuid) -> AppResult<DashboardStats> {
+    pub async fn get_stats(&self, user_id: Uuid, filter: &DashboardFilter) -> AppResult<DashboardStats> {
         info!("Fetching stats for user: {}", user_id);
         
-        let compliance_stats = self.repository.get_compliance_stats(user_id).await?;
-        let document_stats = self.repository.get_document_stats(user_id).await?;
+        let summary = self.summarize(user_id, filter).await?;
+
+        let comparison = match filter.previous_period() {
+            Some(previous) if filter.compare => {
+                let stats = self.summarize(user_id, &previous).await?;
+                Some(StatsComparison {
+                    from: previous.from.unwrap_or_default(),
+                    to: previous.to.unwrap_or_default(),
+                    deltas: summary.delta(&stats),
+                    stats,
+                })
+            }
+            _ => None,
+        };
+
+        let (from, to, burndown_interval) = filter.burndown_range();
+        let burndown = self
+            .repository
+            .get_burndown(user_id, filter, from, to, burndown_interval)
+            .await?;
+
+        Ok(DashboardStats {
+            summary,
+            from: filter.from,
+            to: filter.to,
+            comparison,
+            burndown_interval,
+            burndown,
+        })
+    }
+
+    /// Count compliance items and documents matching a filter
+    async fn summarize(&self, user_id: Uuid, filter: &DashboardFilter) -> AppResult<StatsSummary> {
+        let compliance_stats = self.repository.get_compliance_stats(user_id, filter).await?;
+        let document_stats = self.repository.get_document_stats(user_id, filter).await?;
 
         // Calculate compliance score (percentage of completed items)
         let compliance_score = if compliance_stats.total > 0 {
@@ -103,7 +326,7 @@ impl DashboardService {
             0.0
         };
 
-        Ok(DashboardStats {
+        Ok(StatsSummary {
             total_compliance_items: compliance_stats.total,
             pending_items: compliance_stats.pending,
             in_progress_items: compliance_stats.in_progress,
@@ -157,4 +380,159 @@ impl DashboardService {
             })
             .collect())
     }
+
+    /// Get the risk heatmap of the latest assessment per compliance item
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `axes` - Heatmap axes
+    /// * `matrix` - Risk matrix (scale and cell levels)
+    ///
+    /// # Returns
+    ///
+    /// Heatmap cells with item counts
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if queries fail
+    #[instrument(skip(self, matrix))]
+    pub async fn get_risk_heatmap(
+        &self,
+        user_id: Uuid,
+        axes: HeatmapAxes,
+        matrix: &RiskMatrix,
+    ) -> AppResult<RiskHeatmap> {
+        let latest = self.repository.get_latest_risk_scores(user_id).await?;
+        let mut unplotted_items = 0;
+
+        let cells = match axes {
+            HeatmapAxes::Matrix => {
+                let mut cells: Vec<HeatmapCell> = matrix
+                    .cells()
+                    .into_iter()
+                    .map(|cell| HeatmapCell {
+                        likelihood: Some(cell.likelihood),
+                        impact: Some(cell.impact),
+                        risk_category: None,
+                        risk_level: cell.level,
+                        count: 0,
+                        compliance_item_ids: Vec::new(),
+                    })
+                    .collect();
+
+                for row in &latest {
+                    let position = match (row.likelihood, row.impact) {
+                        (Some(l), Some(i)) if (1..=matrix.scale).contains(&l) && (1..=matrix.scale).contains(&i) => {
+                            ((l - 1) * matrix.scale + (i - 1)) as usize
+                        }
+                        _ => {
+                            unplotted_items += 1;
+                            continue;
+                        }
+                    };
+                    cells[position].count += 1;
+                    cells[position].compliance_item_ids.push(row.compliance_item_id);
+                }
+
+                cells
+            }
+            HeatmapAxes::Category => {
+                let mut by_category: BTreeMap<&str, Vec<HeatmapCell>> = BTreeMap::new();
+                for row in &latest {
+                    let cells = by_category.entry(row.risk_category.as_str()).or_insert_with(|| {
+                        RISK_LEVELS
+                            .iter()
+                            .map(|level| HeatmapCell {
+                                likelihood: None,
+                                impact: None,
+                                risk_category: Some(row.risk_category.clone()),
+                                risk_level: level.to_string(),
+                                count: 0,
+                                compliance_item_ids: Vec::new(),
+                            })
+                            .collect()
+                    });
+                    if let Some(cell) = cells.iter_mut().find(|c| c.risk_level == row.risk_level) {
+                        cell.count += 1;
+                        cell.compliance_item_ids.push(row.compliance_item_id);
+                    }
+                }
+
+                by_category.into_values().flatten().collect()
+            }
+        };
+
+        Ok(RiskHeatmap {
+            axes,
+            cells,
+            unplotted_items,
+        })
+    }
+
+    /// Get the aggregate risk posture
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `top` - Number of riskiest items to return
+    /// * `stale_after_days` - Staleness window in days
+    ///
+    /// # Returns
+    ///
+    /// Posture score, riskiest items and staleness counts
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if queries fail
+    #[instrument(skip(self))]
+    pub async fn get_risk_posture(
+        &self,
+        user_id: Uuid,
+        top: usize,
+        stale_after_days: i64,
+    ) -> AppResult<RiskPosture> {
+        let compliance_stats = self
+            .repository
+            .get_compliance_stats(user_id, &DashboardFilter::default())
+            .await?;
+        let mut latest = self.repository.get_latest_risk_scores(user_id).await?;
+
+        let assessed_items = latest.len() as i64;
+        let stale_before = Utc::now() - Duration::days(stale_after_days);
+        let stale_items = latest.iter().filter(|r| r.assessment_date < stale_before).count() as i64;
+
+        let mut level_counts: BTreeMap<String, i64> =
+            RISK_LEVELS.iter().map(|level| (level.to_string(), 0)).collect();
+        for row in &latest {
+            *level_counts.entry(row.residual_level.clone()).or_default() += 1;
+        }
+
+        let average = |score: fn(&LatestRiskQuery) -> i32| {
+            (!latest.is_empty()).then(|| {
+                latest.iter().map(|r| f64::from(score(r))).sum::<f64>() / latest.len() as f64
+            })
+        };
+        let average_risk_score = average(|r| r.risk_score);
+        let average_residual_score = average(|r| r.residual_score);
+
+        latest.sort_by(|a, b| {
+            b.residual_score
+                .cmp(&a.residual_score)
+                .then(b.risk_score.cmp(&a.risk_score))
+        });
+        let top_risks = latest.into_iter().take(top).map(RiskItem::from).collect();
+
+        Ok(RiskPosture {
+            assessed_items,
+            unassessed_items: (compliance_stats.total - assessed_items).max(0),
+            average_risk_score,
+            average_residual_score,
+            posture_score: average_residual_score.map(|avg| 100.0 - avg),
+            level_counts,
+            top_risks,
+            stale_items,
+            stale_after_days,
+        })
+    }
 }
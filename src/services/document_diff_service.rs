@@ -0,0 +1,154 @@
+use sqlx::PgPool;
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    db::repository::{ComplianceRepository, DocumentRepository, DocumentVersionRepository},
+    error::{AppError, AppResult},
+    models::{MetadataFilter, VersionDiff, VersionDiffQuery},
+    services::{ai_service::AiService, ensure_scan_passed},
+    utils::{
+        pii::PiiMode,
+        text_diff::{diff_segments, segment},
+    },
+};
+
+/// Document diff service for comparing document versions
+///
+/// Diffs the extracted text of two versions and optionally has the LLM
+/// classify the changes and flag compliance items they may affect.
+pub struct DocumentDiffService {
+    /// Document repository
+    documents: DocumentRepository,
+
+    /// Document version repository
+    versions: DocumentVersionRepository,
+
+    /// Compliance repository (impact candidates)
+    compliance: ComplianceRepository,
+
+    /// OLLAMA API base URL
+    ollama_url: String,
+
+    /// Masking of personal data sent to OLLAMA
+    pii_mode: PiiMode,
+
+    /// Whether a malware scanner is configured
+    scanning_enabled: bool,
+}
+
+impl DocumentDiffService {
+    /// Create a new DocumentDiffService
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    /// * `ollama_url` - OLLAMA API base URL (used when classifying)
+    /// * `pii_mode` - Masking of personal data sent to OLLAMA
+    /// * `scanning_enabled` - Whether a malware scanner is configured
+    ///
+    /// # Returns
+    ///
+    /// New DocumentDiffService instance
+    pub fn new(pool: PgPool, ollama_url: String, pii_mode: PiiMode, scanning_enabled: bool) -> Self {
+        info!("🔀 DocumentDiffService started");
+        Self {
+            documents: DocumentRepository::new(pool.clone()),
+            versions: DocumentVersionRepository::new(pool.clone()),
+            compliance: ComplianceRepository::new(pool),
+            ollama_url,
+            pii_mode,
+            scanning_enabled,
+        }
+    }
+
+    /// Compare two versions of a document
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `document_id` - Document UUID
+    /// * `query` - Versions, granularity and whether to classify
+    ///
+    /// # Returns
+    ///
+    /// Changes between the versions, classified if requested
+    ///
+    /// # Errors
+    ///
+    /// Returns 404 if the document or a version is not found, validation
+    /// error if a version is below 1, there is nothing to compare or a
+    /// version has no extracted text, forbidden error if a version is quarantined by the malware scan,
+    /// OLLAMA error if classification fails
+    #[instrument(skip(self))]
+    pub async fn diff(&self, user_id: Uuid, document_id: Uuid, query: &VersionDiffQuery) -> AppResult<VersionDiff> {
+        let document = self
+            .documents
+            .find_by_id(document_id, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Document not found".to_string()))?;
+
+        let to_version = query.to.unwrap_or(document.current_version);
+        if to_version < 1 || query.from.is_some_and(|from| from < 1) {
+            return Err(AppError::Validation("Versions are numbered from 1".to_string()));
+        }
+        let from_version = query.from.unwrap_or(to_version - 1);
+        if from_version < 1 {
+            return Err(AppError::Validation("The document has no earlier version to compare with".to_string()));
+        }
+        if from_version == to_version {
+            return Err(AppError::Validation("Choose two different versions to compare".to_string()));
+        }
+
+        let granularity = query.granularity.unwrap_or_default();
+        let before = self.version_text(document_id, from_version).await?;
+        let after = self.version_text(document_id, to_version).await?;
+        let (unchanged, mut changes) = tokio::task::spawn_blocking(move || {
+            diff_segments(&segment(&before, granularity), &segment(&after, granularity))
+        })
+        .await
+        .map_err(|e| AppError::Internal(format!("Version comparison failed: {}", e)))?;
+
+        let mut impacted_items = Vec::new();
+        if query.classify {
+            let items = self.compliance.find_by_user(user_id, &MetadataFilter::default()).await?;
+            impacted_items = AiService::new(self.ollama_url.clone(), self.pii_mode)
+                .classify_changes(&mut changes, &items)
+                .await?;
+        }
+
+        info!(
+            "Compared versions {} and {} of document {}: {} changes, {} unchanged",
+            from_version,
+            to_version,
+            document_id,
+            changes.len(),
+            unchanged
+        );
+
+        Ok(VersionDiff {
+            document_id,
+            from_version,
+            to_version,
+            granularity,
+            unchanged,
+            changes,
+            classified: query.classify,
+            impacted_items,
+        })
+    }
+
+    /// Extracted text of a version that passed its malware scan
+    async fn version_text(&self, document_id: Uuid, version_number: i32) -> AppResult<String> {
+        let version = self
+            .versions
+            .find_by_number(document_id, version_number)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Document version {} not found", version_number)))?;
+        ensure_scan_passed(&version.scan_status, version.scan_signature.as_deref(), self.scanning_enabled)?;
+
+        version
+            .extracted_text
+            .ok_or_else(|| AppError::Validation(format!("Version {} has no extracted text", version_number)))
+    }
+}
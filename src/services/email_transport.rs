@@ -0,0 +1,198 @@
+use std::{path::PathBuf, sync::Arc};
+
+use axum::async_trait;
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::{
+    config::{EmailConfig, EmailTransportKind, SmtpSecurity},
+    error::{AppError, AppResult},
+};
+
+/// Plain-text email to deliver
+#[derive(Debug, Clone)]
+pub struct EmailMessage {
+    /// Recipient mailbox
+    pub to: Mailbox,
+
+    /// Subject line
+    pub subject: String,
+
+    /// Plain-text body
+    pub body: String,
+}
+
+/// Pluggable email delivery
+#[async_trait]
+pub trait EmailTransport: Send + Sync {
+    /// Deliver a message
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - Message to deliver
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the message cannot be built or delivered
+    async fn send(&self, message: &EmailMessage) -> AppResult<()>;
+}
+
+/// Build the transport selected in the configuration
+///
+/// # Arguments
+///
+/// * `config` - Email configuration
+///
+/// # Returns
+///
+/// Shared transport
+///
+/// # Errors
+///
+/// Returns error for an invalid sender or SMTP relay
+pub fn transport_from_config(config: &EmailConfig) -> AppResult<Arc<dyn EmailTransport>> {
+    let transport: Arc<dyn EmailTransport> = match config.transport {
+        EmailTransportKind::Smtp => Arc::new(SmtpEmailTransport::new(config)?),
+        EmailTransportKind::File => Arc::new(FileEmailTransport::new(&config.from, &config.file_dir)?),
+        EmailTransportKind::Disabled => Arc::new(DisabledEmailTransport),
+    };
+
+    Ok(transport)
+}
+
+/// Delivers email through an SMTP relay
+pub struct SmtpEmailTransport {
+    /// Pooled SMTP client
+    mailer: AsyncSmtpTransport<Tokio1Executor>,
+
+    /// Sender mailbox
+    from: Mailbox,
+}
+
+impl SmtpEmailTransport {
+    /// Create a new SmtpEmailTransport
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Email configuration
+    ///
+    /// # Returns
+    ///
+    /// New SmtpEmailTransport instance
+    ///
+    /// # Errors
+    ///
+    /// Returns error for an invalid sender or relay host
+    pub fn new(config: &EmailConfig) -> AppResult<Self> {
+        let builder = match config.smtp_security {
+            SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host),
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host),
+            SmtpSecurity::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host)),
+        }
+        .map_err(|e| AppError::Internal(format!("Invalid SMTP relay: {}", e)))?;
+
+        let mut builder = builder.port(config.smtp_port);
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        info!("📧 SMTP email transport using {}:{}", config.smtp_host, config.smtp_port);
+        Ok(Self {
+            mailer: builder.build(),
+            from: parse_mailbox(&config.from)?,
+        })
+    }
+}
+
+#[async_trait]
+impl EmailTransport for SmtpEmailTransport {
+    async fn send(&self, message: &EmailMessage) -> AppResult<()> {
+        let email = build_message(&self.from, message)?;
+        self.mailer
+            .send(email)
+            .await
+            .map_err(|e| AppError::Internal(format!("SMTP delivery failed: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+/// Writes each email as an `.eml` file into a directory
+pub struct FileEmailTransport {
+    /// Output directory
+    dir: PathBuf,
+
+    /// Sender mailbox
+    from: Mailbox,
+}
+
+impl FileEmailTransport {
+    /// Create a new FileEmailTransport
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - Sender mailbox
+    /// * `dir` - Output directory (created on first delivery)
+    ///
+    /// # Returns
+    ///
+    /// New FileEmailTransport instance
+    ///
+    /// # Errors
+    ///
+    /// Returns error for an invalid sender
+    pub fn new(from: &str, dir: impl Into<PathBuf>) -> AppResult<Self> {
+        Ok(Self {
+            dir: dir.into(),
+            from: parse_mailbox(from)?,
+        })
+    }
+}
+
+#[async_trait]
+impl EmailTransport for FileEmailTransport {
+    async fn send(&self, message: &EmailMessage) -> AppResult<()> {
+        let email = build_message(&self.from, message)?;
+
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let path = self.dir.join(format!("{}.eml", Uuid::new_v4()));
+        tokio::fs::write(&path, email.formatted()).await?;
+        debug!("Wrote email to {}", path.display());
+
+        Ok(())
+    }
+}
+
+/// Drops emails (delivery disabled)
+pub struct DisabledEmailTransport;
+
+#[async_trait]
+impl EmailTransport for DisabledEmailTransport {
+    async fn send(&self, message: &EmailMessage) -> AppResult<()> {
+        debug!("Email delivery disabled, dropping '{}' to {}", message.subject, message.to);
+        Ok(())
+    }
+}
+
+/// Parse a mailbox such as `Name <user@example.com>`
+fn parse_mailbox(value: &str) -> AppResult<Mailbox> {
+    value
+        .parse()
+        .map_err(|e| AppError::Internal(format!("Invalid email address '{}': {}", value, e)))
+}
+
+/// Build a plain-text MIME message
+fn build_message(from: &Mailbox, message: &EmailMessage) -> AppResult<Message> {
+    Message::builder()
+        .from(from.clone())
+        .to(message.to.clone())
+        .subject(&message.subject)
+        .header(ContentType::TEXT_PLAIN)
+        .body(message.body.clone())
+        .map_err(|e| AppError::Internal(format!("Failed to build email: {}", e)))
+}
@@ -0,0 +1,715 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use sqlx::PgPool;
+use tracing::{info, instrument};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    db::repository::{
+        ComplianceRepository, CustomFieldRepository, RiskScoreRepository, TagRepository, UserRepository,
+    },
+    error::{AppError, AppResult},
+    models::{
+        ComplianceItem, CreateComplianceDto, CreateRiskScoreDto, CustomFieldDefinition, CustomFieldType,
+        ImportFieldInfo, ImportFieldKind, ImportPreview, ImportReport, ImportRowError, ImportTarget,
+        MetadataEntityType, MetadataFilter, RiskMatrix, RiskScore, Tag, RISK_LEVELS,
+    },
+    services::{metadata_service::MetadataChanges, MetadataService},
+    utils::import::ImportTable,
+};
+
+/// Rows included in a preview
+const PREVIEW_ROWS: usize = 5;
+
+/// Prefix of custom field names in a mapping
+const CUSTOM_FIELD_PREFIX: &str = "cf.";
+
+/// Allowed compliance statuses
+const COMPLIANCE_STATUSES: [&str; 4] = ["pending", "in_progress", "completed", "expired"];
+
+/// Maximum compliance item title length (database column size)
+const MAX_TITLE_LENGTH: usize = 500;
+
+/// Import service for compliance items and risk scores
+///
+/// An import maps spreadsheet columns to fields, converts and validates
+/// every row with the same rules as the single-create endpoints, and only
+/// writes when no row has an error. All rows are written in one transaction.
+pub struct ImportService {
+    /// Database connection pool
+    pool: PgPool,
+
+    /// Risk matrix used to resolve risk scores
+    matrix: RiskMatrix,
+
+    /// Compliance repository
+    compliance: ComplianceRepository,
+
+    /// User repository (assignee lookup)
+    users: UserRepository,
+
+    /// Tag repository (tag name lookup)
+    tags: TagRepository,
+
+    /// Custom field repository
+    custom_fields: CustomFieldRepository,
+
+    /// Metadata service (tag and custom field validation)
+    metadata: MetadataService,
+}
+
+/// Column mapping resolved against the file headers
+struct ColumnMapping {
+    /// Field name and column index
+    columns: Vec<(String, usize)>,
+}
+
+impl ColumnMapping {
+    /// Non-empty cells of a row by field name
+    fn cells<'a>(&'a self, row: &'a [String]) -> impl Iterator<Item = (&'a str, &'a str)> {
+        self.columns
+            .iter()
+            .map(move |(field, col)| (field.as_str(), row[*col].trim()))
+            .filter(|(_, value)| !value.is_empty())
+    }
+}
+
+/// Row that passed validation
+struct PreparedCompliance {
+    /// Creation data
+    dto: CreateComplianceDto,
+
+    /// Tag and custom field changes
+    changes: MetadataChanges,
+}
+
+impl ImportService {
+    /// Create a new ImportService
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    /// * `matrix` - Risk matrix used to resolve imported risk scores
+    ///
+    /// # Returns
+    ///
+    /// New ImportService instance
+    pub fn new(pool: PgPool, matrix: RiskMatrix) -> Self {
+        info!("📥 ImportService started");
+        Self {
+            compliance: ComplianceRepository::new(pool.clone()),
+            users: UserRepository::new(pool.clone()),
+            tags: TagRepository::new(pool.clone()),
+            custom_fields: CustomFieldRepository::new(pool.clone()),
+            metadata: MetadataService::new(pool.clone()),
+            matrix,
+            pool,
+        }
+    }
+
+    /// Describe a parsed file for the column-mapping step
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID (custom fields are per user)
+    /// * `target` - Entity being imported
+    /// * `table` - Parsed file
+    ///
+    /// # Returns
+    ///
+    /// Headers, sample rows, mappable fields and a suggested mapping
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    #[instrument(skip(self, table))]
+    pub async fn preview(&self, user_id: Uuid, target: ImportTarget, table: &ImportTable) -> AppResult<ImportPreview> {
+        let definitions = self.definitions(user_id, target).await?;
+        let fields = field_infos(target, &definitions);
+
+        let mut suggested_mapping = BTreeMap::new();
+        for field in &fields {
+            let label = field
+                .name
+                .strip_prefix(CUSTOM_FIELD_PREFIX)
+                .and_then(|name| definitions.iter().find(|d| d.name == name))
+                .map(|d| normalize_header(&d.label));
+
+            let column = table.headers.iter().find(|header| {
+                let header = normalize_header(header);
+                header == normalize_header(&field.name) || Some(&header) == label.as_ref()
+            });
+            if let Some(column) = column {
+                suggested_mapping.insert(field.name.clone(), column.clone());
+            }
+        }
+
+        Ok(ImportPreview {
+            headers: table.headers.clone(),
+            sample_rows: table.rows.iter().take(PREVIEW_ROWS).cloned().collect(),
+            row_count: table.rows.len(),
+            fields,
+            suggested_mapping,
+        })
+    }
+
+    /// Validate and optionally create compliance items
+    ///
+    /// Activity events, webhooks, realtime pushes and notifications for the
+    /// created items are left to the caller, once the transaction committed.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - Owner user UUID
+    /// * `table` - Parsed file
+    /// * `mapping` - Field → column mapping (the suggested mapping if omitted)
+    /// * `dry_run` - Only validate
+    ///
+    /// # Returns
+    ///
+    /// Report and the created items (empty unless committed)
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error for an unusable mapping, database error if
+    /// the transaction fails (nothing is written in that case)
+    #[instrument(skip(self, table, mapping))]
+    pub async fn import_compliance(
+        &self,
+        user_id: Uuid,
+        table: &ImportTable,
+        mapping: Option<HashMap<String, String>>,
+        dry_run: bool,
+    ) -> AppResult<(ImportReport, Vec<ComplianceItem>)> {
+        let target = ImportTarget::ComplianceItem;
+        let definitions = self.definitions(user_id, target).await?;
+        let mapping = self.resolve_mapping(user_id, target, table, mapping, &definitions).await?;
+        let tags = self.tags.find_by_user(user_id).await?;
+        let mut assignees: HashMap<String, Option<Uuid>> = HashMap::new();
+
+        let mut errors = Vec::new();
+        let mut prepared = Vec::new();
+        for (index, row) in table.rows.iter().enumerate() {
+            let line = index + 2;
+            let mut row_errors = Vec::new();
+            let mut object = Map::new();
+            let mut custom_fields = Map::new();
+
+            for (field, text) in mapping.cells(row) {
+                let converted = if let Some(name) = field.strip_prefix(CUSTOM_FIELD_PREFIX) {
+                    definitions
+                        .iter()
+                        .find(|d| d.name == name)
+                        .map(|d| custom_field_value(d, text))
+                        .unwrap_or_else(|| Ok(Value::String(text.to_string())))
+                        .map(|value| {
+                            custom_fields.insert(name.to_string(), value);
+                        })
+                } else {
+                    match field {
+                        "assignee_email" => self
+                            .assignee(user_id, &mut assignees, text)
+                            .await
+                            .map(|id| {
+                                object.insert("assignee_id".to_string(), Value::String(id.to_string()));
+                            }),
+                        "tags" => tag_ids(&tags, text).map(|ids| {
+                            object.insert("tag_ids".to_string(), ids);
+                        }),
+                        _ => convert(field_kind(target, field), text).map(|value| {
+                            object.insert(field.to_string(), value);
+                        }),
+                    }
+                };
+
+                if let Err(message) = converted {
+                    row_errors.push(row_error(line, Some(field), message));
+                }
+            }
+            object.insert("custom_fields".to_string(), Value::Object(custom_fields));
+
+            let missing = missing_fields(target, &object, &row_errors, line);
+            if missing.is_empty() {
+                match self.prepare_compliance(user_id, object, line).await? {
+                    Ok(row) if row_errors.is_empty() => prepared.push(row),
+                    Ok(_) => {}
+                    Err(problems) => row_errors.extend(problems),
+                }
+            }
+            row_errors.extend(missing);
+            row_errors.sort_by_key(|e| e.field.clone());
+            errors.extend(row_errors);
+        }
+
+        let mut report = report(table, dry_run, prepared.len(), errors);
+        if dry_run || !report.errors.is_empty() {
+            return Ok((report, Vec::new()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+        let mut items = Vec::with_capacity(prepared.len());
+        for row in &prepared {
+            let item = ComplianceRepository::create_in(&mut tx, user_id, &row.dto).await?;
+            MetadataService::apply_in(&mut tx, MetadataEntityType::ComplianceItem, item.id, &row.changes).await?;
+            items.push(item);
+        }
+        tx.commit().await?;
+
+        report.committed = true;
+        report.created_ids = items.iter().map(|item| item.id).collect();
+        info!("Imported {} compliance items for user {}", items.len(), user_id);
+
+        Ok((report, items))
+    }
+
+    /// Validate and optionally create risk scores
+    ///
+    /// Each row references its compliance item by ID or by (unique) title.
+    /// Activity events, webhooks, realtime pushes and risk alerts for the
+    /// created scores are left to the caller, once the transaction committed.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `table` - Parsed file
+    /// * `mapping` - Field → column mapping (the suggested mapping if omitted)
+    /// * `dry_run` - Only validate
+    ///
+    /// # Returns
+    ///
+    /// Report and the created scores (empty unless committed)
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error for an unusable mapping, database error if
+    /// the transaction fails (nothing is written in that case)
+    #[instrument(skip(self, table, mapping))]
+    pub async fn import_risk_scores(
+        &self,
+        user_id: Uuid,
+        table: &ImportTable,
+        mapping: Option<HashMap<String, String>>,
+        dry_run: bool,
+    ) -> AppResult<(ImportReport, Vec<RiskScore>)> {
+        let target = ImportTarget::RiskScore;
+        let mapping = self.resolve_mapping(user_id, target, table, mapping, &[]).await?;
+        let items = self.compliance.find_by_user(user_id, &MetadataFilter::default()).await?;
+
+        let mut errors = Vec::new();
+        let mut prepared = Vec::new();
+        for (index, row) in table.rows.iter().enumerate() {
+            let line = index + 2;
+            let mut row_errors = Vec::new();
+            let mut object = Map::new();
+
+            for (field, text) in mapping.cells(row) {
+                let converted = match field {
+                    "compliance_item_id" => convert(ImportFieldKind::Uuid, text).and_then(|id| {
+                        if items.iter().any(|item| Value::String(item.id.to_string()) == id) {
+                            object.insert("compliance_item_id".to_string(), id);
+                            Ok(())
+                        } else {
+                            Err("Compliance item not found".to_string())
+                        }
+                    }),
+                    "compliance_item_title" => {
+                        let mut matches = items.iter().filter(|item| item.title.trim() == text);
+                        match (matches.next(), matches.next()) {
+                            (Some(item), None) => {
+                                object
+                                    .entry("compliance_item_id")
+                                    .or_insert_with(|| Value::String(item.id.to_string()));
+                                Ok(())
+                            }
+                            (None, _) => Err(format!("No compliance item titled '{}'", text)),
+                            (Some(_), Some(_)) => Err(format!(
+                                "Several compliance items are titled '{}', use compliance_item_id",
+                                text
+                            )),
+                        }
+                    }
+                    _ => convert(field_kind(target, field), text).map(|value| {
+                        object.insert(field.to_string(), value);
+                    }),
+                };
+
+                if let Err(message) = converted {
+                    row_errors.push(row_error(line, Some(field), message));
+                }
+            }
+
+            if row_errors.is_empty() && !object.contains_key("compliance_item_id") {
+                row_errors.push(row_error(
+                    line,
+                    None,
+                    "compliance_item_id or compliance_item_title is required".to_string(),
+                ));
+            }
+
+            let missing = missing_fields(target, &object, &row_errors, line);
+            if missing.is_empty() && object.contains_key("compliance_item_id") {
+                match deserialize_row::<CreateRiskScoreDto>(object, line) {
+                    Ok(dto) => {
+                        let problems = validation_errors(&dto, line);
+                        if problems.is_empty() {
+                            if let Err(message) = self.matrix.resolve(
+                                dto.likelihood,
+                                dto.impact,
+                                dto.risk_score,
+                                dto.risk_level.as_deref(),
+                            ) {
+                                row_errors.push(row_error(line, None, message));
+                            }
+                        }
+                        row_errors.extend(problems);
+                        if row_errors.is_empty() {
+                            prepared.push(dto);
+                        }
+                    }
+                    Err(problem) => row_errors.push(problem),
+                }
+            }
+            row_errors.extend(missing);
+            row_errors.sort_by_key(|e| e.field.clone());
+            errors.extend(row_errors);
+        }
+
+        let mut report = report(table, dry_run, prepared.len(), errors);
+        if dry_run || !report.errors.is_empty() {
+            return Ok((report, Vec::new()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+        let mut scores = Vec::with_capacity(prepared.len());
+        for dto in &prepared {
+            scores.push(RiskScoreRepository::create_in(&mut tx, user_id, dto, &self.matrix).await?);
+        }
+        tx.commit().await?;
+
+        report.committed = true;
+        report.created_ids = scores.iter().map(|score| score.id).collect();
+        info!("Imported {} risk scores for user {}", scores.len(), user_id);
+
+        Ok((report, scores))
+    }
+
+    /// Custom field definitions that can be imported for a target
+    async fn definitions(&self, user_id: Uuid, target: ImportTarget) -> AppResult<Vec<CustomFieldDefinition>> {
+        match target {
+            ImportTarget::ComplianceItem => {
+                self.custom_fields
+                    .find_by_user(user_id, Some(MetadataEntityType::ComplianceItem))
+                    .await
+            }
+            ImportTarget::RiskScore => Ok(Vec::new()),
+        }
+    }
+
+    /// Check a requested mapping (or the suggested one) against the file
+    async fn resolve_mapping(
+        &self,
+        user_id: Uuid,
+        target: ImportTarget,
+        table: &ImportTable,
+        mapping: Option<HashMap<String, String>>,
+        definitions: &[CustomFieldDefinition],
+    ) -> AppResult<ColumnMapping> {
+        let mapping = match mapping {
+            Some(mapping) => mapping,
+            None => self
+                .preview(user_id, target, table)
+                .await?
+                .suggested_mapping
+                .into_iter()
+                .collect(),
+        };
+        let fields = field_infos(target, definitions);
+
+        let mut columns = Vec::new();
+        for (field, header) in mapping {
+            if !fields.iter().any(|f| f.name == field) {
+                return Err(AppError::Validation(format!("Unknown import field '{}'", field)));
+            }
+            let col = table
+                .headers
+                .iter()
+                .position(|h| *h == header)
+                .ok_or_else(|| AppError::Validation(format!("Column '{}' is not in the file", header)))?;
+            columns.push((field, col));
+        }
+
+        let unmapped: Vec<&str> = target
+            .fields()
+            .iter()
+            .filter(|f| f.required && !columns.iter().any(|(name, _)| name == f.name))
+            .map(|f| f.name)
+            .collect();
+        if !unmapped.is_empty() {
+            return Err(AppError::Validation(format!(
+                "Required fields are not mapped: {}",
+                unmapped.join(", ")
+            )));
+        }
+
+        if target == ImportTarget::RiskScore
+            && !columns
+                .iter()
+                .any(|(name, _)| name == "compliance_item_id" || name == "compliance_item_title")
+        {
+            return Err(AppError::Validation(
+                "Map compliance_item_id or compliance_item_title".to_string(),
+            ));
+        }
+
+        columns.sort_by_key(|(_, col)| *col);
+        Ok(ColumnMapping { columns })
+    }
+
+    /// Resolve an assignee email, caching lookups
+    ///
+    /// Only users the importer can see are resolved, which is the importer
+    /// alone; every other email gets the same error, so an import cannot be
+    /// used to find out which addresses have an account.
+    async fn assignee(
+        &self,
+        user_id: Uuid,
+        cache: &mut HashMap<String, Option<Uuid>>,
+        email: &str,
+    ) -> Result<Uuid, String> {
+        let key = email.to_lowercase();
+        if !cache.contains_key(&key) {
+            let user = self.users.find_by_email(&key).await.map_err(|e| e.to_string())?;
+            cache.insert(key.clone(), user.map(|u| u.id).filter(|id| *id == user_id));
+        }
+
+        cache[&key].ok_or_else(|| format!("'{}' is not a user you can assign", email))
+    }
+
+    /// Validate a converted compliance row like the create endpoint does
+    async fn prepare_compliance(
+        &self,
+        user_id: Uuid,
+        object: Map<String, Value>,
+        line: usize,
+    ) -> AppResult<Result<PreparedCompliance, Vec<ImportRowError>>> {
+        let dto = match deserialize_row::<CreateComplianceDto>(object, line) {
+            Ok(dto) => dto,
+            Err(problem) => return Ok(Err(vec![problem])),
+        };
+
+        let mut problems = validation_errors(&dto, line);
+        if dto.title.chars().count() > MAX_TITLE_LENGTH {
+            problems.push(row_error(
+                line,
+                Some("title"),
+                format!("Title must be at most {} characters", MAX_TITLE_LENGTH),
+            ));
+        }
+        if !RISK_LEVELS.contains(&dto.risk_level.as_str()) {
+            problems.push(row_error(
+                line,
+                Some("risk_level"),
+                format!("Risk level must be one of: {}", RISK_LEVELS.join(", ")),
+            ));
+        }
+        if !COMPLIANCE_STATUSES.contains(&dto.status.as_str()) {
+            problems.push(row_error(
+                line,
+                Some("status"),
+                format!("Status must be one of: {}", COMPLIANCE_STATUSES.join(", ")),
+            ));
+        }
+
+        let changes = self
+            .metadata
+            .prepare(
+                user_id,
+                MetadataEntityType::ComplianceItem,
+                dto.tag_ids.as_deref(),
+                dto.custom_fields.as_ref(),
+                true,
+            )
+            .await;
+        match changes {
+            Ok(changes) if problems.is_empty() => Ok(Ok(PreparedCompliance { dto, changes })),
+            Ok(_) => Ok(Err(problems)),
+            Err(AppError::Validation(message)) => {
+                problems.push(row_error(line, None, message));
+                Ok(Err(problems))
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Mappable fields of a target, including compliance custom fields
+fn field_infos(target: ImportTarget, definitions: &[CustomFieldDefinition]) -> Vec<ImportFieldInfo> {
+    let builtin = target.fields().iter().map(|f| ImportFieldInfo {
+        name: f.name.to_string(),
+        required: f.required,
+        kind: f.kind,
+    });
+
+    let custom = definitions.iter().map(|d| ImportFieldInfo {
+        name: format!("{}{}", CUSTOM_FIELD_PREFIX, d.name),
+        required: d.required,
+        kind: match CustomFieldType::parse(&d.field_type) {
+            Some(CustomFieldType::Enum) => ImportFieldKind::Choice,
+            Some(CustomFieldType::Date) => ImportFieldKind::Date,
+            _ => ImportFieldKind::Text,
+        },
+    });
+
+    builtin.chain(custom).collect()
+}
+
+/// Conversion used for a built-in field
+fn field_kind(target: ImportTarget, field: &str) -> ImportFieldKind {
+    target
+        .fields()
+        .iter()
+        .find(|f| f.name == field)
+        .map(|f| f.kind)
+        .unwrap_or(ImportFieldKind::Text)
+}
+
+/// Header normalized for matching (`Due Date` → `due_date`)
+fn normalize_header(header: &str) -> String {
+    header
+        .trim()
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Convert a cell to the JSON value the DTO expects
+fn convert(kind: ImportFieldKind, text: &str) -> Result<Value, String> {
+    match kind {
+        ImportFieldKind::Text | ImportFieldKind::Email | ImportFieldKind::TagList => {
+            Ok(Value::String(text.to_string()))
+        }
+        ImportFieldKind::Choice => Ok(Value::String(normalize_header(text))),
+        ImportFieldKind::Date => parse_date(text).map(|date| Value::String(date.to_rfc3339())),
+        ImportFieldKind::Integer => text
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| format!("'{}' is not a whole number", text)),
+        ImportFieldKind::Uuid => Uuid::parse_str(text)
+            .map(|id| Value::String(id.to_string()))
+            .map_err(|_| format!("'{}' is not a valid ID", text)),
+    }
+}
+
+/// Parse `YYYY-MM-DD` (midnight UTC) or an RFC 3339 timestamp
+fn parse_date(text: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).expect("valid time").and_utc());
+    }
+
+    DateTime::parse_from_rfc3339(text)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|_| format!("'{}' is not a date (YYYY-MM-DD)", text))
+}
+
+/// Convert a cell for a custom field; the definition validates it later
+fn custom_field_value(definition: &CustomFieldDefinition, text: &str) -> Result<Value, String> {
+    match CustomFieldType::parse(&definition.field_type) {
+        Some(CustomFieldType::Number) => text
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| format!("'{}' is not a number", text)),
+        Some(CustomFieldType::Date) => {
+            parse_date(text).map(|date| Value::String(date.date_naive().format("%Y-%m-%d").to_string()))
+        }
+        _ => Ok(Value::String(text.to_string())),
+    }
+}
+
+/// Resolve comma-separated tag names (case-insensitive)
+fn tag_ids(tags: &[Tag], text: &str) -> Result<Value, String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            tags.iter()
+                .find(|tag| tag.name.eq_ignore_ascii_case(name))
+                .map(|tag| Value::String(tag.id.to_string()))
+                .ok_or_else(|| format!("Unknown tag '{}'", name))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Value::Array)
+}
+
+/// Errors for required built-in fields without a value
+///
+/// Fields that already failed conversion are not reported again.
+fn missing_fields(
+    target: ImportTarget,
+    object: &Map<String, Value>,
+    reported: &[ImportRowError],
+    line: usize,
+) -> Vec<ImportRowError> {
+    target
+        .fields()
+        .iter()
+        .filter(|f| f.required && !object.contains_key(f.name))
+        .filter(|f| !reported.iter().any(|e| e.field.as_deref() == Some(f.name)))
+        .map(|f| row_error(line, Some(f.name), format!("{} is required", f.name)))
+        .collect()
+}
+
+/// Deserialize a converted row into its creation DTO
+fn deserialize_row<T: DeserializeOwned>(object: Map<String, Value>, line: usize) -> Result<T, ImportRowError> {
+    serde_json::from_value(Value::Object(object)).map_err(|e| row_error(line, None, e.to_string()))
+}
+
+/// Problems found by the DTO's validation rules
+fn validation_errors(dto: &impl Validate, line: usize) -> Vec<ImportRowError> {
+    let Err(errors) = dto.validate() else {
+        return Vec::new();
+    };
+
+    errors
+        .field_errors()
+        .into_iter()
+        .flat_map(|(field, errors)| {
+            errors.iter().map(move |e| {
+                let message = e
+                    .message
+                    .as_ref()
+                    .map(|m| m.to_string())
+                    .unwrap_or_else(|| format!("Invalid {}", field));
+                row_error(line, Some(field), message)
+            })
+        })
+        .collect()
+}
+
+/// Build a row error
+fn row_error(row: usize, field: Option<&str>, message: String) -> ImportRowError {
+    ImportRowError {
+        row,
+        field: field.map(str::to_string),
+        message,
+    }
+}
+
+/// Report for a validated file
+fn report(table: &ImportTable, dry_run: bool, valid_rows: usize, errors: Vec<ImportRowError>) -> ImportReport {
+    ImportReport {
+        dry_run,
+        committed: false,
+        total_rows: table.rows.len(),
+        valid_rows,
+        errors,
+        created_ids: Vec::new(),
+    }
+}
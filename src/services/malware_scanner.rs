@@ -0,0 +1,161 @@
+use std::{sync::Arc, time::Duration};
+
+use axum::async_trait;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    net::{TcpStream, UnixStream},
+};
+use tracing::debug;
+
+use crate::{
+    config::{ClamdAddress, Config},
+    error::{AppError, AppResult},
+};
+
+/// Bytes sent per INSTREAM chunk (below clamd's default StreamMaxLength)
+const INSTREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Outcome of a malware scan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanVerdict {
+    /// Scanning is disabled
+    NotScanned,
+
+    /// No malware found
+    Clean,
+
+    /// Malware found (signature name)
+    Infected(String),
+}
+
+/// Pluggable malware scanning of uploaded files
+#[async_trait]
+pub trait MalwareScanner: Send + Sync {
+    /// Scan file content
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - File content
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the scanner cannot be reached or rejects the file
+    async fn scan(&self, bytes: &[u8]) -> AppResult<ScanVerdict>;
+}
+
+/// Build the scanner selected in the configuration
+///
+/// # Arguments
+///
+/// * `config` - Application configuration
+///
+/// # Returns
+///
+/// clamd scanner, or a scanner that skips files if no clamd address is set
+pub fn scanner_from_config(config: &Config) -> Arc<dyn MalwareScanner> {
+    match &config.clamd_address {
+        Some(address) => Arc::new(ClamdScanner::new(
+            address.clone(),
+            Duration::from_secs(config.clamd_timeout_secs),
+        )),
+        None => Arc::new(DisabledMalwareScanner),
+    }
+}
+
+/// Scans files with clamd using its INSTREAM command
+pub struct ClamdScanner {
+    /// clamd socket
+    address: ClamdAddress,
+
+    /// Limit for connecting, streaming and waiting for the verdict
+    timeout: Duration,
+}
+
+impl ClamdScanner {
+    /// Create a new ClamdScanner
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - clamd TCP or Unix socket
+    /// * `timeout` - Limit of a whole scan
+    ///
+    /// # Returns
+    ///
+    /// New ClamdScanner instance
+    pub fn new(address: ClamdAddress, timeout: Duration) -> Self {
+        Self { address, timeout }
+    }
+
+    /// Stream the content to clamd and return its raw reply
+    async fn instream(&self, bytes: &[u8]) -> std::io::Result<String> {
+        match &self.address {
+            ClamdAddress::Tcp(address) => instream(TcpStream::connect(address).await?, bytes).await,
+            ClamdAddress::Unix(path) => instream(UnixStream::connect(path).await?, bytes).await,
+        }
+    }
+}
+
+#[async_trait]
+impl MalwareScanner for ClamdScanner {
+    async fn scan(&self, bytes: &[u8]) -> AppResult<ScanVerdict> {
+        let reply = tokio::time::timeout(self.timeout, self.instream(bytes))
+            .await
+            .map_err(|_| AppError::Internal(format!("clamd did not answer within {:?}", self.timeout)))?
+            .map_err(|e| AppError::Internal(format!("clamd connection failed: {}", e)))?;
+        debug!("clamd replied {:?} for {} bytes", reply, bytes.len());
+
+        parse_instream_reply(&reply)
+    }
+}
+
+/// Send `zINSTREAM` with the content in length-prefixed chunks
+///
+/// clamd answers once the zero-length chunk ends the stream, or early when
+/// the stream exceeds its size limit; the reply is read in both cases.
+async fn instream<S>(stream: S, bytes: &[u8]) -> std::io::Result<String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut stream = BufReader::new(stream);
+    let sent = async {
+        stream.get_mut().write_all(b"zINSTREAM\0").await?;
+        for chunk in bytes.chunks(INSTREAM_CHUNK_SIZE) {
+            stream.get_mut().write_all(&(chunk.len() as u32).to_be_bytes()).await?;
+            stream.get_mut().write_all(chunk).await?;
+        }
+        stream.get_mut().write_all(&[0; 4]).await?;
+        stream.get_mut().flush().await
+    }
+    .await;
+
+    let mut reply = Vec::new();
+    let read = stream.read_until(b'\0', &mut reply).await;
+    if reply.is_empty() {
+        sent?;
+        read?;
+    }
+
+    Ok(String::from_utf8_lossy(&reply).trim_end_matches('\0').trim().to_string())
+}
+
+/// Interpret a clamd reply (`stream: OK`, `stream: <signature> FOUND` or an error)
+fn parse_instream_reply(reply: &str) -> AppResult<ScanVerdict> {
+    let result = reply.strip_prefix("stream:").map(str::trim);
+    match result {
+        Some("OK") => Ok(ScanVerdict::Clean),
+        Some(found) if found.ends_with(" FOUND") => {
+            Ok(ScanVerdict::Infected(found.trim_end_matches(" FOUND").trim().to_string()))
+        }
+        _ => Err(AppError::Internal(format!("clamd could not scan the file: {}", reply))),
+    }
+}
+
+/// Leaves files unscanned
+pub struct DisabledMalwareScanner;
+
+#[async_trait]
+impl MalwareScanner for DisabledMalwareScanner {
+    async fn scan(&self, _bytes: &[u8]) -> AppResult<ScanVerdict> {
+        Ok(ScanVerdict::NotScanned)
+    }
+}
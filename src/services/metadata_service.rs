@@ -0,0 +1,229 @@
+use std::collections::{BTreeMap, HashMap};
+
+use sqlx::{PgPool, Postgres, Transaction};
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    db::repository::{CustomFieldRepository, TagRepository},
+    error::{AppError, AppResult},
+    models::{ComplianceItem, Document, MetadataEntityType, Tag},
+};
+
+/// Validated tag and custom field changes ready to be written
+#[derive(Debug, Default)]
+pub struct MetadataChanges {
+    /// New tag set (None leaves tags untouched)
+    tag_ids: Option<Vec<Uuid>>,
+
+    /// Custom field values by definition ID (None clears the value)
+    values: Vec<(Uuid, Option<serde_json::Value>)>,
+}
+
+/// Metadata service for tags and custom fields
+///
+/// Validates incoming values against their definitions and attaches stored
+/// metadata to compliance items and documents.
+pub struct MetadataService {
+    /// Tag repository
+    tags: TagRepository,
+
+    /// Custom field repository
+    custom_fields: CustomFieldRepository,
+}
+
+impl MetadataService {
+    /// Create a new MetadataService
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    ///
+    /// # Returns
+    ///
+    /// New MetadataService instance
+    pub fn new(pool: PgPool) -> Self {
+        info!("🏷️ MetadataService started");
+        Self {
+            tags: TagRepository::new(pool.clone()),
+            custom_fields: CustomFieldRepository::new(pool),
+        }
+    }
+
+    /// Validate tag IDs and custom field values before writing an entity
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID (tags and definitions must belong to the user)
+    /// * `entity_type` - Type of the entity being written
+    /// * `tag_ids` - Requested tag set (optional)
+    /// * `custom_fields` - Requested custom field values (optional)
+    /// * `creating` - Whether the entity is being created (enforces required fields)
+    ///
+    /// # Returns
+    ///
+    /// Changes to apply once the entity has been written
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error for unknown tags, unknown fields, mistyped
+    /// values or missing required fields
+    #[instrument(skip(self, custom_fields))]
+    pub async fn prepare(
+        &self,
+        user_id: Uuid,
+        entity_type: MetadataEntityType,
+        tag_ids: Option<&[Uuid]>,
+        custom_fields: Option<&HashMap<String, serde_json::Value>>,
+        creating: bool,
+    ) -> AppResult<MetadataChanges> {
+        let mut changes = MetadataChanges::default();
+
+        if let Some(tag_ids) = tag_ids {
+            let mut unique = tag_ids.to_vec();
+            unique.sort();
+            unique.dedup();
+
+            if self.tags.count_owned(&unique, user_id).await? != unique.len() as i64 {
+                return Err(AppError::Validation("Unknown tag ID".to_string()));
+            }
+            changes.tag_ids = Some(unique);
+        }
+
+        let empty = HashMap::new();
+        let custom_fields = custom_fields.unwrap_or(&empty);
+        if custom_fields.is_empty() && !creating {
+            return Ok(changes);
+        }
+
+        let definitions = self.custom_fields.find_by_user(user_id, Some(entity_type)).await?;
+
+        if let Some(unknown) = custom_fields
+            .keys()
+            .find(|name| !definitions.iter().any(|d| &d.name == *name))
+        {
+            return Err(AppError::Validation(format!("Unknown custom field '{}'", unknown)));
+        }
+
+        for definition in &definitions {
+            let missing = match custom_fields.get(&definition.name) {
+                None => creating,
+                Some(serde_json::Value::Null) => true,
+                Some(_) => false,
+            };
+            if missing && definition.required {
+                return Err(AppError::Validation(format!(
+                    "Custom field '{}' is required",
+                    definition.name
+                )));
+            }
+
+            match custom_fields.get(&definition.name) {
+                Some(serde_json::Value::Null) => changes.values.push((definition.id, None)),
+                Some(value) => {
+                    definition.validate_value(value).map_err(AppError::Validation)?;
+                    changes.values.push((definition.id, Some(value.clone())));
+                }
+                None => {}
+            }
+        }
+
+        Ok(changes)
+    }
+
+    /// Write prepared tag and custom field changes inside an open transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Open transaction
+    /// * `entity_type` - Type of the entity
+    /// * `entity_id` - Entity UUID
+    /// * `changes` - Changes returned by [`MetadataService::prepare`]
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if writes fail
+    pub async fn apply_in(
+        tx: &mut Transaction<'_, Postgres>,
+        entity_type: MetadataEntityType,
+        entity_id: Uuid,
+        changes: &MetadataChanges,
+    ) -> AppResult<()> {
+        if let Some(ref tag_ids) = changes.tag_ids {
+            TagRepository::replace_entity_tags(tx, entity_type, entity_id, tag_ids).await?;
+        }
+
+        for (definition_id, value) in &changes.values {
+            CustomFieldRepository::set_value(tx, *definition_id, entity_id, value.as_ref()).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Attach tags and custom field values to compliance items
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - Compliance items to populate
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if queries fail
+    pub async fn attach_to_compliance_items(&self, items: &mut [ComplianceItem]) -> AppResult<()> {
+        let ids: Vec<Uuid> = items.iter().map(|i| i.id).collect();
+        let (mut tags, mut fields) = self.load(MetadataEntityType::ComplianceItem, &ids).await?;
+
+        for item in items.iter_mut() {
+            item.tags = tags.remove(&item.id).unwrap_or_default();
+            item.custom_fields = fields.remove(&item.id).unwrap_or_default();
+        }
+
+        Ok(())
+    }
+
+    /// Attach tags and custom field values to documents
+    ///
+    /// # Arguments
+    ///
+    /// * `documents` - Documents to populate
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if queries fail
+    pub async fn attach_to_documents(&self, documents: &mut [Document]) -> AppResult<()> {
+        let ids: Vec<Uuid> = documents.iter().map(|d| d.id).collect();
+        let (mut tags, mut fields) = self.load(MetadataEntityType::Document, &ids).await?;
+
+        for document in documents.iter_mut() {
+            document.tags = tags.remove(&document.id).unwrap_or_default();
+            document.custom_fields = fields.remove(&document.id).unwrap_or_default();
+        }
+
+        Ok(())
+    }
+
+    /// Load tags and custom field values grouped by entity ID
+    #[allow(clippy::type_complexity)]
+    async fn load(
+        &self,
+        entity_type: MetadataEntityType,
+        ids: &[Uuid],
+    ) -> AppResult<(HashMap<Uuid, Vec<Tag>>, HashMap<Uuid, BTreeMap<String, serde_json::Value>>)> {
+        let mut tags: HashMap<Uuid, Vec<Tag>> = HashMap::new();
+        let mut fields: HashMap<Uuid, BTreeMap<String, serde_json::Value>> = HashMap::new();
+
+        if ids.is_empty() {
+            return Ok((tags, fields));
+        }
+
+        for entity_tag in self.tags.find_for_entities(entity_type, ids).await? {
+            tags.entry(entity_tag.entity_id).or_default().push(entity_tag.tag);
+        }
+
+        for value in self.custom_fields.find_values(entity_type, ids).await? {
+            fields.entry(value.entity_id).or_default().insert(value.name, value.value.0);
+        }
+
+        Ok((tags, fields))
+    }
+}
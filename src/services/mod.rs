@@ -1,8 +1,51 @@
+pub mod activity_service;
 pub mod ai_service;
 pub mod auth_service;
 pub mod base;
+pub mod calendar_service;
+pub mod comment_service;
 pub mod dashboard_service;
+pub mod document_diff_service;
+pub mod email_transport;
+pub mod import_service;
+pub mod malware_scanner;
+pub mod metadata_service;
+pub mod notification_service;
+pub mod ocr_engine;
+pub mod realtime_service;
+pub mod report_service;
+pub mod risk_control_service;
+pub mod risk_trend_service;
+pub mod storage_backend;
+pub mod storage_service;
+pub mod webhook_service;
 
+pub use activity_service::ActivityService;
 pub use auth_service::AuthService;
 pub use base::BaseService;
-pub use dashboard_service::{ActivityItem, DashboardService, DashboardStats};
+pub use calendar_service::CalendarService;
+pub use comment_service::CommentService;
+pub use dashboard_service::{
+    ActivityItem, DashboardService, DashboardStats, HeatmapAxes, HeatmapCell, RiskHeatmap, RiskItem,
+    RiskPosture, StatsComparison, StatsSummary,
+};
+pub use document_diff_service::DocumentDiffService;
+pub use email_transport::{
+    transport_from_config, DisabledEmailTransport, EmailMessage, EmailTransport, FileEmailTransport,
+    SmtpEmailTransport,
+};
+pub use import_service::ImportService;
+pub use malware_scanner::{scanner_from_config, ClamdScanner, DisabledMalwareScanner, MalwareScanner, ScanVerdict};
+pub use metadata_service::MetadataService;
+pub use notification_service::NotificationService;
+pub use ocr_engine::{ocr_engine_from_config, DisabledOcrEngine, OcrEngine, OcrPage, TesseractOcr};
+pub use realtime_service::{RealtimeHub, RealtimeService};
+pub use report_service::ReportService;
+pub use risk_control_service::RiskControlService;
+pub use risk_trend_service::RiskTrendService;
+pub use storage_backend::{
+    storage_from_config, validate_storage_key, ByteStream, LocalStorageBackend, S3StorageBackend, StorageBackend,
+    StoredObject,
+};
+pub use storage_service::{ensure_scan_passed, StorageService};
+pub use webhook_service::{sign_payload, WebhookService};
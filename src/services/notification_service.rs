@@ -0,0 +1,479 @@
+use std::{sync::Arc, time::Duration as StdDuration};
+
+use chrono::{DateTime, Duration, Utc};
+use lettre::message::Mailbox;
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use tracing::{info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::repository::{ComplianceRepository, NotificationRepository, UserRepository},
+    error::{AppError, AppResult},
+    models::{
+        ActivityEntityType, ComplianceItem, NewNotification, Notification, NotificationKind,
+        NotificationList, NotificationListQuery, NotificationPreferences, RiskScore,
+        UpdateNotificationPreferencesDto,
+    },
+    services::email_transport::{EmailMessage, EmailTransport},
+};
+
+/// Default number of notifications per list
+const DEFAULT_LIST_SIZE: i64 = 50;
+
+/// Maximum number of notifications per list
+const MAX_LIST_SIZE: i64 = 200;
+
+/// Risk levels that raise an alert
+const ALERT_RISK_LEVELS: [&str; 2] = ["high", "critical"];
+
+/// Notification service for in-app and email notifications
+///
+/// Each notification is filtered through the recipient's preferences, stored
+/// for the in-app list and emailed in the background.
+pub struct NotificationService {
+    /// Notification repository
+    notifications: NotificationRepository,
+
+    /// User repository (email addresses)
+    users: UserRepository,
+
+    /// Compliance repository (risk alert context)
+    compliance: ComplianceRepository,
+
+    /// Email transport
+    mailer: Arc<dyn EmailTransport>,
+}
+
+impl NotificationService {
+    /// Create a new NotificationService
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    /// * `mailer` - Email transport
+    ///
+    /// # Returns
+    ///
+    /// New NotificationService instance
+    pub fn new(pool: PgPool, mailer: Arc<dyn EmailTransport>) -> Self {
+        info!("🔔 NotificationService started");
+        Self {
+            notifications: NotificationRepository::new(pool.clone()),
+            users: UserRepository::new(pool.clone()),
+            compliance: ComplianceRepository::new(pool),
+            mailer,
+        }
+    }
+
+    /// Run due-date reminders periodically in the background
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    /// * `mailer` - Email transport
+    /// * `interval` - Time between runs
+    ///
+    /// # Returns
+    ///
+    /// Handle of the background task
+    pub fn spawn_reminder_scheduler(
+        pool: PgPool,
+        mailer: Arc<dyn EmailTransport>,
+        interval: StdDuration,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let service = Self::new(pool, mailer);
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match service.send_due_date_reminders(Utc::now()).await {
+                    Ok(0) => {}
+                    Ok(sent) => info!("Sent {} due-date reminders", sent),
+                    Err(e) => warn!("Due-date reminder run failed: {}", e),
+                }
+            }
+        })
+    }
+
+    /// Get a user's notification preferences
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// Saved preferences, or the defaults
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    #[instrument(skip(self))]
+    pub async fn preferences(&self, user_id: Uuid) -> AppResult<NotificationPreferences> {
+        Ok(self
+            .notifications
+            .find_preferences(user_id)
+            .await?
+            .unwrap_or_else(|| NotificationPreferences::defaults(user_id)))
+    }
+
+    /// Update a user's notification preferences
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `dto` - Changed preferences
+    ///
+    /// # Returns
+    ///
+    /// Updated preferences
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if the update fails
+    #[instrument(skip(self, dto))]
+    pub async fn update_preferences(
+        &self,
+        user_id: Uuid,
+        dto: &UpdateNotificationPreferencesDto,
+    ) -> AppResult<NotificationPreferences> {
+        let mut preferences = self.preferences(user_id).await?;
+
+        if let Some(value) = dto.in_app_enabled {
+            preferences.in_app_enabled = value;
+        }
+        if let Some(value) = dto.email_enabled {
+            preferences.email_enabled = value;
+        }
+        if let Some(value) = dto.due_date_reminders {
+            preferences.due_date_reminders = value;
+        }
+        if let Some(value) = dto.status_changes {
+            preferences.status_changes = value;
+        }
+        if let Some(value) = dto.assignments {
+            preferences.assignments = value;
+        }
+        if let Some(value) = dto.risk_alerts {
+            preferences.risk_alerts = value;
+        }
+        if let Some(days) = &dto.reminder_lead_days {
+            let mut days = days.clone();
+            days.sort_unstable_by(|a, b| b.cmp(a));
+            days.dedup();
+            preferences.reminder_lead_days = days;
+        }
+
+        self.notifications.save_preferences(&preferences).await
+    }
+
+    /// List a user's in-app notifications
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `query` - Unread filter and limit
+    ///
+    /// # Returns
+    ///
+    /// Notifications, newest first, with the unread count
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    #[instrument(skip(self))]
+    pub async fn list(&self, user_id: Uuid, query: &NotificationListQuery) -> AppResult<NotificationList> {
+        let limit = query
+            .limit
+            .filter(|l| (1..=MAX_LIST_SIZE).contains(l))
+            .unwrap_or(DEFAULT_LIST_SIZE);
+
+        Ok(NotificationList {
+            notifications: self.notifications.find_by_user(user_id, query.unread_only, limit).await?,
+            unread_count: self.notifications.count_unread(user_id).await?,
+        })
+    }
+
+    /// Mark a notification as read
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `id` - Notification UUID
+    ///
+    /// # Returns
+    ///
+    /// Updated notification
+    ///
+    /// # Errors
+    ///
+    /// Returns 404 if not found or not owned by user
+    #[instrument(skip(self))]
+    pub async fn mark_read(&self, user_id: Uuid, id: Uuid) -> AppResult<Notification> {
+        self.notifications
+            .mark_read(id, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Notification not found".to_string()))
+    }
+
+    /// Mark all of a user's notifications as read
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// Number of notifications marked
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if update fails
+    #[instrument(skip(self))]
+    pub async fn mark_all_read(&self, user_id: Uuid) -> AppResult<u64> {
+        self.notifications.mark_all_read(user_id).await
+    }
+
+    /// Notify of a newly created compliance item (assignment)
+    ///
+    /// Assignees who cannot open the item are not notified.
+    ///
+    /// # Arguments
+    ///
+    /// * `actor_id` - User who created the item
+    /// * `item` - Created item
+    #[instrument(skip(self, item), fields(item_id = %item.id))]
+    pub async fn compliance_created(&self, actor_id: Uuid, item: &ComplianceItem) {
+        if let Some(assignee_id) = item.assignee_id.filter(|id| *id != actor_id && can_open(*id, item)) {
+            self.deliver(assigned_notification(assignee_id, item)).await;
+        }
+    }
+
+    /// Notify of a changed compliance item (assignment and status)
+    ///
+    /// Owner and assignee are told of status changes made by someone else, as
+    /// long as they can open the item.
+    ///
+    /// # Arguments
+    ///
+    /// * `actor_id` - User who changed the item
+    /// * `before` - Item before the change
+    /// * `after` - Item after the change
+    #[instrument(skip(self, before, after), fields(item_id = %after.id))]
+    pub async fn compliance_updated(&self, actor_id: Uuid, before: &ComplianceItem, after: &ComplianceItem) {
+        if after.assignee_id != before.assignee_id {
+            if let Some(assignee_id) = after.assignee_id.filter(|id| *id != actor_id && can_open(*id, after)) {
+                self.deliver(assigned_notification(assignee_id, after)).await;
+            }
+        }
+
+        if after.status != before.status {
+            for user_id in recipients(after).into_iter().filter(|id| *id != actor_id) {
+                self.deliver(NewNotification {
+                    user_id,
+                    kind: NotificationKind::StatusChanged,
+                    title: format!("Status changed: {}", after.title),
+                    body: format!(
+                        "\"{}\" changed from {} to {}.",
+                        after.title,
+                        before.status.replace('_', " "),
+                        after.status.replace('_', " ")
+                    ),
+                    entity_type: ActivityEntityType::ComplianceItem,
+                    entity_id: after.id,
+                })
+                .await;
+            }
+        }
+    }
+
+    /// Alert owner and assignee of a new high or critical risk score, if they can open the item
+    ///
+    /// # Arguments
+    ///
+    /// * `score` - Recorded risk score
+    #[instrument(skip(self, score), fields(score_id = %score.id))]
+    pub async fn risk_scored(&self, score: &RiskScore) {
+        if !ALERT_RISK_LEVELS.contains(&score.risk_level.as_str()) {
+            return;
+        }
+
+        let item = match self.compliance.find_by_id(score.compliance_item_id, score.user_id).await {
+            Ok(Some(item)) => item,
+            Ok(None) => return,
+            Err(e) => {
+                warn!("Failed to load compliance item for risk alert: {}", e);
+                return;
+            }
+        };
+
+        for user_id in recipients(&item) {
+            self.deliver(NewNotification {
+                user_id,
+                kind: NotificationKind::RiskAlert,
+                title: format!("{} risk: {}", capitalize(&score.risk_level), item.title),
+                body: format!(
+                    "A {} risk ({}, score {}) was recorded for \"{}\".",
+                    score.risk_level, score.risk_category, score.risk_score, item.title
+                ),
+                entity_type: ActivityEntityType::RiskScore,
+                entity_id: score.id,
+            })
+            .await;
+        }
+    }
+
+    /// Send due-date reminders that have come within reach
+    ///
+    /// Each lead time fires once per due date. When several lead times are
+    /// reached at once only the shortest is sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - Reference time
+    ///
+    /// # Returns
+    ///
+    /// Number of reminders sent
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if a query fails
+    #[instrument(skip(self))]
+    pub async fn send_due_date_reminders(&self, now: DateTime<Utc>) -> AppResult<usize> {
+        let mut sent = 0;
+
+        for candidate in self.notifications.find_due_reminder_candidates(now).await? {
+            let reached: Vec<i32> = candidate
+                .reminder_lead_days
+                .iter()
+                .copied()
+                .filter(|days| candidate.due_date <= now + Duration::days(i64::from(*days)))
+                .collect();
+            let Some(&shortest) = reached.iter().min() else { continue };
+
+            let recorded = self
+                .notifications
+                .record_reminders(candidate.compliance_item_id, candidate.user_id, candidate.due_date, &reached)
+                .await?;
+            if !recorded.contains(&shortest) {
+                continue;
+            }
+
+            let due_in = match (candidate.due_date - now).num_hours() {
+                hours if hours < 24 => "today".to_string(),
+                hours if hours < 48 => "tomorrow".to_string(),
+                hours => format!("in {} days", hours / 24),
+            };
+            self.deliver(NewNotification {
+                user_id: candidate.user_id,
+                kind: NotificationKind::DueDateReminder,
+                title: format!("Due {}: {}", due_in, candidate.title),
+                body: format!(
+                    "\"{}\" is due on {}.",
+                    candidate.title,
+                    candidate.due_date.format("%Y-%m-%d %H:%M UTC")
+                ),
+                entity_type: ActivityEntityType::ComplianceItem,
+                entity_id: candidate.compliance_item_id,
+            })
+            .await;
+            sent += 1;
+        }
+
+        Ok(sent)
+    }
+
+    /// Deliver a notification according to the recipient's preferences
+    ///
+    /// Triggers run after the change was committed, so failures are logged
+    /// rather than returned. Emails are sent in the background.
+    async fn deliver(&self, notification: NewNotification) {
+        if let Err(e) = self.try_deliver(notification).await {
+            warn!("Failed to deliver notification: {}", e);
+        }
+    }
+
+    /// Store and email a notification
+    async fn try_deliver(&self, notification: NewNotification) -> AppResult<()> {
+        let preferences = self.preferences(notification.user_id).await?;
+        if !preferences.wants(notification.kind) {
+            return Ok(());
+        }
+
+        if preferences.in_app_enabled {
+            self.notifications.create(&notification).await?;
+        }
+
+        if preferences.email_enabled {
+            let Some(user) = self.users.find_by_id(notification.user_id).await? else {
+                return Ok(());
+            };
+            let address = user
+                .email
+                .parse()
+                .map_err(|e| AppError::Internal(format!("Invalid email address '{}': {}", user.email, e)))?;
+            let message = EmailMessage {
+                to: Mailbox::new(Some(user.full_name.clone()), address),
+                subject: notification.title.clone(),
+                body: format!(
+                    "Hello {},\n\n{}\n\n--\nYou can change which notifications you receive in your ParseGuard settings.\n",
+                    user.full_name, notification.body
+                ),
+            };
+            let mailer = self.mailer.clone();
+            tokio::spawn(async move {
+                if let Err(e) = mailer.send(&message).await {
+                    warn!("Failed to email notification to {}: {}", message.to, e);
+                }
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether a user can open a compliance item
+///
+/// Items are visible to their owner only. Anyone can be named as assignee, so
+/// assignees without access are never notified: the item would be unreadable
+/// to them and its title is chosen by whoever assigned it.
+fn can_open(user_id: Uuid, item: &ComplianceItem) -> bool {
+    user_id == item.user_id
+}
+
+/// Owner and assignee of a compliance item, if they can open it
+fn recipients(item: &ComplianceItem) -> Vec<Uuid> {
+    let mut users = vec![item.user_id];
+    if let Some(assignee_id) = item.assignee_id.filter(|id| *id != item.user_id && can_open(*id, item)) {
+        users.push(assignee_id);
+    }
+    users
+}
+
+/// Notification telling a user they were assigned an item
+fn assigned_notification(assignee_id: Uuid, item: &ComplianceItem) -> NewNotification {
+    let due = item
+        .due_date
+        .map(|due| format!(", due on {}", due.format("%Y-%m-%d")))
+        .unwrap_or_default();
+
+    NewNotification {
+        user_id: assignee_id,
+        kind: NotificationKind::Assigned,
+        title: format!("Assigned to you: {}", item.title),
+        body: format!("You have been assigned the compliance item \"{}\"{}.", item.title, due),
+        entity_type: ActivityEntityType::ComplianceItem,
+        entity_id: item.id,
+    }
+}
+
+/// Uppercase the first letter
+fn capitalize(value: &str) -> String {
+    let mut chars = value.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,263 @@
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::Duration,
+};
+
+use axum::async_trait;
+use tempfile::TempDir;
+use tokio::{io::AsyncWriteExt, process::Command};
+
+use crate::{
+    config::Config,
+    error::{AppError, AppResult},
+};
+
+/// Text recognized on one page
+#[derive(Debug, Clone, PartialEq)]
+pub struct OcrPage {
+    /// Recognized text, lines separated by new lines and paragraphs by blank lines
+    pub text: String,
+
+    /// Mean word confidence (0-100), 0 if nothing was recognized
+    pub confidence: f32,
+}
+
+/// Pluggable optical character recognition
+#[async_trait]
+pub trait OcrEngine: Send + Sync {
+    /// Recognize the text of an image
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - PNG, JPEG or TIFF content
+    ///
+    /// # Returns
+    ///
+    /// One entry per page (multi-page TIFFs have several), empty if OCR is disabled
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the OCR engine fails or times out
+    async fn recognize_image(&self, image: &[u8]) -> AppResult<Vec<OcrPage>>;
+
+    /// Recognize the text of one PDF page
+    ///
+    /// # Arguments
+    ///
+    /// * `pdf` - PDF content
+    /// * `page` - Page number (from 1)
+    ///
+    /// # Returns
+    ///
+    /// The page, empty if OCR is disabled
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the page cannot be rendered or the OCR engine fails
+    async fn recognize_pdf_page(&self, pdf: &[u8], page: u32) -> AppResult<Vec<OcrPage>>;
+}
+
+/// Build the OCR engine selected in the configuration
+///
+/// # Arguments
+///
+/// * `config` - Application configuration
+///
+/// # Returns
+///
+/// Tesseract engine, or an engine that recognizes nothing if no Tesseract path is set
+pub fn ocr_engine_from_config(config: &Config) -> Arc<dyn OcrEngine> {
+    match &config.ocr.tesseract_path {
+        Some(tesseract) => Arc::new(TesseractOcr {
+            tesseract: tesseract.clone(),
+            pdftoppm: config.ocr.pdftoppm_path.clone(),
+            languages: config.ocr.languages.clone(),
+            dpi: config.ocr.dpi,
+            timeout: Duration::from_secs(config.ocr.timeout_secs),
+        }),
+        None => Arc::new(DisabledOcrEngine),
+    }
+}
+
+/// Runs a locally installed Tesseract
+///
+/// Images are piped to `tesseract stdin stdout tsv`; PDF pages are first
+/// rendered to PNG with Poppler's `pdftoppm` in a temporary directory that
+/// is removed when the rendering ends, also when it times out.
+pub struct TesseractOcr {
+    /// Tesseract executable
+    tesseract: PathBuf,
+
+    /// pdftoppm executable
+    pdftoppm: PathBuf,
+
+    /// Languages (`eng+deu`)
+    languages: String,
+
+    /// Resolution PDF pages are rendered at
+    dpi: u32,
+
+    /// Limit of one recognition, rendering included
+    timeout: Duration,
+}
+
+impl TesseractOcr {
+    /// Run Tesseract on an image and parse its TSV output
+    async fn tesseract(&self, image: &[u8]) -> AppResult<Vec<OcrPage>> {
+        let mut child = Command::new(&self.tesseract)
+            .args(["stdin", "stdout", "-l", &self.languages, "tsv"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| AppError::Internal(format!("Tesseract could not be started: {}", e)))?;
+
+        // Write while Tesseract runs, closing stdin at the end so it sees EOF
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let write = async move { stdin.write_all(image).await };
+        let (written, output) = tokio::join!(write, child.wait_with_output());
+        let output = output.map_err(|e| AppError::Internal(format!("Tesseract failed: {}", e)))?;
+        if !output.status.success() {
+            return Err(AppError::Internal(format!(
+                "Tesseract exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        written.map_err(|e| AppError::Internal(format!("Image could not be passed to Tesseract: {}", e)))?;
+
+        Ok(parse_tsv(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Render one PDF page to PNG
+    async fn render_pdf_page(&self, pdf: &[u8], page: u32) -> AppResult<Vec<u8>> {
+        // Dropping the directory removes it, even when the timeout drops this future
+        let dir = TempDir::with_prefix("parseguard-ocr-")?;
+        self.render_pdf_page_in(dir.path(), pdf, page).await
+    }
+
+    async fn render_pdf_page_in(&self, dir: &Path, pdf: &[u8], page: u32) -> AppResult<Vec<u8>> {
+        let input = dir.join("input.pdf");
+        tokio::fs::write(&input, pdf).await?;
+
+        let page = page.to_string();
+        let output = Command::new(&self.pdftoppm)
+            .args(["-f", &page, "-l", &page, "-r", &self.dpi.to_string(), "-png", "-singlefile"])
+            .arg(&input)
+            .arg(dir.join("page"))
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output()
+            .await
+            .map_err(|e| AppError::Internal(format!("pdftoppm could not be started: {}", e)))?;
+        if !output.status.success() {
+            return Err(AppError::Internal(format!(
+                "pdftoppm exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(tokio::fs::read(dir.join("page.png")).await?)
+    }
+
+    /// Fail with a timeout error if the recognition takes too long
+    async fn limited<F>(&self, recognition: F) -> AppResult<Vec<OcrPage>>
+    where
+        F: Future<Output = AppResult<Vec<OcrPage>>>,
+    {
+        tokio::time::timeout(self.timeout, recognition)
+            .await
+            .map_err(|_| AppError::Internal(format!("OCR did not finish within {:?}", self.timeout)))?
+    }
+}
+
+#[async_trait]
+impl OcrEngine for TesseractOcr {
+    async fn recognize_image(&self, image: &[u8]) -> AppResult<Vec<OcrPage>> {
+        self.limited(self.tesseract(image)).await
+    }
+
+    async fn recognize_pdf_page(&self, pdf: &[u8], page: u32) -> AppResult<Vec<OcrPage>> {
+        self.limited(async {
+            let image = self.render_pdf_page(pdf, page).await?;
+            let pages = self.tesseract(&image).await?;
+            Ok(pages.into_iter().take(1).collect())
+        })
+        .await
+    }
+}
+
+/// Rebuild page text and confidence from Tesseract's TSV output
+///
+/// Columns: level, page_num, block_num, par_num, line_num, word_num, left,
+/// top, width, height, conf, text. Level 1 rows start a page, level 5 rows
+/// are words with their confidence (-1 for rows without text).
+fn parse_tsv(tsv: &str) -> Vec<OcrPage> {
+    /// Words of a page keyed by (block, paragraph, line), with their confidences
+    #[derive(Default)]
+    struct Page {
+        lines: BTreeMap<(u32, u32, u32), Vec<String>>,
+        confidences: Vec<f32>,
+    }
+
+    let mut pages: BTreeMap<u32, Page> = BTreeMap::new();
+    for row in tsv.lines().skip(1) {
+        let columns: Vec<&str> = row.split('\t').collect();
+        if columns.len() < 12 {
+            continue;
+        }
+        let number = |i: usize| columns[i].trim().parse::<u32>().unwrap_or_default();
+        let page = pages.entry(number(1)).or_default();
+
+        let text = columns[11].trim();
+        let confidence = columns[10].trim().parse::<f32>().unwrap_or(-1.0);
+        if number(0) != 5 || text.is_empty() || confidence < 0.0 {
+            continue;
+        }
+        page.lines.entry((number(2), number(3), number(4))).or_default().push(text.to_string());
+        page.confidences.push(confidence);
+    }
+
+    pages
+        .into_values()
+        .map(|page| {
+            let mut text = String::new();
+            let mut previous = None;
+            for ((block, paragraph, _), words) in page.lines {
+                match previous {
+                    Some(last) if last == (block, paragraph) => text.push('\n'),
+                    Some(_) => text.push_str("\n\n"),
+                    None => {}
+                }
+                text.push_str(&words.join(" "));
+                previous = Some((block, paragraph));
+            }
+
+            let confidence = match page.confidences.len() {
+                0 => 0.0,
+                n => page.confidences.iter().sum::<f32>() / n as f32,
+            };
+            OcrPage { text, confidence }
+        })
+        .collect()
+}
+
+/// Recognizes nothing (OCR disabled)
+pub struct DisabledOcrEngine;
+
+#[async_trait]
+impl OcrEngine for DisabledOcrEngine {
+    async fn recognize_image(&self, _image: &[u8]) -> AppResult<Vec<OcrPage>> {
+        Ok(Vec::new())
+    }
+
+    async fn recognize_pdf_page(&self, _pdf: &[u8], _page: u32) -> AppResult<Vec<OcrPage>> {
+        Ok(Vec::new())
+    }
+}
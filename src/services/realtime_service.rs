@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use sqlx::{postgres::PgListener, PgPool};
+use tokio::sync::broadcast;
+use tracing::{info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::repository::{RealtimeRepository, REALTIME_CHANNEL},
+    error::AppResult,
+    models::{RealtimeEvent, RealtimeMessage},
+};
+
+/// Events buffered per connected client before it is considered lagging
+const HUB_CAPACITY: usize = 1024;
+
+/// Longest error message sent with analysis progress
+const MAX_MESSAGE_CHARS: usize = 500;
+
+/// Delay before reconnecting a lost listener
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Fan-out of real-time events to the clients connected to this replica
+///
+/// Events from every replica arrive through Postgres `LISTEN` and are
+/// rebroadcast to local WebSocket sessions.
+#[derive(Clone)]
+pub struct RealtimeHub {
+    /// Local broadcast channel
+    sender: broadcast::Sender<RealtimeMessage>,
+}
+
+impl RealtimeHub {
+    /// Start listening for real-time events
+    ///
+    /// Returns once the channel is being listened on, so events published
+    /// afterwards are not missed.
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    ///
+    /// # Returns
+    ///
+    /// Hub shared by all sessions of this replica
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if the listener cannot connect
+    pub async fn start(pool: PgPool) -> AppResult<Self> {
+        let (sender, _) = broadcast::channel(HUB_CAPACITY);
+        let listener = Self::listen(&pool).await?;
+
+        tokio::spawn(Self::forward(pool, listener, sender.clone()));
+
+        info!("📡 RealtimeHub listening on {}", REALTIME_CHANNEL);
+        Ok(Self { sender })
+    }
+
+    /// Receive the events published from now on
+    ///
+    /// # Returns
+    ///
+    /// Receiver of all events; sessions filter by user and topic
+    pub fn subscribe(&self) -> broadcast::Receiver<RealtimeMessage> {
+        self.sender.subscribe()
+    }
+
+    /// Connect a listener to the real-time channel
+    async fn listen(pool: &PgPool) -> AppResult<PgListener> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(REALTIME_CHANNEL).await?;
+        Ok(listener)
+    }
+
+    /// Rebroadcast notifications locally, reconnecting when the listener fails
+    async fn forward(pool: PgPool, mut listener: PgListener, sender: broadcast::Sender<RealtimeMessage>) {
+        loop {
+            match listener.recv().await {
+                Ok(notification) => match serde_json::from_str::<RealtimeMessage>(notification.payload()) {
+                    // Sending only fails when no session is connected
+                    Ok(message) => {
+                        let _ = sender.send(message);
+                    }
+                    Err(e) => warn!("Ignoring malformed realtime event: {}", e),
+                },
+                Err(e) => {
+                    warn!("Realtime listener failed, reconnecting: {}", e);
+                    loop {
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                        match Self::listen(&pool).await {
+                            Ok(reconnected) => {
+                                listener = reconnected;
+                                break;
+                            }
+                            Err(e) => warn!("Realtime listener reconnect failed: {}", e),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Realtime service for publishing events to connected clients
+pub struct RealtimeService {
+    /// Realtime repository
+    repository: RealtimeRepository,
+}
+
+impl RealtimeService {
+    /// Create a new RealtimeService
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    ///
+    /// # Returns
+    ///
+    /// New RealtimeService instance
+    pub fn new(pool: PgPool) -> Self {
+        info!("📡 RealtimeService started");
+        Self {
+            repository: RealtimeRepository::new(pool),
+        }
+    }
+
+    /// Publish an event to the user's clients on every replica
+    ///
+    /// Real-time delivery is best effort, so a failure is logged rather than
+    /// failing the request.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User whose clients receive the event
+    /// * `event` - Event to publish
+    #[instrument(skip(self, event), fields(topic = ?event.topic()))]
+    pub async fn publish(&self, user_id: Uuid, event: RealtimeEvent) {
+        let event = match event {
+            RealtimeEvent::AnalysisProgress { job_id, stage, progress, message } => RealtimeEvent::AnalysisProgress {
+                job_id,
+                stage,
+                progress,
+                message: message.map(|m| m.chars().take(MAX_MESSAGE_CHARS).collect()),
+            },
+            event => event,
+        };
+
+        if let Err(e) = self.repository.notify(&RealtimeMessage { user_id, event }).await {
+            warn!("Failed to publish realtime event: {}", e);
+        }
+    }
+}
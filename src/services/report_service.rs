@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use futures_util::TryStreamExt;
+use sqlx::PgPool;
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    db::repository::{ComplianceRepository, ReportRepository, UserRepository},
+    error::{AppError, AppResult},
+    models::{AuditHeatmapCell, AuditReport, Document, MetadataFilter, RiskMatrix},
+    services::StorageService,
+    utils::report::render_audit_report,
+};
+
+/// MIME type of generated reports
+const PDF_MIME_TYPE: &str = "application/pdf";
+
+/// Report service for auditor-facing exports
+///
+/// Takes a point-in-time snapshot of the compliance register, renders it as
+/// a PDF and stores the file as a regular document.
+pub struct ReportService {
+    /// Report repository
+    reports: ReportRepository,
+
+    /// Compliance repository
+    compliance: ComplianceRepository,
+
+    /// User repository
+    users: UserRepository,
+
+    /// Risk matrix (heatmap layout)
+    matrix: RiskMatrix,
+
+    /// Storage service generated files are stored with
+    files: StorageService,
+}
+
+impl ReportService {
+    /// Create a new ReportService
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    /// * `matrix` - Risk matrix
+    /// * `files` - Storage service generated files are stored with
+    ///
+    /// # Returns
+    ///
+    /// New ReportService instance
+    pub fn new(pool: PgPool, matrix: RiskMatrix, files: StorageService) -> Self {
+        info!("📄 ReportService started");
+        Self {
+            reports: ReportRepository::new(pool.clone()),
+            compliance: ComplianceRepository::new(pool.clone()),
+            users: UserRepository::new(pool),
+            matrix,
+            files,
+        }
+    }
+
+    /// Collect the data of an audit report
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// Snapshot of the register, latest assessments and evidence
+    ///
+    /// # Errors
+    ///
+    /// Returns not found if the user does not exist, database error if queries fail
+    #[instrument(skip(self))]
+    pub async fn audit_report(&self, user_id: Uuid) -> AppResult<AuditReport> {
+        let user = self
+            .users
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        let mut items: Vec<_> = self
+            .compliance
+            .stream_export(user_id, MetadataFilter::default())
+            .try_collect()
+            .await?;
+        items.sort_by_key(|item| item.title.to_lowercase());
+
+        let mut status_counts = BTreeMap::new();
+        for item in &items {
+            *status_counts.entry(item.status.clone()).or_insert(0) += 1;
+        }
+
+        let assessments = self.reports.find_latest_assessments(user_id).await?;
+        let mut heatmap: Vec<AuditHeatmapCell> = self
+            .matrix
+            .cells()
+            .into_iter()
+            .map(|cell| AuditHeatmapCell {
+                likelihood: cell.likelihood,
+                impact: cell.impact,
+                risk_level: cell.level,
+                count: 0,
+            })
+            .collect();
+        let mut unplotted_items = 0;
+        for assessment in &assessments {
+            match heatmap
+                .iter_mut()
+                .find(|cell| Some(cell.likelihood) == assessment.likelihood && Some(cell.impact) == assessment.impact)
+            {
+                Some(cell) => cell.count += 1,
+                None => unplotted_items += 1,
+            }
+        }
+
+        Ok(AuditReport {
+            generated_at: Utc::now(),
+            prepared_for: user.full_name,
+            prepared_for_email: user.email,
+            items,
+            status_counts,
+            matrix_scale: self.matrix.scale,
+            heatmap,
+            unplotted_items,
+            assessments,
+            evidence: self.reports.find_evidence(user_id).await?,
+        })
+    }
+
+    /// Generate an audit report PDF and store it as a document
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// Document holding the PDF
+    ///
+    /// # Errors
+    ///
+    /// Returns internal error if rendering or writing the file fails,
+    /// database error if queries fail
+    #[instrument(skip(self))]
+    pub async fn generate_audit_report(&self, user_id: Uuid) -> AppResult<Document> {
+        let report = self.audit_report(user_id).await?;
+        let (report, pdf) = tokio::task::spawn_blocking(move || render_audit_report(&report).map(|pdf| (report, pdf)))
+            .await
+            .map_err(|e| AppError::Internal(format!("Audit report rendering failed: {}", e)))??;
+
+        let filename = format!("audit-report-{}.pdf", report.generated_at.format("%Y-%m-%d-%H%M%S"));
+        let file_size = pdf.len();
+        let document = self
+            .files
+            .store_document(user_id, filename, PDF_MIME_TYPE.to_string(), pdf, None)
+            .await?
+            .document;
+
+        info!(
+            "Generated audit report {} ({} items, {} bytes) for user {}",
+            document.id,
+            report.items.len(),
+            file_size,
+            user_id
+        );
+
+        Ok(document)
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::BTreeMap;
+
+use sqlx::PgPool;
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    db::repository::{RiskControlRepository, RiskScoreRepository},
+    error::{AppError, AppResult},
+    models::{
+        control_reductions, ControlEffectivenessReport, CreateRiskControlDto, RiskControl,
+        RiskControlResponse, RiskMatrix, UpdateRiskControlDto,
+    },
+};
+
+/// Risk control service for inherent vs residual risk
+///
+/// Links compliance items as mitigating controls to risk assessments and
+/// reports how much risk each control removes.
+pub struct RiskControlService {
+    /// Risk control repository
+    controls: RiskControlRepository,
+
+    /// Risk score repository
+    scores: RiskScoreRepository,
+}
+
+impl RiskControlService {
+    /// Create a new RiskControlService
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    /// * `matrix` - Risk matrix used to classify residual scores
+    ///
+    /// # Returns
+    ///
+    /// New RiskControlService instance
+    pub fn new(pool: PgPool, matrix: RiskMatrix) -> Self {
+        info!("🛡️ RiskControlService started");
+        Self {
+            controls: RiskControlRepository::new(pool.clone(), matrix.clone()),
+            scores: RiskScoreRepository::new(pool, matrix),
+        }
+    }
+
+    /// List the controls mitigating a risk assessment
+    ///
+    /// # Arguments
+    ///
+    /// * `risk_score_id` - Risk score UUID
+    /// * `user_id` - User UUID for authorization
+    ///
+    /// # Returns
+    ///
+    /// Controls with the risk reduction each delivers
+    ///
+    /// # Errors
+    ///
+    /// Returns 404 if the risk score is not found or not owned by the user
+    #[instrument(skip(self))]
+    pub async fn list(&self, risk_score_id: Uuid, user_id: Uuid) -> AppResult<Vec<RiskControlResponse>> {
+        let score = self
+            .scores
+            .find_by_id(risk_score_id, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Risk score not found".to_string()))?;
+
+        let controls = self.controls.find_by_risk_score(risk_score_id, user_id).await?;
+
+        Ok(with_reductions(score.risk_score, controls))
+    }
+
+    /// Link a compliance item as a mitigating control
+    ///
+    /// # Arguments
+    ///
+    /// * `risk_score_id` - Risk score UUID
+    /// * `user_id` - User UUID
+    /// * `dto` - Control data
+    ///
+    /// # Returns
+    ///
+    /// Created control with its risk reduction
+    ///
+    /// # Errors
+    ///
+    /// Returns 404 if the risk score or compliance item is not found,
+    /// validation error if the control is already linked
+    #[instrument(skip(self, dto))]
+    pub async fn create(
+        &self,
+        risk_score_id: Uuid,
+        user_id: Uuid,
+        dto: &CreateRiskControlDto,
+    ) -> AppResult<RiskControlResponse> {
+        let control = self
+            .controls
+            .create(risk_score_id, user_id, dto)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Risk score or compliance item not found".to_string()))?;
+
+        self.find(risk_score_id, control.id, user_id).await
+    }
+
+    /// Update a control's effectiveness or notes
+    ///
+    /// # Arguments
+    ///
+    /// * `risk_score_id` - Risk score UUID
+    /// * `control_id` - Control UUID
+    /// * `user_id` - User UUID for authorization
+    /// * `dto` - Update data
+    ///
+    /// # Returns
+    ///
+    /// Updated control with its risk reduction
+    ///
+    /// # Errors
+    ///
+    /// Returns 404 if the control is not found
+    #[instrument(skip(self, dto))]
+    pub async fn update(
+        &self,
+        risk_score_id: Uuid,
+        control_id: Uuid,
+        user_id: Uuid,
+        dto: &UpdateRiskControlDto,
+    ) -> AppResult<RiskControlResponse> {
+        self.controls
+            .update(control_id, risk_score_id, user_id, dto)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Risk control not found".to_string()))?;
+
+        self.find(risk_score_id, control_id, user_id).await
+    }
+
+    /// Unlink a control from a risk assessment
+    ///
+    /// # Arguments
+    ///
+    /// * `risk_score_id` - Risk score UUID
+    /// * `control_id` - Control UUID
+    /// * `user_id` - User UUID for authorization
+    ///
+    /// # Errors
+    ///
+    /// Returns 404 if the control is not found
+    #[instrument(skip(self))]
+    pub async fn delete(&self, risk_score_id: Uuid, control_id: Uuid, user_id: Uuid) -> AppResult<()> {
+        if self.controls.delete(control_id, risk_score_id, user_id).await? {
+            Ok(())
+        } else {
+            Err(AppError::NotFound("Risk control not found".to_string()))
+        }
+    }
+
+    /// Report the risk reduction delivered by each control across all assessments
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// One entry per control compliance item, largest total reduction first
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if queries fail
+    #[instrument(skip(self))]
+    pub async fn effectiveness_report(&self, user_id: Uuid) -> AppResult<Vec<ControlEffectivenessReport>> {
+        let mut by_risk: BTreeMap<Uuid, (i32, Vec<RiskControl>)> = BTreeMap::new();
+        for row in self.controls.find_by_user(user_id).await? {
+            by_risk
+                .entry(row.control.risk_score_id)
+                .or_insert_with(|| (row.inherent_score, Vec::new()))
+                .1
+                .push(row.control);
+        }
+
+        let mut reports: BTreeMap<Uuid, (ControlEffectivenessReport, i64)> = BTreeMap::new();
+        for (inherent, controls) in by_risk.into_values() {
+            for response in with_reductions(inherent, controls) {
+                let control = response.control;
+                let (report, effectiveness_sum) = reports
+                    .entry(control.compliance_item_id)
+                    .or_insert_with(|| {
+                        (
+                            ControlEffectivenessReport {
+                                compliance_item_id: control.compliance_item_id,
+                                control_title: control.control_title.clone(),
+                                risks_mitigated: 0,
+                                average_effectiveness: 0.0,
+                                total_risk_reduction: 0,
+                            },
+                            0,
+                        )
+                    });
+                report.risks_mitigated += 1;
+                report.total_risk_reduction += i64::from(response.risk_reduction);
+                *effectiveness_sum += i64::from(control.effectiveness);
+            }
+        }
+
+        let mut reports: Vec<ControlEffectivenessReport> = reports
+            .into_values()
+            .map(|(mut report, effectiveness_sum)| {
+                report.average_effectiveness = effectiveness_sum as f64 / report.risks_mitigated as f64;
+                report
+            })
+            .collect();
+        reports.sort_by_key(|r| std::cmp::Reverse(r.total_risk_reduction));
+
+        Ok(reports)
+    }
+
+    /// Find one control of an assessment with its risk reduction
+    async fn find(&self, risk_score_id: Uuid, control_id: Uuid, user_id: Uuid) -> AppResult<RiskControlResponse> {
+        self.list(risk_score_id, user_id)
+            .await?
+            .into_iter()
+            .find(|c| c.control.id == control_id)
+            .ok_or_else(|| AppError::NotFound("Risk control not found".to_string()))
+    }
+}
+
+/// Pair each control with the reduction it delivers on an assessment
+fn with_reductions(inherent: i32, controls: Vec<RiskControl>) -> Vec<RiskControlResponse> {
+    let effectiveness: Vec<i32> = controls.iter().map(|c| c.effectiveness).collect();
+
+    controls
+        .into_iter()
+        .zip(control_reductions(inherent, &effectiveness))
+        .map(|(control, risk_reduction)| RiskControlResponse { control, risk_reduction })
+        .collect()
+}
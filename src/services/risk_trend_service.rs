@@ -0,0 +1,114 @@
+use sqlx::PgPool;
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    db::repository::{ComplianceRepository, RiskScoreRepository},
+    error::{AppError, AppResult},
+    models::{CategoryTrend, RiskMatrix, RiskTrend, RiskTrendQuery, DEFAULT_JUMP_THRESHOLD},
+};
+
+/// Risk trend service for risk score history
+///
+/// Buckets assessments over time and flags significant jumps between
+/// consecutive assessments.
+pub struct RiskTrendService {
+    /// Risk score repository
+    scores: RiskScoreRepository,
+
+    /// Compliance repository (ownership checks)
+    compliance: ComplianceRepository,
+}
+
+impl RiskTrendService {
+    /// Create a new RiskTrendService
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    /// * `matrix` - Risk matrix
+    ///
+    /// # Returns
+    ///
+    /// New RiskTrendService instance
+    pub fn new(pool: PgPool, matrix: RiskMatrix) -> Self {
+        info!("📈 RiskTrendService started");
+        Self {
+            scores: RiskScoreRepository::new(pool.clone(), matrix),
+            compliance: ComplianceRepository::new(pool),
+        }
+    }
+
+    /// Build the risk trend of a user, optionally for one compliance item
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `compliance_item_id` - Compliance item UUID (None for all items)
+    /// * `query` - Interval, date range and jump threshold
+    ///
+    /// # Returns
+    ///
+    /// Overall and per-category buckets with flagged jumps
+    ///
+    /// # Errors
+    ///
+    /// Returns 404 if the compliance item is not found, validation error for
+    /// an empty date range
+    #[instrument(skip(self))]
+    pub async fn trend(
+        &self,
+        user_id: Uuid,
+        compliance_item_id: Option<Uuid>,
+        query: &RiskTrendQuery,
+    ) -> AppResult<RiskTrend> {
+        if let Some(id) = compliance_item_id {
+            self.compliance
+                .find_by_id(id, user_id)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Compliance item not found".to_string()))?;
+        }
+
+        if let (Some(from), Some(to)) = (query.from, query.to) {
+            if from >= to {
+                return Err(AppError::Validation("'from' must be before 'to'".to_string()));
+            }
+        }
+
+        let interval = query.interval.unwrap_or_default();
+        let jump_threshold = query.jump_threshold.unwrap_or(DEFAULT_JUMP_THRESHOLD);
+
+        let buckets = self
+            .scores
+            .find_trend_buckets(user_id, compliance_item_id, interval, query.from, query.to)
+            .await?;
+
+        let mut categories: Vec<CategoryTrend> = Vec::new();
+        for row in self
+            .scores
+            .find_category_trend_buckets(user_id, compliance_item_id, interval, query.from, query.to)
+            .await?
+        {
+            match categories.last_mut() {
+                Some(trend) if trend.risk_category == row.risk_category => trend.buckets.push(row.bucket),
+                _ => categories.push(CategoryTrend {
+                    risk_category: row.risk_category,
+                    buckets: vec![row.bucket],
+                }),
+            }
+        }
+
+        let jumps = self
+            .scores
+            .find_jumps(user_id, compliance_item_id, jump_threshold, query.from, query.to)
+            .await?;
+
+        Ok(RiskTrend {
+            interval,
+            jump_threshold,
+            buckets,
+            categories,
+            jumps,
+        })
+    }
+}
@@ -0,0 +1,729 @@
+use std::{
+    io::SeekFrom,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use futures_util::{stream::BoxStream, StreamExt};
+use quick_xml::events::Event;
+use reqwest::{Method, StatusCode, Url};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tracing::info;
+
+use crate::{
+    config::{Config, StorageBackendKind, StorageConfig},
+    error::{AppError, AppResult},
+    utils::{
+        download::ByteRange,
+        sigv4::{self, SignableRequest, SigningCredentials},
+    },
+};
+
+/// Stream of object chunks
+pub type ByteStream = BoxStream<'static, AppResult<Vec<u8>>>;
+
+/// Bytes read per streamed chunk from local files
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Longest storage key accepted (S3 limit in bytes)
+const MAX_KEY_LENGTH: usize = 1024;
+
+/// Stored object as listed by a backend
+#[derive(Debug, Clone)]
+pub struct StoredObject {
+    /// Storage key
+    pub key: String,
+
+    /// Size in bytes
+    pub size: u64,
+
+    /// Last modification time, if the backend reports it
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+/// Pluggable storage of document files
+///
+/// Objects are addressed by backend-neutral keys such as `<uuid>.pdf`;
+/// [`validate_storage_key`] defines which keys are valid.
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    /// Store an object, replacing any object with the same key
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Storage key
+    /// * `bytes` - Object content
+    /// * `content_type` - MIME type of the content
+    ///
+    /// # Returns
+    ///
+    /// Number of bytes stored
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error for an invalid key, internal error if the write fails
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> AppResult<u64>;
+
+    /// Read a whole object
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Storage key
+    ///
+    /// # Returns
+    ///
+    /// Object content
+    ///
+    /// # Errors
+    ///
+    /// Returns not found if the object does not exist, internal error if the read fails
+    async fn get(&self, key: &str) -> AppResult<Vec<u8>>;
+
+    /// Look up an object's size and modification time
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Storage key
+    ///
+    /// # Returns
+    ///
+    /// Object details, None if it does not exist
+    ///
+    /// # Errors
+    ///
+    /// Returns internal error if the backend cannot be reached
+    async fn head(&self, key: &str) -> AppResult<Option<StoredObject>>;
+
+    /// Stream a byte range of an object in chunks
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Storage key
+    /// * `range` - Bytes to read
+    ///
+    /// # Returns
+    ///
+    /// Stream of chunks
+    ///
+    /// # Errors
+    ///
+    /// Returns not found if the object does not exist, internal error if the read fails
+    async fn stream(&self, key: &str, range: ByteRange) -> AppResult<ByteStream>;
+
+    /// Delete an object (deleting a missing object is not an error)
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Storage key
+    ///
+    /// # Errors
+    ///
+    /// Returns internal error if the deletion fails
+    async fn delete(&self, key: &str) -> AppResult<()>;
+
+    /// List every stored object
+    ///
+    /// # Returns
+    ///
+    /// All objects of the backend
+    ///
+    /// # Errors
+    ///
+    /// Returns internal error if the listing fails
+    async fn list(&self) -> AppResult<Vec<StoredObject>>;
+
+    /// Create a time-limited URL that downloads an object without credentials
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Storage key
+    /// * `expires_in` - Lifetime of the URL
+    /// * `content_disposition` - `Content-Disposition` the download should carry
+    ///
+    /// # Returns
+    ///
+    /// Presigned URL, None if the backend can only be read through the API
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error for an invalid key
+    async fn presign(&self, key: &str, expires_in: Duration, content_disposition: &str) -> AppResult<Option<String>>;
+}
+
+/// Build the storage backend selected in the configuration
+///
+/// # Arguments
+///
+/// * `config` - Application configuration
+///
+/// # Returns
+///
+/// Shared storage backend
+///
+/// # Errors
+///
+/// Returns error for an invalid S3 endpoint
+pub fn storage_from_config(config: &Config) -> AppResult<Arc<dyn StorageBackend>> {
+    let storage: Arc<dyn StorageBackend> = match config.storage.backend {
+        StorageBackendKind::Local => Arc::new(LocalStorageBackend::new(&config.upload_dir)),
+        StorageBackendKind::S3 => Arc::new(S3StorageBackend::new(&config.storage)?),
+    };
+
+    Ok(storage)
+}
+
+/// Check that a storage key is a relative, normalized path
+///
+/// Keys use `/` as separator and may not contain empty, `.` or `..`
+/// segments, so a key can never escape the local upload directory.
+///
+/// # Arguments
+///
+/// * `key` - Storage key
+///
+/// # Errors
+///
+/// Returns validation error if the key is not allowed
+pub fn validate_storage_key(key: &str) -> AppResult<()> {
+    let valid = !key.is_empty()
+        && key.len() <= MAX_KEY_LENGTH
+        && !key.contains('\\')
+        && !key.chars().any(char::is_control)
+        && key.split('/').all(|segment| !segment.is_empty() && segment != "." && segment != "..");
+
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!("Invalid storage key '{}'", key)))
+    }
+}
+
+/// Error of a read from a missing object
+fn object_not_found() -> AppError {
+    AppError::NotFound("Document content not found".to_string())
+}
+
+/// Stores objects as files below a local directory
+pub struct LocalStorageBackend {
+    /// Root directory
+    root: PathBuf,
+}
+
+impl LocalStorageBackend {
+    /// Create a new LocalStorageBackend
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - Directory holding the files
+    ///
+    /// # Returns
+    ///
+    /// New LocalStorageBackend instance
+    pub fn new(root: &str) -> Self {
+        info!("🗄️ Local storage backend using {}", root);
+        Self { root: PathBuf::from(root) }
+    }
+
+    /// Path of the file holding an object
+    fn path(&self, key: &str) -> AppResult<PathBuf> {
+        validate_storage_key(key)?;
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl StorageBackend for LocalStorageBackend {
+    async fn put(&self, key: &str, bytes: Vec<u8>, _content_type: &str) -> AppResult<u64> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to create upload directory: {}", e)))?;
+        }
+
+        // Write next to the target and rename, so readers never see a partial file
+        let partial = path.with_file_name(format!(
+            ".{}.partial",
+            path.file_name().and_then(|name| name.to_str()).unwrap_or("object")
+        ));
+        tokio::fs::write(&partial, &bytes)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to save file: {}", e)))?;
+        tokio::fs::rename(&partial, &path)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to save file: {}", e)))?;
+
+        Ok(bytes.len() as u64)
+    }
+
+    async fn get(&self, key: &str) -> AppResult<Vec<u8>> {
+        let path = self.path(key)?;
+        tokio::fs::read(&path).await.map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => object_not_found(),
+            _ => AppError::Internal(format!("Failed to read file: {}", e)),
+        })
+    }
+
+    async fn head(&self, key: &str) -> AppResult<Option<StoredObject>> {
+        let Ok(path) = self.path(key) else {
+            return Ok(None);
+        };
+        match tokio::fs::metadata(&path).await {
+            Ok(metadata) if metadata.is_file() => Ok(Some(StoredObject {
+                key: key.to_string(),
+                size: metadata.len(),
+                last_modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+            })),
+            Ok(_) => Ok(None),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn stream(&self, key: &str, range: ByteRange) -> AppResult<ByteStream> {
+        let path = self.path(key)?;
+        let mut file = tokio::fs::File::open(&path).await.map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => object_not_found(),
+            _ => AppError::Internal(format!("Failed to read file: {}", e)),
+        })?;
+        file.seek(SeekFrom::Start(range.start)).await?;
+
+        let stream = async_stream::try_stream! {
+            let mut remaining = range.length();
+            while remaining > 0 {
+                let mut chunk = vec![0; CHUNK_SIZE.min(remaining as usize)];
+                let read = file.read(&mut chunk).await?;
+                if read == 0 {
+                    break;
+                }
+                chunk.truncate(read);
+                remaining -= read as u64;
+                yield chunk;
+            }
+        };
+
+        Ok(stream.boxed())
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        let path = self.path(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(AppError::Internal(format!("Failed to delete file: {}", e))),
+        }
+    }
+
+    async fn list(&self) -> AppResult<Vec<StoredObject>> {
+        let mut objects = Vec::new();
+        if !tokio::fs::try_exists(&self.root).await? {
+            return Ok(objects);
+        }
+
+        let mut directories = vec![self.root.clone()];
+        while let Some(directory) = directories.pop() {
+            let mut entries = tokio::fs::read_dir(&directory).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let metadata = entry.metadata().await?;
+                let path = entry.path();
+                if metadata.is_dir() {
+                    directories.push(path);
+                } else if metadata.is_file() {
+                    if let Some(key) = local_key(&self.root, &path) {
+                        objects.push(StoredObject {
+                            key,
+                            size: metadata.len(),
+                            last_modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(objects)
+    }
+
+    async fn presign(&self, key: &str, _expires_in: Duration, _content_disposition: &str) -> AppResult<Option<String>> {
+        validate_storage_key(key)?;
+        Ok(None)
+    }
+}
+
+/// Storage key of a file below the local root
+fn local_key(root: &Path, path: &Path) -> Option<String> {
+    let segments: Option<Vec<&str>> = path
+        .strip_prefix(root)
+        .ok()?
+        .components()
+        .map(|component| match component {
+            Component::Normal(segment) => segment.to_str(),
+            _ => None,
+        })
+        .collect();
+
+    Some(segments?.join("/"))
+}
+
+/// Stores objects in an S3-compatible bucket (AWS S3, MinIO, ...)
+///
+/// Requests are signed with AWS Signature Version 4.
+pub struct S3StorageBackend {
+    /// HTTP client
+    client: reqwest::Client,
+
+    /// Endpoint URL
+    endpoint: Url,
+
+    /// Bucket name
+    bucket: String,
+
+    /// Address the bucket in the path instead of the host name
+    path_style: bool,
+
+    /// Signing credentials
+    credentials: SigningCredentials,
+}
+
+impl S3StorageBackend {
+    /// Create a new S3StorageBackend
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Storage configuration
+    ///
+    /// # Returns
+    ///
+    /// New S3StorageBackend instance
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the endpoint is not a valid URL
+    pub fn new(config: &StorageConfig) -> AppResult<Self> {
+        let endpoint = Url::parse(&config.s3_endpoint)
+            .ok()
+            .filter(|url| url.host_str().is_some())
+            .ok_or_else(|| AppError::Internal(format!("Invalid S3 endpoint '{}'", config.s3_endpoint)))?;
+
+        info!("🗄️ S3 storage backend using bucket {} at {}", config.s3_bucket, endpoint);
+        Ok(Self {
+            client: reqwest::Client::new(),
+            endpoint,
+            bucket: config.s3_bucket.clone(),
+            path_style: config.s3_path_style,
+            credentials: SigningCredentials {
+                access_key_id: config.s3_access_key_id.clone(),
+                secret_access_key: config.s3_secret_access_key.clone(),
+                region: config.s3_region.clone(),
+            },
+        })
+    }
+
+    /// Host header value of bucket requests
+    fn host(&self) -> String {
+        let host = self.endpoint.host_str().unwrap_or_default();
+        let host = if self.path_style {
+            host.to_string()
+        } else {
+            format!("{}.{}", self.bucket, host)
+        };
+
+        match self.endpoint.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host,
+        }
+    }
+
+    /// URI-encoded path of an object (or of the bucket for an empty key)
+    fn path(&self, key: &str) -> String {
+        let base = self.endpoint.path().trim_end_matches('/');
+        if self.path_style {
+            format!("{}/{}/{}", base, uri_encode_path(&self.bucket), uri_encode_path(key))
+        } else {
+            format!("{}/{}", base, uri_encode_path(key))
+        }
+    }
+
+    /// Full URL of a host, path and encoded query
+    fn url(&self, host: &str, path: &str, query: &str) -> String {
+        let mut url = format!("{}://{}{}", self.endpoint.scheme(), host, path);
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(query);
+        }
+        url
+    }
+
+    /// Send a signed request
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(String, String)],
+        extra_headers: &[(String, String)],
+        body: Option<Vec<u8>>,
+    ) -> AppResult<reqwest::Response> {
+        let now = Utc::now();
+        let host = self.host();
+        let path = self.path(key);
+        let payload_hash = sigv4::sha256_hex(body.as_deref().unwrap_or_default());
+
+        let mut headers = vec![
+            ("host".to_string(), host.clone()),
+            ("x-amz-content-sha256".to_string(), payload_hash.clone()),
+            ("x-amz-date".to_string(), sigv4::amz_date(now)),
+        ];
+        headers.extend_from_slice(extra_headers);
+
+        let authorization = sigv4::authorization_header(
+            &self.credentials,
+            &SignableRequest {
+                method: method.as_str(),
+                path: &path,
+                query,
+                headers: &headers,
+                payload_hash: &payload_hash,
+            },
+            now,
+        );
+
+        let url = self.url(&host, &path, &sigv4::canonical_query(query));
+        let mut request = self.client.request(method.clone(), &url).header("authorization", authorization);
+        for (name, value) in headers.iter().filter(|(name, _)| name != "host") {
+            request = request.header(name, value);
+        }
+        if let Some(body) = body {
+            request = request.body(body);
+        }
+
+        request
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("S3 {} {} failed: {}", method, key, e)))
+    }
+}
+
+/// Error of an unexpected S3 response
+async fn s3_error(operation: &str, key: &str, response: reqwest::Response) -> AppError {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    let code = xml_text(&body, "Code").unwrap_or_default();
+    AppError::Internal(format!("S3 {} {} returned {} {}", operation, key, status, code).trim_end().to_string())
+}
+
+#[async_trait]
+impl StorageBackend for S3StorageBackend {
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> AppResult<u64> {
+        validate_storage_key(key)?;
+        let size = bytes.len() as u64;
+        let headers = [("content-type".to_string(), content_type.to_string())];
+        let response = self.send(Method::PUT, key, &[], &headers, Some(bytes)).await?;
+        if !response.status().is_success() {
+            return Err(s3_error("PUT", key, response).await);
+        }
+
+        Ok(size)
+    }
+
+    async fn get(&self, key: &str) -> AppResult<Vec<u8>> {
+        validate_storage_key(key)?;
+        let response = self.send(Method::GET, key, &[], &[], None).await?;
+        match response.status() {
+            status if status.is_success() => Ok(response
+                .bytes()
+                .await
+                .map_err(|e| AppError::Internal(format!("S3 GET {} failed: {}", key, e)))?
+                .to_vec()),
+            StatusCode::NOT_FOUND => Err(object_not_found()),
+            _ => Err(s3_error("GET", key, response).await),
+        }
+    }
+
+    async fn head(&self, key: &str) -> AppResult<Option<StoredObject>> {
+        if validate_storage_key(key).is_err() {
+            return Ok(None);
+        }
+        let response = self.send(Method::HEAD, key, &[], &[], None).await?;
+        match response.status() {
+            status if status.is_success() => {
+                let header = |name: &str| response.headers().get(name).and_then(|v| v.to_str().ok());
+                Ok(Some(StoredObject {
+                    key: key.to_string(),
+                    size: header("content-length").and_then(|v| v.parse().ok()).unwrap_or(0),
+                    last_modified: header("last-modified")
+                        .and_then(|v| DateTime::parse_from_rfc2822(v).ok())
+                        .map(|v| v.with_timezone(&Utc)),
+                }))
+            }
+            StatusCode::NOT_FOUND => Ok(None),
+            _ => Err(s3_error("HEAD", key, response).await),
+        }
+    }
+
+    async fn stream(&self, key: &str, range: ByteRange) -> AppResult<ByteStream> {
+        validate_storage_key(key)?;
+        let headers = [("range".to_string(), format!("bytes={}-{}", range.start, range.end))];
+        let mut response = self.send(Method::GET, key, &[], &headers, None).await?;
+        match response.status() {
+            status if status.is_success() => {}
+            StatusCode::NOT_FOUND => return Err(object_not_found()),
+            _ => return Err(s3_error("GET", key, response).await),
+        }
+
+        let key = key.to_string();
+        let stream = async_stream::try_stream! {
+            while let Some(chunk) = response
+                .chunk()
+                .await
+                .map_err(|e| AppError::Internal(format!("S3 GET {} failed: {}", key, e)))?
+            {
+                yield chunk.to_vec();
+            }
+        };
+
+        Ok(stream.boxed())
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        validate_storage_key(key)?;
+        let response = self.send(Method::DELETE, key, &[], &[], None).await?;
+        if response.status().is_success() || response.status() == StatusCode::NOT_FOUND {
+            Ok(())
+        } else {
+            Err(s3_error("DELETE", key, response).await)
+        }
+    }
+
+    async fn list(&self) -> AppResult<Vec<StoredObject>> {
+        let mut objects = Vec::new();
+        let mut continuation_token: Option<String> = None;
+
+        loop {
+            let mut query = vec![("list-type".to_string(), "2".to_string())];
+            if let Some(token) = continuation_token.take() {
+                query.push(("continuation-token".to_string(), token));
+            }
+
+            let response = self.send(Method::GET, "", &query, &[], None).await?;
+            if !response.status().is_success() {
+                return Err(s3_error("LIST", &self.bucket, response).await);
+            }
+            let body = response
+                .text()
+                .await
+                .map_err(|e| AppError::Internal(format!("S3 LIST {} failed: {}", self.bucket, e)))?;
+
+            let page = parse_list_objects(&body)?;
+            objects.extend(page.objects);
+            match page.next_continuation_token {
+                Some(token) if page.truncated => continuation_token = Some(token),
+                _ => break,
+            }
+        }
+
+        Ok(objects)
+    }
+
+    async fn presign(&self, key: &str, expires_in: Duration, content_disposition: &str) -> AppResult<Option<String>> {
+        validate_storage_key(key)?;
+        let host = self.host();
+        let path = self.path(key);
+        let query = [("response-content-disposition".to_string(), content_disposition.to_string())];
+        let query = sigv4::presigned_query(
+            &self.credentials,
+            "GET",
+            &host,
+            &path,
+            &query,
+            expires_in.as_secs(),
+            Utc::now(),
+        );
+
+        Ok(Some(self.url(&host, &path, &query)))
+    }
+}
+
+/// URI-encode an object path, keeping `/` separators
+fn uri_encode_path(path: &str) -> String {
+    sigv4::uri_encode(path, true)
+}
+
+/// One page of a `ListObjectsV2` response
+struct ListObjectsPage {
+    /// Objects of the page
+    objects: Vec<StoredObject>,
+
+    /// Whether more pages follow
+    truncated: bool,
+
+    /// Token of the next page
+    next_continuation_token: Option<String>,
+}
+
+/// Parse a `ListObjectsV2` XML response
+fn parse_list_objects(xml: &str) -> AppResult<ListObjectsPage> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut page = ListObjectsPage {
+        objects: Vec::new(),
+        truncated: false,
+        next_continuation_token: None,
+    };
+    let mut path: Vec<String> = Vec::new();
+    let mut current: Option<StoredObject> = None;
+
+    loop {
+        match reader
+            .read_event()
+            .map_err(|e| AppError::Internal(format!("Invalid S3 listing: {}", e)))?
+        {
+            Event::Start(start) => {
+                let name = String::from_utf8_lossy(start.local_name().as_ref()).to_string();
+                if name == "Contents" {
+                    current = Some(StoredObject {
+                        key: String::new(),
+                        size: 0,
+                        last_modified: None,
+                    });
+                }
+                path.push(name);
+            }
+            Event::End(_) => {
+                let closed = path.pop();
+                if closed.as_deref() == Some("Contents") {
+                    page.objects.extend(current.take());
+                }
+            }
+            Event::Text(text) => {
+                let value = text
+                    .unescape()
+                    .map_err(|e| AppError::Internal(format!("Invalid S3 listing: {}", e)))?
+                    .to_string();
+                match (path.last().map(String::as_str), current.as_mut()) {
+                    (Some("Key"), Some(object)) => object.key = value,
+                    (Some("Size"), Some(object)) => object.size = value.parse().unwrap_or(0),
+                    (Some("LastModified"), Some(object)) => {
+                        object.last_modified = DateTime::parse_from_rfc3339(&value)
+                            .ok()
+                            .map(|v| v.with_timezone(&Utc));
+                    }
+                    (Some("IsTruncated"), None) => page.truncated = value == "true",
+                    (Some("NextContinuationToken"), None) => page.next_continuation_token = Some(value),
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(page)
+}
+
+/// Text of the first element with the given name in an XML document
+fn xml_text(xml: &str, element: &str) -> Option<String> {
+    let start = xml.find(&format!("<{}>", element))? + element.len() + 2;
+    let end = start + xml[start..].find(&format!("</{}>", element))?;
+    Some(xml[start..end].to_string())
+}
@@ -0,0 +1,1011 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use sqlx::{types::Json, PgPool, Postgres, Transaction};
+use tokio::task::JoinHandle;
+use tracing::{info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    db::repository::{BlobRepository, DocumentRepository, DocumentVersionRepository},
+    error::{AppError, AppResult},
+    models::{
+        Blob, CreateDocumentDto, Document, DocumentUploadResponse, DocumentVersion, KeyRotationReport, OcrPageConfidence,
+        OrphanSweepReport, ScanStatus,
+    },
+    services::{ocr_engine_from_config, scanner_from_config, MalwareScanner, OcrEngine, ScanVerdict, StorageBackend},
+    utils::{
+        envelope::{MasterKeyRing, WrappedDataKey},
+        pdf_text::pdf_page_texts,
+        pii,
+        sigv4::sha256_hex,
+    },
+};
+
+/// Image types whose text is recognized with OCR
+const OCR_IMAGE_MIME_TYPES: &[&str] = &["image/png", "image/jpeg", "image/tiff"];
+
+/// Storage service for document files
+///
+/// Scans uploaded files for malware, extracts the text of PDFs and images
+/// (with OCR where there is no text layer), stores clean files once per
+/// distinct content (keyed by SHA-256) and everything else in quarantine, encrypts
+/// stored files with per-file data keys when master keys are configured,
+/// removes stored objects once no document references them and reconciles
+/// the storage backend against the documents table.
+pub struct StorageService {
+    /// Database connection pool (transactions)
+    pool: PgPool,
+
+    /// Document repository
+    documents: DocumentRepository,
+
+    /// Document version repository
+    versions: DocumentVersionRepository,
+
+    /// Blob repository
+    blobs: BlobRepository,
+
+    /// Storage backend
+    storage: Arc<dyn StorageBackend>,
+
+    /// Malware scanner for uploads
+    scanner: Arc<dyn MalwareScanner>,
+
+    /// Whether a malware scanner is configured
+    scanning_enabled: bool,
+
+    /// OCR engine for scanned pages and images
+    ocr: Arc<dyn OcrEngine>,
+
+    /// Page confidence below which a document is flagged as a low-quality scan
+    ocr_min_confidence: f32,
+
+    /// Most pages of one PDF recognized with OCR
+    ocr_max_pages: u32,
+
+    /// Master keys wrapping the data keys (None stores files unencrypted)
+    keys: Option<MasterKeyRing>,
+
+    /// Minimum age before an unreferenced object counts as orphaned
+    grace: Duration,
+}
+
+/// Storage key of content-addressed files
+///
+/// # Arguments
+///
+/// * `sha256` - SHA-256 of the content (hex)
+///
+/// # Returns
+///
+/// Storage key (`blobs/<first two digits>/<sha256>`)
+pub fn blob_storage_key(sha256: &str) -> String {
+    format!("blobs/{}/{}", &sha256[..2], sha256)
+}
+
+/// Malware scan result of an upload
+struct ScanResult {
+    status: ScanStatus,
+    signature: Option<String>,
+    scanned_at: Option<DateTime<Utc>>,
+}
+
+impl ScanResult {
+    /// Whether the file must be kept away from users and analysis
+    fn quarantined(&self) -> bool {
+        matches!(self.status, ScanStatus::Infected | ScanStatus::Failed)
+    }
+}
+
+/// Text of an upload
+#[derive(Default)]
+struct Extraction {
+    text: Option<String>,
+
+    /// OCR confidence of the pages that were recognized, None without OCR
+    ocr_pages: Option<Vec<OcrPageConfidence>>,
+
+    /// The user's version with identical content whose text or analysis is reused
+    processed: Option<DocumentVersion>,
+}
+
+/// Refuse access to a file that did not pass its malware scan
+///
+/// Files stored while scanning was disabled are only accessible as long as
+/// it stays disabled; once a scanner is configured they need a rescan.
+///
+/// # Arguments
+///
+/// * `scan_status` - Stored scan status of the file
+/// * `scan_signature` - Stored malware signature
+/// * `scanning_enabled` - Whether a malware scanner is configured
+///
+/// # Errors
+///
+/// Returns forbidden error if the file is infected, could not be scanned, or
+/// was never scanned although scanning is enabled
+pub fn ensure_scan_passed(scan_status: &str, scan_signature: Option<&str>, scanning_enabled: bool) -> AppResult<()> {
+    if scan_status == ScanStatus::Infected.as_str() {
+        return Err(AppError::Forbidden(format!(
+            "The file contains malware ({}) and is quarantined",
+            scan_signature.unwrap_or("unknown signature")
+        )));
+    }
+    if scan_status == ScanStatus::Failed.as_str() {
+        return Err(AppError::Forbidden(
+            "The file could not be scanned for malware and is quarantined".to_string(),
+        ));
+    }
+    if scanning_enabled && scan_status == ScanStatus::NotScanned.as_str() {
+        return Err(AppError::Forbidden(
+            "The file has not been scanned for malware yet; scan it before accessing it".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+impl StorageService {
+    /// Create a new StorageService
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    /// * `storage` - Storage backend
+    /// * `config` - Application configuration (grace period, malware scanner, OCR, master keys)
+    ///
+    /// # Returns
+    ///
+    /// New StorageService instance
+    pub fn new(pool: PgPool, storage: Arc<dyn StorageBackend>, config: &Config) -> Self {
+        info!("🗄️ StorageService started");
+        Self {
+            documents: DocumentRepository::new(pool.clone()),
+            versions: DocumentVersionRepository::new(pool.clone()),
+            blobs: BlobRepository::new(pool.clone()),
+            pool,
+            storage,
+            scanner: scanner_from_config(config),
+            scanning_enabled: config.clamd_address.is_some(),
+            ocr: ocr_engine_from_config(config),
+            ocr_min_confidence: config.ocr.min_confidence,
+            ocr_max_pages: config.ocr.max_pages,
+            keys: config.encryption_keys.clone(),
+            grace: Duration::from_secs(config.orphan_grace_secs),
+        }
+    }
+
+    /// Sweep orphaned objects periodically in the background
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    /// * `storage` - Storage backend
+    /// * `config` - Application configuration
+    ///
+    /// # Returns
+    ///
+    /// Handle of the background task, None if the sweeper is disabled
+    pub fn spawn_orphan_sweeper(
+        pool: PgPool,
+        storage: Arc<dyn StorageBackend>,
+        config: &Config,
+    ) -> Option<JoinHandle<()>> {
+        if config.orphan_sweep_interval_secs == 0 {
+            return None;
+        }
+
+        let interval = Duration::from_secs(config.orphan_sweep_interval_secs);
+        let service = Self::new(pool, storage, config);
+        Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = service.sweep_orphans(false).await {
+                    warn!("Orphaned object sweep failed: {}", e);
+                }
+            }
+        }))
+    }
+
+    /// Store an uploaded file as a new document
+    ///
+    /// Identical content is stored only once: a file whose SHA-256 is already
+    /// known reuses the existing object, and the extracted text and AI
+    /// analysis of an earlier document of the user with the same content are
+    /// copied over.
+    /// Otherwise the text of PDFs and images is extracted, using OCR for
+    /// pages without a text layer. Files found infected, or that could not be
+    /// scanned, are quarantined.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `filename` - Original filename
+    /// * `mime_type` - MIME type
+    /// * `bytes` - File content
+    /// * `extracted_text` - Text already extracted by the caller, if any
+    ///
+    /// # Returns
+    ///
+    /// Created document, the user's duplicates and whether results were reused
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if queries fail, internal error if the file cannot be stored
+    #[instrument(skip(self, bytes, extracted_text), fields(size = bytes.len()))]
+    pub async fn store_document(
+        &self,
+        user_id: Uuid,
+        filename: String,
+        mime_type: String,
+        bytes: Vec<u8>,
+        extracted_text: Option<String>,
+    ) -> AppResult<DocumentUploadResponse> {
+        let scan = self.scan(&filename, &bytes).await;
+        let extraction = self.extract(user_id, &filename, &mime_type, &bytes, extracted_text, &scan).await?;
+        let mut tx = self.pool.begin().await?;
+        let (dto, reused_analysis) =
+            self.store_content(&mut tx, filename, mime_type, bytes, extraction, scan).await?;
+        let document = DocumentRepository::create_in(&mut tx, user_id, &dto).await?;
+        tx.commit().await?;
+
+        self.upload_response(user_id, document, reused_analysis).await
+    }
+
+    /// Store an uploaded file as the next version of a document
+    ///
+    /// The document then describes the new version; earlier versions keep
+    /// their files, text and analysis.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Document UUID
+    /// * `user_id` - User UUID (for authorization)
+    /// * `filename` - Filename of the new version
+    /// * `mime_type` - MIME type
+    /// * `bytes` - File content
+    /// * `extracted_text` - Text already extracted by the caller, if any
+    ///
+    /// # Returns
+    ///
+    /// Updated document with duplicate information, None if not found/unauthorized
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error if the file equals the current version,
+    /// database error if queries fail, internal error if the file cannot be stored
+    #[instrument(skip(self, bytes, extracted_text), fields(size = bytes.len()))]
+    pub async fn store_version(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        filename: String,
+        mime_type: String,
+        bytes: Vec<u8>,
+        extracted_text: Option<String>,
+    ) -> AppResult<Option<DocumentUploadResponse>> {
+        let scan = self.scan(&filename, &bytes).await;
+        let extraction = self.extract(user_id, &filename, &mime_type, &bytes, extracted_text, &scan).await?;
+        let mut tx = self.pool.begin().await?;
+        let Some(current) = DocumentRepository::lock_in(&mut tx, id, user_id).await? else {
+            return Ok(None);
+        };
+        if current.content_sha256.as_deref() == Some(sha256_hex(&bytes).as_str()) {
+            return Err(AppError::Validation(
+                "The uploaded file is identical to the current version".to_string(),
+            ));
+        }
+
+        let (dto, reused_analysis) =
+            self.store_content(&mut tx, filename, mime_type, bytes, extraction, scan).await?;
+        let version = DocumentVersionRepository::create_in(&mut tx, id, current.current_version + 1, &dto).await?;
+        let document = DocumentRepository::set_current_version_in(&mut tx, &version).await?;
+        tx.commit().await?;
+
+        info!("Stored version {} of document {}", version.version_number, id);
+
+        self.upload_response(user_id, document, reused_analysis).await.map(Some)
+    }
+
+    /// Scan an upload for malware
+    ///
+    /// A scanner failure is logged and recorded as a failed scan, so the file
+    /// is kept but quarantined until it is scanned again.
+    async fn scan(&self, filename: &str, bytes: &[u8]) -> ScanResult {
+        let (status, signature) = match self.scanner.scan(bytes).await {
+            Ok(ScanVerdict::NotScanned) => (ScanStatus::NotScanned, None),
+            Ok(ScanVerdict::Clean) => (ScanStatus::Clean, None),
+            Ok(ScanVerdict::Infected(signature)) => {
+                warn!("Upload \"{}\" is infected with {}", filename, signature);
+                (ScanStatus::Infected, Some(signature))
+            }
+            Err(e) => {
+                warn!("Malware scan of upload \"{}\" failed: {}", filename, e);
+                (ScanStatus::Failed, None)
+            }
+        };
+
+        ScanResult {
+            scanned_at: (status != ScanStatus::NotScanned).then(Utc::now),
+            status,
+            signature,
+        }
+    }
+
+    /// Store file content once per SHA-256 and describe it for a new row
+    ///
+    /// The claim locks the blob row until the transaction ends, so the object
+    /// cannot be deleted before the new row references it. Extracted text and
+    /// analysis of identical content the user already processed (found by
+    /// [`Self::extract`]) are carried over, and the personal data categories
+    /// of the resulting text are recorded.
+    /// Quarantined files bypass deduplication and are stored under their own
+    /// key without text or analysis. New objects are encrypted with a fresh
+    /// data key, while a file that is already stored keeps the data key of
+    /// the versions sharing it.
+    ///
+    /// # Returns
+    ///
+    /// Row data and whether processing results were reused
+    async fn store_content(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        filename: String,
+        mime_type: String,
+        bytes: Vec<u8>,
+        extraction: Extraction,
+        scan: ScanResult,
+    ) -> AppResult<(CreateDocumentDto, bool)> {
+        let size = bytes.len() as i64;
+        if scan.quarantined() {
+            let storage_key = format!("quarantine/{}", Uuid::new_v4());
+            let encryption = self.generate_data_key()?;
+            self.storage.put(&storage_key, self.seal(bytes, encryption.as_ref())?, &mime_type).await?;
+
+            let dto = CreateDocumentDto {
+                filename,
+                storage_key,
+                file_size: size,
+                mime_type,
+                content_sha256: None,
+                extracted_text: None,
+                ai_analysis: None,
+                scan_status: Some(scan.status.as_str().to_string()),
+                scan_signature: scan.signature,
+                scanned_at: scan.scanned_at,
+                encryption_key_id: encryption.as_ref().map(|key| key.key_id.clone()),
+                wrapped_data_key: encryption.map(|key| key.wrapped),
+                pii_categories: Vec::new(),
+                ocr_pages: None,
+                ocr_low_confidence: false,
+                tag_ids: None,
+                custom_fields: None,
+            };
+            return Ok((dto, false));
+        }
+
+        let sha256 = sha256_hex(&bytes);
+        let claimed = BlobRepository::claim_in(tx, &sha256, &blob_storage_key(&sha256), size).await?;
+        let storage_key = claimed.blob.storage_key;
+        let shared = if claimed.inserted || claimed.blob.ref_count == 0 {
+            None
+        } else {
+            DocumentVersionRepository::find_by_sha256_in(tx, &sha256).await?
+        };
+        let (encryption, missing) = match shared {
+            Some(version) => (
+                WrappedDataKey::from_parts(version.encryption_key_id.as_deref(), version.wrapped_data_key.as_deref()),
+                self.storage.head(&storage_key).await?.is_none(),
+            ),
+            None => (self.generate_data_key()?, true),
+        };
+        if missing {
+            self.storage.put(&storage_key, self.seal(bytes, encryption.as_ref())?, &mime_type).await?;
+        }
+
+        let Extraction { text, ocr_pages, processed } = extraction;
+        let (reused_text, reused_ocr_pages, ai_analysis) = match processed {
+            Some(version) => (version.extracted_text, version.ocr_pages, version.ai_analysis),
+            None => (None, None, None),
+        };
+        let reused_analysis = ai_analysis.is_some() || (text.is_none() && reused_text.is_some());
+        let (extracted_text, ocr_pages) = match (text, ocr_pages) {
+            (None, None) => (reused_text, reused_ocr_pages.map(|pages| pages.0)),
+            (text, ocr_pages) => (text, ocr_pages),
+        };
+        let pii_categories = match &extracted_text {
+            Some(text) => pii::scan_category_names(text.clone()).await?,
+            None => Vec::new(),
+        };
+        let ocr_low_confidence = ocr_pages
+            .iter()
+            .flatten()
+            .any(|page| page.confidence < self.ocr_min_confidence);
+
+        let dto = CreateDocumentDto {
+            filename,
+            storage_key,
+            file_size: size,
+            mime_type,
+            content_sha256: Some(sha256),
+            extracted_text,
+            ai_analysis,
+            scan_status: Some(scan.status.as_str().to_string()),
+            scan_signature: None,
+            scanned_at: scan.scanned_at,
+            encryption_key_id: encryption.as_ref().map(|key| key.key_id.clone()),
+            wrapped_data_key: encryption.map(|key| key.wrapped),
+            pii_categories,
+            ocr_pages: ocr_pages.map(Json),
+            ocr_low_confidence,
+            tag_ids: None,
+            custom_fields: None,
+        };
+
+        Ok((dto, reused_analysis))
+    }
+
+    /// Extract the text of an upload the caller supplied none for
+    ///
+    /// Also finds the user's identical, already processed content whose
+    /// results [`Self::store_content`] reuses; its text is not extracted
+    /// again. Quarantined files are skipped. Extraction failures are logged
+    /// and leave the document without text, which can be added later.
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if the lookup of processed content fails
+    async fn extract(
+        &self,
+        user_id: Uuid,
+        filename: &str,
+        mime_type: &str,
+        bytes: &[u8],
+        extracted_text: Option<String>,
+        scan: &ScanResult,
+    ) -> AppResult<Extraction> {
+        if scan.quarantined() {
+            return Ok(Extraction::default());
+        }
+        let processed = self.versions.find_processed_by_sha256(user_id, &sha256_hex(bytes)).await?;
+        let skip = extracted_text.is_some()
+            || (mime_type != "application/pdf" && !OCR_IMAGE_MIME_TYPES.contains(&mime_type))
+            || processed.as_ref().is_some_and(|version| version.extracted_text.is_some());
+
+        let extraction = if skip {
+            Extraction { text: extracted_text, ..Extraction::default() }
+        } else {
+            match self.extract_text(mime_type, bytes).await {
+                Ok(extraction) => extraction,
+                Err(e) => {
+                    warn!("Text extraction of upload \"{}\" failed: {}", filename, e);
+                    Extraction::default()
+                }
+            }
+        };
+
+        Ok(Extraction { processed, ..extraction })
+    }
+
+    /// Read the text layer of a PDF, recognizing pages without one, or recognize an image
+    ///
+    /// Pages past the OCR page limit and pages whose recognition fails stay
+    /// without text and are recorded with confidence 0, which flags the
+    /// document as a low-quality scan.
+    async fn extract_text(&self, mime_type: &str, bytes: &[u8]) -> AppResult<Extraction> {
+        let mut texts = Vec::new();
+        let mut ocr_pages = Vec::new();
+        if mime_type == "application/pdf" {
+            let pdf = bytes.to_vec();
+            let pages = tokio::task::spawn_blocking(move || pdf_page_texts(&pdf))
+                .await
+                .map_err(|e| AppError::Internal(format!("PDF text extraction failed: {}", e)))??;
+            let mut recognized = 0;
+            for (number, text) in (1..).zip(pages) {
+                if !text.is_empty() {
+                    texts.push(text);
+                    continue;
+                }
+                if recognized == self.ocr_max_pages {
+                    warn!("Page {} not recognized, OCR is limited to {} pages", number, self.ocr_max_pages);
+                    ocr_pages.push(OcrPageConfidence { page: number as i32, confidence: 0.0 });
+                    continue;
+                }
+                recognized += 1;
+                match self.ocr.recognize_pdf_page(bytes, number).await {
+                    Ok(pages) => {
+                        for page in pages {
+                            ocr_pages.push(OcrPageConfidence { page: number as i32, confidence: page.confidence });
+                            texts.push(page.text);
+                        }
+                    }
+                    Err(e) => {
+                        warn!("OCR of page {} failed: {}", number, e);
+                        ocr_pages.push(OcrPageConfidence { page: number as i32, confidence: 0.0 });
+                    }
+                }
+            }
+        } else {
+            for (number, page) in (1..).zip(self.ocr.recognize_image(bytes).await?) {
+                ocr_pages.push(OcrPageConfidence { page: number, confidence: page.confidence });
+                texts.push(page.text);
+            }
+        }
+        if !ocr_pages.is_empty() {
+            info!("Recognized {} page(s) with OCR: {:?}", ocr_pages.len(), ocr_pages);
+        }
+
+        let text = texts.into_iter().filter(|text| !text.trim().is_empty()).collect::<Vec<_>>().join("\n\n");
+        Ok(Extraction {
+            text: (!text.is_empty()).then_some(text),
+            ocr_pages: (!ocr_pages.is_empty()).then_some(ocr_pages),
+            processed: None,
+        })
+    }
+
+    /// Create the data key of a new object
+    ///
+    /// # Returns
+    ///
+    /// Wrapped data key, None if encryption is disabled
+    fn generate_data_key(&self) -> AppResult<Option<WrappedDataKey>> {
+        self.keys
+            .as_ref()
+            .map(|keys| keys.generate_data_key().map(|(_, wrapped)| wrapped))
+            .transpose()
+    }
+
+    /// Encrypt content with a data key (no-op for unencrypted files)
+    fn seal(&self, bytes: Vec<u8>, encryption: Option<&WrappedDataKey>) -> AppResult<Vec<u8>> {
+        match encryption {
+            Some(wrapped) => self.master_keys()?.unwrap(wrapped)?.encrypt(&bytes),
+            None => Ok(bytes),
+        }
+    }
+
+    /// Master keys, required to read or write encrypted files
+    fn master_keys(&self) -> AppResult<&MasterKeyRing> {
+        self.keys
+            .as_ref()
+            .ok_or_else(|| AppError::Internal("Stored file is encrypted but no master key is configured".to_string()))
+    }
+
+    /// Read a stored file, decrypting it if needed
+    ///
+    /// # Arguments
+    ///
+    /// * `storage_key` - Storage key of the file
+    /// * `encryption` - Wrapped data key, None for files stored unencrypted
+    ///
+    /// # Returns
+    ///
+    /// File content
+    ///
+    /// # Errors
+    ///
+    /// Returns not found error if the object is missing, internal error if it
+    /// cannot be read or decrypted
+    pub async fn read_file(&self, storage_key: &str, encryption: Option<&WrappedDataKey>) -> AppResult<Vec<u8>> {
+        let bytes = self.storage.get(storage_key).await?;
+        match encryption {
+            Some(wrapped) => self.master_keys()?.unwrap(wrapped)?.decrypt(&bytes),
+            None => Ok(bytes),
+        }
+    }
+
+    /// Describe a stored upload, naming the user's documents with the same content
+    async fn upload_response(
+        &self,
+        user_id: Uuid,
+        document: Document,
+        reused_analysis: bool,
+    ) -> AppResult<DocumentUploadResponse> {
+        if let Err(AppError::Forbidden(message)) =
+            ensure_scan_passed(&document.scan_status, document.scan_signature.as_deref(), self.scanning_enabled)
+        {
+            return Ok(DocumentUploadResponse {
+                document,
+                duplicate_of: Vec::new(),
+                reused_analysis,
+                message: Some(message),
+            });
+        }
+
+        let sha256 = document.content_sha256.clone().unwrap_or_default();
+        let duplicate_of = self.documents.find_duplicates(user_id, &sha256, document.id).await?;
+        let message = duplicate_of
+            .first()
+            .map(|original| format!("This file already exists as \"{}\"", original.filename));
+        if !duplicate_of.is_empty() {
+            info!(
+                "Document {} duplicates {} earlier upload(s) of user {}",
+                document.id,
+                duplicate_of.len(),
+                user_id
+            );
+        }
+
+        Ok(DocumentUploadResponse {
+            document,
+            duplicate_of,
+            reused_analysis,
+            message,
+        })
+    }
+
+    /// Resolve a storage key registered by a client to a file they uploaded
+    ///
+    /// Content-addressed keys are predictable, so a key is only accepted if
+    /// one of the user's own documents stores its file under it; quarantined
+    /// files cannot be registered again.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `storage_key` - Storage key
+    ///
+    /// # Returns
+    ///
+    /// Newest version of the user's documents stored under the key
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error if the key is not one of the user's files
+    pub async fn uploaded_file(&self, user_id: Uuid, storage_key: &str) -> AppResult<DocumentVersion> {
+        let unknown_key = || AppError::Validation(format!("Storage key '{}' is not a file you uploaded", storage_key));
+        if storage_key.starts_with("quarantine/") {
+            return Err(unknown_key());
+        }
+
+        self.versions
+            .find_by_storage_key(user_id, storage_key)
+            .await?
+            .ok_or_else(unknown_key)
+    }
+
+    /// Scan the current file of a document again
+    ///
+    /// Lifts the quarantine of a file whose earlier scan failed once it is
+    /// found clean, and checks files stored while scanning was disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Document UUID
+    /// * `user_id` - User UUID (for authorization)
+    ///
+    /// # Returns
+    ///
+    /// Document with the new scan result, None if not found/unauthorized
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error if scanning is disabled or the scanner fails,
+    /// internal error if the file cannot be read
+    #[instrument(skip(self))]
+    pub async fn rescan_document(&self, id: Uuid, user_id: Uuid) -> AppResult<Option<Document>> {
+        let Some(document) = self.documents.find_by_id(id, user_id).await? else {
+            return Ok(None);
+        };
+
+        let encryption = WrappedDataKey::from_parts(
+            document.encryption_key_id.as_deref(),
+            document.wrapped_data_key.as_deref(),
+        );
+        let bytes = self.read_file(&document.storage_key, encryption.as_ref()).await?;
+        let scan = self.scan(&document.filename, &bytes).await;
+        match scan.status {
+            ScanStatus::NotScanned => {
+                return Err(AppError::Validation("Malware scanning is not enabled".to_string()));
+            }
+            ScanStatus::Failed if document.scan_status != ScanStatus::Failed.as_str() => {
+                // Keep the earlier result rather than quarantining a file over a scanner outage
+                return Err(AppError::Validation("The malware scanner is unavailable".to_string()));
+            }
+            _ => {}
+        }
+
+        info!("Rescanned document {}: {}", id, scan.status.as_str());
+        self.documents
+            .set_scan_result(id, user_id, scan.status, scan.signature.as_deref())
+            .await
+    }
+
+    /// Re-wrap all data keys with the active master key
+    ///
+    /// Run after putting a new master key first in the key ring; retired keys
+    /// can be removed once no data key is left under them. Files themselves
+    /// are not rewritten.
+    ///
+    /// # Arguments
+    ///
+    /// * `dry_run` - Only count the data keys that would be re-wrapped
+    ///
+    /// # Returns
+    ///
+    /// Rotation report
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error if encryption is disabled, database error if queries fail
+    #[instrument(skip(self))]
+    pub async fn rotate_data_keys(&self, dry_run: bool) -> AppResult<KeyRotationReport> {
+        let keys = self
+            .keys
+            .as_ref()
+            .ok_or_else(|| AppError::Validation("Encryption is not enabled".to_string()))?;
+        let mut report = KeyRotationReport {
+            dry_run,
+            active_key_id: keys.active_key_id().to_string(),
+            ..Default::default()
+        };
+
+        for old in self.versions.find_wrapped_keys_not_under(keys.active_key_id()).await? {
+            let new = match keys.rewrap(&old) {
+                Ok(new) => new,
+                Err(e) => {
+                    warn!("Cannot re-wrap a data key under master key {}: {}", old.key_id, e);
+                    report.unreadable_keys += 1;
+                    continue;
+                }
+            };
+            report.rewrapped_keys += 1;
+            if dry_run {
+                continue;
+            }
+
+            let mut tx = self.pool.begin().await?;
+            report.documents += DocumentRepository::rewrap_data_key_in(&mut tx, &old, &new).await?;
+            report.versions += DocumentVersionRepository::rewrap_data_key_in(&mut tx, &old, &new).await?;
+            tx.commit().await?;
+        }
+
+        info!(
+            "Data key rotation{} to master key {}: {} keys re-wrapped ({} documents, {} versions), {} unreadable",
+            if dry_run { " (dry run)" } else { "" },
+            report.active_key_id,
+            report.rewrapped_keys,
+            report.documents,
+            report.versions,
+            report.unreadable_keys
+        );
+
+        Ok(report)
+    }
+
+    /// Delete a document with all its versions and release their stored files
+    ///
+    /// Content-addressed files are removed once the last reference is gone:
+    /// their blob row is deleted with the document and the object after the
+    /// commit, unless the same content was uploaded again in between.
+    /// Failing to remove the object is only logged, the sweeper reclaims it.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Document UUID
+    /// * `user_id` - User UUID (for authorization)
+    ///
+    /// # Returns
+    ///
+    /// Deleted document, None if not found/unauthorized
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if deletion fails
+    #[instrument(skip(self))]
+    pub async fn delete_document(&self, id: Uuid, user_id: Uuid) -> AppResult<Option<Document>> {
+        let mut tx = self.pool.begin().await?;
+        let versions = DocumentVersionRepository::find_by_document_in(&mut tx, id).await?;
+        let Some(document) = DocumentRepository::delete_in(&mut tx, id, user_id).await? else {
+            return Ok(None);
+        };
+
+        let mut hashes = BTreeSet::new();
+        let mut legacy_keys = BTreeSet::new();
+        for (sha256, storage_key) in versions
+            .into_iter()
+            .map(|version| (version.content_sha256, version.storage_key))
+            .chain([(document.content_sha256.clone(), document.storage_key.clone())])
+        {
+            match sha256 {
+                Some(sha256) => hashes.insert(sha256),
+                None => legacy_keys.insert(storage_key),
+            };
+        }
+
+        let mut released = Vec::new();
+        for sha256 in &hashes {
+            if let Some(blob) = BlobRepository::lock_unreferenced_in(&mut tx, sha256).await? {
+                BlobRepository::delete_in(&mut tx, sha256).await?;
+                released.push(blob);
+            }
+        }
+        tx.commit().await?;
+
+        for blob in &released {
+            if let Err(e) = self.remove_released_blob(blob).await {
+                warn!("Failed to remove file of deleted document {}: {}", id, e);
+            }
+        }
+
+        // Files stored before deduplication are shared by storage key only
+        for storage_key in &legacy_keys {
+            if let Err(e) = self.remove_document_file(storage_key).await {
+                warn!("Failed to remove file of deleted document {}: {}", id, e);
+            }
+        }
+
+        Ok(Some(document))
+    }
+
+    /// Remove the stored object of a deleted document without a content hash
+    ///
+    /// The object is kept while another document still references it.
+    ///
+    /// # Arguments
+    ///
+    /// * `storage_key` - Storage key of the deleted document
+    ///
+    /// # Returns
+    ///
+    /// Bytes reclaimed (0 if the object was kept or already gone)
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if the reference check fails, internal error if deletion fails
+    #[instrument(skip(self))]
+    async fn remove_document_file(&self, storage_key: &str) -> AppResult<u64> {
+        if self.documents.storage_key_in_use(storage_key).await? {
+            return Ok(0);
+        }
+
+        let Some(object) = self.storage.head(storage_key).await? else {
+            return Ok(0);
+        };
+        self.storage.delete(storage_key).await?;
+
+        Ok(object.size)
+    }
+
+    /// Reconcile the storage backend against the documents table
+    ///
+    /// Blobs left without references (e.g. after a user was deleted) are
+    /// released first. Objects no document references are removed once they
+    /// are older than the grace period, which protects uploads whose row is
+    /// not written yet. Documents whose object is missing are reported but
+    /// left alone.
+    ///
+    /// # Arguments
+    ///
+    /// * `dry_run` - Only report what would be removed
+    ///
+    /// # Returns
+    ///
+    /// Sweep report with the reclaimed bytes
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails, internal error if the backend cannot be listed
+    #[instrument(skip(self))]
+    pub async fn sweep_orphans(&self, dry_run: bool) -> AppResult<OrphanSweepReport> {
+        let mut report = OrphanSweepReport {
+            dry_run,
+            ..Default::default()
+        };
+
+        if dry_run {
+            report.released_blobs = self.blobs.count_unreferenced().await? as usize;
+        } else {
+            report.released_blobs = self.release_unreferenced_blobs().await?;
+        }
+
+        // List the objects before the rows so that an object and its row written
+        // in between are both seen (the object is then simply too recent to sweep)
+        let objects = self.storage.list().await?;
+        let referenced: HashSet<String> = self.documents.find_all_storage_keys().await?.into_iter().collect();
+
+        let stored: HashSet<&str> = objects.iter().map(|object| object.key.as_str()).collect();
+        report.missing_keys = referenced
+            .iter()
+            .filter(|key| !stored.contains(key.as_str()))
+            .cloned()
+            .collect();
+
+        let cutoff = Utc::now() - self.grace;
+        for object in &objects {
+            report.scanned_objects += 1;
+            if referenced.contains(&object.key) {
+                continue;
+            }
+            if object.last_modified.is_none_or(|modified| modified > cutoff) {
+                report.recent_objects += 1;
+                continue;
+            }
+
+            if !dry_run {
+                if let Err(e) = self.storage.delete(&object.key).await {
+                    warn!("Failed to remove orphaned object {}: {}", object.key, e);
+                    continue;
+                }
+            }
+            report.reclaimed_bytes += object.size;
+            report.orphaned_keys.push(object.key.clone());
+        }
+
+        report.orphaned_keys.sort();
+        report.missing_keys.sort();
+        info!(
+            "Orphaned object sweep{}: {} unreferenced blobs, {} objects scanned, {} orphaned, {} bytes reclaimed, {} documents missing their object",
+            if dry_run { " (dry run)" } else { "" },
+            report.released_blobs,
+            report.scanned_objects,
+            report.orphaned_keys.len(),
+            report.reclaimed_bytes,
+            report.missing_keys.len()
+        );
+
+        Ok(report)
+    }
+
+    /// Remove all blobs no document references, with their objects
+    ///
+    /// Blobs locked by an upload in progress are skipped.
+    ///
+    /// # Returns
+    ///
+    /// Number of blobs removed
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if queries fail
+    async fn release_unreferenced_blobs(&self) -> AppResult<usize> {
+        let mut released = 0;
+        loop {
+            let mut tx = self.pool.begin().await?;
+            let Some(blob) = BlobRepository::lock_next_unreferenced_in(&mut tx).await? else {
+                return Ok(released);
+            };
+
+            BlobRepository::delete_in(&mut tx, &blob.sha256).await?;
+            tx.commit().await?;
+            released += 1;
+
+            // A failed object delete leaves an orphan for the object pass below
+            if let Err(e) = self.remove_released_blob(&blob).await {
+                warn!("Failed to remove unreferenced blob {}: {}", blob.sha256, e);
+            }
+        }
+    }
+
+    /// Remove the object of a blob whose row was deleted and committed
+    ///
+    /// The object is only removed if the content was not uploaded again
+    /// since; the row is re-created meanwhile so a concurrent upload waits
+    /// and stores the object afresh.
+    ///
+    /// # Arguments
+    ///
+    /// * `blob` - Released blob
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if the guard row cannot be written, internal error if deletion fails
+    async fn remove_released_blob(&self, blob: &Blob) -> AppResult<()> {
+        let mut tx = self.pool.begin().await?;
+        if !BlobRepository::claim_released_in(&mut tx, blob).await? {
+            return Ok(());
+        }
+
+        let deleted = self.storage.delete(&blob.storage_key).await;
+        BlobRepository::delete_in(&mut tx, &blob.sha256).await?;
+        tx.commit().await?;
+
+        deleted
+    }
+}
@@ -0,0 +1,479 @@
+use std::{sync::Arc, time::Duration as StdDuration};
+
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect::Policy,
+    Client, Response,
+};
+use sha2::Sha256;
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use tracing::{info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    db::repository::{AttemptOutcome, ClaimedDelivery, WebhookRepository},
+    error::{AppError, AppResult},
+    models::{
+        CreateWebhookDto, UpdateWebhookDto, WebhookDelivery, WebhookDeliveryQuery, WebhookDeliveryStatus,
+        WebhookEvent, WebhookEventType, WebhookSubscription, WebhookWithSecret,
+    },
+    utils::net::{ensure_public_url, resolve_public_host},
+};
+
+/// Deliveries claimed per queue poll
+const DELIVERY_BATCH_SIZE: i64 = 50;
+
+/// Longest delay between two attempts
+const MAX_RETRY_DELAY_SECS: i64 = 6 * 60 * 60;
+
+/// Longest response body kept in the delivery log
+const MAX_LOGGED_BODY_CHARS: usize = 2048;
+
+/// Most response bytes read for the delivery log (four per character at worst)
+const MAX_LOGGED_BODY_BYTES: usize = MAX_LOGGED_BODY_CHARS * 4;
+
+/// Default number of deliveries in the log
+const DEFAULT_LOG_SIZE: i64 = 50;
+
+/// Maximum number of deliveries in the log
+const MAX_LOG_SIZE: i64 = 200;
+
+/// Webhook service for subscriptions and signed event delivery
+///
+/// Events are queued in the database and delivered by a background worker,
+/// retrying failed attempts with exponential backoff.
+pub struct WebhookService {
+    /// Webhook repository
+    repository: WebhookRepository,
+
+    /// HTTP client
+    client: Client,
+
+    /// Attempts per delivery before giving up
+    max_attempts: i32,
+
+    /// Delay before the first retry in seconds
+    retry_base_secs: i64,
+
+    /// Hosts allowed to resolve to private or local addresses
+    allowed_hosts: Arc<Vec<String>>,
+}
+
+impl WebhookService {
+    /// Create a new WebhookService
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    /// * `config` - Application configuration (retry policy and timeout)
+    ///
+    /// # Returns
+    ///
+    /// New WebhookService instance
+    pub fn new(pool: PgPool, config: &Config) -> Self {
+        info!("🪝 WebhookService started");
+        let allowed_hosts = Arc::new(config.webhook_allowed_hosts.clone());
+        let client = Client::builder()
+            .timeout(StdDuration::from_secs(config.webhook_timeout_secs))
+            .redirect(Policy::none())
+            .dns_resolver(Arc::new(PublicResolver {
+                allowed_hosts: allowed_hosts.clone(),
+            }))
+            .user_agent(concat!("ParseGuard-Webhooks/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .expect("Webhook HTTP client must be buildable");
+
+        Self {
+            repository: WebhookRepository::new(pool),
+            client,
+            max_attempts: config.webhook_max_attempts.max(1),
+            retry_base_secs: config.webhook_retry_base_secs.max(1),
+            allowed_hosts,
+        }
+    }
+
+    /// Deliver queued events periodically in the background
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Database connection pool
+    /// * `config` - Application configuration
+    ///
+    /// # Returns
+    ///
+    /// Handle of the background task
+    pub fn spawn_delivery_worker(pool: PgPool, config: &Config) -> JoinHandle<()> {
+        let service = Self::new(pool, config);
+        let interval = StdDuration::from_secs(config.webhook_poll_interval_secs.max(1));
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = service.process_due(Utc::now()).await {
+                    warn!("Webhook delivery run failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// List the user's webhook subscriptions
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    ///
+    /// # Returns
+    ///
+    /// Subscriptions, newest first
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if query fails
+    #[instrument(skip(self))]
+    pub async fn list(&self, user_id: Uuid) -> AppResult<Vec<WebhookSubscription>> {
+        self.repository.find_by_user(user_id).await
+    }
+
+    /// Get a webhook subscription
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `id` - Subscription UUID
+    ///
+    /// # Returns
+    ///
+    /// Subscription
+    ///
+    /// # Errors
+    ///
+    /// Returns 404 if not found or not owned by user
+    #[instrument(skip(self))]
+    pub async fn get(&self, user_id: Uuid, id: Uuid) -> AppResult<WebhookSubscription> {
+        self.repository
+            .find_by_id(id, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Webhook not found".to_string()))
+    }
+
+    /// Create a webhook subscription
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `dto` - Subscription data (a secret is generated if none is given)
+    ///
+    /// # Returns
+    ///
+    /// Created subscription with its signing secret
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error if the URL does not resolve to a public address,
+    /// database error if insertion fails
+    #[instrument(skip(self, dto))]
+    pub async fn create(&self, user_id: Uuid, dto: &CreateWebhookDto) -> AppResult<WebhookWithSecret> {
+        ensure_public_url(&dto.url, &self.allowed_hosts).await?;
+        let secret = dto.secret.clone().unwrap_or_else(generate_secret);
+        let subscription = self.repository.create(user_id, dto, &secret).await?;
+
+        Ok(subscription.into())
+    }
+
+    /// Update a webhook subscription
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `id` - Subscription UUID
+    /// * `dto` - Update data
+    ///
+    /// # Returns
+    ///
+    /// Updated subscription; the secret is included only when rotated
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error if the URL does not resolve to a public address,
+    /// 404 if not found or not owned by user
+    #[instrument(skip(self, dto))]
+    pub async fn update(&self, user_id: Uuid, id: Uuid, dto: &UpdateWebhookDto) -> AppResult<serde_json::Value> {
+        if let Some(url) = &dto.url {
+            ensure_public_url(url, &self.allowed_hosts).await?;
+        }
+        let secret = dto.rotate_secret.then(generate_secret);
+        let subscription = self
+            .repository
+            .update(id, user_id, dto, secret.as_deref())
+            .await?
+            .ok_or_else(|| AppError::NotFound("Webhook not found".to_string()))?;
+
+        let body = if secret.is_some() {
+            serde_json::to_value(WebhookWithSecret::from(subscription))
+        } else {
+            serde_json::to_value(subscription)
+        };
+        body.map_err(|e| AppError::Internal(format!("Failed to serialize webhook: {}", e)))
+    }
+
+    /// Delete a webhook subscription
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `id` - Subscription UUID
+    ///
+    /// # Errors
+    ///
+    /// Returns 404 if not found or not owned by user
+    #[instrument(skip(self))]
+    pub async fn delete(&self, user_id: Uuid, id: Uuid) -> AppResult<()> {
+        if self.repository.delete(id, user_id).await? {
+            Ok(())
+        } else {
+            Err(AppError::NotFound("Webhook not found".to_string()))
+        }
+    }
+
+    /// Get the delivery log of a subscription
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `id` - Subscription UUID
+    /// * `query` - Status filter and limit
+    ///
+    /// # Returns
+    ///
+    /// Deliveries, newest first
+    ///
+    /// # Errors
+    ///
+    /// Returns 404 if the subscription is not found or not owned by user
+    #[instrument(skip(self))]
+    pub async fn deliveries(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        query: &WebhookDeliveryQuery,
+    ) -> AppResult<Vec<WebhookDelivery>> {
+        let subscription = self.get(user_id, id).await?;
+        let limit = query
+            .limit
+            .filter(|l| (1..=MAX_LOG_SIZE).contains(l))
+            .unwrap_or(DEFAULT_LOG_SIZE);
+
+        self.repository.find_deliveries(subscription.id, query.status, limit).await
+    }
+
+    /// Send a test event to a subscription and attempt it right away
+    ///
+    /// A failed attempt is retried like any other delivery.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User UUID
+    /// * `id` - Subscription UUID
+    ///
+    /// # Returns
+    ///
+    /// Delivery with the outcome of the first attempt
+    ///
+    /// # Errors
+    ///
+    /// Returns 404 if the subscription is not found or not owned by user
+    #[instrument(skip(self))]
+    pub async fn send_test(&self, user_id: Uuid, id: Uuid) -> AppResult<WebhookDelivery> {
+        let subscription = self.get(user_id, id).await?;
+
+        let event = WebhookEvent {
+            id: Uuid::new_v4(),
+            event_type: WebhookEventType::Test,
+            created_at: Utc::now(),
+            data: serde_json::json!({
+                "webhook_id": subscription.id,
+                "message": "This is a test event from ParseGuard",
+            }),
+        };
+        let payload = serde_json::to_value(&event)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize webhook event: {}", e)))?;
+        let delivery = self
+            .repository
+            .enqueue_for(subscription.id, event.event_type, event.id, &payload)
+            .await?;
+
+        match self.repository.claim_due(Utc::now(), Some(delivery.id), 1).await?.pop() {
+            Some(claimed) => self.attempt(claimed).await,
+            None => Ok(delivery),
+        }
+    }
+
+    /// Attempt all deliveries that are due
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - Reference time
+    ///
+    /// # Returns
+    ///
+    /// Number of attempts made
+    ///
+    /// # Errors
+    ///
+    /// Returns database error if a query fails
+    #[instrument(skip(self))]
+    pub async fn process_due(&self, now: DateTime<Utc>) -> AppResult<usize> {
+        let mut attempted = 0;
+
+        loop {
+            let batch = self.repository.claim_due(now, None, DELIVERY_BATCH_SIZE).await?;
+            let claimed = batch.len();
+            for delivery in batch {
+                self.attempt(delivery).await?;
+            }
+            attempted += claimed;
+
+            if (claimed as i64) < DELIVERY_BATCH_SIZE {
+                return Ok(attempted);
+            }
+        }
+    }
+
+    /// Send a claimed delivery and record the outcome
+    async fn attempt(&self, claimed: ClaimedDelivery) -> AppResult<WebhookDelivery> {
+        let ClaimedDelivery { delivery, url, secret } = claimed;
+
+        let body = serde_json::to_string(&delivery.payload.0)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize webhook payload: {}", e)))?;
+        let timestamp = Utc::now().timestamp();
+        let signature = format!("t={},v1={}", timestamp, sign_payload(&secret, timestamp, &body));
+
+        // Host names are checked again by the resolver when connecting, so a
+        // rebinding DNS answer cannot redirect the request to a local address
+        let result = match ensure_public_url(&url, &self.allowed_hosts).await {
+            Ok(()) => self
+                .client
+                .post(&url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header("X-ParseGuard-Event", &delivery.event_type)
+                .header("X-ParseGuard-Delivery", delivery.id.to_string())
+                .header("X-ParseGuard-Signature", signature)
+                .body(body)
+                .send()
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+
+        let (succeeded, outcome) = match result {
+            Ok(response) => {
+                let status = response.status();
+                (
+                    status.is_success(),
+                    AttemptOutcome {
+                        response_status: Some(i32::from(status.as_u16())),
+                        response_body: Some(logged_body(response).await),
+                        error: None,
+                    },
+                )
+            }
+            Err(e) => (
+                false,
+                AttemptOutcome {
+                    response_status: None,
+                    response_body: None,
+                    error: Some(e),
+                },
+            ),
+        };
+
+        let (status, next_attempt_at) = if succeeded {
+            (WebhookDeliveryStatus::Succeeded, None)
+        } else if delivery.attempts >= self.max_attempts {
+            warn!("Webhook delivery {} failed after {} attempts", delivery.id, delivery.attempts);
+            (WebhookDeliveryStatus::Failed, None)
+        } else {
+            (
+                WebhookDeliveryStatus::Pending,
+                Some(Utc::now() + self.retry_delay(delivery.attempts)),
+            )
+        };
+
+        self.repository
+            .record_attempt(delivery.id, status, &outcome, next_attempt_at)
+            .await
+    }
+
+    /// Delay after a failed attempt: the base delay doubled per attempt, capped at six hours
+    fn retry_delay(&self, attempts: i32) -> Duration {
+        let factor = 2_i64.saturating_pow(attempts.saturating_sub(1).max(0) as u32);
+        Duration::seconds(self.retry_base_secs.saturating_mul(factor).min(MAX_RETRY_DELAY_SECS))
+    }
+}
+
+/// Read the start of a response body for the delivery log
+///
+/// At most [`MAX_LOGGED_BODY_BYTES`] are read, so a large or endless body
+/// cannot exhaust memory; a read error ends the body early.
+async fn logged_body(mut response: Response) -> String {
+    let mut body = Vec::new();
+    while body.len() < MAX_LOGGED_BODY_BYTES {
+        match response.chunk().await {
+            Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+            _ => break,
+        }
+    }
+    body.truncate(MAX_LOGGED_BODY_BYTES);
+
+    String::from_utf8_lossy(&body).chars().take(MAX_LOGGED_BODY_CHARS).collect()
+}
+
+/// Resolves webhook hosts, refusing private and local addresses
+struct PublicResolver {
+    /// Hosts allowed to resolve to private or local addresses
+    allowed_hosts: Arc<Vec<String>>,
+}
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let allowed_hosts = self.allowed_hosts.clone();
+        Box::pin(async move {
+            let addresses = resolve_public_host(name.as_str(), 0, &allowed_hosts)
+                .await
+                .map_err(|e| std::io::Error::other(e.to_string()))?;
+            let addresses: Addrs = Box::new(addresses.into_iter());
+            Ok(addresses)
+        })
+    }
+}
+
+/// Compute the signature of a webhook request
+///
+/// The signature is the hex-encoded HMAC-SHA256 of `"{timestamp}.{body}"`
+/// keyed with the subscription secret. It is sent as
+/// `X-ParseGuard-Signature: t={timestamp},v1={signature}`.
+///
+/// # Arguments
+///
+/// * `secret` - Subscription secret
+/// * `timestamp` - Unix timestamp of the request
+/// * `body` - Request body
+///
+/// # Returns
+///
+/// Hex-encoded signature
+pub fn sign_payload(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Generate a random signing secret
+fn generate_secret() -> String {
+    format!("whsec_{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
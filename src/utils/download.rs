@@ -0,0 +1,161 @@
+/// Characters kept verbatim in an RFC 5987 `filename*` value
+const ATTR_CHARS: &[u8] = b"!#$&+-.^_`|~";
+
+/// MIME types a browser may display inline; anything else, such as HTML or
+/// SVG that could run script on the API origin, is always an attachment
+const INLINE_MIME_TYPES: &[&str] = &["application/pdf", "image/png", "image/jpeg", "text/plain"];
+
+/// Byte range requested with a `Range` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    /// First byte (inclusive)
+    pub start: u64,
+
+    /// Last byte (inclusive)
+    pub end: u64,
+}
+
+impl ByteRange {
+    /// Number of bytes in the range
+    pub fn length(&self) -> u64 {
+        self.end - self.start + 1
+    }
+}
+
+/// Interpretation of a `Range` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeRequest {
+    /// Serve the whole file (no header, unsupported unit or several ranges)
+    Full,
+
+    /// Serve one range with 206 Partial Content
+    Partial(ByteRange),
+
+    /// No requested byte exists (416 Range Not Satisfiable)
+    Unsatisfiable,
+}
+
+/// Parse a `Range` header against the file length
+///
+/// Only single `bytes` ranges are honored; anything else falls back to the
+/// full file, which RFC 9110 allows.
+///
+/// # Arguments
+///
+/// * `header` - `Range` header value
+/// * `length` - File length in bytes
+///
+/// # Returns
+///
+/// How to answer the request
+pub fn parse_range(header: &str, length: u64) -> RangeRequest {
+    let Some(spec) = header.trim().strip_prefix("bytes=") else {
+        return RangeRequest::Full;
+    };
+    if spec.contains(',') {
+        return RangeRequest::Full;
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return RangeRequest::Full;
+    };
+
+    let range = match (start.trim(), end.trim()) {
+        ("", "") => return RangeRequest::Full,
+        ("", suffix) => match suffix.parse::<u64>() {
+            Ok(0) => return RangeRequest::Unsatisfiable,
+            Ok(suffix) => ByteRange {
+                start: length.saturating_sub(suffix),
+                end: length.saturating_sub(1),
+            },
+            Err(_) => return RangeRequest::Full,
+        },
+        (start, end) => {
+            let Ok(start) = start.parse::<u64>() else {
+                return RangeRequest::Full;
+            };
+            let end = match end {
+                "" => length.saturating_sub(1),
+                end => match end.parse::<u64>() {
+                    Ok(end) if end >= start => end.min(length.saturating_sub(1)),
+                    _ => return RangeRequest::Full,
+                },
+            };
+            ByteRange { start, end }
+        }
+    };
+
+    if length == 0 || range.start >= length {
+        RangeRequest::Unsatisfiable
+    } else {
+        RangeRequest::Partial(range)
+    }
+}
+
+/// Check an `If-None-Match` or `If-Range` header against an entity tag
+///
+/// Weak comparison is used, as RFC 9110 requires for `If-None-Match`.
+///
+/// # Arguments
+///
+/// * `header` - Header value (`*` or a list of entity tags)
+/// * `etag` - Current entity tag, quoted
+///
+/// # Returns
+///
+/// Whether any listed tag matches
+pub fn etag_matches(header: &str, etag: &str) -> bool {
+    let etag = etag.trim_start_matches("W/");
+    header
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+/// Whether a file of this type may be displayed inline
+///
+/// # Arguments
+///
+/// * `mime_type` - Stored MIME type
+///
+/// # Returns
+///
+/// True for types on the inline allowlist
+pub fn inline_allowed(mime_type: &str) -> bool {
+    INLINE_MIME_TYPES.contains(&mime_type)
+}
+
+/// `Content-Disposition` value for a download
+///
+/// Includes an ASCII `filename` fallback and the original name as an
+/// RFC 5987 `filename*` parameter.
+///
+/// # Arguments
+///
+/// * `filename` - Original filename
+/// * `inline` - Display in the browser instead of saving
+///
+/// # Returns
+///
+/// Header value
+pub fn content_disposition(filename: &str, inline: bool) -> String {
+    let fallback: String = filename
+        .chars()
+        .map(|c| if (c.is_ascii_graphic() && c != '"' && c != '\\') || c == ' ' { c } else { '_' })
+        .collect();
+
+    let mut encoded = String::new();
+    for byte in filename.bytes() {
+        if byte.is_ascii_alphanumeric() || ATTR_CHARS.contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    format!(
+        "{}; filename=\"{}\"; filename*=UTF-8''{}",
+        if inline { "inline" } else { "attachment" },
+        fallback,
+        encoded
+    )
+}
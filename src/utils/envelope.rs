@@ -0,0 +1,232 @@
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use sha2::{Digest, Sha256};
+
+use crate::error::{AppError, AppResult};
+
+/// Length of an AES-GCM nonce, prefixed to every ciphertext
+const NONCE_LEN: usize = 12;
+
+/// Master keys that wrap the per-file data keys
+///
+/// The first key is active and wraps new data keys; the others are retired
+/// keys kept only to unwrap data keys until they are rotated.
+#[derive(Clone)]
+pub struct MasterKeyRing {
+    keys: Vec<MasterKey>,
+}
+
+/// AES-256 master key
+#[derive(Clone)]
+struct MasterKey {
+    /// Fingerprint identifying the key in stored metadata
+    id: String,
+
+    cipher: Aes256Gcm,
+}
+
+/// Data key of one stored file
+pub struct DataKey {
+    cipher: Aes256Gcm,
+}
+
+/// Data key wrapped by a master key, as stored with the document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrappedDataKey {
+    /// Fingerprint of the wrapping master key
+    pub key_id: String,
+
+    /// Nonce and encrypted data key (hex)
+    pub wrapped: String,
+}
+
+impl WrappedDataKey {
+    /// Combine the stored encryption columns
+    ///
+    /// # Arguments
+    ///
+    /// * `key_id` - Master key fingerprint
+    /// * `wrapped` - Wrapped data key (hex)
+    ///
+    /// # Returns
+    ///
+    /// Wrapped key, None for files stored unencrypted
+    pub fn from_parts(key_id: Option<&str>, wrapped: Option<&str>) -> Option<Self> {
+        Some(Self {
+            key_id: key_id?.to_string(),
+            wrapped: wrapped?.to_string(),
+        })
+    }
+}
+
+impl MasterKeyRing {
+    /// Parse master keys
+    ///
+    /// Keys are 64 hex digits (256 bits), separated by commas or new lines;
+    /// blank lines and `#` comments are ignored. The first key is active.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Key list
+    ///
+    /// # Returns
+    ///
+    /// Key ring
+    ///
+    /// # Errors
+    ///
+    /// Returns validation error if no key is given or a key is malformed
+    pub fn parse(text: &str) -> AppResult<Self> {
+        let keys = text
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default())
+            .flat_map(|line| line.split(','))
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(|key| {
+                let bytes = hex::decode(key)
+                    .ok()
+                    .filter(|bytes| bytes.len() == 32)
+                    .ok_or_else(|| AppError::Validation("Master keys must be 64 hex digits".to_string()))?;
+                Ok(MasterKey {
+                    id: hex::encode(&Sha256::digest(&bytes)[..8]),
+                    cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&bytes)),
+                })
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+
+        if keys.is_empty() {
+            return Err(AppError::Validation("No master key given".to_string()));
+        }
+
+        Ok(Self { keys })
+    }
+
+    /// Fingerprint of the key that wraps new data keys
+    pub fn active_key_id(&self) -> &str {
+        &self.keys[0].id
+    }
+
+    /// Create a data key for a new file
+    ///
+    /// # Returns
+    ///
+    /// Data key and its wrapped form to store
+    ///
+    /// # Errors
+    ///
+    /// Returns internal error if wrapping fails
+    pub fn generate_data_key(&self) -> AppResult<(DataKey, WrappedDataKey)> {
+        let key = Aes256Gcm::generate_key(OsRng);
+        let wrapped = self.wrap(&key)?;
+
+        Ok((DataKey { cipher: Aes256Gcm::new(&key) }, wrapped))
+    }
+
+    /// Recover the data key of a stored file
+    ///
+    /// # Arguments
+    ///
+    /// * `wrapped` - Stored wrapped key
+    ///
+    /// # Errors
+    ///
+    /// Returns internal error if the master key is not configured or the
+    /// wrapped key is corrupt
+    pub fn unwrap(&self, wrapped: &WrappedDataKey) -> AppResult<DataKey> {
+        let key = self.unwrap_key(wrapped)?;
+
+        Ok(DataKey { cipher: Aes256Gcm::new(&key) })
+    }
+
+    /// Wrap a data key with the active master key
+    ///
+    /// # Arguments
+    ///
+    /// * `wrapped` - Data key wrapped by any configured master key
+    ///
+    /// # Returns
+    ///
+    /// The same data key wrapped by the active master key
+    ///
+    /// # Errors
+    ///
+    /// Returns internal error if the data key cannot be unwrapped
+    pub fn rewrap(&self, wrapped: &WrappedDataKey) -> AppResult<WrappedDataKey> {
+        self.wrap(&self.unwrap_key(wrapped)?)
+    }
+
+    fn wrap(&self, key: &Key<Aes256Gcm>) -> AppResult<WrappedDataKey> {
+        let master = &self.keys[0];
+
+        Ok(WrappedDataKey {
+            key_id: master.id.clone(),
+            wrapped: hex::encode(seal(&master.cipher, key)?),
+        })
+    }
+
+    fn unwrap_key(&self, wrapped: &WrappedDataKey) -> AppResult<Key<Aes256Gcm>> {
+        let master = self
+            .keys
+            .iter()
+            .find(|master| master.id == wrapped.key_id)
+            .ok_or_else(|| AppError::Internal(format!("Master key {} is not configured", wrapped.key_id)))?;
+        let sealed = hex::decode(&wrapped.wrapped)
+            .map_err(|_| AppError::Internal("Wrapped data key is not valid hex".to_string()))?;
+        let key = open(&master.cipher, &sealed)?;
+        if key.len() != 32 {
+            return Err(AppError::Internal("Wrapped data key has an invalid length".to_string()));
+        }
+
+        Ok(*Key::<Aes256Gcm>::from_slice(&key))
+    }
+}
+
+impl DataKey {
+    /// Encrypt file content
+    ///
+    /// # Returns
+    ///
+    /// Nonce followed by the ciphertext and authentication tag
+    ///
+    /// # Errors
+    ///
+    /// Returns internal error if encryption fails
+    pub fn encrypt(&self, plaintext: &[u8]) -> AppResult<Vec<u8>> {
+        seal(&self.cipher, plaintext)
+    }
+
+    /// Decrypt file content written by [`DataKey::encrypt`]
+    ///
+    /// # Errors
+    ///
+    /// Returns internal error if the content was altered or the key is wrong
+    pub fn decrypt(&self, sealed: &[u8]) -> AppResult<Vec<u8>> {
+        open(&self.cipher, sealed)
+    }
+}
+
+fn seal(cipher: &Aes256Gcm, plaintext: &[u8]) -> AppResult<Vec<u8>> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| AppError::Internal("Encryption failed".to_string()))?;
+
+    let mut sealed = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(&nonce);
+    sealed.extend(ciphertext);
+    Ok(sealed)
+}
+
+fn open(cipher: &Aes256Gcm, sealed: &[u8]) -> AppResult<Vec<u8>> {
+    if sealed.len() < NONCE_LEN {
+        return Err(AppError::Internal("Encrypted content is truncated".to_string()));
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| AppError::Internal("Decryption failed: content altered or wrong key".to_string()))
+}
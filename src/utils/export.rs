@@ -0,0 +1,175 @@
+use chrono::NaiveDate;
+use futures_util::{stream::BoxStream, StreamExt, TryStreamExt};
+use rust_xlsxwriter::{Format, Workbook};
+use serde_json::Value;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{ExportFormat, ExportRecord, ExportRowStream},
+};
+
+/// Rows encoded per CSV or JSON chunk
+const ROWS_PER_CHUNK: usize = 200;
+
+/// Leading characters that make spreadsheet applications evaluate a cell
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// Encode exported rows in the requested format
+///
+/// CSV and JSON are produced chunk by chunk as rows arrive. XLSX is a zip
+/// archive that can only be written once complete, so it is assembled in
+/// memory and emitted as a single chunk.
+///
+/// # Arguments
+///
+/// * `rows` - Rows streamed from the database
+/// * `format` - Output format
+///
+/// # Returns
+///
+/// Stream of encoded chunks
+pub fn encode<T: ExportRecord>(rows: ExportRowStream<T>, format: ExportFormat) -> BoxStream<'static, AppResult<Vec<u8>>> {
+    match format {
+        ExportFormat::Csv => encode_csv(rows),
+        ExportFormat::Json => encode_json(rows),
+        ExportFormat::Xlsx => futures_util::stream::once(encode_xlsx(rows)).boxed(),
+    }
+}
+
+/// File name for an export, e.g. `risk-scores-2026-03-01.xlsx`
+///
+/// # Arguments
+///
+/// * `name` - File name stem
+/// * `format` - Output format
+/// * `date` - Export date
+///
+/// # Returns
+///
+/// File name
+pub fn export_filename(name: &str, format: ExportFormat, date: NaiveDate) -> String {
+    format!("{}-{}.{}", name, date.format("%Y-%m-%d"), format.extension())
+}
+
+/// Encode rows as CSV with a header line
+fn encode_csv<T: ExportRecord>(mut rows: ExportRowStream<T>) -> BoxStream<'static, AppResult<Vec<u8>>> {
+    async_stream::try_stream! {
+        let mut records: Vec<Vec<String>> = vec![T::COLUMNS.iter().map(|c| c.to_string()).collect()];
+        while let Some(row) = rows.try_next().await? {
+            records.push(cells(&row, T::COLUMNS)?.iter().map(csv_text).collect());
+
+            if records.len() == ROWS_PER_CHUNK {
+                yield csv_chunk(&std::mem::take(&mut records))?;
+            }
+        }
+
+        yield csv_chunk(&records)?;
+    }
+    .boxed()
+}
+
+/// Write records as CSV lines
+fn csv_chunk(records: &[Vec<String>]) -> AppResult<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for record in records {
+        writer.write_record(record).map_err(csv_error)?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| AppError::Internal(format!("Failed to write CSV: {}", e)))
+}
+
+/// Encode rows as a JSON array
+fn encode_json<T: ExportRecord>(mut rows: ExportRowStream<T>) -> BoxStream<'static, AppResult<Vec<u8>>> {
+    async_stream::try_stream! {
+        let mut chunk = b"[".to_vec();
+        let mut count = 0;
+        while let Some(row) = rows.try_next().await? {
+            if count > 0 {
+                chunk.push(b',');
+            }
+            chunk.push(b'\n');
+            serde_json::to_writer(&mut chunk, &row).map_err(json_error)?;
+
+            count += 1;
+            if count % ROWS_PER_CHUNK == 0 {
+                yield std::mem::take(&mut chunk);
+            }
+        }
+
+        chunk.extend_from_slice(b"\n]\n");
+        yield chunk;
+    }
+    .boxed()
+}
+
+/// Build an XLSX workbook with a bold, frozen header row
+async fn encode_xlsx<T: ExportRecord>(mut rows: ExportRowStream<T>) -> AppResult<Vec<u8>> {
+    let mut workbook = Workbook::new();
+    let header = Format::new().set_bold();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(T::NAME).map_err(xlsx_error)?;
+    sheet.set_freeze_panes(1, 0).map_err(xlsx_error)?;
+
+    for (col, name) in T::COLUMNS.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, *name, &header).map_err(xlsx_error)?;
+    }
+
+    let mut row_index: u32 = 1;
+    while let Some(row) = rows.try_next().await? {
+        for (col, value) in cells(&row, T::COLUMNS)?.iter().enumerate() {
+            let col = col as u16;
+            match value {
+                Value::Null => {}
+                Value::Bool(b) => {
+                    sheet.write_boolean(row_index, col, *b).map_err(xlsx_error)?;
+                }
+                Value::Number(n) => {
+                    sheet.write_number(row_index, col, n.as_f64().unwrap_or_default()).map_err(xlsx_error)?;
+                }
+                Value::String(s) => {
+                    sheet.write_string(row_index, col, s).map_err(xlsx_error)?;
+                }
+                other => {
+                    sheet.write_string(row_index, col, other.to_string()).map_err(xlsx_error)?;
+                }
+            }
+        }
+        row_index += 1;
+    }
+
+    sheet.autofit();
+    workbook.save_to_buffer().map_err(xlsx_error)
+}
+
+/// Values of a row in column order
+fn cells<T: ExportRecord>(row: &T, columns: &[&str]) -> AppResult<Vec<Value>> {
+    let value = serde_json::to_value(row).map_err(json_error)?;
+    Ok(columns.iter().map(|c| value.get(*c).cloned().unwrap_or(Value::Null)).collect())
+}
+
+/// CSV text of a value, neutralizing cells a spreadsheet would run as a formula
+fn csv_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) if s.starts_with(FORMULA_PREFIXES) => format!("'{}", s),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Report a CSV writer failure
+fn csv_error(e: csv::Error) -> AppError {
+    AppError::Internal(format!("Failed to write CSV: {}", e))
+}
+
+/// Report a row serialization failure
+fn json_error(e: serde_json::Error) -> AppError {
+    AppError::Internal(format!("Failed to serialize export row: {}", e))
+}
+
+/// Report a workbook failure
+fn xlsx_error(e: rust_xlsxwriter::XlsxError) -> AppError {
+    AppError::Internal(format!("Failed to write XLSX: {}", e))
+}
@@ -1,5 +1,5 @@
 use crate::error::{AppError, AppResult};
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use uuid::Uuid;
 
 /// File upload result
@@ -11,8 +11,8 @@ pub struct UploadedFile {
     /// Stored filename (UUID-based)
     pub stored_name: String,
     
-    /// Full file path
-    pub file_path: PathBuf,
+    /// Key of the file in the storage backend
+    pub storage_key: String,
     
     /// File size in bytes
     pub size: u64,
@@ -66,89 +66,36 @@ pub fn validate_mime_type(mime_type: &str) -> AppResult<()> {
         "text/plain",
         "text/csv",
         "application/json",
+        "image/png",
+        "image/jpeg",
+        "image/tiff",
     ];
 
     if !ALLOWED_TYPES.contains(&mime_type) {
         return Err(AppError::Validation(format!(
-            "File type '{}' not allowed. Allowed types: PDF, DOCX, DOC, TXT, CSV, JSON",
+            "File type '{}' not allowed. Allowed types: PDF, DOCX, DOC, TXT, CSV, JSON, PNG, JPEG, TIFF",
             mime_type
         )));
     }
     Ok(())
 }
 
-/// Generate secure file path
+/// Generate a unique storage key for an uploaded file
 ///
 /// # Arguments
 ///
-/// * `upload_dir` - Base upload directory
-/// * `original_filename` - Original filename
+/// * `original_filename` - Original filename (only its extension is kept)
 ///
 /// # Returns
 ///
-/// Tuple of (stored_filename, full_path)
-///
-/// # Errors
-///
-/// Returns error if path operations fail
-pub fn generate_file_path(upload_dir: &str, original_filename: &str) -> AppResult<(String, PathBuf)> {
-    // Create upload directory if it doesn't exist
-    std::fs::create_dir_all(upload_dir)
-        .map_err(|e| AppError::Internal(format!("Failed to create upload directory: {}", e)))?;
-
+/// Storage key (`<uuid>.<extension>`)
+pub fn generate_storage_key(original_filename: &str) -> String {
     // Extract extension
     let extension = Path::new(original_filename)
         .extension()
         .and_then(|e| e.to_str())
+        .filter(|e| e.chars().all(|c| c.is_ascii_alphanumeric()))
         .unwrap_or("bin");
 
-    // Generate unique filename
-    let stored_filename = format!("{}. {}", Uuid::new_v4(), extension);
-    let full_path = Path::new(upload_dir).join(&stored_filename);
-
-    Ok((stored_filename, full_path))
-}
-
-/// Save file bytes to disk
-///
-/// # Arguments
-///
-/// * `file_path` - Path to save file
-/// * `bytes` - File contents
-///
-/// # Returns
-///
-/// Number of bytes written
-///
-/// # Errors
-///
-/// Returns error if file write fails
-pub async fn save_file(file_path: &Path, bytes: &[u8]) -> AppResult<u64> {
-    tokio::fs::write(file_path, bytes)
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to save file: {}", e)))?;
-    
-    Ok(bytes.len() as u64)
-}
-
-/// Delete file from disk
-///
-/// # Arguments
-///
-/// * `file_path` - Path to file to delete
-///
-/// # Returns
-///
-/// Ok if successful
-///
-/// # Errors
-///
-/// Returns error if deletion fails
-pub async fn delete_file(file_path: &Path) -> AppResult<()> {
-    if file_path.exists() {
-        tokio::fs::remove_file(file_path)
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to delete file: {}", e)))?;
-    }
-    Ok(())
+    format!("{}.{}", Uuid::new_v4(), extension.to_lowercase())
 }
@@ -0,0 +1,88 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::models::ComplianceItem;
+
+/// Maximum length of a content line in octets (RFC 5545, section 3.1)
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Render compliance due dates as an iCalendar (RFC 5545) document
+///
+/// Each item with a due date becomes an all-day event on that date (UTC).
+///
+/// # Arguments
+///
+/// * `calendar_name` - Display name of the calendar
+/// * `items` - Compliance items
+/// * `now` - Generation time (DTSTAMP)
+///
+/// # Returns
+///
+/// iCalendar document with CRLF line endings
+pub fn compliance_calendar(calendar_name: &str, items: &[ComplianceItem], now: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//ParseGuard//Compliance Due Dates//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(calendar_name)),
+    ];
+
+    for item in items {
+        let Some(due) = item.due_date else { continue };
+        let date = due.date_naive();
+
+        let mut description = format!("Status: {}\nRisk level: {}", item.status, item.risk_level);
+        if let Some(framework) = &item.framework {
+            description.push_str(&format!("\nFramework: {}", framework));
+        }
+        if let Some(details) = &item.description {
+            description.push_str(&format!("\n\n{}", details));
+        }
+
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}@parseguard", item.id),
+            format!("DTSTAMP:{}", now.format("%Y%m%dT%H%M%SZ")),
+            format!("LAST-MODIFIED:{}", item.updated_at.format("%Y%m%dT%H%M%SZ")),
+            format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")),
+            format!("DTEND;VALUE=DATE:{}", (date + Duration::days(1)).format("%Y%m%d")),
+            format!("SUMMARY:{}", escape_text(&format!("Due: {}", item.title))),
+            format!("DESCRIPTION:{}", escape_text(&description)),
+            format!("CATEGORIES:{}", escape_text(&item.risk_level)),
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_line(line) + "\r\n").collect()
+}
+
+/// Escape a TEXT property value
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line longer than 75 octets without splitting characters
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / MAX_LINE_OCTETS * 3);
+    let mut line_octets = 0;
+
+    for c in line.chars() {
+        if line_octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the continuation line
+            line_octets = 1;
+        }
+        folded.push(c);
+        line_octets += c.len_utf8();
+    }
+
+    folded
+}
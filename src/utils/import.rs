@@ -0,0 +1,202 @@
+use std::io::Cursor;
+
+use calamine::{open_workbook_from_rs, Data, Reader, Xlsx};
+use chrono::{NaiveDate, TimeDelta};
+
+use crate::error::{AppError, AppResult};
+
+/// Maximum number of data rows per import
+pub const MAX_IMPORT_ROWS: usize = 5000;
+
+/// Spreadsheet read into text cells
+#[derive(Debug, Clone)]
+pub struct ImportTable {
+    /// Column headers
+    pub headers: Vec<String>,
+
+    /// Data rows, padded to the header width
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Read an uploaded CSV or XLSX file
+///
+/// XLSX files are recognized by their zip signature; anything else is read
+/// as UTF-8 CSV. Only the first worksheet of a workbook is imported and
+/// fully empty rows are skipped.
+///
+/// # Arguments
+///
+/// * `bytes` - File content
+///
+/// # Returns
+///
+/// Headers and rows
+///
+/// # Errors
+///
+/// Returns validation error for unreadable files, a missing header row,
+/// duplicate headers or more than [`MAX_IMPORT_ROWS`] rows
+pub fn parse_table(bytes: &[u8]) -> AppResult<ImportTable> {
+    let mut lines = if bytes.starts_with(b"PK\x03\x04") {
+        read_xlsx(bytes)?
+    } else {
+        read_csv(bytes)?
+    }
+    .into_iter()
+    .filter(|row| row.iter().any(|cell| !cell.trim().is_empty()));
+
+    let headers: Vec<String> = lines
+        .next()
+        .ok_or_else(|| AppError::Validation("The file has no header row".to_string()))?
+        .into_iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+
+    for (i, header) in headers.iter().enumerate() {
+        if !header.is_empty() && headers[..i].contains(header) {
+            return Err(AppError::Validation(format!("Duplicate column '{}'", header)));
+        }
+    }
+
+    let mut rows = Vec::new();
+    for mut row in lines {
+        if rows.len() == MAX_IMPORT_ROWS {
+            return Err(AppError::Validation(format!(
+                "Imports are limited to {} rows",
+                MAX_IMPORT_ROWS
+            )));
+        }
+        row.resize(headers.len(), String::new());
+        rows.push(row);
+    }
+
+    Ok(ImportTable { headers, rows })
+}
+
+/// Read all records of a CSV file
+fn read_csv(bytes: &[u8]) -> AppResult<Vec<Vec<String>>> {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(bytes);
+
+    reader
+        .records()
+        .map(|record| {
+            record
+                .map(|r| r.iter().map(str::to_string).collect())
+                .map_err(|e| AppError::Validation(format!("Invalid CSV: {}", e)))
+        })
+        .collect()
+}
+
+/// Read the first worksheet of an XLSX workbook
+fn read_xlsx(bytes: &[u8]) -> AppResult<Vec<Vec<String>>> {
+    let mut workbook: Xlsx<_> = open_workbook_from_rs(Cursor::new(bytes))
+        .map_err(|e| AppError::Validation(format!("Invalid XLSX file: {}", e)))?;
+
+    let range = workbook
+        .worksheet_range_at(0)
+        .ok_or_else(|| AppError::Validation("The workbook has no worksheet".to_string()))?
+        .map_err(|e| AppError::Validation(format!("Invalid XLSX file: {}", e)))?;
+
+    Ok(range.rows().map(|row| row.iter().map(cell_text).collect()).collect())
+}
+
+/// Text of a worksheet cell; dates become `YYYY-MM-DD` (or RFC 3339 with a time)
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty => String::new(),
+        Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => s.clone(),
+        Data::Int(i) => i.to_string(),
+        Data::Float(f) if f.fract() == 0.0 && f.abs() < 1e15 => format!("{}", *f as i64),
+        Data::Float(f) => f.to_string(),
+        Data::Bool(b) => b.to_string(),
+        Data::DateTime(dt) => excel_date_text(dt.as_f64()),
+        Data::Error(e) => format!("#{:?}", e),
+    }
+}
+
+/// Last serial date Excel can display (9999-12-31)
+const MAX_EXCEL_SERIAL: f64 = 2_958_465.0;
+
+/// Convert an Excel serial date (days since 1899-12-30) to text
+///
+/// Serials outside Excel's date range are returned as the plain number so
+/// the row fails validation instead of carrying an invented date.
+fn excel_date_text(serial: f64) -> String {
+    if !(0.0..MAX_EXCEL_SERIAL + 1.0).contains(&serial) {
+        return cell_text(&Data::Float(serial));
+    }
+
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30).expect("valid date");
+    let seconds = (serial * 86_400.0).round() as i64;
+    let datetime = TimeDelta::try_seconds(seconds)
+        .and_then(|offset| epoch.and_hms_opt(0, 0, 0).expect("valid time").checked_add_signed(offset));
+    let Some(datetime) = datetime else {
+        return cell_text(&Data::Float(serial));
+    };
+
+    if seconds % 86_400 == 0 {
+        datetime.date().format("%Y-%m-%d").to_string()
+    } else {
+        datetime.and_utc().to_rfc3339()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn excel_dates_become_iso_text() {
+        assert_eq!("1899-12-30", excel_date_text(0.0));
+        assert_eq!("2026-03-01", excel_date_text(46_082.0));
+        assert_eq!("2026-03-01T12:00:00+00:00", excel_date_text(46_082.5));
+        assert_eq!("9999-12-31", excel_date_text(MAX_EXCEL_SERIAL));
+    }
+
+    #[test]
+    fn serials_outside_the_date_range_stay_numbers() {
+        assert_eq!("-1", excel_date_text(-1.0));
+        assert_eq!("2958466", excel_date_text(MAX_EXCEL_SERIAL + 1.0));
+        assert!(excel_date_text(1e300).starts_with("1000000"));
+        assert_eq!("NaN", excel_date_text(f64::NAN));
+    }
+
+    #[test]
+    fn csv_rows_are_trimmed_padded_and_skip_blank_lines() {
+        let table = parse_table(b"\xEF\xBB\xBF title , status\nAudit,open\n,\nReview\n").unwrap();
+
+        assert_eq!(vec!["title", "status"], table.headers);
+        assert_eq!(vec![vec!["Audit", "open"], vec!["Review", ""]], table.rows);
+    }
+
+    #[test]
+    fn xlsx_dates_are_read_as_text() {
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let sheet = workbook.add_worksheet();
+        let date = rust_xlsxwriter::ExcelDateTime::from_ymd(2026, 3, 1).unwrap();
+        let format = rust_xlsxwriter::Format::new().set_num_format("yyyy-mm-dd");
+        sheet.write(0, 0, "due_date").unwrap();
+        sheet.write(0, 1, "score").unwrap();
+        sheet.write_datetime_with_format(1, 0, &date, &format).unwrap();
+        sheet.write(1, 1, 12.0).unwrap();
+
+        let table = parse_table(&workbook.save_to_buffer().unwrap()).unwrap();
+
+        assert_eq!(vec!["due_date", "score"], table.headers);
+        assert_eq!(vec![vec!["2026-03-01", "12"]], table.rows);
+    }
+
+    #[test]
+    fn malformed_tables_are_rejected() {
+        assert!(matches!(parse_table(b""), Err(AppError::Validation(_))));
+        assert!(matches!(parse_table(b"title,title\nA,B\n"), Err(AppError::Validation(_))));
+        assert!(matches!(parse_table(b"PK\x03\x04broken"), Err(AppError::Validation(_))));
+
+        let too_many = format!("title\n{}", "row\n".repeat(MAX_IMPORT_ROWS + 1));
+        assert!(matches!(parse_table(too_many.as_bytes()), Err(AppError::Validation(_))));
+    }
+}
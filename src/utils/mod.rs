@@ -1,8 +1,18 @@
+pub mod download;
+pub mod envelope;
+pub mod export;
+pub mod import;
 pub mod file_handler;
+pub mod ical;
+pub mod net;
+pub mod pdf_text;
+pub mod pii;
+pub mod report;
+pub mod sigv4;
+pub mod text_diff;
 
 // Public API for when needed
 #[allow(unused_imports)]
 pub use file_handler::{ 
-    delete_file, generate_file_path, save_file, validate_file_size,
-    validate_mime_type, UploadedFile,
+    generate_storage_key, validate_file_size, validate_mime_type, UploadedFile,
 };
@@ -0,0 +1,135 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use reqwest::Url;
+
+use crate::error::{AppError, AppResult};
+
+/// Whether an address is reachable on the public internet
+///
+/// Loopback, private, shared (carrier-grade NAT), link-local, unique-local,
+/// multicast and unspecified addresses are not; IPv4-mapped IPv6 addresses
+/// are judged by their IPv4 address.
+///
+/// # Arguments
+///
+/// * `ip` - Address to check
+///
+/// # Returns
+///
+/// True for public unicast addresses
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(ip),
+            None => {
+                let first = ip.segments()[0];
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || first & 0xfe00 == 0xfc00
+                    || first & 0xffc0 == 0xfe80)
+            }
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || first == 0
+        || (first == 100 && second & 0xc0 == 64))
+}
+
+/// Resolve a host, failing if any of its addresses is not public
+///
+/// Hosts on the allowlist (compared case-insensitively, IP addresses as
+/// written) may resolve to any address.
+///
+/// # Arguments
+///
+/// * `host` - Host name or IP address (IPv6 without brackets)
+/// * `port` - Port to attach to the addresses
+/// * `allowed_hosts` - Hosts exempt from the check
+///
+/// # Returns
+///
+/// Resolved addresses
+///
+/// # Errors
+///
+/// Returns validation error if the host cannot be resolved or resolves to a non-public address
+pub async fn resolve_public_host(host: &str, port: u16, allowed_hosts: &[String]) -> AppResult<Vec<SocketAddr>> {
+    let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| AppError::Validation(format!("Host '{}' cannot be resolved: {}", host, e)))?
+        .collect();
+    if addresses.is_empty() {
+        return Err(AppError::Validation(format!("Host '{}' cannot be resolved", host)));
+    }
+
+    let allowed = allowed_hosts.iter().any(|allowed| allowed.eq_ignore_ascii_case(host));
+    if !allowed && addresses.iter().any(|address| !is_public_ip(address.ip())) {
+        return Err(AppError::Validation(format!(
+            "Host '{}' resolves to a private or local address",
+            host
+        )));
+    }
+
+    Ok(addresses)
+}
+
+/// Check that a URL points at a public host
+///
+/// # Arguments
+///
+/// * `url` - HTTP(S) URL
+/// * `allowed_hosts` - Hosts exempt from the check
+///
+/// # Errors
+///
+/// Returns validation error for URLs without a host or whose host is not public
+pub async fn ensure_public_url(url: &str, allowed_hosts: &[String]) -> AppResult<()> {
+    let url = Url::parse(url).map_err(|e| AppError::Validation(format!("Invalid URL: {}", e)))?;
+    let port = url.port_or_known_default().unwrap_or(80);
+    let host = url
+        .host_str()
+        .map(|host| host.trim_start_matches('[').trim_end_matches(']'))
+        .ok_or_else(|| AppError::Validation("The URL has no host".to_string()))?;
+
+    resolve_public_host(host, port, allowed_hosts).await.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_and_private_addresses_are_not_public() {
+        for ip in [
+            "127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "100.64.0.1", "0.0.0.0",
+            "::1", "::", "fd00::1", "fe80::1", "::ffff:127.0.0.1", "ff02::1",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn internet_addresses_are_public() {
+        for ip in ["93.184.216.34", "1.1.1.1", "2606:4700:4700::1111", "::ffff:8.8.8.8"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn allowlisted_hosts_may_be_local() {
+        assert!(ensure_public_url("http://127.0.0.1:8080/hook", &[]).await.is_err());
+        assert!(ensure_public_url("http://[::1]/hook", &[]).await.is_err());
+        assert!(ensure_public_url("http://127.0.0.1:8080/hook", &["127.0.0.1".to_string()]).await.is_ok());
+    }
+}
@@ -0,0 +1,30 @@
+use lopdf::Document;
+
+use crate::error::{AppError, AppResult};
+
+/// Extract the text layer of every page of a PDF
+///
+/// Scanned pages have no text layer and come back empty, as do pages whose
+/// content cannot be decoded.
+///
+/// # Arguments
+///
+/// * `pdf` - PDF content
+///
+/// # Returns
+///
+/// Text per page, in page order
+///
+/// # Errors
+///
+/// Returns validation error if the file is not a readable PDF
+pub fn pdf_page_texts(pdf: &[u8]) -> AppResult<Vec<String>> {
+    let document = Document::load_mem(pdf)
+        .map_err(|e| AppError::Validation(format!("The PDF cannot be read: {}", e)))?;
+
+    Ok(document
+        .get_pages()
+        .into_keys()
+        .map(|page| document.extract_text(&[page]).unwrap_or_default().trim().to_string())
+        .collect())
+}
@@ -0,0 +1,503 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::LazyLock,
+};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, AppResult};
+
+/// Kind of personal data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiCategory {
+    /// Name following a title (Mr, Ms, Dr, ...)
+    PersonName,
+
+    /// Email address
+    Email,
+
+    /// Phone number in international or trunk-prefixed form
+    Phone,
+
+    /// IBAN with a valid mod-97 check
+    Iban,
+
+    /// Payment card number with a valid Luhn check
+    PaymentCard,
+
+    /// US Social Security or UK National Insurance number
+    NationalId,
+
+    /// IPv4 address
+    IpAddress,
+}
+
+impl PiiCategory {
+    /// Convert PiiCategory to database string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PiiCategory::PersonName => "person_name",
+            PiiCategory::Email => "email",
+            PiiCategory::Phone => "phone",
+            PiiCategory::Iban => "iban",
+            PiiCategory::PaymentCard => "payment_card",
+            PiiCategory::NationalId => "national_id",
+            PiiCategory::IpAddress => "ip_address",
+        }
+    }
+
+    /// Label used in placeholders
+    fn label(&self) -> String {
+        self.as_str().to_uppercase()
+    }
+}
+
+/// How personal data is masked before text leaves the system
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PiiMode {
+    /// Replace values with numbered placeholders that are mapped back afterwards
+    Pseudonymize,
+
+    /// Replace values with their category only
+    Redact,
+
+    /// Send text unchanged
+    Off,
+}
+
+/// Personal data found in a text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiiMatch {
+    /// Kind of data
+    pub category: PiiCategory,
+
+    /// Byte offset of the first character
+    pub start: usize,
+
+    /// Byte offset after the last character
+    pub end: usize,
+}
+
+/// Text with its personal data masked
+#[derive(Debug, Clone)]
+pub struct MaskedText {
+    /// Text to send
+    pub text: String,
+
+    /// Categories found, in category order
+    pub categories: Vec<PiiCategory>,
+
+    /// Original value per placeholder key (`EMAIL_1`), pseudonymization only
+    originals: HashMap<String, String>,
+}
+
+/// A pattern and the check a candidate must pass
+struct Detector {
+    category: PiiCategory,
+    pattern: Regex,
+    validate: fn(&str) -> bool,
+}
+
+/// Detectors, most specific first; earlier matches win over overlapping later ones
+///
+/// Digits are ASCII only: `\d` would also match digits of other scripts.
+static DETECTORS: LazyLock<Vec<Detector>> = LazyLock::new(|| {
+    let detector = |category, pattern: &str, validate| Detector {
+        category,
+        pattern: Regex::new(pattern).expect("PII pattern is valid"),
+        validate,
+    };
+
+    vec![
+        detector(
+            PiiCategory::Email,
+            r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b",
+            |_| true,
+        ),
+        detector(PiiCategory::Iban, r"\b[A-Z]{2}[0-9]{2}(?: ?[A-Z0-9]){11,30}\b", valid_iban),
+        detector(PiiCategory::PaymentCard, r"\b[0-9](?:[ -]?[0-9]){12,18}\b", valid_payment_card),
+        detector(PiiCategory::NationalId, r"\b[0-9]{3}-[0-9]{2}-[0-9]{4}\b", valid_ssn),
+        detector(
+            PiiCategory::NationalId,
+            r"\b[A-CEGHJ-PR-TW-Z][A-CEGHJ-NPR-TW-Z] ?[0-9]{2} ?[0-9]{2} ?[0-9]{2} ?[A-D]\b",
+            valid_nino,
+        ),
+        detector(PiiCategory::IpAddress, r"\b[0-9]{1,3}(?:\.[0-9]{1,3}){3}\b", valid_ipv4),
+        detector(
+            PiiCategory::Phone,
+            r"(?:\+[0-9]{1,3}[ .-]?|\b0)(?:\([0-9]{1,5}\)[ .-]?)?[0-9](?:[ ./-]?[0-9]){5,13}\b",
+            valid_phone,
+        ),
+        detector(
+            PiiCategory::PersonName,
+            r"\b(?:Mr|Mrs|Ms|Miss|Mx|Dr|Prof|Herr|Frau|Mme)\.? (?:[A-Z](?:[\p{Ll}'-]+|\.) ?){1,3}\b",
+            |_| true,
+        ),
+    ]
+});
+
+/// Dates such as `01.02.2024` that the phone pattern also matches
+static DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[0-9]{1,2}[./-][0-9]{1,2}[./-][0-9]{2,4}$").expect("date pattern is valid"));
+
+/// Placeholders written by [`mask`], also when the model changed their case
+static PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\[([a-z_]+_\d+)\]").expect("placeholder pattern is valid"));
+
+/// Find personal data in a text
+///
+/// Candidates found by pattern are kept only if their checksum (IBAN,
+/// payment card) or structure (national IDs, IP addresses) is valid.
+///
+/// # Arguments
+///
+/// * `text` - Text to search
+///
+/// # Returns
+///
+/// Non-overlapping matches in text order
+pub fn detect(text: &str) -> Vec<PiiMatch> {
+    // Accepted matches keyed by start; they never overlap, so only the last
+    // one starting before a candidate ends can overlap it
+    let mut matches: BTreeMap<usize, PiiMatch> = BTreeMap::new();
+    for detector in DETECTORS.iter() {
+        for found in detector.pattern.find_iter(text) {
+            let (start, end) = (found.start(), found.end());
+            let value = found.as_str().trim_end();
+            let overlaps = matches.range(..end).next_back().is_some_and(|(_, m)| start < m.end);
+            if overlaps || !(detector.validate)(value) {
+                continue;
+            }
+            matches.insert(
+                start,
+                PiiMatch {
+                    category: detector.category,
+                    start,
+                    end: start + value.len(),
+                },
+            );
+        }
+    }
+
+    matches.into_values().collect()
+}
+
+/// Categories of personal data in a text
+///
+/// # Arguments
+///
+/// * `text` - Text to search
+///
+/// # Returns
+///
+/// Categories found, in category order
+pub fn categories(text: &str) -> Vec<PiiCategory> {
+    detect(text)
+        .into_iter()
+        .map(|m| m.category)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Categories of personal data in a text, as stored with documents
+///
+/// # Arguments
+///
+/// * `text` - Text to search
+///
+/// # Returns
+///
+/// Database names of the categories found
+pub fn category_names(text: &str) -> Vec<String> {
+    categories(text).iter().map(|category| category.as_str().to_string()).collect()
+}
+
+/// Categories of personal data in a text, searched on the blocking thread pool
+///
+/// For document text, which can be many megabytes long and would otherwise
+/// stall an async worker.
+///
+/// # Arguments
+///
+/// * `text` - Text to search
+///
+/// # Returns
+///
+/// Categories found, in category order
+///
+/// # Errors
+///
+/// Returns internal error if the search task fails
+pub async fn scan_categories(text: String) -> AppResult<Vec<PiiCategory>> {
+    tokio::task::spawn_blocking(move || categories(&text))
+        .await
+        .map_err(|e| AppError::Internal(format!("Personal data detection failed: {}", e)))
+}
+
+/// Database names of the categories of personal data in a text, searched on
+/// the blocking thread pool (see [`scan_categories`])
+///
+/// # Arguments
+///
+/// * `text` - Text to search
+///
+/// # Returns
+///
+/// Database names of the categories found
+///
+/// # Errors
+///
+/// Returns internal error if the search task fails
+pub async fn scan_category_names(text: String) -> AppResult<Vec<String>> {
+    let categories = scan_categories(text).await?;
+    Ok(categories.iter().map(|category| category.as_str().to_string()).collect())
+}
+
+/// Mask the personal data in a text
+///
+/// Pseudonymization replaces every distinct value with a numbered
+/// placeholder such as `[EMAIL_1]`, so the model can still tell values
+/// apart and [`MaskedText::reidentify`] can restore them in its answer.
+/// Redaction replaces values with `[EMAIL]`.
+///
+/// # Arguments
+///
+/// * `text` - Text to mask
+/// * `mode` - Masking mode
+///
+/// # Returns
+///
+/// Masked text with the categories found
+pub fn mask(text: &str, mode: PiiMode) -> MaskedText {
+    let mut masked = MaskedText {
+        text: String::with_capacity(text.len()),
+        categories: Vec::new(),
+        originals: HashMap::new(),
+    };
+    if mode == PiiMode::Off {
+        masked.text.push_str(text);
+        return masked;
+    }
+
+    let mut placeholders: HashMap<(PiiCategory, &str), String> = HashMap::new();
+    let mut counts: HashMap<PiiCategory, usize> = HashMap::new();
+    let mut found = BTreeSet::new();
+    let mut copied = 0;
+    for m in detect(text) {
+        let value = &text[m.start..m.end];
+        masked.text.push_str(&text[copied..m.start]);
+        copied = m.end;
+        found.insert(m.category);
+
+        if mode == PiiMode::Redact {
+            masked.text.push_str(&format!("[{}]", m.category.label()));
+            continue;
+        }
+        let placeholder = placeholders.entry((m.category, value)).or_insert_with(|| {
+            let count = counts.entry(m.category).or_default();
+            *count += 1;
+            let key = format!("{}_{}", m.category.label(), count);
+            masked.originals.insert(key.clone(), value.to_string());
+            format!("[{}]", key)
+        });
+        masked.text.push_str(placeholder);
+    }
+    masked.text.push_str(&text[copied..]);
+    masked.categories = found.into_iter().collect();
+
+    masked
+}
+
+impl MaskedText {
+    /// Put the original values back in place of the placeholders
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Text returned for the masked input (e.g. model output)
+    ///
+    /// # Returns
+    ///
+    /// Text with known placeholders replaced, unknown ones left as they are
+    pub fn reidentify(&self, text: &str) -> String {
+        if self.originals.is_empty() {
+            return text.to_string();
+        }
+
+        PLACEHOLDER
+            .replace_all(text, |caps: &regex::Captures| {
+                self.originals
+                    .get(&caps[1].to_uppercase())
+                    .cloned()
+                    .unwrap_or_else(|| caps[0].to_string())
+            })
+            .into_owned()
+    }
+}
+
+/// Digits of a candidate, ignoring separators
+fn digits(value: &str) -> Vec<u32> {
+    value.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+/// IBAN check: mod 97 of the rearranged number equals 1
+fn valid_iban(value: &str) -> bool {
+    let compact: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+    if !compact.is_ascii() || !(15..=34).contains(&compact.len()) {
+        return false;
+    }
+
+    let rearranged = compact[4..].chars().chain(compact[..4].chars());
+    let mut remainder = 0u32;
+    for c in rearranged {
+        let Some(n) = c.to_digit(36) else {
+            return false;
+        };
+        remainder = if n >= 10 { (remainder * 100 + n) % 97 } else { (remainder * 10 + n) % 97 };
+    }
+    remainder == 1
+}
+
+/// Payment card check: 13-19 digits passing the Luhn checksum
+fn valid_payment_card(value: &str) -> bool {
+    let digits = digits(value);
+    if !(13..=19).contains(&digits.len()) || digits.iter().all(|d| *d == digits[0]) {
+        return false;
+    }
+
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, d)| match i % 2 {
+            1 if *d * 2 > 9 => *d * 2 - 9,
+            1 => *d * 2,
+            _ => *d,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// US SSN structure: area not 000, 666 or 9xx, group not 00, serial not 0000
+fn valid_ssn(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('-').collect();
+    let [area, group, serial] = parts.as_slice() else {
+        return false;
+    };
+    *area != "000" && *area != "666" && !area.starts_with('9') && *group != "00" && *serial != "0000"
+}
+
+/// UK National Insurance number: prefixes that are never issued are rejected
+fn valid_nino(value: &str) -> bool {
+    let prefix: String = value.chars().take(2).collect();
+    !["BG", "GB", "NK", "KN", "TN", "NT", "ZZ"].contains(&prefix.as_str())
+}
+
+/// Dotted IPv4 address with octets up to 255, excluding version-like `0.x.y.z`
+fn valid_ipv4(value: &str) -> bool {
+    let octets: Vec<Option<u8>> = value.split('.').map(|octet| octet.parse().ok()).collect();
+    octets.iter().all(Option::is_some) && octets[0] != Some(0)
+}
+
+/// Phone numbers have 8-15 digits and are not dates
+fn valid_phone(value: &str) -> bool {
+    (8..=15).contains(&digits(value).len()) && !DATE.is_match(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iban_checks_mod_97() {
+        assert!(valid_iban("DE89 3704 0044 0532 0130 00"));
+        assert!(valid_iban("GB82WEST12345698765432"));
+        assert!(!valid_iban("DE89 3704 0044 0532 0130 01"));
+        assert!(!valid_iban("DE89 3704"));
+        assert!(!valid_iban("DE\u{967}\u{967}12345678901"));
+    }
+
+    #[test]
+    fn non_ascii_digits_are_not_identifiers() {
+        assert!(detect("Account DE\u{967}\u{967}12345678901 closed").is_empty());
+        assert!(categories("\u{967}\u{968}\u{969}-45-6789").is_empty());
+    }
+
+    #[test]
+    fn payment_card_checks_luhn() {
+        assert!(valid_payment_card("4111 1111 1111 1111"));
+        assert!(valid_payment_card("5500-0000-0000-0004"));
+        assert!(!valid_payment_card("4111 1111 1111 1112"));
+        assert!(!valid_payment_card("0000 0000 0000 0000"));
+        assert!(!valid_payment_card("4111 1111 11"));
+    }
+
+    #[test]
+    fn ssn_rejects_unissued_numbers() {
+        assert!(valid_ssn("123-45-6789"));
+        assert!(!valid_ssn("000-45-6789"));
+        assert!(!valid_ssn("666-45-6789"));
+        assert!(!valid_ssn("912-45-6789"));
+        assert!(!valid_ssn("123-00-6789"));
+        assert!(!valid_ssn("123-45-0000"));
+    }
+
+    #[test]
+    fn nino_rejects_unissued_prefixes() {
+        assert!(valid_nino("AB 12 34 56 C"));
+        assert!(!valid_nino("GB 12 34 56 C"));
+        assert!(!valid_nino("ZZ123456A"));
+        assert_eq!(vec![PiiCategory::NationalId], categories("NI number AB 12 34 56 C."));
+    }
+
+    #[test]
+    fn phone_needs_enough_digits_and_is_no_date() {
+        assert!(valid_phone("+44 20 7946 0958"));
+        assert!(valid_phone("030 1234567"));
+        assert!(!valid_phone("01.02.2024"));
+        assert!(!valid_phone("+1 234"));
+        assert!(categories("Due 01.02.2024").is_empty());
+    }
+
+    #[test]
+    fn pseudonymized_values_are_restored() {
+        let text = "Mail jane@example.com or jane@example.com, not joe@example.com";
+        let masked = mask(text, PiiMode::Pseudonymize);
+        assert_eq!("Mail [EMAIL_1] or [EMAIL_1], not [EMAIL_2]", masked.text);
+        assert_eq!(vec![PiiCategory::Email], masked.categories);
+        assert_eq!(
+            "Write to jane@example.com, joe@example.com and [EMAIL_3]",
+            masked.reidentify("Write to [email_1], [EMAIL_2] and [EMAIL_3]")
+        );
+    }
+
+    #[test]
+    fn redaction_and_off_keep_answers_unchanged() {
+        let text = "Pay DE89 3704 0044 0532 0130 00 from 10.0.0.1";
+        let redacted = mask(text, PiiMode::Redact);
+        assert_eq!("Pay [IBAN] from [IP_ADDRESS]", redacted.text);
+        assert_eq!(vec![PiiCategory::Iban, PiiCategory::IpAddress], redacted.categories);
+        assert_eq!("[IBAN_1]", redacted.reidentify("[IBAN_1]"));
+
+        let off = mask(text, PiiMode::Off);
+        assert_eq!(text, off.text);
+        assert!(off.categories.is_empty());
+    }
+
+    #[test]
+    fn many_matches_are_found_without_quadratic_overlap_checks() {
+        let text = "1.1.1.1 ".repeat(200_000);
+        let matches = detect(&text);
+        assert_eq!(200_000, matches.len());
+        assert!(matches.windows(2).all(|pair| pair[0].end <= pair[1].start));
+    }
+
+    #[test]
+    fn earlier_detectors_win_overlapping_matches() {
+        let matches = detect("Mail jane@10.0.0.1.example.com or call +44 20 7946 0958");
+        let categories: Vec<PiiCategory> = matches.iter().map(|m| m.category).collect();
+        assert_eq!(vec![PiiCategory::Email, PiiCategory::Phone], categories);
+    }
+}
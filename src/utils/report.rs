@@ -0,0 +1,491 @@
+use chrono::{DateTime, Utc};
+use printpdf::{
+    BuiltinFont, Color, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, Rect, Rgb,
+};
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{AuditReport, ComplianceExportRow},
+};
+
+/// A4 page width in mm
+const PAGE_WIDTH: f32 = 210.0;
+
+/// A4 page height in mm
+const PAGE_HEIGHT: f32 = 297.0;
+
+/// Page margin in mm
+const MARGIN: f32 = 18.0;
+
+/// Printable width in mm
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+
+/// Millimetres per typographic point
+const MM_PER_PT: f32 = 0.3528;
+
+/// Average Helvetica glyph width relative to the font size, used for wrapping
+const AVERAGE_GLYPH_WIDTH: f32 = 0.5;
+
+/// Body text size in points
+const BODY_SIZE: f32 = 9.0;
+
+/// Heatmap cell edge in mm
+const HEATMAP_CELL: f32 = 14.0;
+
+/// Width of the status breakdown bars in mm
+const BAR_WIDTH: f32 = 80.0;
+
+/// Render an audit report as a PDF
+///
+/// The report uses the built-in Helvetica fonts, so text is limited to the
+/// Windows-1252 character set; other characters are replaced with `?`.
+///
+/// # Arguments
+///
+/// * `report` - Report data
+///
+/// # Returns
+///
+/// PDF file content
+///
+/// # Errors
+///
+/// Returns internal error if the PDF cannot be written
+pub fn render_audit_report(report: &AuditReport) -> AppResult<Vec<u8>> {
+    let mut pdf = PdfWriter::new("Compliance Audit Report")?;
+
+    pdf.text("Compliance Audit Report", 20.0, true);
+    pdf.gap(1.0);
+    pdf.text(
+        &format!("Prepared for {} <{}>", report.prepared_for, report.prepared_for_email),
+        BODY_SIZE,
+        false,
+    );
+    pdf.text(&format!("Generated {}", timestamp(report.generated_at)), BODY_SIZE, false);
+
+    summary(&mut pdf, report);
+    status_breakdown(&mut pdf, report);
+    heatmap(&mut pdf, report);
+    register(&mut pdf, &report.items);
+    assessments(&mut pdf, report);
+    evidence(&mut pdf, report);
+
+    pdf.finish()
+}
+
+/// Register summary section
+fn summary(pdf: &mut PdfWriter, report: &AuditReport) {
+    pdf.heading("Register summary");
+
+    let overdue = report
+        .items
+        .iter()
+        .filter(|item| item.status != "completed" && item.due_date.is_some_and(|due| due < report.generated_at))
+        .count();
+    let mut frameworks: Vec<&str> = report.items.iter().filter_map(|item| item.framework.as_deref()).collect();
+    frameworks.sort_unstable();
+    frameworks.dedup();
+
+    pdf.row(&[("Compliance items", 45.0), (&report.items.len().to_string(), 0.0)], false);
+    pdf.row(&[("Overdue", 45.0), (&overdue.to_string(), 0.0)], false);
+    pdf.row(&[("Assessed items", 45.0), (&report.assessments.len().to_string(), 0.0)], false);
+    pdf.row(&[("Evidence documents", 45.0), (&report.evidence.len().to_string(), 0.0)], false);
+    pdf.row(
+        &[("Frameworks", 45.0), (&or_dash(&frameworks.join(", ")), 0.0)],
+        false,
+    );
+}
+
+/// Status breakdown section with proportional bars
+fn status_breakdown(pdf: &mut PdfWriter, report: &AuditReport) {
+    pdf.heading("Status breakdown");
+
+    let total = report.items.len().max(1) as f32;
+    for (status, count) in &report.status_counts {
+        pdf.ensure(6.0);
+        let share = *count as f32 / total;
+        let top = pdf.y;
+        pdf.fill(
+            Rect::new(
+                Mm(MARGIN + 45.0),
+                Mm(top - 3.8),
+                Mm(MARGIN + 45.0 + BAR_WIDTH * share),
+                Mm(top),
+            ),
+            (0.25, 0.45, 0.75),
+        );
+        pdf.row(
+            &[
+                (&label(status), 45.0 + BAR_WIDTH + 4.0),
+                (&format!("{} ({:.0}%)", count, share * 100.0), 0.0),
+            ],
+            false,
+        );
+        pdf.gap(1.0);
+    }
+}
+
+/// Likelihood × impact heatmap section
+fn heatmap(pdf: &mut PdfWriter, report: &AuditReport) {
+    pdf.heading("Risk heatmap");
+
+    let scale = report.matrix_scale.max(1);
+    pdf.ensure(HEATMAP_CELL * scale as f32 + 16.0);
+    pdf.text("Likelihood (rows, highest first) by impact (columns)", BODY_SIZE, false);
+    pdf.gap(2.0);
+
+    let left = MARGIN + 8.0;
+    let top = pdf.y;
+    for cell in &report.heatmap {
+        let x = left + (cell.impact - 1) as f32 * HEATMAP_CELL;
+        let y = top - (scale - cell.likelihood + 1) as f32 * HEATMAP_CELL;
+        pdf.fill(
+            Rect::new(Mm(x), Mm(y), Mm(x + HEATMAP_CELL - 0.6), Mm(y + HEATMAP_CELL - 0.6)),
+            level_color(&cell.risk_level),
+        );
+        if cell.count > 0 {
+            pdf.text_at(&cell.count.to_string(), 11.0, true, x + HEATMAP_CELL / 2.0 - 2.0, y + HEATMAP_CELL / 2.0 - 1.5);
+        }
+    }
+
+    for rating in 1..=scale {
+        let y = top - (scale - rating + 1) as f32 * HEATMAP_CELL + HEATMAP_CELL / 2.0 - 1.5;
+        pdf.text_at(&rating.to_string(), BODY_SIZE, false, MARGIN + 2.0, y);
+        let x = left + (rating - 1) as f32 * HEATMAP_CELL + HEATMAP_CELL / 2.0 - 1.0;
+        pdf.text_at(&rating.to_string(), BODY_SIZE, false, x, top - scale as f32 * HEATMAP_CELL - 5.0);
+    }
+
+    pdf.y = top - scale as f32 * HEATMAP_CELL - 9.0;
+    if report.unplotted_items > 0 {
+        pdf.text(
+            &format!(
+                "{} assessed item(s) have a manual score without likelihood and impact and are not plotted.",
+                report.unplotted_items
+            ),
+            BODY_SIZE,
+            false,
+        );
+    }
+}
+
+/// Compliance register section
+fn register(pdf: &mut PdfWriter, items: &[ComplianceExportRow]) {
+    pdf.heading("Compliance register");
+    if items.is_empty() {
+        pdf.text("No compliance items.", BODY_SIZE, false);
+    }
+
+    for item in items {
+        pdf.ensure(14.0);
+        pdf.text(&item.title, 10.0, true);
+        pdf.text(
+            &format!(
+                "Status: {} | Risk level: {} | Framework: {} | Due: {}",
+                label(&item.status),
+                label(&item.risk_level),
+                or_dash(item.framework.as_deref().unwrap_or_default()),
+                item.due_date.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_else(|| "-".to_string()),
+            ),
+            BODY_SIZE,
+            false,
+        );
+        pdf.text(
+            &format!(
+                "Assignee: {} | Tags: {}",
+                or_dash(item.assignee_name.as_deref().unwrap_or_default()),
+                or_dash(&item.tags),
+            ),
+            BODY_SIZE,
+            false,
+        );
+        if let Some(description) = item.description.as_deref().filter(|d| !d.trim().is_empty()) {
+            pdf.text(description, BODY_SIZE, false);
+        }
+        pdf.gap(2.0);
+    }
+}
+
+/// Latest assessment per item section
+fn assessments(pdf: &mut PdfWriter, report: &AuditReport) {
+    pdf.heading("Latest risk assessments");
+    if report.assessments.is_empty() {
+        pdf.text("No risk assessments.", BODY_SIZE, false);
+    }
+
+    for assessment in &report.assessments {
+        pdf.ensure(14.0);
+        pdf.text(&assessment.compliance_item_title, 10.0, true);
+
+        let rating = match (assessment.likelihood, assessment.impact) {
+            (Some(l), Some(i)) => format!(" | Likelihood {} x impact {}", l, i),
+            _ => String::new(),
+        };
+        pdf.text(
+            &format!(
+                "{}: score {} ({}) | Residual {} ({}){}",
+                assessment.risk_category,
+                assessment.risk_score,
+                assessment.risk_level,
+                assessment.residual_score,
+                assessment.residual_level,
+                rating,
+            ),
+            BODY_SIZE,
+            false,
+        );
+        pdf.text(
+            &format!(
+                "Assessed {} by {}",
+                assessment.assessment_date.format("%Y-%m-%d"),
+                or_dash(assessment.assessed_by.as_deref().unwrap_or_default()),
+            ),
+            BODY_SIZE,
+            false,
+        );
+        if let Some(reasoning) = assessment.ai_reasoning.as_deref().filter(|r| !r.trim().is_empty()) {
+            let confidence = assessment
+                .ai_confidence
+                .map(|c| format!(" (confidence {:.0}%)", c * 100.0))
+                .unwrap_or_default();
+            pdf.text(&format!("AI reasoning{}: {}", confidence, reasoning), BODY_SIZE, false);
+        }
+        if let Some(notes) = assessment.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+            pdf.text(&format!("Notes: {}", notes), BODY_SIZE, false);
+        }
+        pdf.gap(2.0);
+    }
+}
+
+/// Evidence list section
+fn evidence(pdf: &mut PdfWriter, report: &AuditReport) {
+    pdf.heading("Evidence");
+    if report.evidence.is_empty() {
+        pdf.text("No documents are linked to risk assessments.", BODY_SIZE, false);
+    }
+
+    for document in &report.evidence {
+        pdf.ensure(10.0);
+        pdf.text(&document.filename, BODY_SIZE, true);
+        pdf.text(
+            &format!(
+                "{} | {} | uploaded {} | supports: {}",
+                document.mime_type,
+                file_size(document.file_size),
+                document.uploaded_at.format("%Y-%m-%d"),
+                document.compliance_items.join(", "),
+            ),
+            BODY_SIZE,
+            false,
+        );
+        pdf.gap(1.5);
+    }
+}
+
+/// Page-flowing writer over a printpdf document
+struct PdfWriter {
+    /// Document being written
+    doc: PdfDocumentReference,
+
+    /// Current page layer
+    layer: PdfLayerReference,
+
+    /// Regular font
+    regular: IndirectFontRef,
+
+    /// Bold font
+    bold: IndirectFontRef,
+
+    /// Baseline of the next line in mm from the bottom edge
+    y: f32,
+
+    /// Number of pages so far
+    pages: usize,
+}
+
+impl PdfWriter {
+    /// Start a document with one page
+    fn new(title: &str) -> AppResult<Self> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Content");
+        let regular = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(pdf_error)?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold).map_err(pdf_error)?;
+        let layer = doc.get_page(page).get_layer(layer);
+
+        let writer = Self {
+            doc,
+            layer,
+            regular,
+            bold,
+            y: PAGE_HEIGHT - MARGIN,
+            pages: 1,
+        };
+        writer.footer();
+
+        Ok(writer)
+    }
+
+    /// Section heading, kept together with the first lines after it
+    fn heading(&mut self, text: &str) {
+        self.gap(5.0);
+        self.ensure(20.0);
+        self.text(text, 13.0, true);
+        self.gap(1.5);
+    }
+
+    /// Wrapped text at the left margin
+    fn text(&mut self, text: &str, size: f32, bold: bool) {
+        let line_height = size * MM_PER_PT * 1.35;
+        let max_chars = (CONTENT_WIDTH / (size * MM_PER_PT * AVERAGE_GLYPH_WIDTH)) as usize;
+
+        for line in wrap(&printable(text), max_chars) {
+            self.ensure(line_height);
+            self.y -= line_height;
+            self.text_at(&line, size, bold, MARGIN, self.y);
+        }
+    }
+
+    /// Single line of columns at fixed offsets (0 width means the rest of the line)
+    fn row(&mut self, columns: &[(&str, f32)], bold: bool) {
+        let line_height = BODY_SIZE * MM_PER_PT * 1.35;
+        self.ensure(line_height);
+        self.y -= line_height;
+
+        let mut x = MARGIN;
+        for (text, width) in columns {
+            self.text_at(&printable(text), BODY_SIZE, bold, x, self.y);
+            x += width;
+        }
+    }
+
+    /// Unwrapped text at an absolute position
+    fn text_at(&self, text: &str, size: f32, bold: bool, x: f32, y: f32) {
+        let font = if bold { &self.bold } else { &self.regular };
+        self.layer.use_text(printable(text), size, Mm(x), Mm(y), font);
+    }
+
+    /// Filled rectangle, restoring black text afterwards
+    fn fill(&self, rect: Rect, (r, g, b): (f32, f32, f32)) {
+        self.layer.set_fill_color(Color::Rgb(Rgb::new(r, g, b, None)));
+        self.layer.add_rect(rect);
+        self.layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+    }
+
+    /// Vertical space in mm
+    fn gap(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    /// Start a new page unless `height` mm still fit
+    fn ensure(&mut self, height: f32) {
+        if self.y - height < MARGIN + 6.0 {
+            let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Content");
+            self.layer = self.doc.get_page(page).get_layer(layer);
+            self.y = PAGE_HEIGHT - MARGIN;
+            self.pages += 1;
+            self.footer();
+        }
+    }
+
+    /// Page number at the bottom of the current page
+    fn footer(&self) {
+        self.text_at(&format!("Page {}", self.pages), 8.0, false, PAGE_WIDTH - MARGIN - 12.0, MARGIN - 8.0);
+    }
+
+    /// Serialize the document
+    fn finish(self) -> AppResult<Vec<u8>> {
+        self.doc.save_to_bytes().map_err(pdf_error)
+    }
+}
+
+/// Split text into lines of at most `max_chars` characters at word boundaries
+fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word = word.to_string();
+            while word.chars().count() > max_chars {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                let split = word.char_indices().nth(max_chars).map(|(i, _)| i).unwrap_or(word.len());
+                lines.push(word[..split].to_string());
+                word = word[split..].to_string();
+            }
+
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Replace characters the built-in fonts cannot encode
+fn printable(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\t' => ' ',
+            '\u{2018}' | '\u{2019}' => '\'',
+            '\u{201C}' | '\u{201D}' => '"',
+            '\u{2013}' | '\u{2014}' => '-',
+            '\u{00D7}' => 'x',
+            c if c == '\n' || (' '..='\u{00FF}').contains(&c) && !('\u{007F}'..'\u{00A0}').contains(&c) => c,
+            _ => '?',
+        })
+        .collect()
+}
+
+/// Fill colour of a risk level
+fn level_color(level: &str) -> (f32, f32, f32) {
+    match level {
+        "low" => (0.55, 0.80, 0.55),
+        "medium" => (0.98, 0.85, 0.40),
+        "high" => (0.96, 0.60, 0.30),
+        "critical" => (0.88, 0.32, 0.30),
+        _ => (0.85, 0.85, 0.85),
+    }
+}
+
+/// Human-readable label of a snake_case value
+fn label(value: &str) -> String {
+    let text = value.replace('_', " ");
+    let mut chars = text.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Value or a dash when empty
+fn or_dash(value: &str) -> String {
+    if value.trim().is_empty() {
+        "-".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Human-readable file size
+fn file_size(bytes: i64) -> String {
+    match bytes {
+        b if b >= 1024 * 1024 => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+        b if b >= 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b => format!("{} B", b),
+    }
+}
+
+/// Timestamp as `YYYY-MM-DD HH:MM UTC`
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M UTC").to_string()
+}
+
+/// Report a PDF writer failure
+fn pdf_error(e: printpdf::Error) -> AppError {
+    AppError::Internal(format!("Failed to write PDF: {}", e))
+}
@@ -0,0 +1,193 @@
+use similar::{capture_diff_slices, Algorithm, DiffTag};
+
+use crate::models::{ChangeKind, DiffGranularity, VersionChange};
+
+/// Split text into the passages it is compared in
+///
+/// Paragraphs are separated by blank lines. Whitespace inside a passage is
+/// collapsed, so re-wrapped text compares equal.
+///
+/// # Arguments
+///
+/// * `text` - Extracted text
+/// * `granularity` - Paragraphs or sentences
+///
+/// # Returns
+///
+/// Non-empty passages in document order
+pub fn segment(text: &str, granularity: DiffGranularity) -> Vec<String> {
+    let paragraphs = text
+        .replace("\r\n", "\n")
+        .split("\n\n")
+        .map(|paragraph| paragraph.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect::<Vec<_>>();
+
+    match granularity {
+        DiffGranularity::Paragraph => paragraphs,
+        DiffGranularity::Sentence => paragraphs.iter().flat_map(|paragraph| split_sentences(paragraph)).collect(),
+    }
+}
+
+/// Split a paragraph after `.`, `!` or `?` followed by a space
+fn split_sentences(paragraph: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let mut chars = paragraph.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        current.push(c);
+        if matches!(c, '.' | '!' | '?') && chars.peek().is_none_or(|next| *next == ' ') {
+            sentences.push(current.trim().to_string());
+            current.clear();
+        }
+    }
+    if !current.trim().is_empty() {
+        sentences.push(current.trim().to_string());
+    }
+
+    sentences
+}
+
+/// Compare two passage lists
+///
+/// Adjacent removals and additions are paired up as modifications, so a
+/// reworded sentence shows as one change rather than two.
+///
+/// # Arguments
+///
+/// * `before` - Passages of the older version
+/// * `after` - Passages of the newer version
+///
+/// # Returns
+///
+/// Number of unchanged passages and the changes in document order
+pub fn diff_segments(before: &[String], after: &[String]) -> (usize, Vec<VersionChange>) {
+    let mut unchanged = 0;
+    let mut changes = Vec::new();
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+
+    // Linear-space Myers diff, so long documents with many changes stay cheap in memory
+    for op in capture_diff_slices(Algorithm::Myers, before, after) {
+        let (tag, old, new) = op.as_tag_tuple();
+        match tag {
+            DiffTag::Equal => {
+                unchanged += old.len();
+                changes.extend(pair_up(before, after, &removed, &added, (old.start, new.start)));
+                removed.clear();
+                added.clear();
+            }
+            _ => {
+                removed.extend(old);
+                added.extend(new);
+            }
+        }
+    }
+    changes.extend(pair_up(before, after, &removed, &added, (before.len(), after.len())));
+
+    (unchanged, changes)
+}
+
+/// Turn one run of removals and additions into changes
+///
+/// `next` holds the indices of the passages following the run, which is
+/// where pure additions and removals sit in the other version.
+fn pair_up(
+    before: &[String],
+    after: &[String],
+    removed: &[usize],
+    added: &[usize],
+    next: (usize, usize),
+) -> Vec<VersionChange> {
+    (0..removed.len().max(added.len()))
+        .map(|n| {
+            let (i, j) = (removed.get(n).copied(), added.get(n).copied());
+            VersionChange {
+                kind: match (i, j) {
+                    (Some(_), Some(_)) => ChangeKind::Modified,
+                    (Some(_), None) => ChangeKind::Removed,
+                    _ => ChangeKind::Added,
+                },
+                before_index: i.unwrap_or(next.0),
+                after_index: j.unwrap_or(next.1),
+                before: i.map(|i| before[i].clone()),
+                after: j.map(|j| after[j].clone()),
+                classification: None,
+                rationale: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    #[test]
+    fn paragraphs_ignore_wrapping_and_blank_passages() {
+        let text = "First  line\r\nwrapped.\r\n\r\n\n\nSecond. Third?\n\n   ";
+
+        assert_eq!(strings(&["First line wrapped.", "Second. Third?"]), segment(text, DiffGranularity::Paragraph));
+        assert_eq!(
+            strings(&["First line wrapped.", "Second.", "Third?"]),
+            segment(text, DiffGranularity::Sentence)
+        );
+    }
+
+    #[test]
+    fn sentences_end_at_punctuation_followed_by_a_space() {
+        assert_eq!(
+            strings(&["Version 1.2 applies.", "Really?!", "Fees are 3.5% unless waived."]),
+            split_sentences("Version 1.2 applies. Really?! Fees are 3.5% unless waived.")
+        );
+        assert_eq!(strings(&["No end"]), split_sentences("No end"));
+        assert!(split_sentences("").is_empty());
+    }
+
+    #[test]
+    fn reworded_passages_pair_up_as_modifications() {
+        let before = strings(&["a", "b", "c", "d"]);
+        let after = strings(&["a", "B", "c", "d", "e"]);
+
+        let (unchanged, changes) = diff_segments(&before, &after);
+
+        assert_eq!(3, unchanged);
+        assert_eq!(2, changes.len());
+        assert_eq!(ChangeKind::Modified, changes[0].kind);
+        assert_eq!((1, 1), (changes[0].before_index, changes[0].after_index));
+        assert_eq!(Some("b"), changes[0].before.as_deref());
+        assert_eq!(Some("B"), changes[0].after.as_deref());
+        assert_eq!(ChangeKind::Added, changes[1].kind);
+        assert_eq!((4, 4), (changes[1].before_index, changes[1].after_index));
+    }
+
+    #[test]
+    fn removals_point_at_the_following_passage() {
+        let before = strings(&["a", "b", "c"]);
+        let after = strings(&["a", "c"]);
+
+        let (unchanged, changes) = diff_segments(&before, &after);
+
+        assert_eq!(2, unchanged);
+        assert_eq!(1, changes.len());
+        assert_eq!(ChangeKind::Removed, changes[0].kind);
+        assert_eq!((1, 1), (changes[0].before_index, changes[0].after_index));
+        assert!(changes[0].after.is_none());
+    }
+
+    #[test]
+    fn identical_and_empty_lists_have_no_changes() {
+        let text = strings(&["a", "b"]);
+
+        let (unchanged, changes) = diff_segments(&text, &text);
+        assert_eq!(2, unchanged);
+        assert!(changes.is_empty());
+        assert!(diff_segments(&[], &[]).1.is_empty());
+        assert_eq!(2, diff_segments(&[], &text).1.len());
+    }
+}
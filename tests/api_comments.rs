@@ -0,0 +1,79 @@
+use common::spawn_app;
+
+mod common;
+
+#[tokio::test]
+async fn comments_support_replies_mentions_and_edit_history() {
+    let app = spawn_app().await;
+    let other_email = app.register_and_login().await;
+    let email = app.register_and_login().await;
+    let item = app.create_compliance_item("Vendor due diligence").await;
+    let path = format!("/compliance/{}/comments", item["id"].as_str().unwrap());
+
+    // 1. Top-level comment mentioning ourselves
+    let response = app
+        .post_json(&path, &serde_json::json!({ "body": format!("Please review @{}", email) }))
+        .await;
+    assert_eq!(201, response.status().as_u16());
+    let comment: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(1, comment["mentions"].as_array().unwrap().len());
+    let comment_id = comment["id"].as_str().unwrap();
+
+    // 2. Reply
+    let response = app
+        .post_json(&path, &serde_json::json!({ "body": "Done", "parent_id": comment_id }))
+        .await;
+    assert_eq!(201, response.status().as_u16());
+
+    // 3. Edit keeps the previous body
+    let response = app
+        .put_json(&format!("{}/{}", path, comment_id), &serde_json::json!({ "body": "Reviewed" }))
+        .await;
+    assert_eq!(200, response.status().as_u16());
+    let history: serde_json::Value = app
+        .get(&format!("{}/{}/history", path, comment_id))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(1, history.as_array().unwrap().len());
+
+    // 4. Thread nests the reply
+    let threads: serde_json::Value = app.get(&path).await.json().await.unwrap();
+    let threads = threads.as_array().unwrap();
+    assert_eq!(1, threads.len());
+    assert_eq!("Reviewed", threads[0]["body"]);
+    assert_eq!(1, threads[0]["replies"].as_array().unwrap().len());
+
+    // 5. Delete keeps the reply attached
+    let response = app.delete(&format!("{}/{}", path, comment_id)).await;
+    assert_eq!(204, response.status().as_u16());
+    let threads: serde_json::Value = app.get(&path).await.json().await.unwrap();
+    assert_eq!(true, threads[0]["deleted"]);
+    assert_eq!(1, threads[0]["replies"].as_array().unwrap().len());
+
+    // 6. Other accounts are not resolved, so mentions reveal nobody's account
+    let response = app
+        .post_json(&path, &serde_json::json!({ "body": format!("FYI @{} @nobody@example.com", other_email) }))
+        .await;
+    assert_eq!(201, response.status().as_u16());
+    let comment: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(serde_json::json!([]), comment["mentions"]);
+}
+
+#[tokio::test]
+async fn comments_require_ownership_of_parent_entity() {
+    let owner = spawn_app().await;
+    owner.register_and_login().await;
+    let item = owner.create_compliance_item("Private item").await;
+    let path = format!("/compliance/{}/comments", item["id"].as_str().unwrap());
+
+    let other = spawn_app().await;
+    other.register_and_login().await;
+
+    let response = other.post_json(&path, &serde_json::json!({ "body": "Hello" })).await;
+    assert_eq!(404, response.status().as_u16());
+
+    let response = other.get(&path).await;
+    assert_eq!(404, response.status().as_u16());
+}
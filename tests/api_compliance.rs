@@ -0,0 +1,282 @@
+use chrono::{Duration, Utc};
+use common::spawn_app;
+
+mod common;
+
+#[tokio::test]
+async fn upcoming_groups_open_items_by_due_window() {
+    let app = spawn_app().await;
+    app.register_and_login().await;
+
+    let now = Utc::now();
+    for (title, status, due_in_days) in [
+        ("Renew certificate", "pending", -2),
+        ("Closed and late", "completed", -2),
+        ("Quarterly access review", "in_progress", 3),
+        ("Vendor assessment", "pending", 20),
+        ("Annual pen test", "pending", 90),
+    ] {
+        let body = serde_json::json!({
+            "title": title,
+            "risk_level": "high",
+            "status": status,
+            "due_date": now + Duration::days(due_in_days)
+        });
+        let response = app.post_json("/compliance", &body).await;
+        assert_eq!(201, response.status().as_u16());
+    }
+    app.create_compliance_item("No deadline").await;
+
+    let response = app.get("/compliance/upcoming").await;
+    assert_eq!(200, response.status().as_u16());
+    let upcoming: serde_json::Value = response.json().await.unwrap();
+
+    let titles = |group: &str| -> Vec<String> {
+        upcoming[group]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|i| i["title"].as_str().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(vec!["Renew certificate"], titles("overdue"));
+    assert_eq!(vec!["Quarterly access review"], titles("this_week"));
+    assert_eq!(vec!["Vendor assessment"], titles("this_month"));
+}
+
+#[tokio::test]
+async fn calendar_feed_is_served_by_secret_token() {
+    let app = spawn_app().await;
+    app.register_and_login().await;
+
+    let body = serde_json::json!({
+        "title": "Review DPA; sign, file",
+        "risk_level": "medium",
+        "status": "pending",
+        "due_date": "2026-03-15T09:00:00Z",
+        "framework": "GDPR"
+    });
+    let item: serde_json::Value = app.post_json("/compliance", &body).await.json().await.unwrap();
+    app.create_compliance_item("No deadline").await;
+
+    let feed: serde_json::Value = app.get("/compliance/calendar-feed").await.json().await.unwrap();
+    let token = feed["token"].as_str().unwrap().to_string();
+    assert_eq!(64, token.len());
+    assert_eq!(format!("/api/calendar/{}.ics", token), feed["feed_path"]);
+
+    // The token is stable until rotated
+    let again: serde_json::Value = app.get("/compliance/calendar-feed").await.json().await.unwrap();
+    assert_eq!(token, again["token"]);
+
+    let response = app.get(&format!("/calendar/{}.ics", token)).await;
+    assert_eq!(200, response.status().as_u16());
+    assert!(response.headers()["content-type"].to_str().unwrap().starts_with("text/calendar"));
+    let ics = response.text().await.unwrap();
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert_eq!(1, ics.matches("BEGIN:VEVENT").count());
+    assert!(ics.contains(&format!("UID:{}@parseguard", item["id"].as_str().unwrap())));
+    assert!(ics.contains("DTSTART;VALUE=DATE:20260315\r\n"));
+    assert!(ics.contains("DTEND;VALUE=DATE:20260316\r\n"));
+    assert!(ics.contains("SUMMARY:Due: Review DPA\\; sign\\, file\r\n"));
+    assert!(ics.lines().all(|line| line.len() <= 75));
+
+    let rotated: serde_json::Value = app
+        .post_json("/compliance/calendar-feed/rotate", &serde_json::json!({}))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_ne!(token, rotated["token"]);
+    assert_eq!(404, app.get(&format!("/calendar/{}.ics", token)).await.status().as_u16());
+
+    let response = app.delete("/compliance/calendar-feed").await;
+    assert_eq!(204, response.status().as_u16());
+    let path = format!("/calendar/{}.ics", rotated["token"].as_str().unwrap());
+    assert_eq!(404, app.get(&path).await.status().as_u16());
+}
+
+#[tokio::test]
+async fn compliance_register_exports_filtered_rows_with_joined_columns() {
+    let app = spawn_app().await;
+    app.register_and_login().await;
+
+    let tag: serde_json::Value = app
+        .post_json("/tags", &serde_json::json!({ "name": "Audit" }))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let body = serde_json::json!({
+        "title": "=HYPERLINK(\"http://evil\")",
+        "risk_level": "high",
+        "status": "pending",
+        "tag_ids": [tag["id"]]
+    });
+    let item: serde_json::Value = app.post_json("/compliance", &body).await.json().await.unwrap();
+    app.create_compliance_item("Untagged item").await;
+
+    let document: serde_json::Value = app
+        .post_json("/documents/text", &serde_json::json!({ "title": "Pen test", "content": "Findings" }))
+        .await
+        .json()
+        .await
+        .unwrap();
+    for (risk_score, document_id) in [(30, serde_json::Value::Null), (60, document["id"].clone())] {
+        let body = serde_json::json!({
+            "compliance_item_id": item["id"], "risk_category": "Security",
+            "risk_score": risk_score, "document_id": document_id
+        });
+        assert_eq!(201, app.post_json("/risk-scores", &body).await.status().as_u16());
+    }
+
+    let response = app.get("/compliance/export?tags=audit").await;
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!("text/csv; charset=utf-8", response.headers()["content-type"]);
+    let disposition = response.headers()["content-disposition"].to_str().unwrap().to_string();
+    assert!(disposition.starts_with("attachment; filename=\"compliance-register-"));
+    let csv = response.text().await.unwrap();
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let headers = reader.headers().unwrap().clone();
+    let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+    assert_eq!(1, rows.len());
+    let column = |name: &str| &rows[0][headers.iter().position(|h| h == name).unwrap()];
+    assert_eq!("'=HYPERLINK(\"http://evil\")", column("title"));
+    assert_eq!("Audit", column("tags"));
+    assert_eq!(document["filename"].as_str().unwrap(), column("evidence_files"));
+    assert_eq!("60", column("latest_risk_score"));
+
+    let items: Vec<serde_json::Value> = app.get("/compliance/export?format=json").await.json().await.unwrap();
+    assert_eq!(2, items.len());
+
+    let response = app.get("/risk-scores/export?format=xlsx").await;
+    assert_eq!(200, response.status().as_u16());
+    assert!(response.headers()["content-disposition"].to_str().unwrap().contains(".xlsx"));
+    let workbook = response.bytes().await.unwrap();
+    assert_eq!(b"PK", &workbook[..2]);
+
+    assert_eq!(400, app.get("/compliance/export?format=pdf").await.status().as_u16());
+}
+
+/// Multipart form with a CSV file and optional mapping
+fn import_form(csv: &str, mapping: Option<serde_json::Value>, dry_run: bool) -> reqwest::multipart::Form {
+    let file = reqwest::multipart::Part::bytes(csv.as_bytes().to_vec())
+        .file_name("register.csv")
+        .mime_str("text/csv")
+        .unwrap();
+    let mut form = reqwest::multipart::Form::new()
+        .part("file", file)
+        .text("dry_run", dry_run.to_string());
+    if let Some(mapping) = mapping {
+        form = form.text("mapping", mapping.to_string());
+    }
+    form
+}
+
+#[tokio::test]
+async fn compliance_import_validates_every_row_before_committing_all_of_them() {
+    let app = spawn_app().await;
+    let other_email = app.register_and_login().await;
+    let email = app.register_and_login().await;
+    app.post_json("/tags", &serde_json::json!({ "name": "Audit" })).await;
+
+    let csv = format!(
+        "Name,Severity,Status,Due Date,Owner,Labels\n\
+         Encrypt backups,High,In Progress,2026-12-01,{email},audit\n\
+         Rotate keys,critical,pending,,,\n\
+         No,catastrophic,pending,someday,nobody@example.com,Unknown\n"
+    );
+    let mapping = serde_json::json!({
+        "title": "Name", "risk_level": "Severity", "status": "Status",
+        "due_date": "Due Date", "assignee_email": "Owner", "tags": "Labels"
+    });
+
+    let preview: serde_json::Value = app
+        .post_multipart("/compliance/import/preview", import_form(&csv, None, false))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(3, preview["row_count"]);
+    assert_eq!("Due Date", preview["suggested_mapping"]["due_date"]);
+    assert_eq!("Status", preview["suggested_mapping"]["status"]);
+    assert!(preview["suggested_mapping"].get("title").is_none());
+    assert!(preview["fields"].as_array().unwrap().iter().any(|f| f["name"] == "assignee_email"));
+
+    let response = app
+        .post_multipart("/compliance/import", import_form(&csv, Some(mapping.clone()), false))
+        .await;
+    assert_eq!(422, response.status().as_u16());
+    let report: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(false, report["committed"]);
+    assert_eq!(2, report["valid_rows"]);
+    let fields: Vec<&str> = report["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .inspect(|e| assert_eq!(4, e["row"]))
+        .map(|e| e["field"].as_str().unwrap())
+        .collect();
+    for field in ["due_date", "assignee_email", "tags", "title", "risk_level"] {
+        assert!(fields.contains(&field), "missing error for {field}: {fields:?}");
+    }
+    let items: Vec<serde_json::Value> = app.get("/compliance").await.json().await.unwrap();
+    assert!(items.is_empty());
+
+    // Other accounts cannot be told apart from unknown addresses
+    let strangers = format!(
+        "Name,Severity,Status,Owner\n\
+         Review access,low,pending,{other_email}\n\
+         Review vendors,low,pending,nobody@example.com\n"
+    );
+    let owner_mapping = serde_json::json!({
+        "title": "Name", "risk_level": "Severity", "status": "Status", "assignee_email": "Owner"
+    });
+    let report: serde_json::Value = app
+        .post_multipart("/compliance/import", import_form(&strangers, Some(owner_mapping), true))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let messages: Vec<String> = report["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["message"].as_str().unwrap().replace(&other_email, "nobody@example.com"))
+        .collect();
+    assert_eq!(2, messages.len());
+    assert_eq!(messages[0], messages[1]);
+
+    let valid: String = csv.lines().take(3).map(|line| format!("{line}\n")).collect();
+    let response = app
+        .post_multipart("/compliance/import", import_form(&valid, Some(mapping.clone()), true))
+        .await;
+    assert_eq!(200, response.status().as_u16());
+    let items: Vec<serde_json::Value> = app.get("/compliance").await.json().await.unwrap();
+    assert!(items.is_empty());
+
+    let response = app
+        .post_multipart("/compliance/import", import_form(&valid, Some(mapping), false))
+        .await;
+    assert_eq!(201, response.status().as_u16());
+    let report: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(2, report["created_ids"].as_array().unwrap().len());
+
+    let items: Vec<serde_json::Value> = app.get("/compliance").await.json().await.unwrap();
+    let item = items.iter().find(|i| i["title"] == "Encrypt backups").unwrap();
+    assert_eq!("in_progress", item["status"]);
+    assert_eq!("Audit", item["tags"][0]["name"]);
+    assert!(item["due_date"].as_str().unwrap().starts_with("2026-12-01"));
+    assert!(item["assignee_id"].is_string());
+
+    // Imported items show up in the activity feed like created ones
+    let feed: serde_json::Value = app.get("/activity?entity_type=compliance_item").await.json().await.unwrap();
+    let verbs: Vec<&str> = feed["events"].as_array().unwrap().iter().map(|e| e["verb"].as_str().unwrap()).collect();
+    assert_eq!(vec!["created", "created"], verbs);
+
+    let unmapped = serde_json::json!({ "title": "Name" });
+    let response = app
+        .post_multipart("/compliance/import", import_form(&valid, Some(unmapped), false))
+        .await;
+    assert_eq!(400, response.status().as_u16());
+}
@@ -38,3 +38,218 @@ async fn dashboard_stats_returns_200_with_valid_auth() {
     let response = app.get_dashboard_stats().await;
     assert_eq!(200, response.status().as_u16());
 }
+
+#[tokio::test]
+async fn risk_heatmap_and_posture_use_latest_assessment_per_item() {
+    let app = spawn_app().await;
+    app.register_and_login().await;
+
+    let payments = app.create_compliance_item("Payment processing").await;
+    let backups = app.create_compliance_item("Backup restore test").await;
+    app.create_compliance_item("Never assessed").await;
+
+    for body in [
+        // Superseded by the later assessment of the same item
+        serde_json::json!({
+            "compliance_item_id": payments["id"], "risk_category": "Security",
+            "likelihood": 1, "impact": 1, "assessment_date": "2026-01-01T00:00:00Z"
+        }),
+        serde_json::json!({
+            "compliance_item_id": payments["id"], "risk_category": "Security",
+            "likelihood": 4, "impact": 5
+        }),
+        serde_json::json!({
+            "compliance_item_id": backups["id"], "risk_category": "Operations",
+            "risk_score": 20, "assessment_date": "2020-01-01T00:00:00Z"
+        }),
+    ] {
+        let response = app.post_json("/risk-scores", &body).await;
+        assert_eq!(201, response.status().as_u16());
+    }
+
+    let heatmap: serde_json::Value = app.get("/dashboard/risk-heatmap").await.json().await.unwrap();
+    let cells = heatmap["cells"].as_array().unwrap();
+    assert_eq!(25, cells.len());
+    let cell = cells
+        .iter()
+        .find(|c| c["likelihood"] == 4 && c["impact"] == 5)
+        .unwrap();
+    assert_eq!(1, cell["count"]);
+    assert_eq!("critical", cell["risk_level"]);
+    assert_eq!(0, cells.iter().find(|c| c["likelihood"] == 1 && c["impact"] == 1).unwrap()["count"]);
+    assert_eq!(1, heatmap["unplotted_items"]);
+
+    let heatmap: serde_json::Value = app
+        .get("/dashboard/risk-heatmap?axes=category")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(8, heatmap["cells"].as_array().unwrap().len());
+
+    let posture: serde_json::Value = app
+        .get("/dashboard/risk-posture?top=1")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(2, posture["assessed_items"]);
+    assert_eq!(1, posture["unassessed_items"]);
+    assert_eq!(50.0, posture["average_risk_score"]);
+    assert_eq!(50.0, posture["posture_score"]);
+    assert_eq!(1, posture["top_risks"].as_array().unwrap().len());
+    assert_eq!("Payment processing", posture["top_risks"][0]["title"]);
+    assert_eq!(1, posture["stale_items"]);
+    assert_eq!(1, posture["level_counts"]["critical"]);
+
+    for stale_days in ["-1", "3651", "1000000000"] {
+        let response = app.get(&format!("/dashboard/risk-posture?stale_days={stale_days}")).await;
+        assert_eq!(400, response.status().as_u16(), "stale_days={stale_days}");
+    }
+}
+
+#[tokio::test]
+async fn dashboard_stats_support_filters_comparison_and_burndown() {
+    let app = spawn_app().await;
+    app.register_and_login().await;
+
+    let audit = app.create_compliance_item("Annual audit").await;
+    let user_id = audit["user_id"].as_str().unwrap();
+    let response = app
+        .post_json(
+            "/compliance",
+            &serde_json::json!({
+                "title": "Access reviews",
+                "risk_level": "high",
+                "status": "pending",
+                "framework": "SOC 2",
+                "assignee_id": user_id
+            }),
+        )
+        .await;
+    assert_eq!(201, response.status().as_u16());
+    let reviews: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("SOC 2", reviews["framework"]);
+
+    let response = app
+        .put_json(
+            &format!("/compliance/{}", audit["id"].as_str().unwrap()),
+            &serde_json::json!({ "status": "completed", "framework": "SOC 2" }),
+        )
+        .await;
+    assert_eq!(200, response.status().as_u16());
+    app.create_compliance_item("Unrelated policy").await;
+
+    let stats: serde_json::Value = app
+        .get("/dashboard/stats?framework=SOC%202")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(2, stats["total_compliance_items"]);
+    assert_eq!(1, stats["completed_items"]);
+    assert_eq!(50.0, stats["compliance_score"]);
+
+    let stats: serde_json::Value = app
+        .get(&format!("/dashboard/stats?assignee={}", user_id))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(1, stats["total_compliance_items"]);
+
+    let now = chrono::Utc::now();
+    let from = (now - chrono::Duration::days(2)).to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let to = (now + chrono::Duration::hours(1)).to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let response = app
+        .get(&format!("/dashboard/stats?from={}&to={}&compare=true", from, to))
+        .await;
+    assert_eq!(200, response.status().as_u16());
+    let stats: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(3, stats["total_compliance_items"]);
+    assert_eq!(0, stats["comparison"]["stats"]["total_compliance_items"]);
+    assert_eq!(3, stats["comparison"]["deltas"]["total_compliance_items"]);
+
+    assert_eq!("day", stats["burndown_interval"]);
+    let burndown = stats["burndown"].as_array().unwrap();
+    assert_eq!(0, burndown.first().unwrap()["open_items"]);
+    assert_eq!(2, burndown.last().unwrap()["open_items"]);
+
+    // Comparison needs a start date
+    let response = app.get("/dashboard/stats?compare=true").await;
+    assert_eq!(400, response.status().as_u16());
+
+    // Assignees must exist
+    let response = app
+        .post_json(
+            "/compliance",
+            &serde_json::json!({
+                "title": "Orphaned item",
+                "risk_level": "low",
+                "status": "pending",
+                "assignee_id": Uuid::new_v4()
+            }),
+        )
+        .await;
+    assert_eq!(400, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn activity_feed_records_changes_and_paginates() {
+    let app = spawn_app().await;
+    app.register_and_login().await;
+
+    let item = app.create_compliance_item("Access review").await;
+    let id = item["id"].as_str().unwrap();
+    let response = app
+        .put_json(&format!("/compliance/{}", id), &serde_json::json!({ "status": "completed" }))
+        .await;
+    assert_eq!(200, response.status().as_u16());
+    let doomed = app.create_compliance_item("Obsolete control").await;
+    let response = app.delete(&format!("/compliance/{}", doomed["id"].as_str().unwrap())).await;
+    assert_eq!(204, response.status().as_u16());
+
+    let feed: serde_json::Value = app.get("/activity").await.json().await.unwrap();
+    let events = feed["events"].as_array().unwrap();
+    let verbs: Vec<&str> = events.iter().map(|e| e["verb"].as_str().unwrap()).collect();
+    assert_eq!(vec!["deleted", "created", "status_changed", "created"], verbs);
+    assert!(feed["next_cursor"].is_null());
+
+    let change = &events[2];
+    assert_eq!(id, change["entity_id"]);
+    assert_eq!("Test User", change["actor_name"]);
+    assert_eq!("pending", change["before"]["status"]);
+    assert_eq!("completed", change["after"]["status"]);
+    assert!(change["after"].get("title").is_none());
+    assert_eq!("Obsolete control", events[0]["before"]["title"]);
+
+    // Cursor pagination walks the same events one at a time
+    let mut walked = Vec::new();
+    let mut path = "/activity?limit=1".to_string();
+    loop {
+        let page: serde_json::Value = app.get(&path).await.json().await.unwrap();
+        walked.push(page["events"][0]["id"].clone());
+        match page["next_cursor"].as_str() {
+            Some(cursor) => path = format!("/activity?limit=1&cursor={}", cursor),
+            None => break,
+        }
+    }
+    let ids: Vec<serde_json::Value> = events.iter().map(|e| e["id"].clone()).collect();
+    assert_eq!(ids, walked);
+
+    let filtered: serde_json::Value = app
+        .get(&format!("/activity?entity_type=compliance_item&entity_id={}", id))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(2, filtered["events"].as_array().unwrap().len());
+
+    let response = app.get("/activity?entity_type=document").await;
+    let documents: serde_json::Value = response.json().await.unwrap();
+    assert!(documents["events"].as_array().unwrap().is_empty());
+
+    // The dashboard's recent activity reads from the same events
+    let recent: serde_json::Value = app.get("/dashboard/activity").await.json().await.unwrap();
+    assert_eq!(4, recent.as_array().unwrap().len());
+}
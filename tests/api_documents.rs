@@ -0,0 +1,938 @@
+use common::{spawn_app, spawn_app_with};
+use parseguard_backend::services::{storage_from_config, StorageService};
+
+mod common;
+
+#[tokio::test]
+async fn document_content_supports_ranges_and_conditional_requests() {
+    let app = spawn_app().await;
+    app.register_and_login().await;
+
+    let content: String = (0..100).map(|i| format!("line {:03}\n", i)).collect();
+    let document: serde_json::Value = app
+        .post_json("/documents/text", &serde_json::json!({ "title": "Café policy", "content": content }))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let path = format!("/documents/{}/content", document["id"].as_str().unwrap());
+
+    let response = app.get(&path).await;
+    assert_eq!(200, response.status().as_u16());
+    let headers = response.headers().clone();
+    assert_eq!("text/plain", headers["content-type"]);
+    assert_eq!("bytes", headers["accept-ranges"]);
+    assert_eq!(
+        "attachment; filename=\"Caf__policy.txt\"; filename*=UTF-8''Caf%C3%A9_policy.txt",
+        headers["content-disposition"]
+    );
+    assert_eq!(content, response.text().await.unwrap());
+    let etag = headers["etag"].to_str().unwrap().to_string();
+
+    let inline = app.get(&format!("{path}?disposition=inline")).await;
+    assert!(inline.headers()["content-disposition"].to_str().unwrap().starts_with("inline;"));
+    assert_eq!("sandbox", inline.headers()["content-security-policy"]);
+
+    let url = format!("{}/api{}", app.address, path);
+    let response = app.api_client.get(&url).header("If-None-Match", &etag).send().await.unwrap();
+    assert_eq!(304, response.status().as_u16());
+    assert_eq!(etag, response.headers()["etag"].to_str().unwrap());
+
+    let response = app.api_client.get(&url).header("Range", "bytes=9-17").send().await.unwrap();
+    assert_eq!(206, response.status().as_u16());
+    assert_eq!(format!("bytes 9-17/{}", content.len()), response.headers()["content-range"].to_str().unwrap());
+    assert_eq!("line 001\n", response.text().await.unwrap());
+
+    let response = app.api_client.get(&url).header("Range", "bytes=-9").send().await.unwrap();
+    assert_eq!(206, response.status().as_u16());
+    assert_eq!("line 099\n", response.text().await.unwrap());
+
+    let response = app
+        .api_client
+        .get(&url)
+        .header("Range", "bytes=0-3")
+        .header("If-Range", "\"stale\"")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(content.len(), response.bytes().await.unwrap().len());
+
+    let response = app.api_client.get(&url).header("Range", "bytes=5000-").send().await.unwrap();
+    assert_eq!(416, response.status().as_u16());
+    assert_eq!(format!("bytes */{}", content.len()), response.headers()["content-range"].to_str().unwrap());
+
+    for key in ["/etc/passwd", "../etc/passwd", "a//b", "a/./b"] {
+        let response = app
+            .post_json(
+                "/documents",
+                &serde_json::json!({ "filename": "passwd", "storage_key": key, "file_size": 1, "mime_type": "text/plain" }),
+            )
+            .await;
+        assert_eq!(400, response.status().as_u16(), "{}", key);
+    }
+
+    assert!(document.get("storage_key").is_none());
+    let storage_key = app.storage_key(&document).await;
+    let response = app
+        .post_json(
+            "/documents",
+            &serde_json::json!({ "filename": "page.html", "storage_key": storage_key, "file_size": 1, "mime_type": "text/html" }),
+        )
+        .await;
+    assert_eq!(400, response.status().as_u16());
+
+    let json: serde_json::Value = app
+        .post_json(
+            "/documents",
+            &serde_json::json!({ "filename": "data.json", "storage_key": storage_key, "file_size": 1, "mime_type": "application/json" }),
+        )
+        .await
+        .json()
+        .await
+        .unwrap();
+    let response = app.get(&format!("/documents/{}/content?disposition=inline", json["id"].as_str().unwrap())).await;
+    assert!(response.headers()["content-disposition"].to_str().unwrap().starts_with("attachment;"));
+
+    let link: serde_json::Value = app
+        .get(&format!("/documents/{}/download-url?disposition=inline", document["id"].as_str().unwrap()))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(false, link["presigned"]);
+    assert_eq!(format!("/api{path}?disposition=inline"), link["url"]);
+
+    app.register_and_login().await;
+    assert_eq!(404, app.get(&path).await.status().as_u16());
+    let response = app
+        .post_json(
+            "/documents",
+            &serde_json::json!({ "filename": "stolen.txt", "storage_key": storage_key, "file_size": 1, "mime_type": "text/plain" }),
+        )
+        .await;
+    assert_eq!(400, response.status().as_u16());
+}
+
+fn unique_upload_dir() -> String {
+    let dir = std::env::temp_dir().join(format!("pg_uploads_{}", uuid::Uuid::new_v4().simple()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.to_string_lossy().to_string()
+}
+
+#[tokio::test]
+async fn deleting_a_document_removes_its_file_once_unreferenced() {
+    let upload_dir = unique_upload_dir();
+    let dir = upload_dir.clone();
+    let app = spawn_app_with(move |c| c.upload_dir = dir).await;
+    app.register_and_login().await;
+
+    let document: serde_json::Value = app
+        .post_json("/documents/text", &serde_json::json!({ "title": "Retention", "content": "Keep for 7 years" }))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let storage_key = app.storage_key(&document).await;
+    let file = std::path::Path::new(&upload_dir).join(&storage_key);
+    assert!(file.exists());
+
+    let copy: serde_json::Value = app
+        .post_json(
+            "/documents",
+            &serde_json::json!({
+                "filename": "retention-copy.txt",
+                "storage_key": storage_key,
+                "file_size": 16,
+                "mime_type": "text/plain"
+            }),
+        )
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let response = app.delete(&format!("/documents/{}", document["id"].as_str().unwrap())).await;
+    assert_eq!(204, response.status().as_u16());
+    assert!(file.exists());
+
+    let response = app.delete(&format!("/documents/{}", copy["id"].as_str().unwrap())).await;
+    assert_eq!(204, response.status().as_u16());
+    assert!(!file.exists());
+
+    std::fs::remove_dir_all(upload_dir).unwrap();
+}
+
+#[tokio::test]
+async fn orphan_sweep_reclaims_unreferenced_uploads() {
+    let upload_dir = unique_upload_dir();
+    let dir = upload_dir.clone();
+    let app = spawn_app_with(move |c| c.upload_dir = dir).await;
+    app.register_and_login().await;
+
+    let kept: serde_json::Value = app
+        .post_json("/documents/text", &serde_json::json!({ "title": "Kept", "content": "still referenced" }))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let missing: serde_json::Value = app
+        .post_json("/documents/text", &serde_json::json!({ "title": "Missing", "content": "file removed by hand" }))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let missing_key = app.storage_key(&missing).await;
+    std::fs::remove_file(std::path::Path::new(&upload_dir).join(&missing_key)).unwrap();
+
+    let nested = std::path::Path::new(&upload_dir).join("2026/01");
+    std::fs::create_dir_all(&nested).unwrap();
+    std::fs::write(nested.join("stray.bin"), vec![0u8; 1024]).unwrap();
+    std::fs::write(std::path::Path::new(&upload_dir).join("leftover.txt"), "12345").unwrap();
+
+    let mut config = parseguard_backend::config::Config::from_env();
+    config.upload_dir = upload_dir.clone();
+    let storage = storage_from_config(&config).unwrap();
+
+    // Within the grace period nothing is removed
+    config.orphan_grace_secs = 3600;
+    let report = StorageService::new(app.pool.clone(), storage.clone(), &config).sweep_orphans(false).await.unwrap();
+    assert_eq!(3, report.scanned_objects);
+    assert_eq!(2, report.recent_objects);
+    assert!(report.orphaned_keys.is_empty());
+    assert!(report.missing_keys.contains(&missing_key));
+
+    config.orphan_grace_secs = 0;
+    let service = StorageService::new(app.pool.clone(), storage, &config);
+    let report = service.sweep_orphans(true).await.unwrap();
+    assert!(report.dry_run);
+    assert_eq!(vec!["2026/01/stray.bin", "leftover.txt"], report.orphaned_keys);
+    assert_eq!(1029, report.reclaimed_bytes);
+    assert!(nested.join("stray.bin").exists());
+
+    let report = service.sweep_orphans(false).await.unwrap();
+    assert_eq!(1029, report.reclaimed_bytes);
+    assert!(!nested.join("stray.bin").exists());
+    assert!(std::path::Path::new(&upload_dir).join(app.storage_key(&kept).await).exists());
+
+    let report = service.sweep_orphans(false).await.unwrap();
+    assert_eq!(1, report.scanned_objects);
+    assert_eq!(0, report.reclaimed_bytes);
+
+    std::fs::remove_dir_all(upload_dir).unwrap();
+}
+
+fn file_form(filename: &str, mime_type: &str, content: &str) -> reqwest::multipart::Form {
+    let part = reqwest::multipart::Part::bytes(content.as_bytes().to_vec())
+        .file_name(filename.to_string())
+        .mime_str(mime_type)
+        .unwrap();
+    reqwest::multipart::Form::new().part("file", part)
+}
+
+#[tokio::test]
+async fn identical_uploads_share_one_blob_and_reuse_analysis() {
+    let upload_dir = unique_upload_dir();
+    let dir = upload_dir.clone();
+    let app = spawn_app_with(move |c| c.upload_dir = dir).await;
+    app.register_and_login().await;
+
+    let content = format!("Access reviews happen quarterly ({})", uuid::Uuid::new_v4());
+    let response = app.post_multipart("/documents/upload", file_form("policy.txt", "text/plain", &content)).await;
+    assert_eq!(201, response.status().as_u16());
+    let first: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(content, first["extracted_text"]);
+    assert_eq!(serde_json::json!([]), first["duplicate_of"]);
+    assert_eq!(false, first["reused_analysis"]);
+    assert!(first["message"].is_null());
+
+    let sha256 = first["content_sha256"].as_str().unwrap().to_string();
+    let storage_key = app.storage_key(&first).await;
+    assert_eq!(format!("blobs/{}/{}", &sha256[..2], sha256), storage_key);
+
+    let analysis = serde_json::json!({ "summary": "Quarterly access reviews", "key_points": [] });
+    let response = app
+        .put_json(&format!("/documents/{}", first["id"].as_str().unwrap()), &serde_json::json!({ "ai_analysis": analysis }))
+        .await;
+    assert_eq!(200, response.status().as_u16());
+
+    let response = app.post_multipart("/documents/upload", file_form("policy (1).txt", "text/plain", &content)).await;
+    assert_eq!(201, response.status().as_u16());
+    let second: serde_json::Value = response.json().await.unwrap();
+    assert_ne!(first["id"], second["id"]);
+    assert_eq!(storage_key, app.storage_key(&second).await);
+    assert_eq!(first["id"], second["duplicate_of"][0]["id"]);
+    assert_eq!("This file already exists as \"policy.txt\"", second["message"]);
+    assert_eq!(true, second["reused_analysis"]);
+    assert_eq!(analysis, second["ai_analysis"]);
+
+    // Another user shares the stored file but neither the first user's analysis,
+    // which clients can write, nor the knowledge of their files
+    app.register_and_login().await;
+    let response = app.post_multipart("/documents/upload", file_form("copy.txt", "text/plain", &content)).await;
+    let other: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(serde_json::json!([]), other["duplicate_of"]);
+    assert!(other["ai_analysis"].is_null());
+    assert_eq!(false, other["reused_analysis"]);
+
+    let files = std::fs::read_dir(std::path::Path::new(&upload_dir).join(&storage_key).parent().unwrap())
+        .unwrap()
+        .count();
+    assert_eq!(1, files);
+    let ref_count: i32 = sqlx::query_scalar("SELECT ref_count FROM blobs WHERE sha256 = $1")
+        .bind(&sha256)
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+    // Each of the three documents and its first version
+    assert_eq!(6, ref_count);
+
+    let response = app.delete(&format!("/documents/{}", other["id"].as_str().unwrap())).await;
+    assert_eq!(204, response.status().as_u16());
+    let file = std::path::Path::new(&upload_dir).join(&storage_key);
+    assert!(file.exists());
+
+    // Deleting the owner cascades to the remaining documents; the sweeper releases the blob
+    sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(uuid::Uuid::parse_str(first["user_id"].as_str().unwrap()).unwrap())
+        .execute(&app.pool)
+        .await
+        .unwrap();
+    let mut config = parseguard_backend::config::Config::from_env();
+    config.upload_dir = upload_dir.clone();
+    let storage = storage_from_config(&config).unwrap();
+    let report = StorageService::new(app.pool.clone(), storage, &config).sweep_orphans(false).await.unwrap();
+    assert!(report.released_blobs >= 1);
+    assert!(!file.exists());
+    let blobs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM blobs WHERE sha256 = $1")
+        .bind(&sha256)
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+    assert_eq!(0, blobs);
+
+    std::fs::remove_dir_all(upload_dir).unwrap();
+}
+
+#[tokio::test]
+async fn upload_rejects_missing_and_unsupported_files() {
+    let app = spawn_app().await;
+    app.register_and_login().await;
+
+    let response = app
+        .post_multipart("/documents/upload", file_form("tool.exe", "application/x-msdownload", "MZ"))
+        .await;
+    assert_eq!(400, response.status().as_u16());
+
+    let form = reqwest::multipart::Form::new().text("note", "no file");
+    let response = app.post_multipart("/documents/upload", form).await;
+    assert_eq!(400, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn documents_keep_their_version_history() {
+    let upload_dir = unique_upload_dir();
+    let dir = upload_dir.clone();
+    let app = spawn_app_with(move |c| c.upload_dir = dir).await;
+    app.register_and_login().await;
+
+    let original = format!("Term: 12 months ({})", uuid::Uuid::new_v4());
+    let amended = format!("Term: 24 months ({})", uuid::Uuid::new_v4());
+    let document: serde_json::Value = app
+        .post_multipart("/documents/upload", file_form("contract.txt", "text/plain", &original))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let id = document["id"].as_str().unwrap().to_string();
+    assert_eq!(1, document["current_version"]);
+    let analysis = serde_json::json!({ "summary": "One-year term" });
+    app.put_json(&format!("/documents/{id}"), &serde_json::json!({ "ai_analysis": analysis })).await;
+
+    let response = app
+        .post_multipart(&format!("/documents/{id}/versions"), file_form("contract-v2.txt", "text/plain", &amended))
+        .await;
+    assert_eq!(201, response.status().as_u16());
+    let updated: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(id, updated["id"]);
+    assert_eq!(2, updated["current_version"]);
+    assert_eq!("contract-v2.txt", updated["filename"]);
+    assert_eq!(amended, updated["extracted_text"]);
+    assert!(updated["ai_analysis"].is_null());
+
+    let response = app
+        .post_multipart(&format!("/documents/{id}/versions"), file_form("same.txt", "text/plain", &amended))
+        .await;
+    assert_eq!(400, response.status().as_u16());
+
+    let versions: serde_json::Value = app.get(&format!("/documents/{id}/versions")).await.json().await.unwrap();
+    let versions = versions.as_array().unwrap();
+    assert_eq!(2, versions.len());
+    assert_eq!((1, true), (versions[0]["version_number"].as_i64().unwrap(), versions[0]["has_ai_analysis"] == true));
+    assert_eq!((2, false), (versions[1]["version_number"].as_i64().unwrap(), versions[1]["has_ai_analysis"] == true));
+
+    let first: serde_json::Value = app.get(&format!("/documents/{id}/versions/1")).await.json().await.unwrap();
+    assert_eq!("contract.txt", first["filename"]);
+    assert_eq!(analysis, first["ai_analysis"]);
+    assert_eq!(original, app.get(&format!("/documents/{id}/versions/1/content")).await.text().await.unwrap());
+    assert_eq!(amended, app.get(&format!("/documents/{id}/content")).await.text().await.unwrap());
+    assert_eq!(404, app.get(&format!("/documents/{id}/versions/3")).await.status().as_u16());
+
+    // Analysis added now belongs to version 2
+    let analysis = serde_json::json!({ "summary": "Two-year term" });
+    app.put_json(&format!("/documents/{id}"), &serde_json::json!({ "ai_analysis": analysis })).await;
+    let second: serde_json::Value = app.get(&format!("/documents/{id}/versions/2")).await.json().await.unwrap();
+    assert_eq!(analysis, second["ai_analysis"]);
+
+    let item = app.create_compliance_item("Contract term").await;
+    let score = |version: Option<i64>| {
+        serde_json::json!({
+            "compliance_item_id": item["id"], "document_id": id, "document_version": version,
+            "risk_category": "Legal", "risk_score": 40
+        })
+    };
+    let current: serde_json::Value = app.post_json("/risk-scores", &score(None)).await.json().await.unwrap();
+    assert_eq!(2, current["document_version"]);
+    let earlier: serde_json::Value = app.post_json("/risk-scores", &score(Some(1))).await.json().await.unwrap();
+    assert_eq!(1, earlier["document_version"]);
+    assert_eq!(400, app.post_json("/risk-scores", &score(Some(3))).await.status().as_u16());
+
+    let files = [
+        std::path::Path::new(&upload_dir).join(app.storage_key(&first).await),
+        std::path::Path::new(&upload_dir).join(app.storage_key(&second).await),
+    ];
+    assert!(files.iter().all(|file| file.exists()));
+    assert_eq!(204, app.delete(&format!("/documents/{id}")).await.status().as_u16());
+    assert!(!files.iter().any(|file| file.exists()));
+
+    std::fs::remove_dir_all(upload_dir).unwrap();
+}
+
+/// Ollama stand-in answering every generate request with a canned response
+async fn spawn_ollama_stub(answer: &'static str) -> (String, std::sync::Arc<std::sync::Mutex<Vec<String>>>) {
+    let prompts = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let seen = prompts.clone();
+    let app = axum::Router::new().route(
+        "/api/generate",
+        axum::routing::post(move |axum::Json(request): axum::Json<serde_json::Value>| {
+            let seen = seen.clone();
+            async move {
+                seen.lock().unwrap().push(request["prompt"].as_str().unwrap_or_default().to_string());
+                axum::Json(serde_json::json!({ "response": answer, "done": true }))
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (address, prompts)
+}
+
+#[tokio::test]
+async fn version_diff_classifies_changes_and_flags_impacted_items() {
+    let (ollama, prompts) = spawn_ollama_stub(
+        "CHANGE 1: ADDED_OBLIGATION - training is required more often\n\
+         CHANGE 2: wording only\n\
+         CHANGE 9: REMOVED_OBLIGATION - no such change\n\
+         ITEM 1: Training cadence changed",
+    )
+    .await;
+    let app = spawn_app_with(move |c| c.ollama_url = ollama).await;
+    app.register_and_login().await;
+
+    let tag = uuid::Uuid::new_v4();
+    let original = format!(
+        "Training policy {tag}\n\nAll staff must complete training annually.\n\n\
+         Backups are kept for 30 days. Restores are tested yearly."
+    );
+    let revised = format!(
+        "Training policy {tag}\n\nAll staff must complete training every six months.\n\n\
+         Backups are kept for 30 days.  Restores are\ntested yearly!\n\nVendors must sign an NDA."
+    );
+    let document: serde_json::Value = app
+        .post_multipart("/documents/upload", file_form("training.txt", "text/plain", &original))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let id = document["id"].as_str().unwrap().to_string();
+
+    let response = app.get(&format!("/documents/{id}/diff")).await;
+    assert_eq!(400, response.status().as_u16());
+
+    app.post_multipart(&format!("/documents/{id}/versions"), file_form("training.txt", "text/plain", &revised))
+        .await;
+
+    let diff: serde_json::Value = app.get(&format!("/documents/{id}/diff")).await.json().await.unwrap();
+    assert_eq!((1, 2), (diff["from_version"].as_i64().unwrap(), diff["to_version"].as_i64().unwrap()));
+    assert_eq!("paragraph", diff["granularity"]);
+    assert_eq!(1, diff["unchanged"]);
+    assert_eq!(false, diff["classified"]);
+    let changes = diff["changes"].as_array().unwrap();
+    assert_eq!(
+        vec!["modified", "modified", "added"],
+        changes.iter().map(|c| c["kind"].as_str().unwrap()).collect::<Vec<_>>()
+    );
+    assert_eq!("All staff must complete training every six months.", changes[0]["after"]);
+    assert!(changes[2]["before"].is_null());
+    assert_eq!(3, changes[2]["before_index"]);
+    assert!(changes[0]["classification"].is_null());
+    assert!(prompts.lock().unwrap().is_empty());
+
+    // Sentences only differ where the wording does, whitespace is ignored
+    let diff: serde_json::Value = app
+        .get(&format!("/documents/{id}/diff?from=1&to=2&granularity=sentence"))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(2, diff["unchanged"]);
+    let changes = diff["changes"].as_array().unwrap();
+    assert_eq!(3, changes.len());
+    assert_eq!("Restores are tested yearly.", changes[1]["before"]);
+    assert_eq!("Restores are tested yearly!", changes[1]["after"]);
+
+    let item = app.create_compliance_item("Security awareness training").await;
+    let diff: serde_json::Value = app
+        .get(&format!("/documents/{id}/diff?granularity=sentence&classify=true"))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(true, diff["classified"]);
+    let changes = diff["changes"].as_array().unwrap();
+    assert_eq!("added_obligation", changes[0]["classification"]);
+    assert_eq!("training is required more often", changes[0]["rationale"]);
+    assert_eq!("wording_only", changes[1]["classification"]);
+    assert!(changes[1]["rationale"].is_null());
+    assert!(changes[2]["classification"].is_null());
+    assert_eq!(item["id"], diff["impacted_items"][0]["id"]);
+    assert_eq!("Training cadence changed", diff["impacted_items"][0]["reason"]);
+
+    let prompt = prompts.lock().unwrap().pop().unwrap();
+    assert!(prompt.contains("AFTER: Vendors must sign an NDA."));
+    assert!(prompt.contains("ITEM 1: Security awareness training"));
+
+    assert_eq!(404, app.get(&format!("/documents/{id}/diff?from=1&to=5")).await.status().as_u16());
+    for query in ["to=-2147483648", "from=0&to=2", "from=1&to=0"] {
+        assert_eq!(400, app.get(&format!("/documents/{id}/diff?{query}")).await.status().as_u16());
+    }
+}
+
+/// clamd stand-in answering INSTREAM scans; flags the EICAR test string, or
+/// fails every scan while `available` is false
+async fn spawn_clamd_stub(available: std::sync::Arc<std::sync::atomic::AtomicBool>) -> String {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let available = available.clone();
+            tokio::spawn(async move {
+                let mut command = [0u8; 10];
+                socket.read_exact(&mut command).await.unwrap();
+                assert_eq!(b"zINSTREAM\0", &command);
+
+                let mut content = Vec::new();
+                loop {
+                    let length = socket.read_u32().await.unwrap() as usize;
+                    if length == 0 {
+                        break;
+                    }
+                    let mut chunk = vec![0u8; length];
+                    socket.read_exact(&mut chunk).await.unwrap();
+                    content.extend(chunk);
+                }
+
+                let reply: &[u8] = if !available.load(std::sync::atomic::Ordering::SeqCst) {
+                    b"INSTREAM size limit exceeded. ERROR\0"
+                } else if String::from_utf8_lossy(&content).contains("EICAR-STANDARD-ANTIVIRUS-TEST-FILE") {
+                    b"stream: Eicar-Test-Signature FOUND\0"
+                } else {
+                    b"stream: OK\0"
+                };
+                socket.write_all(reply).await.unwrap();
+            });
+        }
+    });
+    address
+}
+
+#[tokio::test]
+async fn uploads_are_scanned_and_infected_files_quarantined() {
+    let available = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
+    let clamd = spawn_clamd_stub(available.clone()).await;
+    let upload_dir = unique_upload_dir();
+    let dir = upload_dir.clone();
+    let app = spawn_app_with(move |c| {
+        c.upload_dir = dir;
+        c.clamd_address = parseguard_backend::config::ClamdAddress::parse(&format!("tcp://{}", clamd));
+    })
+    .await;
+    app.register_and_login().await;
+
+    let content = format!("Vendor contract ({})", uuid::Uuid::new_v4());
+    let response = app.post_multipart("/documents/upload", file_form("contract.txt", "text/plain", &content)).await;
+    assert_eq!(201, response.status().as_u16());
+    let clean: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("clean", clean["scan_status"]);
+    assert!(clean["scanned_at"].is_string());
+    let clean_id = clean["id"].as_str().unwrap().to_string();
+    assert_eq!(200, app.get(&format!("/documents/{}/content", clean_id)).await.status().as_u16());
+
+    let eicar = r"X5O!P%@AP[4\PZX54(P^)7CC)7}$EICAR-STANDARD-ANTIVIRUS-TEST-FILE!$H+H*";
+    let response = app.post_multipart("/documents/upload", file_form("invoice.txt", "text/plain", eicar)).await;
+    assert_eq!(201, response.status().as_u16());
+    let infected: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("infected", infected["scan_status"]);
+    assert_eq!("Eicar-Test-Signature", infected["scan_signature"]);
+    let quarantine_key = app.storage_key(&infected).await;
+    assert!(quarantine_key.starts_with("quarantine/"));
+    assert!(infected["content_sha256"].is_null());
+    assert!(infected["extracted_text"].is_null());
+    assert!(infected["message"].as_str().unwrap().contains("Eicar-Test-Signature"));
+    assert!(std::path::Path::new(&upload_dir).join(&quarantine_key).exists());
+
+    let path = format!("/documents/{}", infected["id"].as_str().unwrap());
+    assert_eq!(403, app.get(&format!("{}/content", path)).await.status().as_u16());
+    assert_eq!(403, app.get(&format!("{}/download-url", path)).await.status().as_u16());
+    assert_eq!(403, app.get(&format!("{}/versions/1/content", path)).await.status().as_u16());
+    let response = app.put_json(&path, &serde_json::json!({ "ai_analysis": { "summary": "Invoice" } })).await;
+    assert_eq!(403, response.status().as_u16());
+
+    // An infected new version blocks the document and its comparison
+    let response = app
+        .post_multipart(&format!("/documents/{}/versions", clean_id), file_form("contract-v2.txt", "text/plain", eicar))
+        .await;
+    assert_eq!(201, response.status().as_u16());
+    assert_eq!(403, app.get(&format!("/documents/{}/content", clean_id)).await.status().as_u16());
+    assert_eq!(200, app.get(&format!("/documents/{}/versions/1/content", clean_id)).await.status().as_u16());
+    assert_eq!(403, app.get(&format!("/documents/{}/diff", clean_id)).await.status().as_u16());
+
+    // A failed scan quarantines the file until a rescan finds it clean
+    available.store(false, std::sync::atomic::Ordering::SeqCst);
+    let response = app.post_multipart("/documents/upload", file_form("policy.txt", "text/plain", &content)).await;
+    let failed: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("failed", failed["scan_status"]);
+    let path = format!("/documents/{}", failed["id"].as_str().unwrap());
+    assert_eq!(403, app.get(&format!("{}/content", path)).await.status().as_u16());
+
+    available.store(true, std::sync::atomic::Ordering::SeqCst);
+    let response = app.post_json(&format!("{}/scan", path), &serde_json::json!({})).await;
+    assert_eq!(200, response.status().as_u16());
+    let rescanned: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("clean", rescanned["scan_status"]);
+    let response = app.get(&format!("{}/content", path)).await;
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(content, response.text().await.unwrap());
+
+    let versions: serde_json::Value = app.get(&format!("{}/versions", path)).await.json().await.unwrap();
+    assert_eq!("clean", versions[0]["scan_status"]);
+
+    // Files stored while scanning was disabled stay blocked until they are scanned
+    let legacy = format!("Legacy policy ({})", uuid::Uuid::new_v4());
+    let response = app.post_multipart("/documents/upload", file_form("legacy.txt", "text/plain", &legacy)).await;
+    let unscanned: serde_json::Value = response.json().await.unwrap();
+    sqlx::query("UPDATE documents SET scan_status = 'not_scanned', scanned_at = NULL WHERE id = $1::uuid")
+        .bind(unscanned["id"].as_str().unwrap())
+        .execute(&app.pool)
+        .await
+        .unwrap();
+    let path = format!("/documents/{}", unscanned["id"].as_str().unwrap());
+    assert_eq!(403, app.get(&format!("{}/content", path)).await.status().as_u16());
+    assert_eq!(403, app.get(&format!("{}/versions/1/content", path)).await.status().as_u16());
+    let response = app.post_json(&format!("{}/scan", path), &serde_json::json!({})).await;
+    assert_eq!("clean", response.json::<serde_json::Value>().await.unwrap()["scan_status"]);
+    assert_eq!(200, app.get(&format!("{}/content", path)).await.status().as_u16());
+
+    std::fs::remove_dir_all(upload_dir).unwrap();
+}
+
+#[tokio::test]
+async fn stored_files_are_encrypted_and_data_keys_rotate() {
+    use parseguard_backend::utils::envelope::{MasterKeyRing, WrappedDataKey};
+
+    let old_key = "1f".repeat(32);
+    let new_key = "a7".repeat(32);
+    let upload_dir = unique_upload_dir();
+    let dir = upload_dir.clone();
+    let keys = MasterKeyRing::parse(&old_key).unwrap();
+    let app = spawn_app_with(move |c| {
+        c.upload_dir = dir;
+        c.encryption_keys = Some(keys);
+    })
+    .await;
+    app.register_and_login().await;
+
+    let content = format!("Confidential supplier terms ({})", uuid::Uuid::new_v4());
+    let response = app.post_multipart("/documents/upload", file_form("terms.txt", "text/plain", &content)).await;
+    assert_eq!(201, response.status().as_u16());
+    let document: serde_json::Value = response.json().await.unwrap();
+    let old_key_id = MasterKeyRing::parse(&old_key).unwrap().active_key_id().to_string();
+    assert_eq!(old_key_id, document["encryption_key_id"]);
+    assert!(document.get("wrapped_data_key").is_none());
+
+    let storage_key = app.storage_key(&document).await;
+    let stored = std::fs::read(std::path::Path::new(&upload_dir).join(&storage_key)).unwrap();
+    assert!(!String::from_utf8_lossy(&stored).contains("Confidential"));
+
+    let path = format!("/documents/{}", document["id"].as_str().unwrap());
+    let response = app.get(&format!("{}/content", path)).await;
+    assert_eq!(content.len().to_string(), response.headers()["content-length"].to_str().unwrap());
+    assert_eq!(content, response.text().await.unwrap());
+    let response = app
+        .api_client
+        .get(format!("{}/api{}/content", app.address, path))
+        .header("Range", "bytes=0-11")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(206, response.status().as_u16());
+    assert_eq!("Confidential", response.text().await.unwrap());
+    let link: serde_json::Value = app.get(&format!("{}/download-url", path)).await.json().await.unwrap();
+    assert_eq!(false, link["presigned"]);
+
+    // A duplicate shares the stored object and therefore its data key
+    let response = app.post_multipart("/documents/upload", file_form("terms (1).txt", "text/plain", &content)).await;
+    let duplicate: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(storage_key, app.storage_key(&duplicate).await);
+    let response = app.get(&format!("/documents/{}/content", duplicate["id"].as_str().unwrap())).await;
+    assert_eq!(content, response.text().await.unwrap());
+
+    let version = format!("{} - amended", content);
+    let response = app.post_multipart(&format!("{}/versions", path), file_form("terms.txt", "text/plain", &version)).await;
+    assert_eq!(201, response.status().as_u16());
+    assert_eq!(version, app.get(&format!("{}/content", path)).await.text().await.unwrap());
+    assert_eq!(content, app.get(&format!("{}/versions/1/content", path)).await.text().await.unwrap());
+
+    // Rotate to a new master key while the old one is still configured
+    let mut config = parseguard_backend::config::Config::from_env();
+    config.upload_dir = upload_dir.clone();
+    config.encryption_keys = Some(MasterKeyRing::parse(&format!("{},{}", new_key, old_key)).unwrap());
+    let storage = storage_from_config(&config).unwrap();
+    let service = StorageService::new(app.pool.clone(), storage.clone(), &config);
+
+    let report = service.rotate_data_keys(true).await.unwrap();
+    assert!(report.rewrapped_keys >= 2);
+    assert_eq!(0, report.documents);
+    let report = service.rotate_data_keys(false).await.unwrap();
+    assert!(report.rewrapped_keys >= 2);
+    assert!(report.versions >= 3);
+    assert_eq!(0, service.rotate_data_keys(false).await.unwrap().rewrapped_keys);
+
+    let (key_id, wrapped): (String, String) =
+        sqlx::query_as("SELECT encryption_key_id, wrapped_data_key FROM document_versions WHERE document_id = $1 AND version_number = 1")
+            .bind(uuid::Uuid::parse_str(document["id"].as_str().unwrap()).unwrap())
+            .fetch_one(&app.pool)
+            .await
+            .unwrap();
+    assert_eq!(MasterKeyRing::parse(&new_key).unwrap().active_key_id(), key_id);
+    let encryption = WrappedDataKey::from_parts(Some(&key_id), Some(&wrapped));
+
+    // The retired key is no longer needed to read the file
+    config.encryption_keys = Some(MasterKeyRing::parse(&new_key).unwrap());
+    let service = StorageService::new(app.pool.clone(), storage.clone(), &config);
+    let bytes = service.read_file(&storage_key, encryption.as_ref()).await.unwrap();
+    assert_eq!(content.as_bytes(), bytes.as_slice());
+
+    config.encryption_keys = Some(MasterKeyRing::parse(&old_key).unwrap());
+    let service = StorageService::new(app.pool.clone(), storage, &config);
+    assert!(service.read_file(&storage_key, encryption.as_ref()).await.is_err());
+
+    std::fs::remove_dir_all(upload_dir).unwrap();
+}
+
+#[tokio::test]
+async fn personal_data_is_pseudonymized_for_the_model_and_recorded() {
+    let (ollama, prompts) = spawn_ollama_stub(
+        "Summary: Supplier contract signed by [PERSON_NAME_1], contact [email_1].\n\
+         Risk indicators:\n\
+         - Payments go to [IBAN_1]\n\
+         - [EMAIL_2] is not a placeholder from the prompt",
+    )
+    .await;
+    let app = spawn_app_with(move |c| c.ollama_url = ollama).await;
+    app.register_and_login().await;
+
+    let text = "Agreement between Dr. Jane Doe and ACME.\n\
+                Contact: jane.doe@example.com or +44 20 7946 0958, again jane.doe@example.com.\n\
+                Pay to DE89 3704 0044 0532 0130 00 by 01.02.2024; card 4111 1111 1111 1111 \
+                (not 4111 1111 1111 1112). SSN 123-45-6789, release 1.2.3.";
+
+    let response = app.post_multipart("/documents/upload", file_form("contract.txt", "text/plain", text)).await;
+    assert_eq!(201, response.status().as_u16());
+    let document: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        serde_json::json!(["person_name", "email", "phone", "iban", "payment_card", "national_id"]),
+        document["pii_categories"]
+    );
+    let id = document["id"].as_str().unwrap();
+    let version: serde_json::Value = app.get(&format!("/documents/{id}/versions/1")).await.json().await.unwrap();
+    assert_eq!(document["pii_categories"], version["pii_categories"]);
+
+    let updated: serde_json::Value = app
+        .put_json(&format!("/documents/{id}"), &serde_json::json!({ "extracted_text": "Reach me at 192.168.10.4" }))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(serde_json::json!(["ip_address"]), updated["pii_categories"]);
+
+    let analysis: serde_json::Value = app
+        .post_json("/ai/analyze", &serde_json::json!({ "text": text }))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let prompt = prompts.lock().unwrap().pop().unwrap();
+    for raw in ["Jane Doe", "jane.doe@example.com", "7946", "DE89", "4111 1111 1111 1111", "123-45-6789"] {
+        assert!(!prompt.contains(raw), "{raw} was sent to the model");
+    }
+    assert_eq!(2, prompt.matches("[EMAIL_1]").count());
+    assert!(prompt.contains("between [PERSON_NAME_1] and ACME"));
+    assert!(prompt.contains("[PHONE_1]") && prompt.contains("[PAYMENT_CARD_1]") && prompt.contains("[NATIONAL_ID_1]"));
+    assert!(prompt.contains("by 01.02.2024") && prompt.contains("4111 1111 1111 1112") && prompt.contains("release 1.2.3"));
+
+    let summary = analysis["summary"].as_str().unwrap();
+    assert!(summary.contains("dr. jane doe") && summary.contains("jane.doe@example.com"), "{summary}");
+    let risks = analysis["risk_indicators"].as_array().unwrap();
+    assert_eq!("payments go to de89 3704 0044 0532 0130 00", risks[0]);
+    assert_eq!("[email_2] is not a placeholder from the prompt", risks[1]);
+    assert_eq!(document["pii_categories"], analysis["pii_categories"]);
+}
+
+/// Write an executable shell script standing in for an OCR tool
+fn write_tool_stub(dir: &std::path::Path, name: &str, script: &str) -> std::path::PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = dir.join(name);
+    std::fs::write(&path, format!("#!/bin/sh\n{script}")).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+#[tokio::test]
+async fn scanned_pages_and_images_are_recognized_with_ocr() {
+    use printpdf::{BuiltinFont, Mm, PdfDocument};
+
+    // pdftoppm writes "<root>.png"; tesseract answers TSV by the rendered page
+    let tools = std::path::PathBuf::from(unique_upload_dir());
+    let pdftoppm = write_tool_stub(
+        &tools,
+        "pdftoppm",
+        "[ \"$2\" = 3 ] && exit 1\nfor last; do :; done\nprintf 'rendered page %s' \"$2\" > \"$last.png\"\n",
+    );
+    let tesseract = write_tool_stub(
+        &tools,
+        "tesseract",
+        "input=$(cat)\n\
+         printf 'level\\tpage_num\\tblock_num\\tpar_num\\tline_num\\tword_num\\tleft\\ttop\\twidth\\theight\\tconf\\ttext\\n'\n\
+         row() { printf '%s\\t%s\\t%s\\t%s\\t%s\\t1\\t0\\t0\\t10\\t10\\t%s\\t%s\\n' \"$@\"; }\n\
+         case \"$input\" in\n\
+         'rendered page 2') row 1 1 0 0 0 -1 ''; row 5 1 1 1 1 91 Scanned; row 5 1 1 1 1 85 appendix;\n\
+         row 5 1 1 1 2 89 page; row 5 1 2 1 1 95 two ;;\n\
+         *) row 1 1 0 0 0 -1 ''; row 5 1 1 1 1 30 blurry; row 1 2 0 0 0 -1 ''; row 5 2 1 1 1 50 receipt ;;\n\
+         esac\n",
+    );
+    let app = spawn_app_with(move |c| {
+        c.ocr.tesseract_path = Some(tesseract);
+        c.ocr.pdftoppm_path = pdftoppm;
+        c.ocr.min_confidence = 60.0;
+        c.ocr.max_pages = 2;
+    })
+    .await;
+    app.register_and_login().await;
+
+    // Page 1 has a text layer, page 2 is a scan without one
+    let (pdf, page, layer) = PdfDocument::new("Agreement", Mm(210.0), Mm(297.0), "Text");
+    let font = pdf.add_builtin_font(BuiltinFont::Helvetica).unwrap();
+    let marker = uuid::Uuid::new_v4().simple().to_string();
+    pdf.get_page(page)
+        .get_layer(layer)
+        .use_text(format!("Supplier agreement {marker}"), 12.0, Mm(20.0), Mm(270.0), &font);
+    pdf.add_page(Mm(210.0), Mm(297.0), "Scan");
+    let pdf = pdf.save_to_bytes().unwrap();
+
+    let form = reqwest::multipart::Form::new().part(
+        "file",
+        reqwest::multipart::Part::bytes(pdf).file_name("agreement.pdf").mime_str("application/pdf").unwrap(),
+    );
+    let response = app.post_multipart("/documents/upload", form).await;
+    assert_eq!(201, response.status().as_u16());
+    let document: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(serde_json::json!([{ "page": 2, "confidence": 90.0 }]), document["ocr_pages"]);
+    assert_eq!(false, document["ocr_low_confidence"]);
+    let id = document["id"].as_str().unwrap();
+    let version: serde_json::Value = app.get(&format!("/documents/{id}/versions/1")).await.json().await.unwrap();
+    assert_eq!(
+        format!("Supplier agreement {marker}\n\nScanned appendix\npage\n\ntwo"),
+        version["extracted_text"]
+    );
+    assert_eq!(document["ocr_pages"], version["ocr_pages"]);
+
+    // Page 3 fails to render and page 4 is past the OCR page limit; both are kept without text
+    let (pdf, page, layer) = PdfDocument::new("Annex", Mm(210.0), Mm(297.0), "Text");
+    let font = pdf.add_builtin_font(BuiltinFont::Helvetica).unwrap();
+    pdf.get_page(page)
+        .get_layer(layer)
+        .use_text(format!("Annex {marker}"), 12.0, Mm(20.0), Mm(270.0), &font);
+    for scan in ["Scan", "Damaged", "Overflow"] {
+        pdf.add_page(Mm(210.0), Mm(297.0), scan);
+    }
+    let form = reqwest::multipart::Form::new().part(
+        "file",
+        reqwest::multipart::Part::bytes(pdf.save_to_bytes().unwrap())
+            .file_name("annex.pdf")
+            .mime_str("application/pdf")
+            .unwrap(),
+    );
+    let document: serde_json::Value = app.post_multipart("/documents/upload", form).await.json().await.unwrap();
+    assert_eq!(
+        serde_json::json!([
+            { "page": 2, "confidence": 90.0 },
+            { "page": 3, "confidence": 0.0 },
+            { "page": 4, "confidence": 0.0 }
+        ]),
+        document["ocr_pages"]
+    );
+    assert_eq!(true, document["ocr_low_confidence"]);
+    let id = document["id"].as_str().unwrap();
+    let version: serde_json::Value = app.get(&format!("/documents/{id}/versions/1")).await.json().await.unwrap();
+    assert_eq!(format!("Annex {marker}\n\nScanned appendix\npage\n\ntwo"), version["extracted_text"]);
+
+    // A two-page image scan below the minimum confidence is flagged
+    let form = reqwest::multipart::Form::new().part(
+        "file",
+        reqwest::multipart::Part::bytes(format!("scan {marker}").into_bytes())
+            .file_name("receipt.tiff")
+            .mime_str("image/tiff")
+            .unwrap(),
+    );
+    let document: serde_json::Value = app.post_multipart("/documents/upload", form).await.json().await.unwrap();
+    assert_eq!(
+        serde_json::json!([{ "page": 1, "confidence": 30.0 }, { "page": 2, "confidence": 50.0 }]),
+        document["ocr_pages"]
+    );
+    assert_eq!(true, document["ocr_low_confidence"]);
+    let id = document["id"].as_str().unwrap();
+    let listed: serde_json::Value = app.get("/documents").await.json().await.unwrap();
+    let listed = listed.as_array().unwrap().iter().find(|d| d["id"] == id).unwrap();
+    assert_eq!(true, listed["ocr_low_confidence"]);
+    let version: serde_json::Value = app.get(&format!("/documents/{id}/versions/1")).await.json().await.unwrap();
+    assert_eq!("blurry\n\nreceipt", version["extracted_text"]);
+
+    for (mime_type, status) in [("image/png", 201), ("image/jpeg", 201), ("image/gif", 400)] {
+        let form = reqwest::multipart::Form::new().part(
+            "file",
+            reqwest::multipart::Part::bytes(format!("{mime_type} {marker}").into_bytes())
+                .file_name("photo")
+                .mime_str(mime_type)
+                .unwrap(),
+        );
+        assert_eq!(status, app.post_multipart("/documents/upload", form).await.status().as_u16(), "{mime_type}");
+    }
+
+    std::fs::remove_dir_all(tools).unwrap();
+}
@@ -0,0 +1,150 @@
+use common::spawn_app;
+
+mod common;
+
+#[tokio::test]
+async fn compliance_items_carry_tags_and_custom_fields_and_can_be_filtered() {
+    let app = spawn_app().await;
+    app.register_and_login().await;
+
+    // 1. Define a tag and an enum custom field
+    let tag: serde_json::Value = app
+        .post_json("/tags", &serde_json::json!({ "name": "Vendor" }))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let response = app
+        .post_json(
+            "/custom-fields",
+            &serde_json::json!({
+                "entity_type": "compliance_item",
+                "name": "jurisdiction",
+                "label": "Jurisdiction",
+                "field_type": "enum",
+                "options": ["EU", "US"]
+            }),
+        )
+        .await;
+    assert_eq!(201, response.status().as_u16());
+
+    // 2. Invalid enum value is rejected
+    let response = app
+        .post_json(
+            "/compliance",
+            &serde_json::json!({
+                "title": "GDPR vendor review",
+                "risk_level": "high",
+                "status": "pending",
+                "custom_fields": { "jurisdiction": "APAC" }
+            }),
+        )
+        .await;
+    assert_eq!(400, response.status().as_u16());
+
+    // 3. Valid item is returned with its metadata
+    let response = app
+        .post_json(
+            "/compliance",
+            &serde_json::json!({
+                "title": "GDPR vendor review",
+                "risk_level": "high",
+                "status": "pending",
+                "tag_ids": [tag["id"]],
+                "custom_fields": { "jurisdiction": "EU" }
+            }),
+        )
+        .await;
+    assert_eq!(201, response.status().as_u16());
+    let item: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("Vendor", item["tags"][0]["name"]);
+    assert_eq!("EU", item["custom_fields"]["jurisdiction"]);
+
+    app.create_compliance_item("Untagged item").await;
+
+    // 4. Filters narrow the list
+    let items: serde_json::Value = app.get("/compliance?tags=vendor").await.json().await.unwrap();
+    assert_eq!(1, items.as_array().unwrap().len());
+
+    let items: serde_json::Value = app.get("/compliance?cf.jurisdiction=US").await.json().await.unwrap();
+    assert_eq!(0, items.as_array().unwrap().len());
+
+    let items: serde_json::Value = app.get("/compliance").await.json().await.unwrap();
+    assert_eq!(2, items.as_array().unwrap().len());
+}
+
+#[tokio::test]
+async fn documents_can_be_filtered_by_tag() {
+    let app = spawn_app().await;
+    app.register_and_login().await;
+
+    let tag: serde_json::Value = app
+        .post_json("/tags", &serde_json::json!({ "name": "Contracts" }))
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let document: serde_json::Value = app
+        .post_json("/documents/text", &serde_json::json!({ "title": "MSA", "content": "Master agreement" }))
+        .await
+        .json()
+        .await
+        .unwrap();
+    app.post_json("/documents/text", &serde_json::json!({ "title": "Memo", "content": "Internal" }))
+        .await;
+
+    let response = app
+        .put_json(
+            &format!("/documents/{}", document["id"].as_str().unwrap()),
+            &serde_json::json!({ "tag_ids": [tag["id"]] }),
+        )
+        .await;
+    assert_eq!(200, response.status().as_u16());
+
+    let documents: serde_json::Value = app.get("/documents?tags=contracts").await.json().await.unwrap();
+    let documents = documents.as_array().unwrap();
+    assert_eq!(1, documents.len());
+    assert_eq!("Contracts", documents[0]["tags"][0]["name"]);
+}
+
+#[tokio::test]
+async fn custom_field_options_are_validated_against_the_field_type_on_update() {
+    let app = spawn_app().await;
+    app.register_and_login().await;
+
+    let mut ids = Vec::new();
+    for (name, field_type, options) in [("owner", "text", None), ("region", "enum", Some(["EU", "US"]))] {
+        let response = app
+            .post_json(
+                "/custom-fields",
+                &serde_json::json!({
+                    "entity_type": "compliance_item",
+                    "name": name,
+                    "label": name,
+                    "field_type": field_type,
+                    "options": options
+                }),
+            )
+            .await;
+        assert_eq!(201, response.status().as_u16());
+        let field: serde_json::Value = response.json().await.unwrap();
+        ids.push(field["id"].as_str().unwrap().to_string());
+    }
+
+    let options = serde_json::json!({ "options": ["Legal", "IT"] });
+    let response = app.put_json(&format!("/custom-fields/{}", ids[0]), &options).await;
+    assert_eq!(400, response.status().as_u16());
+
+    let response = app.put_json(&format!("/custom-fields/{}", ids[1]), &serde_json::json!({ "options": [] })).await;
+    assert_eq!(400, response.status().as_u16());
+    let response = app
+        .put_json(&format!("/custom-fields/{}", ids[1]), &serde_json::json!({ "options": ["EU", "US", "APAC"] }))
+        .await;
+    assert_eq!(200, response.status().as_u16());
+    let field: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(serde_json::json!(["EU", "US", "APAC"]), field["options"]);
+
+    let response = app.put_json(&format!("/custom-fields/{}", uuid::Uuid::new_v4()), &options).await;
+    assert_eq!(404, response.status().as_u16());
+}
@@ -0,0 +1,148 @@
+use chrono::{Duration, Utc};
+use common::spawn_app_with;
+use std::sync::Arc;
+
+use parseguard_backend::{
+    config::EmailTransportKind,
+    services::{FileEmailTransport, NotificationService},
+};
+use uuid::Uuid;
+
+mod common;
+
+/// Read the emails to `recipient` written by the file transport, waiting for `count` to arrive
+async fn read_outbox(dir: &std::path::Path, recipient: &str, count: usize) -> Vec<String> {
+    for _ in 0..50 {
+        if let Ok(entries) = std::fs::read_dir(dir) {
+            let emails: Vec<String> = entries
+                .map(|e| std::fs::read_to_string(e.unwrap().path()).unwrap())
+                .filter(|email| email.contains(recipient))
+                .collect();
+            if emails.len() >= count {
+                return emails;
+            }
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    panic!("expected {} emails in {}", count, dir.display());
+}
+
+#[tokio::test]
+async fn notifications_reach_only_users_who_can_open_the_item() {
+    let outbox = std::env::temp_dir().join(format!("parseguard-outbox-{}", Uuid::new_v4()));
+    let outbox_dir = outbox.clone();
+    let app = spawn_app_with(move |config| {
+        config.email.transport = EmailTransportKind::File;
+        config.email.file_dir = outbox_dir.to_string_lossy().into_owned();
+    })
+    .await;
+
+    // The assignee registers first; the owner logs in last and makes the changes
+    let assignee_email = app.register_and_login().await;
+    let invalid = serde_json::json!({ "reminder_lead_days": [0] });
+    let response = app.put_json("/notifications/preferences", &invalid).await;
+    assert_eq!(400, response.status().as_u16());
+    let response = app.get("/notifications/preferences").await;
+    let preferences: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(serde_json::json!([7, 1]), preferences["reminder_lead_days"]);
+    let assignee_id: Uuid = sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+        .bind(&assignee_email)
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+
+    let owner_email = app.register_and_login().await;
+    // Commas, quotes and angle brackets in names must not break the recipient header
+    sqlx::query("UPDATE users SET full_name = $1 WHERE email = $2")
+        .bind(r#"Doe, "Jane" <QA>"#)
+        .bind(&owner_email)
+        .execute(&app.pool)
+        .await
+        .unwrap();
+
+    let body = serde_json::json!({
+        "title": "Rotate signing keys",
+        "risk_level": "high",
+        "status": "pending",
+        "due_date": Utc::now() + Duration::hours(12),
+        "assignee_id": assignee_id
+    });
+    let item: serde_json::Value = app.post_json("/compliance", &body).await.json().await.unwrap();
+    let item_id = item["id"].as_str().unwrap();
+
+    let response = app
+        .put_json(&format!("/compliance/{}", item_id), &serde_json::json!({ "status": "in_progress" }))
+        .await;
+    assert_eq!(200, response.status().as_u16());
+
+    for risk_score in [60, 10] {
+        let body = serde_json::json!({
+            "compliance_item_id": item_id, "risk_category": "Security", "risk_score": risk_score
+        });
+        assert_eq!(201, app.post_json("/risk-scores", &body).await.status().as_u16());
+    }
+
+    // Both lead times are reached at once, only the shorter one is sent, and only once
+    let mailer = FileEmailTransport::new("ParseGuard <noreply@parseguard.local>", &outbox).unwrap();
+    let service = NotificationService::new(app.pool.clone(), Arc::new(mailer));
+    let now = Utc::now();
+    let sent = service.send_due_date_reminders(now).await.unwrap();
+    assert!(sent >= 1);
+    assert_eq!(0, service.send_due_date_reminders(now).await.unwrap());
+
+    // The owner made the changes: only the risk alert and the reminder, in the app and by email
+    let list: serde_json::Value = app.get("/notifications").await.json().await.unwrap();
+    let notifications = list["notifications"].as_array().unwrap();
+    let kinds: Vec<&str> = notifications.iter().map(|n| n["kind"].as_str().unwrap()).collect();
+    assert_eq!(vec!["due_date_reminder", "risk_alert"], kinds);
+    assert_eq!(2, list["unread_count"]);
+    assert_eq!("Due today: Rotate signing keys", notifications[0]["title"]);
+    assert_eq!("High risk: Rotate signing keys", notifications[1]["title"]);
+
+    let emails = read_outbox(&outbox, &owner_email, 2).await;
+    assert_eq!(2, emails.len());
+    assert!(emails.iter().any(|e| e.contains("Subject: High risk: Rotate signing keys")));
+    assert!(emails.iter().all(|e| e.contains(&format!("<{}>", owner_email))));
+
+    // The assignee cannot open the item, so hears of nothing
+    let login = serde_json::json!({ "email": assignee_email, "password": "password123" });
+    assert_eq!(200, app.post_login(&login).await.status().as_u16());
+    let response = app.get(&format!("/compliance/{}", item_id)).await;
+    assert_eq!(404, response.status().as_u16());
+    let assigned: serde_json::Value = app.get("/notifications").await.json().await.unwrap();
+    assert!(assigned["notifications"].as_array().unwrap().is_empty());
+    let outbox_entries = std::fs::read_dir(&outbox).unwrap();
+    assert!(outbox_entries
+        .map(|e| std::fs::read_to_string(e.unwrap().path()).unwrap())
+        .all(|email| !email.contains(&assignee_email)));
+
+    let login = serde_json::json!({ "email": owner_email, "password": "password123" });
+    assert_eq!(200, app.post_login(&login).await.status().as_u16());
+
+    let id = notifications[1]["id"].as_str().unwrap();
+    let read: serde_json::Value = app
+        .post_json(&format!("/notifications/{}/read", id), &serde_json::json!({}))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert!(read["read_at"].is_string());
+    let unread: serde_json::Value = app.get("/notifications?unread_only=true").await.json().await.unwrap();
+    assert_eq!(1, unread["notifications"].as_array().unwrap().len());
+    assert_eq!(1, unread["unread_count"]);
+
+    let marked: serde_json::Value = app
+        .post_json("/notifications/read-all", &serde_json::json!({}))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(1, marked["updated"]);
+
+    let response = app
+        .post_json(&format!("/notifications/{}/read", Uuid::new_v4()), &serde_json::json!({}))
+        .await;
+    assert_eq!(404, response.status().as_u16());
+
+    std::fs::remove_dir_all(&outbox).ok();
+}
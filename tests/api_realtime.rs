@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use common::{spawn_app, TestApp};
+use futures_util::{SinkExt, StreamExt};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+
+mod common;
+
+type Socket = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Log in through `app` and return a bearer token
+async fn bearer_token(app: &TestApp, email: &str) -> String {
+    let body = serde_json::json!({ "email": email, "password": "password123" });
+    let response = app
+        .api_client
+        .post(format!("{}/api/auth/login?return_token=true", &app.address))
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["access_token"].as_str().unwrap().to_string()
+}
+
+async fn connect(app: &TestApp, token: Option<&str>) -> Result<Socket, tokio_tungstenite::tungstenite::Error> {
+    let mut request = format!("{}/api/ws", app.address.replace("http://", "ws://"))
+        .into_client_request()
+        .unwrap();
+    if let Some(token) = token {
+        request
+            .headers_mut()
+            .insert("Authorization", format!("Bearer {}", token).parse().unwrap());
+    }
+    connect_async(request).await.map(|(socket, _)| socket)
+}
+
+async fn send(socket: &mut Socket, message: serde_json::Value) {
+    socket.send(Message::Text(message.to_string())).await.unwrap();
+}
+
+/// Next JSON message, or None if nothing arrives in time
+async fn next_json(socket: &mut Socket, wait: Duration) -> Option<serde_json::Value> {
+    loop {
+        match tokio::time::timeout(wait, socket.next()).await {
+            Ok(Some(Ok(Message::Text(text)))) => return Some(serde_json::from_str(&text).unwrap()),
+            Ok(Some(Ok(_))) => continue,
+            _ => return None,
+        }
+    }
+}
+
+#[tokio::test]
+async fn websocket_pushes_changes_across_replicas() {
+    let app = spawn_app().await;
+    let replica = spawn_app().await;
+    let wait = Duration::from_secs(5);
+
+    assert!(connect(&app, None).await.is_err());
+    assert!(connect(&app, Some("not-a-token")).await.is_err());
+
+    let email = app.register_and_login().await;
+    let token = bearer_token(&app, &email).await;
+    let mut socket = connect(&app, Some(&token)).await.unwrap();
+
+    send(&mut socket, serde_json::json!({ "action": "subscribe", "topics": ["compliance_item", "analysis"] })).await;
+    let reply = next_json(&mut socket, wait).await.unwrap();
+    assert_eq!(serde_json::json!({ "type": "subscribed", "topics": ["compliance_item", "analysis"] }), reply);
+    send(&mut socket, serde_json::json!({ "action": "ping" })).await;
+    assert_eq!("pong", next_json(&mut socket, wait).await.unwrap()["type"]);
+    send(&mut socket, serde_json::json!({ "action": "shout" })).await;
+    assert_eq!("error", next_json(&mut socket, wait).await.unwrap()["type"]);
+
+    // A change made through another replica reaches this one
+    replica.post_login(&serde_json::json!({ "email": email, "password": "password123" })).await;
+    let item = replica.create_compliance_item("Review firewall rules").await;
+    let event = next_json(&mut socket, wait).await.unwrap();
+    assert_eq!("entity_changed", event["type"]);
+    assert_eq!("compliance_item", event["entity_type"]);
+    assert_eq!("created", event["verb"]);
+    assert_eq!(item["id"], event["entity_id"]);
+    assert!(event.get("user_id").is_none());
+
+    // Analysis progress is pushed under the client's job ID
+    let job_id = uuid::Uuid::new_v4();
+    app.post_json("/ai/analyze", &serde_json::json!({ "text": "Policy text", "job_id": job_id })).await;
+    let started = next_json(&mut socket, wait).await.unwrap();
+    assert_eq!("analysis_progress", started["type"]);
+    assert_eq!(job_id.to_string(), started["job_id"]);
+    assert_eq!("started", started["stage"]);
+    let finished = next_json(&mut socket, wait).await.unwrap();
+    assert_eq!(job_id.to_string(), finished["job_id"]);
+    assert_eq!(100, finished["progress"]);
+
+    // Other users' changes and unsubscribed topics are not pushed
+    replica.register_and_login().await;
+    replica.create_compliance_item("Someone else's item").await;
+    send(&mut socket, serde_json::json!({ "action": "unsubscribe", "topics": ["compliance_item"] })).await;
+    assert_eq!(serde_json::json!(["analysis"]), next_json(&mut socket, wait).await.unwrap()["topics"]);
+    app.create_compliance_item("Not subscribed").await;
+    assert!(next_json(&mut socket, Duration::from_millis(500)).await.is_none());
+}
@@ -0,0 +1,51 @@
+use common::spawn_app;
+
+mod common;
+
+#[tokio::test]
+async fn audit_report_is_rendered_as_pdf_and_stored_as_document() {
+    let app = spawn_app().await;
+    app.register_and_login().await;
+
+    let item = app.create_compliance_item("Encrypt laptops").await;
+    app.create_compliance_item("Review vendor contracts").await;
+    let document: serde_json::Value = app
+        .post_json("/documents/text", &serde_json::json!({ "title": "Disk encryption policy", "content": "All laptops use full disk encryption." }))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let body = serde_json::json!({
+        "compliance_item_id": item["id"], "document_id": document["id"], "risk_category": "Security",
+        "likelihood": 4, "impact": 5, "ai_confidence": 0.8,
+        "ai_reasoning": "Unencrypted devices expose customer data when lost."
+    });
+    assert_eq!(201, app.post_json("/risk-scores", &body).await.status().as_u16());
+
+    let response = app.post_json("/reports/audit", &serde_json::json!({})).await;
+    assert_eq!(201, response.status().as_u16());
+    let report: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("application/pdf", report["mime_type"]);
+    assert!(report["filename"].as_str().unwrap().starts_with("audit-report-"));
+
+    let content = app.get(&format!("/documents/{}/content", report["id"].as_str().unwrap())).await;
+    let bytes = content.bytes().await.unwrap();
+    assert_eq!(report["file_size"].as_u64().unwrap(), bytes.len() as u64);
+    assert!(bytes.starts_with(b"%PDF-"));
+
+    let pdf = printpdf::lopdf::Document::load_mem(&bytes).unwrap();
+    let pages: Vec<u32> = pdf.get_pages().keys().copied().collect();
+    let text = pdf.extract_text(&pages).unwrap();
+    for expected in [
+        "Compliance Audit Report",
+        "Encrypt laptops",
+        "Review vendor contracts",
+        "AI reasoning (confidence 80%): Unencrypted devices expose customer data when lost.",
+        "Disk_encryption_policy.txt",
+    ] {
+        assert!(text.contains(expected), "missing '{expected}' in report text:\n{text}");
+    }
+
+    let documents: Vec<serde_json::Value> = app.get("/documents").await.json().await.unwrap();
+    assert!(documents.iter().any(|d| d["id"] == report["id"]));
+}
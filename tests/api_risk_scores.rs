@@ -0,0 +1,336 @@
+use common::spawn_app;
+
+mod common;
+
+#[tokio::test]
+async fn risk_score_is_computed_from_likelihood_and_impact() {
+    let app = spawn_app().await;
+    app.register_and_login().await;
+    let item = app.create_compliance_item("Third-party access").await;
+
+    let response = app
+        .post_json(
+            "/risk-scores",
+            &serde_json::json!({
+                "compliance_item_id": item["id"],
+                "risk_category": "Security",
+                "likelihood": 4,
+                "impact": 5
+            }),
+        )
+        .await;
+    assert_eq!(201, response.status().as_u16());
+    let score: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(80, score["risk_score"]);
+    assert_eq!("critical", score["risk_level"]);
+
+    // Lowering the likelihood recomputes score and level
+    let response = app
+        .put_json(
+            &format!("/risk-scores/{}", score["id"].as_str().unwrap()),
+            &serde_json::json!({ "likelihood": 1 }),
+        )
+        .await;
+    assert_eq!(200, response.status().as_u16());
+    let score: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(20, score["risk_score"]);
+    assert_eq!("low", score["risk_level"]);
+}
+
+#[tokio::test]
+async fn inconsistent_manual_risk_input_is_rejected() {
+    let app = spawn_app().await;
+    app.register_and_login().await;
+    let item = app.create_compliance_item("Data retention").await;
+
+    // Score of 5 cannot be critical
+    let response = app
+        .post_json(
+            "/risk-scores",
+            &serde_json::json!({
+                "compliance_item_id": item["id"],
+                "risk_category": "Privacy",
+                "risk_score": 5,
+                "risk_level": "critical"
+            }),
+        )
+        .await;
+    assert_eq!(400, response.status().as_u16());
+
+    // Score that disagrees with likelihood × impact
+    let response = app
+        .post_json(
+            "/risk-scores",
+            &serde_json::json!({
+                "compliance_item_id": item["id"],
+                "risk_category": "Privacy",
+                "likelihood": 2,
+                "impact": 2,
+                "risk_score": 90
+            }),
+        )
+        .await;
+    assert_eq!(400, response.status().as_u16());
+
+    // Level is derived from a manual score when omitted
+    let response = app
+        .post_json(
+            "/risk-scores",
+            &serde_json::json!({
+                "compliance_item_id": item["id"],
+                "risk_category": "Privacy",
+                "risk_score": 60
+            }),
+        )
+        .await;
+    assert_eq!(201, response.status().as_u16());
+    let score: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("high", score["risk_level"]);
+}
+
+#[tokio::test]
+async fn risk_matrix_definition_is_exposed() {
+    let app = spawn_app().await;
+    app.register_and_login().await;
+
+    let response = app.get("/risk-scores/matrix").await;
+    assert_eq!(200, response.status().as_u16());
+
+    let matrix: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(5, matrix["scale"]);
+    assert_eq!(25, matrix["cells"].as_array().unwrap().len());
+    assert_eq!(5, matrix["levels"].as_array().unwrap().len());
+}
+
+#[tokio::test]
+async fn mitigating_controls_reduce_residual_risk() {
+    let app = spawn_app().await;
+    app.register_and_login().await;
+
+    let item = app.create_compliance_item("Customer data exposure").await;
+    let encryption = app.create_compliance_item("Encrypt data at rest").await;
+    let access_review = app.create_compliance_item("Quarterly access review").await;
+
+    let response = app
+        .post_json(
+            "/risk-scores",
+            &serde_json::json!({
+                "compliance_item_id": item["id"],
+                "risk_category": "Security",
+                "likelihood": 4,
+                "impact": 5
+            }),
+        )
+        .await;
+    let score: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(80, score["risk_score"]);
+    assert_eq!(80, score["residual_score"]);
+    let score_id = score["id"].as_str().unwrap();
+
+    // 80 × (1 - 0.5) = 40
+    let response = app
+        .post_json(
+            &format!("/risk-scores/{}/controls", score_id),
+            &serde_json::json!({ "compliance_item_id": encryption["id"], "effectiveness": 50 }),
+        )
+        .await;
+    assert_eq!(201, response.status().as_u16());
+    let control: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("Encrypt data at rest", control["control_title"]);
+    assert_eq!(40, control["risk_reduction"]);
+
+    let response = app
+        .post_json(
+            &format!("/risk-scores/{}/controls", score_id),
+            &serde_json::json!({ "compliance_item_id": encryption["id"], "effectiveness": 20 }),
+        )
+        .await;
+    assert_eq!(400, response.status().as_u16());
+
+    // 80 × 0.5 × 0.75 = 30
+    app.post_json(
+        &format!("/risk-scores/{}/controls", score_id),
+        &serde_json::json!({ "compliance_item_id": access_review["id"], "effectiveness": 25 }),
+    )
+    .await;
+
+    let score: serde_json::Value = app
+        .get(&format!("/risk-scores/{}", score_id))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(80, score["risk_score"]);
+    assert_eq!("critical", score["risk_level"]);
+    assert_eq!(30, score["residual_score"]);
+    assert_eq!("medium", score["residual_level"]);
+
+    let report: serde_json::Value = app
+        .get("/risk-scores/control-effectiveness")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(2, report.as_array().unwrap().len());
+    assert_eq!("Encrypt data at rest", report[0]["control_title"]);
+    assert_eq!(30, report[0]["total_risk_reduction"]);
+
+    // Deleting the control's compliance item restores the risk it removed
+    let response = app.delete(&format!("/compliance/{}", encryption["id"].as_str().unwrap())).await;
+    assert_eq!(204, response.status().as_u16());
+
+    let score: serde_json::Value = app
+        .get(&format!("/risk-scores/{}", score_id))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(60, score["residual_score"]);
+    assert_eq!("high", score["residual_level"]);
+}
+
+#[tokio::test]
+async fn risk_trend_buckets_scores_and_flags_jumps() {
+    let app = spawn_app().await;
+    app.register_and_login().await;
+
+    let item = app.create_compliance_item("Vendor onboarding").await;
+    let item_id = item["id"].as_str().unwrap();
+
+    for (date, category, score) in [
+        ("2026-01-10T09:00:00Z", "Security", 20),
+        ("2026-01-20T09:00:00Z", "Privacy", 30),
+        ("2026-03-05T09:00:00Z", "Security", 80),
+    ] {
+        let response = app
+            .post_json(
+                "/risk-scores",
+                &serde_json::json!({
+                    "compliance_item_id": item_id,
+                    "risk_category": category,
+                    "risk_score": score,
+                    "assessment_date": date
+                }),
+            )
+            .await;
+        assert_eq!(201, response.status().as_u16());
+    }
+
+    let response = app
+        .get(&format!("/risk-scores/compliance/{}/trend?interval=month", item_id))
+        .await;
+    assert_eq!(200, response.status().as_u16());
+    let trend: serde_json::Value = response.json().await.unwrap();
+
+    let buckets = trend["buckets"].as_array().unwrap();
+    assert_eq!(2, buckets.len());
+    assert_eq!(2, buckets[0]["assessments"]);
+    assert_eq!(30, buckets[0]["latest_score"]);
+    assert_eq!(25.0, buckets[0]["average_score"]);
+    assert_eq!(80, buckets[1]["max_score"]);
+
+    let categories = trend["categories"].as_array().unwrap();
+    assert_eq!(2, categories.len());
+    assert_eq!("Security", categories[1]["risk_category"]);
+    assert_eq!(2, categories[1]["buckets"].as_array().unwrap().len());
+
+    let jumps = trend["jumps"].as_array().unwrap();
+    assert_eq!(1, jumps.len());
+    assert_eq!(20, jumps[0]["previous_score"]);
+    assert_eq!(60, jumps[0]["change"]);
+
+    // The org-wide trend honours the date range and threshold
+    let trend: serde_json::Value = app
+        .get("/risk-scores/trend?interval=day&from=2026-01-15T00:00:00Z&jump_threshold=70")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(2, trend["buckets"].as_array().unwrap().len());
+    assert!(trend["jumps"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn risk_score_import_resolves_compliance_items_by_title() {
+    let app = spawn_app().await;
+    app.register_and_login().await;
+    let item = app.create_compliance_item("Vendor review").await;
+
+    let csv = format!(
+        "compliance_item_title,compliance_item_id,risk_category,likelihood,impact,notes\n\
+         Vendor review,,Third party,4,5,From spreadsheet\n\
+         ,{},Privacy,2,2,\n",
+        item["id"].as_str().unwrap()
+    );
+    let form = || {
+        let file = reqwest::multipart::Part::bytes(csv.as_bytes().to_vec()).file_name("scores.csv");
+        reqwest::multipart::Form::new().part("file", file)
+    };
+
+    let preview: serde_json::Value = app
+        .post_multipart("/risk-scores/import/preview", form())
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!("likelihood", preview["suggested_mapping"]["likelihood"]);
+
+    let response = app.post_multipart("/risk-scores/import", form()).await;
+    assert_eq!(201, response.status().as_u16());
+
+    let scores: Vec<serde_json::Value> = app
+        .get(&format!("/risk-scores/compliance/{}", item["id"].as_str().unwrap()))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(2, scores.len());
+    let third_party = scores.iter().find(|s| s["risk_category"] == "Third party").unwrap();
+    assert_eq!("From spreadsheet", third_party["notes"]);
+    assert_eq!(4, third_party["likelihood"]);
+
+    // Imported scores are recorded and alerted on like created ones
+    let feed: serde_json::Value = app.get("/activity?entity_type=risk_score").await.json().await.unwrap();
+    assert_eq!(2, feed["events"].as_array().unwrap().len());
+    let notifications: serde_json::Value = app.get("/notifications").await.json().await.unwrap();
+    assert!(notifications["notifications"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|n| n["kind"] == "risk_alert" && n["entity_id"] == third_party["id"]));
+
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    let sheet = workbook.add_worksheet();
+    let date = rust_xlsxwriter::ExcelDateTime::from_ymd(2026, 3, 1).unwrap();
+    let date_format = rust_xlsxwriter::Format::new().set_num_format("yyyy-mm-dd");
+    for (col, header) in ["Compliance Item Title", "Risk Category", "Risk Score", "Assessment Date"].iter().enumerate() {
+        sheet.write_string(0, col as u16, *header).unwrap();
+    }
+    sheet.write_string(1, 0, "Vendor review").unwrap();
+    sheet.write_string(1, 1, "Continuity").unwrap();
+    sheet.write_number(1, 2, 35).unwrap();
+    sheet.write_datetime_with_format(1, 3, &date, &date_format).unwrap();
+    let file = reqwest::multipart::Part::bytes(workbook.save_to_buffer().unwrap()).file_name("scores.xlsx");
+    let form = reqwest::multipart::Form::new().part("file", file);
+    assert_eq!(201, app.post_multipart("/risk-scores/import", form).await.status().as_u16());
+
+    let scores: Vec<serde_json::Value> = app
+        .get(&format!("/risk-scores/compliance/{}", item["id"].as_str().unwrap()))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let continuity = scores.iter().find(|s| s["risk_category"] == "Continuity").unwrap();
+    assert_eq!(35, continuity["risk_score"]);
+    assert!(continuity["assessment_date"].as_str().unwrap().starts_with("2026-03-01"));
+
+    let bad = "compliance_item_title,risk_category,risk_score,risk_level\nMissing item,Security,10,low\nVendor review,Security,90,low\n";
+    let file = reqwest::multipart::Part::bytes(bad.as_bytes().to_vec()).file_name("scores.csv");
+    let response = app
+        .post_multipart("/risk-scores/import", reqwest::multipart::Form::new().part("file", file))
+        .await;
+    assert_eq!(422, response.status().as_u16());
+    let report: serde_json::Value = response.json().await.unwrap();
+    let rows: Vec<u64> = report["errors"].as_array().unwrap().iter().map(|e| e["row"].as_u64().unwrap()).collect();
+    assert_eq!(vec![2, 3], rows);
+}
@@ -0,0 +1,161 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{extract::State, http::{HeaderMap, StatusCode}, routing::post, Router};
+use chrono::{Duration, Utc};
+use common::{spawn_app, spawn_app_with};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use parseguard_backend::{config::Config, services::WebhookService};
+
+mod common;
+
+type Received = Arc<Mutex<Vec<(HeaderMap, String)>>>;
+
+/// Start an endpoint that records requests, failing the first one with a 500
+async fn spawn_receiver() -> (String, Received) {
+    async fn receive(State(received): State<Received>, headers: HeaderMap, body: String) -> StatusCode {
+        let mut received = received.lock().unwrap();
+        received.push((headers, body));
+        if received.len() == 1 {
+            StatusCode::INTERNAL_SERVER_ERROR
+        } else {
+            StatusCode::OK
+        }
+    }
+
+    let received = Received::default();
+    let app = Router::new().route("/hook", post(receive)).with_state(received.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    (url, received)
+}
+
+/// Let webhooks reach the receiver on the loopback interface
+fn allow_local_receiver(config: &mut Config) {
+    config.webhook_allowed_hosts = vec!["127.0.0.1".to_string()];
+}
+
+#[tokio::test]
+async fn webhooks_deliver_signed_events_and_retry_failures() {
+    let app = spawn_app_with(allow_local_receiver).await;
+    let (url, received) = spawn_receiver().await;
+    app.register_and_login().await;
+
+    let invalid = serde_json::json!({ "url": "ftp://example.com/hook", "event_types": ["compliance_item.created"] });
+    assert_eq!(400, app.post_json("/webhooks", &invalid).await.status().as_u16());
+    let invalid = serde_json::json!({ "url": url, "event_types": ["webhook.test"] });
+    assert_eq!(400, app.post_json("/webhooks", &invalid).await.status().as_u16());
+
+    let secret = "a-very-secret-signing-key";
+    let body = serde_json::json!({
+        "url": url,
+        "event_types": ["compliance_item.created", "compliance_item.status_changed"],
+        "secret": secret
+    });
+    let response = app.post_json("/webhooks", &body).await;
+    assert_eq!(201, response.status().as_u16());
+    let webhook: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(secret, webhook["secret"]);
+    let webhook_id = webhook["id"].as_str().unwrap();
+    let fetched: serde_json::Value = app.get(&format!("/webhooks/{}", webhook_id)).await.json().await.unwrap();
+    assert!(fetched.get("secret").is_none());
+
+    // Only subscribed events are queued
+    let item = app.create_compliance_item("Encrypt backups").await;
+    let item_id = item["id"].as_str().unwrap();
+    let update = serde_json::json!({ "title": "Encrypt all backups" });
+    assert_eq!(200, app.put_json(&format!("/compliance/{}", item_id), &update).await.status().as_u16());
+
+    let deliveries_path = format!("/webhooks/{}/deliveries", webhook_id);
+    let deliveries: Vec<serde_json::Value> = app.get(&deliveries_path).await.json().await.unwrap();
+    assert_eq!(1, deliveries.len());
+    assert_eq!("compliance_item.created", deliveries[0]["event_type"]);
+    assert_eq!("pending", deliveries[0]["status"]);
+
+    // The first attempt fails and is retried later
+    let mut config = Config::from_env();
+    allow_local_receiver(&mut config);
+    let service = WebhookService::new(app.pool.clone(), &config);
+    assert!(service.process_due(Utc::now()).await.unwrap() >= 1);
+    let deliveries: Vec<serde_json::Value> = app.get(&deliveries_path).await.json().await.unwrap();
+    assert_eq!("pending", deliveries[0]["status"]);
+    assert_eq!(1, deliveries[0]["attempts"]);
+    assert_eq!(500, deliveries[0]["response_status"]);
+
+    assert!(service.process_due(Utc::now() + Duration::hours(1)).await.unwrap() >= 1);
+    let deliveries: Vec<serde_json::Value> = app.get(&deliveries_path).await.json().await.unwrap();
+    assert_eq!("succeeded", deliveries[0]["status"]);
+    assert_eq!(2, deliveries[0]["attempts"]);
+    assert_eq!(200, deliveries[0]["response_status"]);
+    assert!(deliveries[0]["delivered_at"].is_string());
+
+    let (headers, body) = received.lock().unwrap().last().cloned().unwrap();
+    assert_eq!("compliance_item.created", headers["x-parseguard-event"]);
+    assert_eq!(deliveries[0]["id"].as_str().unwrap(), headers["x-parseguard-delivery"]);
+    let signature = headers["x-parseguard-signature"].to_str().unwrap();
+    let (timestamp, digest) = signature
+        .strip_prefix("t=")
+        .and_then(|s| s.split_once(",v1="))
+        .unwrap();
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+    assert_eq!(hex::encode(mac.finalize().into_bytes()), digest);
+    let event: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!("compliance_item.created", event["type"]);
+    assert_eq!(item_id, event["data"]["id"]);
+
+    // Test events are attempted right away
+    let response = app.post_json(&format!("/webhooks/{}/test", webhook_id), &serde_json::json!({})).await;
+    assert_eq!(200, response.status().as_u16());
+    let delivery: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("webhook.test", delivery["event_type"]);
+    assert_eq!("succeeded", delivery["status"]);
+
+    let rotate = serde_json::json!({ "rotate_secret": true, "active": false });
+    let rotated: serde_json::Value = app
+        .put_json(&format!("/webhooks/{}", webhook_id), &rotate)
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert!(rotated["secret"].as_str().unwrap().starts_with("whsec_"));
+    assert_eq!(false, rotated["active"]);
+
+    assert_eq!(204, app.delete(&format!("/webhooks/{}", webhook_id)).await.status().as_u16());
+    assert_eq!(404, app.get(&format!("/webhooks/{}", webhook_id)).await.status().as_u16());
+}
+
+#[tokio::test]
+async fn webhooks_cannot_target_private_or_local_addresses() {
+    let app = spawn_app().await;
+    let (url, received) = spawn_receiver().await;
+    app.register_and_login().await;
+
+    for target in [url.as_str(), "http://localhost/hook", "http://169.254.169.254/latest", "http://[::1]/hook"] {
+        let body = serde_json::json!({ "url": target, "event_types": ["compliance_item.created"] });
+        assert_eq!(400, app.post_json("/webhooks", &body).await.status().as_u16(), "{}", target);
+    }
+
+    // A host that later resolves to a local address is refused at delivery time
+    let body = serde_json::json!({ "url": "http://1.1.1.1/hook", "event_types": ["compliance_item.created"] });
+    let response = app.post_json("/webhooks", &body).await;
+    assert_eq!(201, response.status().as_u16());
+    let webhook: serde_json::Value = response.json().await.unwrap();
+    let webhook_id = webhook["id"].as_str().unwrap();
+    sqlx::query("UPDATE webhook_subscriptions SET url = $1 WHERE id = $2::uuid")
+        .bind(&url)
+        .bind(webhook_id)
+        .execute(&app.pool)
+        .await
+        .unwrap();
+
+    let response = app.post_json(&format!("/webhooks/{}/test", webhook_id), &serde_json::json!({})).await;
+    assert_eq!(200, response.status().as_u16());
+    let delivery: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("pending", delivery["status"]);
+    assert!(delivery["error"].as_str().unwrap().contains("private or local address"));
+    assert!(received.lock().unwrap().is_empty());
+}
@@ -1,3 +1,5 @@
+#![allow(dead_code)]
+
 use std::net::TcpListener;
 
 pub struct TestApp {
@@ -7,18 +9,27 @@ pub struct TestApp {
 }
 
 pub async fn spawn_app() -> TestApp {
+    spawn_app_with(|_| {}).await
+}
+
+/// Spawn the app with an adjusted configuration
+pub async fn spawn_app_with(configure: impl FnOnce(&mut parseguard_backend::config::Config)) -> TestApp {
     let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
     listener.set_nonblocking(true).expect("Failed to set non-blocking");
     let port = listener.local_addr().unwrap().port();
     let address = format!("http://127.0.0.1:{}", port);
 
-    let config = parseguard_backend::config::Config::from_env();
+    let mut config = parseguard_backend::config::Config::from_env();
+    configure(&mut config);
     let pool = parseguard_backend::db::create_pool(&config.database_url).await.unwrap();
     
     // Create AppState
     let state = parseguard_backend::AppState {
         pool: pool.clone(),
         config: config.clone(),
+        mailer: parseguard_backend::services::transport_from_config(&config.email).unwrap(),
+        storage: parseguard_backend::services::storage_from_config(&config).unwrap(),
+        realtime: parseguard_backend::services::RealtimeHub::start(pool.clone()).await.unwrap(),
     };
 
     // Build application router
@@ -28,7 +39,7 @@ pub async fn spawn_app() -> TestApp {
     
     // Spawn the server
     let server = axum::serve(tokio::net::TcpListener::from_std(listener).unwrap(), app);
-    let _ = tokio::spawn(async move {
+    tokio::spawn(async move {
         server.await.unwrap();
     });
 
@@ -48,7 +59,7 @@ pub async fn spawn_app() -> TestApp {
 impl TestApp {
     pub async fn post_register(&self, body: &serde_json::Value) -> reqwest::Response {
         self.api_client
-            .post(&format!("{}/api/auth/register", &self.address))
+            .post(format!("{}/api/auth/register", &self.address))
             .json(body)
             .send()
             .await
@@ -57,7 +68,7 @@ impl TestApp {
 
     pub async fn post_login(&self, body: &serde_json::Value) -> reqwest::Response {
         self.api_client
-            .post(&format!("{}/api/auth/login", &self.address))
+            .post(format!("{}/api/auth/login", &self.address))
             .json(body)
             .send()
             .await
@@ -66,9 +77,99 @@ impl TestApp {
 
     pub async fn get_dashboard_stats(&self) -> reqwest::Response {
         self.api_client
-            .get(&format!("{}/api/dashboard/stats", &self.address))
+            .get(format!("{}/api/dashboard/stats", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    /// Register a fresh user and log in, returning the user's email
+    pub async fn register_and_login(&self) -> String {
+        let email = format!("test-{}@example.com", uuid::Uuid::new_v4());
+        let body = serde_json::json!({
+            "full_name": "Test User",
+            "email": email,
+            "password": "password123"
+        });
+        self.post_register(&body).await;
+
+        let login_body = serde_json::json!({
+            "email": email,
+            "password": "password123"
+        });
+        let response = self.post_login(&login_body).await;
+        assert_eq!(200, response.status().as_u16());
+
+        email
+    }
+
+    pub async fn get(&self, path: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/api{}", &self.address, path))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_json(&self, path: &str, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/api{}", &self.address, path))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_multipart(&self, path: &str, form: reqwest::multipart::Form) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/api{}", &self.address, path))
+            .multipart(form)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn put_json(&self, path: &str, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .put(format!("{}/api{}", &self.address, path))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn delete(&self, path: &str) -> reqwest::Response {
+        self.api_client
+            .delete(format!("{}/api{}", &self.address, path))
             .send()
             .await
             .expect("Failed to execute request.")
     }
+
+    /// Storage key of a document or document version, which the API never returns
+    pub async fn storage_key(&self, entity: &serde_json::Value) -> String {
+        let id = uuid::Uuid::parse_str(entity["id"].as_str().expect("Entity has no id")).unwrap();
+        sqlx::query_scalar(
+            "SELECT storage_key FROM documents WHERE id = $1
+             UNION ALL
+             SELECT storage_key FROM document_versions WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+        .expect("Failed to fetch storage key")
+    }
+
+    /// Create a compliance item for the logged in user, returning its JSON
+    pub async fn create_compliance_item(&self, title: &str) -> serde_json::Value {
+        let body = serde_json::json!({
+            "title": title,
+            "risk_level": "medium",
+            "status": "pending"
+        });
+        let response = self.post_json("/compliance", &body).await;
+        assert_eq!(201, response.status().as_u16());
+
+        response.json().await.expect("Failed to parse compliance item")
+    }
 }
@@ -0,0 +1,279 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, Method, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    Router,
+};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use common::spawn_app_with;
+use parseguard_backend::{
+    config::{Config, StorageBackendKind},
+    services::{storage_from_config, StorageService},
+    utils::sigv4::{self, authorization_header, presigned_query, SignableRequest, SigningCredentials},
+};
+
+mod common;
+
+const BUCKET: &str = "docs";
+
+fn credentials() -> SigningCredentials {
+    SigningCredentials {
+        access_key_id: "test-key".to_string(),
+        secret_access_key: "test-secret".to_string(),
+        region: "eu-central-1".to_string(),
+    }
+}
+
+/// Object content and last modification time
+type StoredObject = (Vec<u8>, DateTime<Utc>);
+
+#[derive(Clone, Default)]
+struct Bucket {
+    objects: Arc<Mutex<BTreeMap<String, StoredObject>>>,
+}
+
+fn s3_error(status: StatusCode, code: &str) -> Response {
+    (status, format!("<?xml version=\"1.0\"?><Error><Code>{}</Code></Error>", code)).into_response()
+}
+
+/// Check the SigV4 signature of a request (header or presigned query)
+fn verify_signature(method: &Method, uri: &Uri, headers: &HeaderMap, body: &[u8]) -> bool {
+    let url = reqwest::Url::parse(&format!("http://stub{}", uri)).unwrap();
+    let query: Vec<(String, String)> = url.query_pairs().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    let host = headers[header::HOST].to_str().unwrap();
+
+    if let Some((_, signature)) = query.iter().find(|(name, _)| name == "X-Amz-Signature") {
+        let value = |name: &str| query.iter().find(|(n, _)| n == name).map(|(_, v)| v.clone()).unwrap_or_default();
+        let date = NaiveDateTime::parse_from_str(&value("X-Amz-Date"), "%Y%m%dT%H%M%SZ").unwrap().and_utc();
+        let extra: Vec<(String, String)> = query.iter().filter(|(name, _)| !name.starts_with("X-Amz-")).cloned().collect();
+        let expected = presigned_query(
+            &credentials(),
+            method.as_str(),
+            host,
+            uri.path(),
+            &extra,
+            value("X-Amz-Expires").parse().unwrap(),
+            date,
+        );
+        return expected.ends_with(&format!("X-Amz-Signature={}", signature))
+            && date + chrono::Duration::seconds(value("X-Amz-Expires").parse().unwrap()) > Utc::now();
+    }
+
+    let header = |name: &str| headers.get(name).map(|v| v.to_str().unwrap().to_string());
+    let (Some(authorization), Some(date), Some(payload_hash)) =
+        (header("authorization"), header("x-amz-date"), header("x-amz-content-sha256"))
+    else {
+        return false;
+    };
+    if payload_hash != sigv4::sha256_hex(body) {
+        return false;
+    }
+
+    let Some(signed_headers) = authorization.split("SignedHeaders=").nth(1).and_then(|v| v.split(',').next()) else {
+        return false;
+    };
+    let signed: Vec<(String, String)> = signed_headers
+        .split(';')
+        .map(|name| (name.to_string(), header(name).unwrap_or_default()))
+        .collect();
+    if !["host", "x-amz-content-sha256", "x-amz-date"].iter().all(|name| signed.iter().any(|(n, _)| n == name)) {
+        return false;
+    }
+    let now = NaiveDateTime::parse_from_str(&date, "%Y%m%dT%H%M%SZ").unwrap().and_utc();
+    let request = SignableRequest {
+        method: method.as_str(),
+        path: uri.path(),
+        query: &query,
+        headers: &signed,
+        payload_hash: &payload_hash,
+    };
+
+    authorization == authorization_header(&credentials(), &request, now)
+}
+
+/// Minimal path-style S3 endpoint: object CRUD, ranges and paginated ListObjectsV2
+async fn s3_stub(State(bucket): State<Bucket>, method: Method, uri: Uri, headers: HeaderMap, body: Bytes) -> Response {
+    if !verify_signature(&method, &uri, &headers, &body) {
+        return s3_error(StatusCode::FORBIDDEN, "SignatureDoesNotMatch");
+    }
+
+    let path = uri.path().strip_prefix(&format!("/{}/", BUCKET)).unwrap_or_default().to_string();
+    let mut objects = bucket.objects.lock().unwrap();
+
+    if path.is_empty() && method == Method::GET {
+        let url = reqwest::Url::parse(&format!("http://stub{}", uri)).unwrap();
+        let token = url.query_pairs().find(|(name, _)| name == "continuation-token").map(|(_, v)| v.to_string());
+        let keys: Vec<&String> = objects.keys().filter(|key| token.as_ref().is_none_or(|t| *key > t)).collect();
+        let page = &keys[..keys.len().min(2)];
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?><ListBucketResult xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\">");
+        xml.push_str(&format!("<Name>{}</Name><KeyCount>{}</KeyCount>", BUCKET, page.len()));
+        for key in page {
+            let (content, modified) = &objects[*key];
+            xml.push_str(&format!(
+                "<Contents><Key>{}</Key><LastModified>{}</LastModified><Size>{}</Size></Contents>",
+                key.replace('&', "&amp;"),
+                modified.format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+                content.len()
+            ));
+        }
+        let truncated = keys.len() > page.len();
+        xml.push_str(&format!("<IsTruncated>{}</IsTruncated>", truncated));
+        if truncated {
+            xml.push_str(&format!("<NextContinuationToken>{}</NextContinuationToken>", page[page.len() - 1]));
+        }
+        xml.push_str("</ListBucketResult>");
+        return (StatusCode::OK, xml).into_response();
+    }
+
+    let key = percent_decode(&path);
+    match method {
+        Method::PUT => {
+            objects.insert(key, (body.to_vec(), Utc::now()));
+            StatusCode::OK.into_response()
+        }
+        Method::DELETE => {
+            objects.remove(&key);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Method::HEAD | Method::GET => {
+            let Some((content, modified)) = objects.get(&key) else {
+                return s3_error(StatusCode::NOT_FOUND, "NoSuchKey");
+            };
+            let last_modified = modified.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+            if method == Method::HEAD {
+                return (
+                    StatusCode::OK,
+                    [(header::CONTENT_LENGTH, content.len().to_string()), (header::LAST_MODIFIED, last_modified)],
+                )
+                    .into_response();
+            }
+
+            let url = reqwest::Url::parse(&format!("http://stub{}", uri)).unwrap();
+            let disposition = url
+                .query_pairs()
+                .find(|(name, _)| name == "response-content-disposition")
+                .map(|(_, v)| v.to_string())
+                .unwrap_or_else(|| "attachment".to_string());
+            match headers.get(header::RANGE).and_then(|v| v.to_str().ok()) {
+                Some(range) => {
+                    let (start, end) = range.trim_start_matches("bytes=").split_once('-').unwrap();
+                    let (start, end): (usize, usize) = (start.parse().unwrap(), end.parse().unwrap());
+                    (StatusCode::PARTIAL_CONTENT, content[start..=end.min(content.len() - 1)].to_vec()).into_response()
+                }
+                None => (StatusCode::OK, [(header::CONTENT_DISPOSITION, disposition)], content.clone()).into_response(),
+            }
+        }
+        _ => s3_error(StatusCode::METHOD_NOT_ALLOWED, "MethodNotAllowed"),
+    }
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            decoded.push(u8::from_str_radix(&value[i + 1..i + 3], 16).unwrap());
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).unwrap()
+}
+
+async fn spawn_s3_stub() -> (String, Bucket) {
+    let bucket = Bucket::default();
+    let app = Router::new().fallback(s3_stub).with_state(bucket.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (address, bucket)
+}
+
+fn use_s3(config: &mut Config, endpoint: &str) {
+    config.storage.backend = StorageBackendKind::S3;
+    config.storage.s3_endpoint = endpoint.to_string();
+    config.storage.s3_region = "eu-central-1".to_string();
+    config.storage.s3_bucket = BUCKET.to_string();
+    config.storage.s3_access_key_id = "test-key".to_string();
+    config.storage.s3_secret_access_key = "test-secret".to_string();
+    config.storage.s3_path_style = true;
+}
+
+#[tokio::test]
+async fn documents_are_stored_in_s3_compatible_storage() {
+    let (endpoint, bucket) = spawn_s3_stub().await;
+    let s3 = endpoint.clone();
+    let app = spawn_app_with(move |c| use_s3(c, &s3)).await;
+    app.register_and_login().await;
+
+    let content = "Vendors must encrypt backups at rest.";
+    let document: serde_json::Value = app
+        .post_json("/documents/text", &serde_json::json!({ "title": "Backup policy", "content": content }))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let key = app.storage_key(&document).await;
+    assert!(key.starts_with("blobs/"));
+    assert_eq!(content.as_bytes(), bucket.objects.lock().unwrap()[&key].0.as_slice());
+
+    let id = document["id"].as_str().unwrap();
+    let response = app.get(&format!("/documents/{}/content", id)).await;
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(content, response.text().await.unwrap());
+
+    let response = app
+        .api_client
+        .get(format!("{}/api/documents/{}/content", app.address, id))
+        .header("Range", "bytes=8-11")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(206, response.status().as_u16());
+    assert_eq!(format!("bytes 8-11/{}", content.len()), response.headers()["content-range"].to_str().unwrap());
+    assert_eq!("must", response.text().await.unwrap());
+
+    // Presigned URLs download straight from the bucket, without the API session
+    let link: serde_json::Value = app.get(&format!("/documents/{}/download-url", id)).await.json().await.unwrap();
+    assert_eq!(true, link["presigned"]);
+    assert!(link["expires_at"].is_string());
+    let url = link["url"].as_str().unwrap();
+    assert!(url.starts_with(&format!("{}/{}/{}?", endpoint, BUCKET, key)));
+    let response = reqwest::get(url).await.unwrap();
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(
+        "attachment; filename=\"Backup_policy.txt\"; filename*=UTF-8''Backup_policy.txt",
+        response.headers()["content-disposition"]
+    );
+    assert_eq!(content, response.text().await.unwrap());
+    let tampered = url.replace(&key, "other.txt");
+    assert_eq!(403, reqwest::get(&tampered).await.unwrap().status().as_u16());
+
+    // The sweeper pages through the bucket listing
+    let stale = Utc::now() - chrono::Duration::days(2);
+    for stray in ["stray/a.bin", "stray/b c.bin", "stray/c&d.bin"] {
+        bucket.objects.lock().unwrap().insert(stray.to_string(), (vec![1; 10], stale));
+    }
+    let mut config = Config::from_env();
+    use_s3(&mut config, &endpoint);
+    config.orphan_grace_secs = 3600;
+    let storage = storage_from_config(&config).unwrap();
+    let report = StorageService::new(app.pool.clone(), storage, &config).sweep_orphans(false).await.unwrap();
+    assert_eq!(4, report.scanned_objects);
+    assert_eq!(vec!["stray/a.bin", "stray/b c.bin", "stray/c&d.bin"], report.orphaned_keys);
+    assert_eq!(30, report.reclaimed_bytes);
+    assert_eq!(vec![key.clone()], bucket.objects.lock().unwrap().keys().cloned().collect::<Vec<_>>());
+
+    let response = app.delete(&format!("/documents/{}", id)).await;
+    assert_eq!(204, response.status().as_u16());
+    assert!(bucket.objects.lock().unwrap().is_empty());
+}